@@ -0,0 +1,72 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Compare passing a name to LLDB as a borrowed `CStr`, as a `str`
+//! copied on the stack, and as a `CString` allocated on each call,
+//! which is what every lookup did before `AsCStr`.
+//!
+//! This debugs a copy of itself, which is stopped at a breakpoint on
+//! `main` and never runs any benchmarks.
+
+#[macro_use]
+extern crate criterion;
+extern crate lldb;
+
+use criterion::{black_box, Criterion};
+use lldb::{AsCStr, SBDebugger, SBLaunchInfo};
+use std::env;
+use std::ffi::CString;
+use std::time::Duration;
+
+const NAME: &str = "pc";
+
+fn conversions(c: &mut Criterion) {
+    let name = CString::new(NAME).unwrap();
+    let long = "x".repeat(200);
+
+    c.bench_function("with_cstr CStr", |b| {
+        b.iter(|| black_box(&*name).with_cstr(|s| s.to_bytes().len()))
+    });
+    c.bench_function("with_cstr short str", |b| {
+        b.iter(|| black_box(NAME).with_cstr(|s| s.to_bytes().len()))
+    });
+    c.bench_function("with_cstr long str", |b| {
+        b.iter(|| black_box(long.as_str()).with_cstr(|s| s.to_bytes().len()))
+    });
+    c.bench_function("CString::new", |b| {
+        b.iter(|| CString::new(black_box(NAME)).unwrap().to_bytes().len())
+    });
+}
+
+fn lookups(c: &mut Criterion) {
+    SBDebugger::initialize();
+    let debugger = SBDebugger::create(false);
+    let exe = env::current_exe().unwrap();
+    let target = debugger
+        .create_target(exe.to_str().unwrap(), None, None, false)
+        .unwrap();
+    target.breakpoint_create_by_name("main", None);
+    let (process, _) = target
+        .launch_and_wait(&SBLaunchInfo::new(), Duration::from_secs(30))
+        .unwrap();
+    let frame = process.selected_thread().unwrap().selected_frame().unwrap();
+    let name = CString::new(NAME).unwrap();
+
+    c.bench_function("find_register CStr", |b| {
+        b.iter(|| frame.find_register(&*name))
+    });
+    c.bench_function("find_register str", |b| {
+        b.iter(|| frame.find_register(NAME))
+    });
+    c.bench_function("find_register CString::new", |b| {
+        b.iter(|| frame.find_register(&*CString::new(NAME).unwrap()))
+    });
+
+    let _ = process.kill();
+}
+
+criterion_group!(benches, conversions, lookups);
+criterion_main!(benches);
@@ -0,0 +1,59 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Compare `SBThread::collect_frames` with calling the accessors on
+//! each frame.
+//!
+//! This debugs a copy of itself, which is stopped at a breakpoint on
+//! `main` and never runs any benchmarks.
+
+#[macro_use]
+extern crate criterion;
+extern crate lldb;
+
+use criterion::Criterion;
+use lldb::{SBDebugger, SBLaunchInfo};
+use std::env;
+use std::time::Duration;
+
+fn backtraces(c: &mut Criterion) {
+    SBDebugger::initialize();
+    let debugger = SBDebugger::create(false);
+    let exe = env::current_exe().unwrap();
+    let target = debugger
+        .create_target(exe.to_str().unwrap(), None, None, false)
+        .unwrap();
+    target.breakpoint_create_by_name("main", None);
+    let (process, _) = target
+        .launch_and_wait(&SBLaunchInfo::new(), Duration::from_secs(30))
+        .unwrap();
+    let thread = process.selected_thread().unwrap();
+
+    c.bench_function("collect_frames", |b| b.iter(|| thread.collect_frames()));
+    c.bench_function("frame accessors", |b| {
+        b.iter(|| {
+            thread
+                .frames()
+                .map(|frame| {
+                    let line_entry = frame.line_entry();
+                    let module = frame.module();
+                    (
+                        frame.pc(),
+                        frame.function_name(),
+                        module.filespec().path(),
+                        line_entry.as_ref().map(|l| l.filespec().path()),
+                        line_entry.as_ref().map(|l| l.line()),
+                    )
+                })
+                .collect::<Vec<_>>()
+        })
+    });
+
+    let _ = process.kill();
+}
+
+criterion_group!(benches, backtraces);
+criterion_main!(benches);
@@ -6,13 +6,17 @@
 
 use super::block::SBBlock;
 use super::compileunit::SBCompileUnit;
+use super::error::InvalidRefError;
 use super::function::SBFunction;
+#[cfg(feature = "graphql")]
+use super::graphql::U64;
 use super::lineentry::SBLineEntry;
 use super::module::SBModule;
 use super::stream::SBStream;
 use super::symbol::SBSymbol;
 use super::symbolcontext::SBSymbolContext;
 use super::target::SBTarget;
+use std::convert::TryFrom;
 use std::fmt;
 use sys;
 
@@ -53,14 +57,24 @@ pub struct SBAddress {
 
 impl SBAddress {
     /// Construct a new `SBAddress`.
-    pub fn wrap(raw: sys::SBAddressRef) -> SBAddress {
+    ///
+    /// # Safety
+    ///
+    /// `raw` must be a valid reference, which is disposed of when the
+    /// `SBAddress` is dropped. Use [`maybe_wrap`] when it may be invalid.
+    ///
+    /// [`maybe_wrap`]: #method.maybe_wrap
+    pub unsafe fn wrap(raw: sys::SBAddressRef) -> SBAddress {
         SBAddress { raw }
     }
 
     /// Construct a new `Some(SBAddress)` or `None`.
+    ///
+    /// `raw` is disposed of if it is invalid.
     pub fn maybe_wrap(raw: sys::SBAddressRef) -> Option<SBAddress> {
-        if unsafe { sys::SBAddressIsValid(raw) != 0 } {
-            Some(SBAddress { raw })
+        let wrapped = SBAddress { raw };
+        if wrapped.is_valid() {
+            Some(wrapped)
         } else {
             None
         }
@@ -94,7 +108,7 @@ impl SBAddress {
     ///   is needed by the caller. These flags have constants starting
     ///   with `SYMBOL_CONTEXT_ITEM_`.
     pub fn symbol_context(&self, resolve_scope: u32) -> SBSymbolContext {
-        SBSymbolContext::wrap(unsafe { sys::SBAddressGetSymbolContext(self.raw, resolve_scope) })
+        unsafe { SBSymbolContext::wrap(sys::SBAddressGetSymbolContext(self.raw, resolve_scope)) }
     }
 
     /// Get the `SBModule` for a given address.
@@ -228,6 +242,12 @@ impl SBAddress {
     pub fn line_entry(&self) -> Option<SBLineEntry> {
         SBLineEntry::maybe_wrap(unsafe { sys::SBAddressGetLineEntry(self.raw) })
     }
+
+    fn description(&self) -> String {
+        let stream = SBStream::new();
+        unsafe { sys::SBAddressGetDescription(self.raw, stream.raw) };
+        stream.data()
+    }
 }
 
 impl Clone for SBAddress {
@@ -240,9 +260,21 @@ impl Clone for SBAddress {
 
 impl fmt::Debug for SBAddress {
     fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
-        let stream = SBStream::new();
-        unsafe { sys::SBAddressGetDescription(self.raw, stream.raw) };
-        write!(fmt, "SBAddress {{ {} }}", stream.data())
+        write!(fmt, "SBAddress {{ {} }}", self.description())
+    }
+}
+
+impl fmt::Display for SBAddress {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "{}", self.description())
+    }
+}
+
+impl TryFrom<sys::SBAddressRef> for SBAddress {
+    type Error = InvalidRefError;
+
+    fn try_from(raw: sys::SBAddressRef) -> Result<SBAddress, InvalidRefError> {
+        SBAddress::maybe_wrap(raw).ok_or(InvalidRefError)
     }
 }
 
@@ -256,37 +288,45 @@ unsafe impl Send for SBAddress {}
 unsafe impl Sync for SBAddress {}
 
 #[cfg(feature = "graphql")]
-graphql_object!(SBAddress: super::debugger::SBDebugger | &self | {
-    field is_valid() -> bool {
+#[graphql_object(context = super::debugger::SBDebugger)]
+impl SBAddress {
+    #[graphql(name = "isValid")]
+    fn graphql_is_valid(&self) -> bool {
         self.is_valid()
     }
 
-    // TODO(bm) This should be u64
-    field file_address() -> i32 {
-        self.file_address() as i32
+    #[graphql(name = "fileAddress")]
+    fn graphql_file_address(&self) -> U64 {
+        self.file_address().into()
     }
 
-    field module() -> Option<SBModule> {
+    #[graphql(name = "module")]
+    fn graphql_module(&self) -> Option<SBModule> {
         self.module()
     }
 
-    field compile_unit() -> Option<SBCompileUnit> {
+    #[graphql(name = "compileUnit")]
+    fn graphql_compile_unit(&self) -> Option<SBCompileUnit> {
         self.compile_unit()
     }
 
-    field function() -> Option<SBFunction> {
+    #[graphql(name = "function")]
+    fn graphql_function(&self) -> Option<SBFunction> {
         self.function()
     }
 
-    field block() -> Option<SBBlock> {
+    #[graphql(name = "block")]
+    fn graphql_block(&self) -> Option<SBBlock> {
         self.block()
     }
 
-    field symbol() -> Option<SBSymbol> {
+    #[graphql(name = "symbol")]
+    fn graphql_symbol(&self) -> Option<SBSymbol> {
         self.symbol()
     }
 
-    field line_entry() -> Option<SBLineEntry> {
+    #[graphql(name = "lineEntry")]
+    fn graphql_line_entry(&self) -> Option<SBLineEntry> {
         self.line_entry()
     }
-});
+}
@@ -0,0 +1,206 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use super::lldb_addr_t;
+use std::fmt;
+use std::iter::FromIterator;
+use std::slice;
+
+/// A range of load addresses, such as the code of a function.
+///
+/// This plays the part of `SBAddressRange`, which is newer than the
+/// version of LLDB that these bindings are built against, so it holds
+/// load addresses rather than section offset `SBAddress`es.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct AddressRange {
+    base: lldb_addr_t,
+    byte_size: u64,
+}
+
+impl AddressRange {
+    /// The range of `byte_size` bytes starting at `base`.
+    pub fn new(base: lldb_addr_t, byte_size: u64) -> AddressRange {
+        AddressRange { base, byte_size }
+    }
+
+    /// The range from `start` up to, but not including, `end`, which
+    /// is empty if `end` isn't after `start`.
+    pub fn from_bounds(start: lldb_addr_t, end: lldb_addr_t) -> AddressRange {
+        AddressRange::new(start, end.saturating_sub(start))
+    }
+
+    /// The first address in the range.
+    pub fn base_address(&self) -> lldb_addr_t {
+        self.base
+    }
+
+    /// The address just past the end of the range.
+    pub fn end_address(&self) -> lldb_addr_t {
+        self.base.saturating_add(self.byte_size)
+    }
+
+    /// The number of bytes in the range.
+    pub fn byte_size(&self) -> u64 {
+        self.byte_size
+    }
+
+    /// Is the range empty?
+    pub fn is_empty(&self) -> bool {
+        self.byte_size == 0
+    }
+
+    /// Is `address` in the range?
+    pub fn contains(&self, address: lldb_addr_t) -> bool {
+        address >= self.base && address < self.end_address()
+    }
+
+    /// Is all of `other` in this range?
+    pub fn contains_range(&self, other: &AddressRange) -> bool {
+        other.base >= self.base && other.end_address() <= self.end_address()
+    }
+
+    /// Do this range and `other` have any addresses in common?
+    pub fn intersects(&self, other: &AddressRange) -> bool {
+        self.base < other.end_address() && other.base < self.end_address()
+    }
+}
+
+impl fmt::Display for AddressRange {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "[{:#x}-{:#x})", self.base, self.end_address())
+    }
+}
+
+/// A list of address ranges, such as those of a function whose code
+/// isn't contiguous.
+///
+/// This plays the part of `SBAddressRangeList`, like
+/// [`AddressRange`] does for `SBAddressRange`.
+///
+/// [`AddressRange`]: struct.AddressRange.html
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct AddressRangeList {
+    ranges: Vec<AddressRange>,
+}
+
+impl AddressRangeList {
+    /// Create an empty list.
+    pub fn new() -> AddressRangeList {
+        AddressRangeList::default()
+    }
+
+    /// The number of ranges in the list.
+    pub fn len(&self) -> usize {
+        self.ranges.len()
+    }
+
+    /// Is the list empty?
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    /// Add `range` to the end of the list.
+    pub fn append(&mut self, range: AddressRange) {
+        self.ranges.push(range);
+    }
+
+    /// Add the ranges of `other` to the end of the list.
+    pub fn append_list(&mut self, other: &AddressRangeList) {
+        self.ranges.extend_from_slice(&other.ranges);
+    }
+
+    /// Remove all of the ranges.
+    pub fn clear(&mut self) {
+        self.ranges.clear();
+    }
+
+    /// The range at `index`.
+    pub fn get(&self, index: usize) -> Option<&AddressRange> {
+        self.ranges.get(index)
+    }
+
+    /// Iterate over the ranges.
+    pub fn iter(&self) -> slice::Iter<'_, AddressRange> {
+        self.ranges.iter()
+    }
+
+    /// Is `address` in any of the ranges?
+    pub fn contains(&self, address: lldb_addr_t) -> bool {
+        self.find(address).is_some()
+    }
+
+    /// The first range containing `address`.
+    pub fn find(&self, address: lldb_addr_t) -> Option<&AddressRange> {
+        self.ranges.iter().find(|range| range.contains(address))
+    }
+
+    /// The total number of bytes in the ranges, counting bytes in
+    /// overlapping ranges more than once.
+    pub fn byte_size(&self) -> u64 {
+        self.ranges.iter().map(AddressRange::byte_size).sum()
+    }
+}
+
+impl FromIterator<AddressRange> for AddressRangeList {
+    fn from_iter<I: IntoIterator<Item = AddressRange>>(iter: I) -> AddressRangeList {
+        AddressRangeList {
+            ranges: iter.into_iter().collect(),
+        }
+    }
+}
+
+impl IntoIterator for AddressRangeList {
+    type Item = AddressRange;
+    type IntoIter = ::std::vec::IntoIter<AddressRange>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.ranges.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a AddressRangeList {
+    type Item = &'a AddressRange;
+    type IntoIter = slice::Iter<'a, AddressRange>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.ranges.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{AddressRange, AddressRangeList};
+
+    #[test]
+    fn containment() {
+        let range = AddressRange::from_bounds(0x1000, 0x1010);
+        assert_eq!(range.byte_size(), 0x10);
+        assert!(range.contains(0x1000));
+        assert!(range.contains(0x100f));
+        assert!(!range.contains(0x1010));
+        assert!(range.contains_range(&AddressRange::new(0x1004, 4)));
+        assert!(!range.contains_range(&AddressRange::new(0x100c, 8)));
+        assert!(range.intersects(&AddressRange::new(0x100c, 8)));
+        assert!(!range.intersects(&AddressRange::new(0x1010, 8)));
+        assert!(AddressRange::from_bounds(0x1010, 0x1000).is_empty());
+        assert_eq!(range.to_string(), "[0x1000-0x1010)");
+    }
+
+    #[test]
+    fn list() {
+        let list: AddressRangeList = vec![
+            AddressRange::new(0x1000, 0x10),
+            AddressRange::new(0x2000, 0x20),
+        ]
+        .into_iter()
+        .collect();
+        assert_eq!(list.len(), 2);
+        assert_eq!(list.byte_size(), 0x30);
+        assert!(list.contains(0x2010));
+        assert!(!list.contains(0x1800));
+        assert_eq!(list.find(0x1008), list.get(0));
+    }
+}
@@ -0,0 +1,152 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Debugging programs on Android devices.
+//!
+//! Debugging on Android needs `lldb-server` running in platform mode
+//! on the device, which comes with the NDK:
+//!
+//! ```text
+//! adb push lldb-server /data/local/tmp/
+//! adb shell /data/local/tmp/lldb-server platform --server --listen "*:5039"
+//! ```
+//!
+//! [`AndroidDevice`] then takes care of the rest: selecting the
+//! `remote-android` platform, connecting to the device through `adb`,
+//! copying the program over and launching or attaching to it:
+//!
+//! ```no_run
+//! # use lldb::{SBDebugger, SBLaunchInfo};
+//! # use lldb::android::AndroidDevice;
+//! SBDebugger::initialize();
+//! let debugger = SBDebugger::create(false);
+//! let device = AndroidDevice::connect(&debugger, None, 5039).unwrap();
+//! let (target, process) = device
+//!     .launch("target/aarch64-linux-android/debug/app", SBLaunchInfo::new())
+//!     .unwrap();
+//! ```
+//!
+//! LLDB forwards the platform port, and the ports of the debug
+//! servers that the platform starts, over `adb` itself, so `adb` must
+//! be on the `PATH`. Set `ANDROID_SERIAL` or pass a serial number to
+//! [`AndroidDevice::connect`] when more than one device is connected.
+//!
+//! [`AndroidDevice`]: struct.AndroidDevice.html
+//! [`AndroidDevice::connect`]: struct.AndroidDevice.html#method.connect
+
+use super::attachinfo::SBAttachInfo;
+use super::debugger::SBDebugger;
+use super::error::SBError;
+use super::filespec::SBFileSpec;
+use super::launchinfo::SBLaunchInfo;
+use super::lldb_pid_t;
+use super::platform::SBPlatform;
+use super::process::SBProcess;
+use super::remoteurl::RemoteUrl;
+use super::target::SBTarget;
+use std::path::Path;
+
+/// The directory that programs are copied to by default, which is
+/// writable and executable for the shell user on every device.
+pub const DEFAULT_REMOTE_DIRECTORY: &str = "/data/local/tmp";
+
+/// An Android device, connected to through `lldb-server platform`.
+pub struct AndroidDevice {
+    debugger: SBDebugger,
+    platform: SBPlatform,
+    remote_directory: String,
+}
+
+impl AndroidDevice {
+    /// Connect to the `lldb-server platform` listening on `port` on
+    /// the device with the serial number `serial`, or on the only
+    /// connected device when `serial` is `None`.
+    ///
+    /// The `remote-android` platform becomes the selected platform of
+    /// `debugger`, so that targets created afterwards use it.
+    pub fn connect(
+        debugger: &SBDebugger,
+        serial: Option<&str>,
+        port: u16,
+    ) -> Result<AndroidDevice, SBError> {
+        let platform = SBPlatform::new("remote-android")
+            .ok_or_else(|| SBError::from_message("this LLDB has no remote-android platform"))?;
+        platform.connect_remote(&RemoteUrl::android(serial, port))?;
+        debugger.set_selected_platform(&platform);
+        Ok(AndroidDevice {
+            debugger: debugger.clone(),
+            platform,
+            remote_directory: DEFAULT_REMOTE_DIRECTORY.to_string(),
+        })
+    }
+
+    /// The platform connected to the device.
+    pub fn platform(&self) -> &SBPlatform {
+        &self.platform
+    }
+
+    /// Copy programs to `directory` on the device, rather than
+    /// [`DEFAULT_REMOTE_DIRECTORY`].
+    ///
+    /// [`DEFAULT_REMOTE_DIRECTORY`]: constant.DEFAULT_REMOTE_DIRECTORY.html
+    pub fn set_remote_directory(&mut self, directory: &str) {
+        self.remote_directory = directory.trim_end_matches('/').to_string();
+    }
+
+    /// Copy the local file `local` to `remote` on the device.
+    pub fn push(&self, local: &str, remote: &str) -> Result<(), SBError> {
+        self.platform.put(
+            &SBFileSpec::from_path(local, true),
+            &SBFileSpec::from_path(remote, false),
+        )
+    }
+
+    /// Copy the program at `executable` to the device and launch it
+    /// there under the debugger.
+    ///
+    /// The program is copied into the remote directory under its own
+    /// file name, and the arguments, environment and flags come from
+    /// `launch_info`.
+    pub fn launch(
+        &self,
+        executable: &str,
+        launch_info: SBLaunchInfo,
+    ) -> Result<(SBTarget, SBProcess), SBError> {
+        let file_name = Path::new(executable)
+            .file_name()
+            .and_then(|name| name.to_str())
+            .unwrap_or(executable);
+        let remote = format!("{}/{}", self.remote_directory, file_name);
+        self.push(executable, &remote)?;
+
+        let target = self.create_target(executable)?;
+        let remote_file = SBFileSpec::from_path(&remote, false);
+        if let Some(module) = target.executable().and_then(|e| target.find_module(&e)) {
+            module.set_platform_filespec(&remote_file);
+        }
+        launch_info.set_executable_file(&remote_file, true);
+        let process = target.launch(launch_info)?;
+        Ok((target, process))
+    }
+
+    /// Attach to the process `pid` running on the device.
+    ///
+    /// `executable` is a local copy of the program, for its symbols.
+    pub fn attach(
+        &self,
+        executable: Option<&str>,
+        pid: lldb_pid_t,
+    ) -> Result<(SBTarget, SBProcess), SBError> {
+        let target = self.create_target(executable.unwrap_or(""))?;
+        let process = target.attach(SBAttachInfo::new_with_pid(pid))?;
+        Ok((target, process))
+    }
+
+    fn create_target(&self, executable: &str) -> Result<SBTarget, SBError> {
+        self.debugger
+            .create_target(executable, None, Some("remote-android"), true)
+    }
+}
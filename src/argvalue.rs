@@ -0,0 +1,153 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use super::lldb_addr_t;
+use std::fmt;
+
+/// An argument for [`SBFrame::call_function`].
+///
+/// Each argument is written into the call expression as a C literal
+/// of the matching type, so strings are escaped rather than being
+/// pasted into the expression.
+///
+/// [`SBFrame::call_function`]: struct.SBFrame.html#method.call_function
+#[derive(Clone, Debug, PartialEq)]
+pub enum ArgValue {
+    /// A signed integer, passed as a `long long`.
+    Int(i64),
+    /// An unsigned integer, passed as an `unsigned long long`.
+    UInt(u64),
+    /// A floating point number, passed as a `double`.
+    Float(f64),
+    /// A boolean, passed as a `bool`.
+    Bool(bool),
+    /// An address in the process, passed as a `void *`.
+    Pointer(lldb_addr_t),
+    /// A string, passed as a `const char *` to a copy of it in the
+    /// process.
+    Str(String),
+}
+
+impl ArgValue {
+    /// The C type that the argument is passed as.
+    pub fn c_type(&self) -> &'static str {
+        match *self {
+            ArgValue::Int(_) => "long long",
+            ArgValue::UInt(_) => "unsigned long long",
+            ArgValue::Float(_) => "double",
+            ArgValue::Bool(_) => "bool",
+            ArgValue::Pointer(_) => "void *",
+            ArgValue::Str(_) => "const char *",
+        }
+    }
+
+    /// Is the argument passed as a pointer?
+    pub fn is_pointer(&self) -> bool {
+        matches!(*self, ArgValue::Pointer(_) | ArgValue::Str(_))
+    }
+}
+
+/// Formats the argument as a C literal.
+impl fmt::Display for ArgValue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            // The magnitude of the smallest value is too large for a
+            // `long long` literal.
+            ArgValue::Int(i64::MIN) => write!(f, "({}LL - 1)", i64::MIN + 1),
+            ArgValue::Int(i) => write!(f, "{}LL", i),
+            ArgValue::UInt(u) => write!(f, "{}ULL", u),
+            ArgValue::Float(x) if x.is_nan() => write!(f, "__builtin_nan(\"\")"),
+            ArgValue::Float(x) if x.is_infinite() => {
+                write!(f, "{}__builtin_inf()", if x < 0.0 { "-" } else { "" })
+            }
+            ArgValue::Float(x) => write!(f, "{:?}", x),
+            ArgValue::Bool(b) => write!(f, "{}", b),
+            ArgValue::Pointer(addr) => write!(f, "(void *){:#x}", addr),
+            ArgValue::Str(ref s) => {
+                f.write_str("\"")?;
+                for b in s.bytes() {
+                    match b {
+                        b'"' => f.write_str("\\\"")?,
+                        b'\\' => f.write_str("\\\\")?,
+                        0x20..=0x7e => write!(f, "{}", b as char)?,
+                        // Octal escapes, unlike hex escapes, can't run
+                        // into the characters after them.
+                        _ => write!(f, "\\{:03o}", b)?,
+                    }
+                }
+                f.write_str("\"")
+            }
+        }
+    }
+}
+
+impl From<i32> for ArgValue {
+    fn from(i: i32) -> ArgValue {
+        ArgValue::Int(i.into())
+    }
+}
+
+impl From<i64> for ArgValue {
+    fn from(i: i64) -> ArgValue {
+        ArgValue::Int(i)
+    }
+}
+
+impl From<u32> for ArgValue {
+    fn from(u: u32) -> ArgValue {
+        ArgValue::UInt(u.into())
+    }
+}
+
+impl From<u64> for ArgValue {
+    fn from(u: u64) -> ArgValue {
+        ArgValue::UInt(u)
+    }
+}
+
+impl From<f64> for ArgValue {
+    fn from(x: f64) -> ArgValue {
+        ArgValue::Float(x)
+    }
+}
+
+impl From<bool> for ArgValue {
+    fn from(b: bool) -> ArgValue {
+        ArgValue::Bool(b)
+    }
+}
+
+impl<'a> From<&'a str> for ArgValue {
+    fn from(s: &'a str) -> ArgValue {
+        ArgValue::Str(s.to_string())
+    }
+}
+
+impl From<String> for ArgValue {
+    fn from(s: String) -> ArgValue {
+        ArgValue::Str(s)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ArgValue;
+
+    #[test]
+    fn literals() {
+        assert_eq!(ArgValue::Int(-1).to_string(), "-1LL");
+        assert_eq!(
+            ArgValue::Int(i64::MIN).to_string(),
+            "(-9223372036854775807LL - 1)"
+        );
+        assert_eq!(ArgValue::Float(1.0).to_string(), "1.0");
+        assert_eq!(ArgValue::Pointer(0x1000).to_string(), "(void *)0x1000");
+        assert_eq!(
+            ArgValue::from("a \"b\"\\\n1").to_string(),
+            "\"a \\\"b\\\"\\\\\\0121\""
+        );
+    }
+}
@@ -7,6 +7,11 @@
 use super::filespec::SBFileSpec;
 use super::listener::SBListener;
 use super::lldb_pid_t;
+use super::strings;
+#[cfg(feature = "lldb-17")]
+use super::structureddata::SBStructuredData;
+#[cfg(feature = "lldb-17")]
+use std::cell::RefCell;
 use std::ffi::CString;
 use sys;
 
@@ -14,22 +19,43 @@ use sys;
 ///
 /// See [`SBTarget::attach`].
 ///
+/// A process can be attached to by its process ID or by the name of
+/// its executable. When attaching by name, it is also possible to
+/// wait for the next launch of a matching process:
+///
+/// ```no_run
+/// # use lldb::{SBAttachInfo, SBError, SBProcess, SBTarget};
+/// # fn attach(target: &SBTarget) -> Result<SBProcess, SBError> {
+/// let attach_info = SBAttachInfo::new_with_path("my-daemon", true, false);
+/// // Only attach to instances launched by a particular user.
+/// attach_info.set_user_id(501);
+/// target.attach(attach_info)
+/// # }
+/// ```
+///
 /// [`SBTarget::attach`]: struct.SBTarget.html#method.attach
 #[derive(Debug)]
 pub struct SBAttachInfo {
     /// The underlying raw `SBAttachInfoRef`.
     pub raw: sys::SBAttachInfoRef,
+    /// The class of a scripted process, which this version of LLDB's
+    /// API can't set on `raw`.
+    #[cfg(feature = "lldb-17")]
+    scripted_process_class_name: RefCell<Option<String>>,
+    /// The arguments given to the class of a scripted process.
+    #[cfg(feature = "lldb-17")]
+    scripted_process_dictionary: RefCell<Option<SBStructuredData>>,
 }
 
 impl SBAttachInfo {
     /// Construct a new `SBAttachInfo`.
     pub fn new() -> SBAttachInfo {
-        SBAttachInfo::wrap(unsafe { sys::CreateSBAttachInfo() })
+        unsafe { SBAttachInfo::wrap(sys::CreateSBAttachInfo()) }
     }
 
     /// Construct a new `SBAttachInfo` for a given process ID (pid).
     pub fn new_with_pid(pid: lldb_pid_t) -> SBAttachInfo {
-        SBAttachInfo::wrap(unsafe { sys::CreateSBAttachInfo2(pid) })
+        unsafe { SBAttachInfo::wrap(sys::CreateSBAttachInfo2(pid)) }
     }
 
     /// Attach to a process by name.
@@ -50,14 +76,30 @@ impl SBAttachInfo {
     ///   called and an `eStateExited` process event will be delivered.
     pub fn new_with_path(path: &str, wait_for: bool, async: bool) -> SBAttachInfo {
         let p = CString::new(path).unwrap();
-        SBAttachInfo::wrap(unsafe {
-            sys::CreateSBAttachInfo4(p.as_ptr(), wait_for as u8, async as u8)
-        })
+        unsafe {
+            SBAttachInfo::wrap(sys::CreateSBAttachInfo4(
+                p.as_ptr(),
+                wait_for as u8,
+                async as u8,
+            ))
+        }
     }
 
     /// Construct a new `SBAttachInfo`.
-    pub fn wrap(raw: sys::SBAttachInfoRef) -> SBAttachInfo {
-        SBAttachInfo { raw }
+    ///
+    /// # Safety
+    ///
+    /// `raw` must be an `SBAttachInfoRef` which isn't used elsewhere,
+    /// such as one from `CreateSBAttachInfo`, as it is disposed of when
+    /// the `SBAttachInfo` is dropped.
+    pub unsafe fn wrap(raw: sys::SBAttachInfoRef) -> SBAttachInfo {
+        SBAttachInfo {
+            raw,
+            #[cfg(feature = "lldb-17")]
+            scripted_process_class_name: RefCell::new(None),
+            #[cfg(feature = "lldb-17")]
+            scripted_process_dictionary: RefCell::new(None),
+        }
     }
 
     #[allow(missing_docs)]
@@ -81,6 +123,21 @@ impl SBAttachInfo {
         unsafe { sys::SBAttachInfoSetExecutable2(self.raw, exe_file.raw) }
     }
 
+    /// Will the attach wait for the next launch of a matching process?
+    pub fn wait_for_launch(&self) -> bool {
+        unsafe { sys::SBAttachInfoGetWaitForLaunch(self.raw) != 0 }
+    }
+
+    /// Wait for the next process whose name matches the executable
+    /// to be launched, rather than attaching to an existing process.
+    ///
+    /// `async` has the same meaning as for [`new_with_path`].
+    ///
+    /// [`new_with_path`]: #method.new_with_path
+    pub fn set_wait_for_launch(&self, wait_for: bool, async: bool) {
+        unsafe { sys::SBAttachInfoSetWaitForLaunch2(self.raw, wait_for as u8, async as u8) }
+    }
+
     #[allow(missing_docs)]
     pub fn ignore_existing(&self) -> bool {
         unsafe { sys::SBAttachInfoGetIgnoreExisting(self.raw) != 0 }
@@ -101,14 +158,136 @@ impl SBAttachInfo {
         unsafe { sys::SBAttachInfoSetResumeCount(self.raw, c) }
     }
 
+    /// The name of the process plugin that will be used to attach, if set.
+    pub fn process_plugin_name(&self) -> Option<String> {
+        unsafe { strings::to_string(sys::SBAttachInfoGetProcessPluginName(self.raw)) }
+    }
+
+    /// Set the name of the process plugin that will be used to attach.
+    pub fn set_process_plugin_name(&self, plugin: &str) {
+        let plugin = CString::new(plugin).unwrap();
+        unsafe { sys::SBAttachInfoSetProcessPluginName(self.raw, plugin.as_ptr()) };
+    }
+
+    /// The Python class which provides the process, if this attaches
+    /// to a scripted process.
+    ///
+    /// See [`set_scripted_process_class_name`].
+    ///
+    /// [`set_scripted_process_class_name`]: #method.set_scripted_process_class_name
+    #[cfg(feature = "lldb-17")]
+    pub fn scripted_process_class_name(&self) -> Option<String> {
+        self.scripted_process_class_name.borrow().clone()
+    }
+
+    /// Attach to the process as a scripted process, whose threads,
+    /// registers and memory are provided by the Python class
+    /// `class_name`. See [`SBTarget::attach_scripted_process`].
+    ///
+    /// This version of LLDB's API can't set the class on the
+    /// underlying `SBAttachInfo`, so it is kept here, and
+    /// [`SBTarget::attach`] then attaches from Python. Only the
+    /// process ID, the class and its arguments are used for such an
+    /// attach.
+    ///
+    /// [`SBTarget::attach_scripted_process`]: struct.SBTarget.html#method.attach_scripted_process
+    /// [`SBTarget::attach`]: struct.SBTarget.html#method.attach
+    #[cfg(feature = "lldb-17")]
+    pub fn set_scripted_process_class_name(&self, class_name: &str) {
+        *self.scripted_process_class_name.borrow_mut() = Some(class_name.to_string());
+    }
+
+    /// The arguments given to the class of a scripted process, if
+    /// they have been set.
+    #[cfg(feature = "lldb-17")]
+    pub fn scripted_process_dictionary(&self) -> Option<SBStructuredData> {
+        self.scripted_process_dictionary.borrow().clone()
+    }
+
+    /// Set the arguments given to the class of a scripted process,
+    /// as its `SBStructuredData` argument.
+    #[cfg(feature = "lldb-17")]
+    pub fn set_scripted_process_dictionary(&self, args: &SBStructuredData) {
+        *self.scripted_process_dictionary.borrow_mut() = Some(args.clone());
+    }
+
+    /// The user ID that a matching process must be running as, if set.
+    pub fn user_id(&self) -> Option<u32> {
+        if unsafe { sys::SBAttachInfoUserIDIsValid(self.raw) != 0 } {
+            Some(unsafe { sys::SBAttachInfoGetUserID(self.raw) })
+        } else {
+            None
+        }
+    }
+
+    /// Only attach to a process running as the given user ID.
+    pub fn set_user_id(&self, uid: u32) {
+        unsafe { sys::SBAttachInfoSetUserID(self.raw, uid) };
+    }
+
+    /// The group ID that a matching process must be running as, if set.
+    pub fn group_id(&self) -> Option<u32> {
+        if unsafe { sys::SBAttachInfoGroupIDIsValid(self.raw) != 0 } {
+            Some(unsafe { sys::SBAttachInfoGetGroupID(self.raw) })
+        } else {
+            None
+        }
+    }
+
+    /// Only attach to a process running as the given group ID.
+    pub fn set_group_id(&self, gid: u32) {
+        unsafe { sys::SBAttachInfoSetGroupID(self.raw, gid) };
+    }
+
+    /// The effective user ID that a matching process must have, if set.
+    pub fn effective_user_id(&self) -> Option<u32> {
+        if unsafe { sys::SBAttachInfoEffectiveUserIDIsValid(self.raw) != 0 } {
+            Some(unsafe { sys::SBAttachInfoGetEffectiveUserID(self.raw) })
+        } else {
+            None
+        }
+    }
+
+    /// Only attach to a process with the given effective user ID.
+    pub fn set_effective_user_id(&self, uid: u32) {
+        unsafe { sys::SBAttachInfoSetEffectiveUserID(self.raw, uid) };
+    }
+
+    /// The effective group ID that a matching process must have, if set.
+    pub fn effective_group_id(&self) -> Option<u32> {
+        if unsafe { sys::SBAttachInfoEffectiveGroupIDIsValid(self.raw) != 0 } {
+            Some(unsafe { sys::SBAttachInfoGetEffectiveGroupID(self.raw) })
+        } else {
+            None
+        }
+    }
+
+    /// Only attach to a process with the given effective group ID.
+    pub fn set_effective_group_id(&self, gid: u32) {
+        unsafe { sys::SBAttachInfoSetEffectiveGroupID(self.raw, gid) };
+    }
+
+    /// The process ID that the parent of a matching process must have, if set.
+    pub fn parent_process_id(&self) -> Option<lldb_pid_t> {
+        if unsafe { sys::SBAttachInfoParentProcessIDIsValid(self.raw) != 0 } {
+            Some(unsafe { sys::SBAttachInfoGetParentProcessID(self.raw) })
+        } else {
+            None
+        }
+    }
+
+    /// Only attach to a process whose parent has the given process ID.
+    pub fn set_parent_process_id(&self, pid: lldb_pid_t) {
+        unsafe { sys::SBAttachInfoSetParentProcessID(self.raw, pid) };
+    }
+
     /// Get the listener that will be used to receive process events.
     ///
     /// If no listener has been set via a call to
-    /// `SBAttachInfo::set_listener()`, then an invalid `SBListener` will be
-    /// returned (`SBListener::is_valid()` will return `false`). If a listener
-    /// has been set, then the valid listener object will be returned.
-    pub fn listener(&self) -> SBListener {
-        SBListener::wrap(unsafe { sys::SBAttachInfoGetListener(self.raw) })
+    /// `SBAttachInfo::set_listener()`, then `None` will be returned.
+    /// If a listener has been set, then the listener object will be returned.
+    pub fn listener(&self) -> Option<SBListener> {
+        SBListener::maybe_wrap(unsafe { sys::SBAttachInfoGetListener(self.raw) })
     }
 
     /// Set the listener that will be used to receive process events.
@@ -118,9 +297,15 @@ impl SBAttachInfo {
     /// process events. Calling this function allows a different
     /// listener to be used to listen for process events.
     ///
+    /// The listener is attached to the process before the attach
+    /// begins, so it will receive every event from the process,
+    /// including the initial stop. Listening for process events after
+    /// [`SBTarget::attach`] has returned can miss that stop.
+    ///
     /// [`SBDebugger`]: struct.SBDebugger.html
     /// [`SBTarget`]: struct.SBTarget.html
-    pub fn set_listener(&self, listener: SBListener) {
+    /// [`SBTarget::attach`]: struct.SBTarget.html#method.attach
+    pub fn set_listener(&self, listener: &SBListener) {
         unsafe { sys::SBAttachInfoSetListener(self.raw, listener.raw) };
     }
 }
@@ -129,6 +314,10 @@ impl Clone for SBAttachInfo {
     fn clone(&self) -> SBAttachInfo {
         SBAttachInfo {
             raw: unsafe { sys::CloneSBAttachInfo(self.raw) },
+            #[cfg(feature = "lldb-17")]
+            scripted_process_class_name: self.scripted_process_class_name.clone(),
+            #[cfg(feature = "lldb-17")]
+            scripted_process_dictionary: self.scripted_process_dictionary.clone(),
         }
     }
 }
@@ -146,4 +335,3 @@ impl Drop for SBAttachInfo {
 }
 
 unsafe impl Send for SBAttachInfo {}
-unsafe impl Sync for SBAttachInfo {}
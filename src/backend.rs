@@ -0,0 +1,463 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use super::error::SBError;
+use super::frame::FrameInfo;
+use super::launchinfo::SBLaunchInfo;
+use super::process::StopSnapshot;
+use super::session::{DebugSession, SessionError};
+use super::thread::StepKind;
+use super::{lldb_addr_t, lldb_pid_t, lldb_tid_t};
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::error::Error;
+use std::fmt;
+use std::time::Duration;
+
+/// The operations on a debugger that a typical program built on this
+/// crate needs, so that the program's logic can be tested without
+/// LLDB.
+///
+/// [`DebugSession`] implements this with LLDB, and [`MockBackend`]
+/// implements it with scripted results. Write the logic to be tested
+/// against `Backend`:
+///
+/// ```
+/// # use lldb::{Backend, BackendError, MockBackend, StopReason, StopSnapshot};
+/// # use std::time::Duration;
+/// fn run_to_main<B: Backend>(backend: &mut B) -> Result<Option<String>, BackendError> {
+///     backend.break_at_function("main");
+///     let stop = backend.launch(&[], Duration::from_secs(10))?;
+///     Ok(stop.function_name)
+/// }
+///
+/// let mut mock = MockBackend::new();
+/// mock.push_stop(StopSnapshot {
+///     process_id: 1234,
+///     state: lldb::StateType::Stopped,
+///     thread_id: 1,
+///     stop_reason: StopReason::Breakpoint,
+///     pc: 0x1000,
+///     function_name: Some("main".to_string()),
+/// });
+/// assert_eq!(run_to_main(&mut mock).unwrap(), Some("main".to_string()));
+/// assert_eq!(mock.breakpoint_locations(), vec!["main".to_string()]);
+/// ```
+///
+/// [`DebugSession`]: struct.DebugSession.html
+/// [`MockBackend`]: struct.MockBackend.html
+pub trait Backend {
+    /// Launch the process with `args` and wait for it to stop.
+    fn launch(&mut self, args: &[&str], timeout: Duration) -> Result<StopSnapshot, BackendError>;
+
+    /// Attach to the process with the ID `pid` and wait for it to
+    /// stop.
+    fn attach(&mut self, pid: lldb_pid_t, timeout: Duration) -> Result<StopSnapshot, BackendError>;
+
+    /// Set a breakpoint on a function, returning its ID.
+    fn break_at_function(&mut self, name: &str) -> i32;
+
+    /// Set a breakpoint on a line of a source file, returning its ID.
+    fn break_at_line(&mut self, file: &str, line: u32) -> i32;
+
+    /// Delete the breakpoint with the ID `id`.
+    fn delete_breakpoint(&mut self, id: i32);
+
+    /// Resume the process and wait for it to stop again.
+    fn continue_until_stop(&mut self, timeout: Duration) -> Result<StopSnapshot, BackendError>;
+
+    /// Step the selected thread and wait for the step to finish.
+    fn step(&mut self, kind: StepKind, timeout: Duration) -> Result<StopSnapshot, BackendError>;
+
+    /// The IDs of the threads in the process.
+    fn threads(&self) -> Vec<lldb_tid_t>;
+
+    /// The stack of the thread with the ID `thread_id`, innermost
+    /// frame first.
+    fn frames(&self, thread_id: lldb_tid_t) -> Vec<FrameInfo>;
+
+    /// Evaluate `expression` in a frame of a thread, returning its
+    /// value as text.
+    fn evaluate(
+        &self,
+        thread_id: lldb_tid_t,
+        frame_index: u32,
+        expression: &str,
+    ) -> Result<String, BackendError>;
+
+    /// Read `size` bytes of memory starting at `addr`.
+    fn read_memory(&self, addr: lldb_addr_t, size: usize) -> Result<Vec<u8>, BackendError>;
+
+    /// Kill the process.
+    fn kill(&mut self) -> Result<(), BackendError>;
+}
+
+/// The error returned by a [`Backend`].
+///
+/// Unlike [`SessionError`], this doesn't hold an [`SBError`], so
+/// that a mock can create it without LLDB.
+///
+/// [`Backend`]: trait.Backend.html
+/// [`SessionError`]: enum.SessionError.html
+/// [`SBError`]: struct.SBError.html
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum BackendError {
+    /// An operation failed, with this message.
+    Failed(String),
+    /// No process has been launched or attached to.
+    NoProcess,
+    /// The process exited rather than stopping.
+    Exited {
+        /// The exit status of the process.
+        status: i32,
+        /// The exit description of the process.
+        description: String,
+    },
+    /// The process was detached from rather than stopping.
+    Detached,
+    /// The process did not stop before the timeout expired.
+    TimedOut,
+}
+
+impl fmt::Display for BackendError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            BackendError::Failed(ref message) => write!(fmt, "{}", message),
+            BackendError::NoProcess => write!(fmt, "there is no process"),
+            BackendError::Exited {
+                status,
+                ref description,
+            } => write!(
+                fmt,
+                "the process exited with status {} ({})",
+                status, description
+            ),
+            BackendError::Detached => write!(fmt, "the process was detached"),
+            BackendError::TimedOut => write!(fmt, "the process did not stop in time"),
+        }
+    }
+}
+
+impl Error for BackendError {}
+
+impl From<SBError> for BackendError {
+    fn from(error: SBError) -> BackendError {
+        BackendError::Failed(error.error_string())
+    }
+}
+
+impl From<SessionError> for BackendError {
+    fn from(error: SessionError) -> BackendError {
+        match error {
+            SessionError::Lldb(error) => error.into(),
+            SessionError::NoProcess => BackendError::NoProcess,
+            SessionError::Exited {
+                status,
+                description,
+            } => BackendError::Exited {
+                status,
+                description,
+            },
+            SessionError::ConnectionLost(lost) => BackendError::Exited {
+                status: lost.status,
+                description: lost.description,
+            },
+            SessionError::Detached => BackendError::Detached,
+            SessionError::TimedOut => BackendError::TimedOut,
+            error @ SessionError::EventThreadStopped => BackendError::Failed(error.to_string()),
+        }
+    }
+}
+
+impl Backend for DebugSession {
+    fn launch(&mut self, args: &[&str], timeout: Duration) -> Result<StopSnapshot, BackendError> {
+        let launch_info = SBLaunchInfo::new();
+        launch_info.set_arguments(args, false);
+        Ok(DebugSession::launch(self, launch_info, timeout)?)
+    }
+
+    fn attach(&mut self, pid: lldb_pid_t, timeout: Duration) -> Result<StopSnapshot, BackendError> {
+        Ok(DebugSession::attach(self, pid, timeout)?)
+    }
+
+    fn break_at_function(&mut self, name: &str) -> i32 {
+        DebugSession::break_at_function(self, name).id()
+    }
+
+    fn break_at_line(&mut self, file: &str, line: u32) -> i32 {
+        DebugSession::break_at_line(self, file, line).id()
+    }
+
+    fn delete_breakpoint(&mut self, id: i32) {
+        self.target().delete_breakpoint(id);
+    }
+
+    fn continue_until_stop(&mut self, timeout: Duration) -> Result<StopSnapshot, BackendError> {
+        Ok(DebugSession::continue_until_stop(self, timeout)?)
+    }
+
+    fn step(&mut self, kind: StepKind, timeout: Duration) -> Result<StopSnapshot, BackendError> {
+        Ok(DebugSession::step(self, kind, timeout)?)
+    }
+
+    fn threads(&self) -> Vec<lldb_tid_t> {
+        self.process()
+            .map(|process| process.threads().map(|t| t.thread_id()).collect())
+            .unwrap_or_default()
+    }
+
+    fn frames(&self, thread_id: lldb_tid_t) -> Vec<FrameInfo> {
+        self.process()
+            .and_then(|process| process.thread_by_id(thread_id))
+            .map(|thread| thread.collect_frames())
+            .unwrap_or_default()
+    }
+
+    fn evaluate(
+        &self,
+        thread_id: lldb_tid_t,
+        frame_index: u32,
+        expression: &str,
+    ) -> Result<String, BackendError> {
+        let process = self.process().ok_or(BackendError::NoProcess)?;
+        let frame = process
+            .thread_by_id(thread_id)
+            .and_then(|thread| thread.frames().nth(frame_index as usize))
+            .ok_or_else(|| BackendError::Failed(format!("no frame {}", frame_index)))?;
+        let value = frame.evaluate_expression(expression, &frame.expression_options());
+        match value.error() {
+            Some(ref error) if error.is_failure() => {
+                Err(BackendError::Failed(error.error_string()))
+            }
+            _ => Ok(value
+                .value()
+                .or_else(|| value.summary())
+                .unwrap_or_default()),
+        }
+    }
+
+    fn read_memory(&self, addr: lldb_addr_t, size: usize) -> Result<Vec<u8>, BackendError> {
+        let process = self.process().ok_or(BackendError::NoProcess)?;
+        let mut buffer = vec![0; size];
+        let count = process.read_memory(addr, &mut buffer)?;
+        buffer.truncate(count);
+        Ok(buffer)
+    }
+
+    fn kill(&mut self) -> Result<(), BackendError> {
+        Ok(DebugSession::kill(self)?)
+    }
+}
+
+/// A [`Backend`] which returns scripted results, for testing without
+/// LLDB.
+///
+/// Each call that resumes the process (`launch`, `attach`,
+/// `continue_until_stop` and `step`) returns the next result queued
+/// with [`push_stop`] or [`push_error`]. When the queue is empty, the
+/// process is treated as having exited with status 0.
+///
+/// [`Backend`]: trait.Backend.html
+/// [`push_stop`]: #method.push_stop
+/// [`push_error`]: #method.push_error
+#[derive(Clone, Debug, Default)]
+pub struct MockBackend {
+    running: bool,
+    results: VecDeque<Result<StopSnapshot, BackendError>>,
+    threads: Vec<(lldb_tid_t, Vec<FrameInfo>)>,
+    expressions: HashMap<String, String>,
+    memory: BTreeMap<lldb_addr_t, Vec<u8>>,
+    breakpoints: BTreeMap<i32, String>,
+    next_breakpoint_id: i32,
+    launches: Vec<Vec<String>>,
+    steps: Vec<StepKind>,
+}
+
+impl MockBackend {
+    /// Create a mock with nothing scripted.
+    pub fn new() -> MockBackend {
+        MockBackend::default()
+    }
+
+    /// Queue a stop to be returned when the process is next resumed.
+    pub fn push_stop(&mut self, stop: StopSnapshot) {
+        self.results.push_back(Ok(stop));
+    }
+
+    /// Queue an error to be returned when the process is next
+    /// resumed.
+    pub fn push_error(&mut self, error: BackendError) {
+        self.results.push_back(Err(error));
+    }
+
+    /// Add a thread with the stack `frames`, innermost frame first.
+    pub fn add_thread(&mut self, thread_id: lldb_tid_t, frames: Vec<FrameInfo>) {
+        self.threads.push((thread_id, frames));
+    }
+
+    /// Make `expression` evaluate to `value` in every frame. Other
+    /// expressions fail.
+    pub fn set_expression(&mut self, expression: &str, value: &str) {
+        self.expressions
+            .insert(expression.to_string(), value.to_string());
+    }
+
+    /// Put `bytes` in memory at `addr`. Reads which aren't within a
+    /// single block of written memory fail.
+    pub fn write_memory(&mut self, addr: lldb_addr_t, bytes: &[u8]) {
+        self.memory.insert(addr, bytes.to_vec());
+    }
+
+    /// The locations of the breakpoints which are set, such as `main`
+    /// or `main.c:12`, in the order that they were set.
+    pub fn breakpoint_locations(&self) -> Vec<String> {
+        self.breakpoints.values().cloned().collect()
+    }
+
+    /// The arguments of each launch, in order.
+    pub fn launches(&self) -> &[Vec<String>] {
+        &self.launches
+    }
+
+    /// The kinds of step taken, in order.
+    pub fn steps(&self) -> &[StepKind] {
+        &self.steps
+    }
+
+    fn add_breakpoint(&mut self, location: String) -> i32 {
+        self.next_breakpoint_id += 1;
+        self.breakpoints.insert(self.next_breakpoint_id, location);
+        self.next_breakpoint_id
+    }
+
+    fn resume(&mut self) -> Result<StopSnapshot, BackendError> {
+        if !self.running {
+            return Err(BackendError::NoProcess);
+        }
+        let result = self
+            .results
+            .pop_front()
+            .unwrap_or(Err(BackendError::Exited {
+                status: 0,
+                description: String::new(),
+            }));
+        if let Err(BackendError::Exited { .. }) | Err(BackendError::Detached) = result {
+            self.running = false;
+        }
+        result
+    }
+}
+
+impl Backend for MockBackend {
+    fn launch(&mut self, args: &[&str], _: Duration) -> Result<StopSnapshot, BackendError> {
+        self.launches
+            .push(args.iter().map(|arg| arg.to_string()).collect());
+        self.running = true;
+        self.resume()
+    }
+
+    fn attach(&mut self, _: lldb_pid_t, _: Duration) -> Result<StopSnapshot, BackendError> {
+        self.running = true;
+        self.resume()
+    }
+
+    fn break_at_function(&mut self, name: &str) -> i32 {
+        self.add_breakpoint(name.to_string())
+    }
+
+    fn break_at_line(&mut self, file: &str, line: u32) -> i32 {
+        self.add_breakpoint(format!("{}:{}", file, line))
+    }
+
+    fn delete_breakpoint(&mut self, id: i32) {
+        self.breakpoints.remove(&id);
+    }
+
+    fn continue_until_stop(&mut self, _: Duration) -> Result<StopSnapshot, BackendError> {
+        self.resume()
+    }
+
+    fn step(&mut self, kind: StepKind, _: Duration) -> Result<StopSnapshot, BackendError> {
+        self.steps.push(kind);
+        self.resume()
+    }
+
+    fn threads(&self) -> Vec<lldb_tid_t> {
+        self.threads.iter().map(|&(id, _)| id).collect()
+    }
+
+    fn frames(&self, thread_id: lldb_tid_t) -> Vec<FrameInfo> {
+        self.threads
+            .iter()
+            .find(|&&(id, _)| id == thread_id)
+            .map(|(_, frames)| frames.clone())
+            .unwrap_or_default()
+    }
+
+    fn evaluate(
+        &self,
+        thread_id: lldb_tid_t,
+        frame_index: u32,
+        expression: &str,
+    ) -> Result<String, BackendError> {
+        if !self.running {
+            return Err(BackendError::NoProcess);
+        }
+        if self.frames(thread_id).len() <= frame_index as usize {
+            return Err(BackendError::Failed(format!("no frame {}", frame_index)));
+        }
+        self.expressions.get(expression).cloned().ok_or_else(|| {
+            BackendError::Failed(format!("use of undeclared identifier '{}'", expression))
+        })
+    }
+
+    fn read_memory(&self, addr: lldb_addr_t, size: usize) -> Result<Vec<u8>, BackendError> {
+        if !self.running {
+            return Err(BackendError::NoProcess);
+        }
+        self.memory
+            .range(..=addr)
+            .next_back()
+            .and_then(|(&start, bytes)| {
+                let offset = (addr - start) as usize;
+                bytes.get(offset..offset.checked_add(size)?)
+            })
+            .map(|bytes| bytes.to_vec())
+            .ok_or_else(|| BackendError::Failed(format!("memory read failed for {:#x}", addr)))
+    }
+
+    fn kill(&mut self) -> Result<(), BackendError> {
+        if !self.running {
+            return Err(BackendError::NoProcess);
+        }
+        self.running = false;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Backend, BackendError, MockBackend};
+    use std::time::Duration;
+
+    #[test]
+    fn mock_memory() {
+        let mut mock = MockBackend::new();
+        mock.write_memory(0x1000, &[1, 2, 3, 4]);
+        assert_eq!(mock.read_memory(0x1000, 1), Err(BackendError::NoProcess));
+        assert!(mock.launch(&[], Duration::from_secs(1)).is_err());
+        // The process exited because no stops were queued.
+        assert_eq!(mock.read_memory(0x1000, 1), Err(BackendError::NoProcess));
+        mock.push_error(BackendError::TimedOut);
+        assert_eq!(
+            mock.launch(&["-v"], Duration::from_secs(1)),
+            Err(BackendError::TimedOut)
+        );
+        assert_eq!(mock.read_memory(0x1001, 2), Ok(vec![2, 3]));
+        assert!(mock.read_memory(0x1003, 2).is_err());
+        assert!(mock.read_memory(0xfff, 1).is_err());
+        assert_eq!(mock.launches(), &[vec![], vec!["-v".to_string()]]);
+    }
+}
@@ -0,0 +1,165 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Debugging firmware on bare-metal targets.
+//!
+//! Microcontrollers and other targets without an operating system are
+//! debugged through a GDB stub, such as the one in OpenOCD, a J-Link
+//! GDB server or qemu, connected to the chip over JTAG or SWD. Nothing
+//! on the target can tell LLDB what it is running or where, so the
+//! bring-up takes more steps than debugging a process on a computer.
+//! [`BareMetalTarget`] takes care of them:
+//!
+//! ```no_run
+//! # use lldb::{RemoteUrl, SBDebugger};
+//! # use lldb::baremetal::BareMetalTarget;
+//! SBDebugger::initialize();
+//! let debugger = SBDebugger::create(false);
+//! let (target, process) = BareMetalTarget::new("firmware.elf", "thumbv7em-none-eabihf")
+//!     .section_load_address(".data", 0x2000_0000)
+//!     .connect(&debugger, &RemoteUrl::tcp("localhost", 3333))
+//!     .unwrap();
+//! let thread = process.selected_thread().unwrap();
+//! println!("Halted at {:#x}", thread.selected_frame().unwrap().pc());
+//! ```
+//!
+//! The steps are:
+//!
+//! 1. Create the target with an explicit triple. The triple can't be
+//!    worked out from the stub, and its OS, `none` or `unknown`, makes
+//!    LLDB use its static dynamic loader, which loads the image at the
+//!    addresses in the ELF file rather than looking for shared
+//!    libraries.
+//! 2. Apply [`DEFAULT_SETTINGS`], which stop LLDB asking the stub for
+//!    the list of shared libraries, along with any given to
+//!    [`BareMetalTarget::setting`]. Stubs for bare-metal targets
+//!    answer such requests slowly or wrongly, if at all.
+//! 3. Connect to the stub with the `gdb-remote` plugin.
+//! 4. Move sections whose runtime address differs from the one in the
+//!    ELF file, such as when the image is copied from flash to RAM at
+//!    startup, so that their symbols resolve.
+//! 5. Halt the core, if the stub left it running.
+//!
+//! [`BareMetalTarget`]: struct.BareMetalTarget.html
+//! [`BareMetalTarget::setting`]: struct.BareMetalTarget.html#method.setting
+//! [`DEFAULT_SETTINGS`]: constant.DEFAULT_SETTINGS.html
+
+use super::debugger::SBDebugger;
+use super::error::SBError;
+use super::lldb_addr_t;
+use super::process::SBProcess;
+use super::remoteurl::RemoteUrl;
+use super::target::SBTarget;
+
+/// The settings which are changed by default, with the values they
+/// are given.
+pub const DEFAULT_SETTINGS: &[(&str, &str)] = &[
+    // Don't ask for the shared libraries over `qXfer:libraries-svr4`.
+    ("plugin.process.gdb-remote.use-libraries-svr4", "false"),
+];
+
+/// How to create a target for a firmware image and connect it to the
+/// GDB stub of a bare-metal target.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BareMetalTarget {
+    executable: String,
+    triple: String,
+    settings: Vec<(String, String)>,
+    slide: Option<i64>,
+    sections: Vec<(String, lldb_addr_t)>,
+    halt: bool,
+}
+
+impl BareMetalTarget {
+    /// Debug the firmware image `executable`, such as an ELF file,
+    /// built for `triple`, such as `thumbv7em-none-eabihf` or
+    /// `riscv32imac-unknown-none-elf`.
+    pub fn new(executable: &str, triple: &str) -> BareMetalTarget {
+        BareMetalTarget {
+            executable: executable.to_string(),
+            triple: triple.to_string(),
+            settings: DEFAULT_SETTINGS
+                .iter()
+                .map(|&(name, value)| (name.to_string(), value.to_string()))
+                .collect(),
+            slide: None,
+            sections: vec![],
+            halt: true,
+        }
+    }
+
+    /// Set the debugger setting `name` to `value` before connecting,
+    /// such as `plugin.process.gdb-remote.target-definition-file` for
+    /// a stub which doesn't describe the registers of the target.
+    pub fn setting(mut self, name: &str, value: &str) -> Self {
+        self.settings.retain(|(setting, _)| setting != name);
+        self.settings.push((name.to_string(), value.to_string()));
+        self
+    }
+
+    /// Load the whole image at its addresses in the file plus
+    /// `slide`, such as for a bootloader which places the image.
+    pub fn slide(mut self, slide: i64) -> Self {
+        self.slide = Some(slide);
+        self
+    }
+
+    /// Load the section named `name`, such as `.data`, at `address`
+    /// rather than at its address in the file.
+    pub fn section_load_address(mut self, name: &str, address: lldb_addr_t) -> Self {
+        self.sections.push((name.to_string(), address));
+        self
+    }
+
+    /// Halt the core after connecting if it is running, which is the
+    /// default.
+    pub fn halt(mut self, halt: bool) -> Self {
+        self.halt = halt;
+        self
+    }
+
+    /// Create the target in `debugger` and connect it to the stub at
+    /// `url`, returning the target and the process for the core.
+    pub fn connect(
+        &self,
+        debugger: &SBDebugger,
+        url: &RemoteUrl,
+    ) -> Result<(SBTarget, SBProcess), SBError> {
+        for (name, value) in &self.settings {
+            debugger.set_setting(name, value)?;
+        }
+        let target = debugger.create_target(&self.executable, Some(&self.triple), None, false)?;
+        let process = target.connect_gdb_remote(url, None)?;
+        self.load_sections(&target)?;
+        if self.halt && process.is_running() {
+            process.stop()?;
+        }
+        Ok((target, process))
+    }
+
+    /// Apply the slide and section load addresses to the image in
+    /// `target`, such as after the stub resets the core and the
+    /// startup code has relocated the image again.
+    pub fn load_sections(&self, target: &SBTarget) -> Result<(), SBError> {
+        if self.slide.is_none() && self.sections.is_empty() {
+            return Ok(());
+        }
+        let module = target
+            .executable()
+            .and_then(|executable| target.find_module(&executable))
+            .ok_or_else(|| SBError::from_message(&format!("{} was not loaded", self.executable)))?;
+        if let Some(slide) = self.slide {
+            target.set_module_load_address(&module, slide)?;
+        }
+        for (name, address) in &self.sections {
+            let section = module.find_section(name).ok_or_else(|| {
+                SBError::from_message(&format!("{} has no section {}", self.executable, name))
+            })?;
+            target.set_section_load_address(&section, *address)?;
+        }
+        Ok(())
+    }
+}
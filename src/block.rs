@@ -5,9 +5,15 @@
 // except according to those terms.
 
 use super::address::SBAddress;
+use super::addressrange::{AddressRange, AddressRangeList};
+use super::error::InvalidRefError;
 use super::filespec::SBFileSpec;
+#[cfg(feature = "graphql")]
+use super::graphql::U64;
 use super::stream::SBStream;
-use std::ffi::CStr;
+use super::strings;
+use super::target::SBTarget;
+use std::convert::TryFrom;
 use std::fmt;
 use sys;
 
@@ -19,14 +25,24 @@ pub struct SBBlock {
 
 impl SBBlock {
     /// Construct a new `SBBlock`.
-    pub fn wrap(raw: sys::SBBlockRef) -> SBBlock {
+    ///
+    /// # Safety
+    ///
+    /// `raw` must be a valid reference, which is disposed of when the
+    /// `SBBlock` is dropped. Use [`maybe_wrap`] when it may be invalid.
+    ///
+    /// [`maybe_wrap`]: #method.maybe_wrap
+    pub unsafe fn wrap(raw: sys::SBBlockRef) -> SBBlock {
         SBBlock { raw }
     }
 
     /// Construct a new `Some(SBBlock)` or `None`.
+    ///
+    /// `raw` is disposed of if it is invalid.
     pub fn maybe_wrap(raw: sys::SBBlockRef) -> Option<SBBlock> {
-        if unsafe { sys::SBBlockIsValid(raw) != 0 } {
-            Some(SBBlock { raw })
+        let wrapped = SBBlock { raw };
+        if wrapped.is_valid() {
+            Some(wrapped)
         } else {
             None
         }
@@ -43,13 +59,8 @@ impl SBBlock {
     }
 
     /// Get the function name if this block represents an inlined function.
-    pub fn inlined_name(&self) -> &str {
-        unsafe {
-            match CStr::from_ptr(sys::SBBlockGetInlinedName(self.raw)).to_str() {
-                Ok(s) => s,
-                _ => panic!("Invalid string?"),
-            }
-        }
+    pub fn inlined_name(&self) -> Option<String> {
+        unsafe { strings::to_string(sys::SBBlockGetInlinedName(self.raw)) }
     }
 
     /// Get the call site file if this block represents an inlined function.
@@ -116,10 +127,29 @@ impl SBBlock {
         }
     }
 
+    /// The address ranges of this block, as load addresses in
+    /// `target`.
+    pub fn ranges(&self, target: &SBTarget) -> AddressRangeList {
+        (0..self.num_ranges())
+            .map(|idx| {
+                AddressRange::from_bounds(
+                    self.range_start_address(idx).load_address(target),
+                    self.range_end_address(idx).load_address(target),
+                )
+            })
+            .collect()
+    }
+
     /// Given an address, find out which address range it is part of.
     pub fn range_index_for_block_address(&self, block_address: &SBAddress) -> u32 {
         unsafe { sys::SBBlockGetRangeIndexForBlockAddress(self.raw, block_address.raw) }
     }
+
+    fn description(&self) -> String {
+        let stream = SBStream::new();
+        unsafe { sys::SBBlockGetDescription(self.raw, stream.raw) };
+        stream.data()
+    }
 }
 
 impl Clone for SBBlock {
@@ -132,9 +162,21 @@ impl Clone for SBBlock {
 
 impl fmt::Debug for SBBlock {
     fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
-        let stream = SBStream::new();
-        unsafe { sys::SBBlockGetDescription(self.raw, stream.raw) };
-        write!(fmt, "SBBlock {{ {} }}", stream.data())
+        write!(fmt, "SBBlock {{ {} }}", self.description())
+    }
+}
+
+impl fmt::Display for SBBlock {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "{}", self.description())
+    }
+}
+
+impl TryFrom<sys::SBBlockRef> for SBBlock {
+    type Error = InvalidRefError;
+
+    fn try_from(raw: sys::SBBlockRef) -> Result<SBBlock, InvalidRefError> {
+        SBBlock::maybe_wrap(raw).ok_or(InvalidRefError)
     }
 }
 
@@ -148,30 +190,35 @@ unsafe impl Send for SBBlock {}
 unsafe impl Sync for SBBlock {}
 
 #[cfg(feature = "graphql")]
-graphql_object!(SBBlock: super::debugger::SBDebugger | &self | {
-    field is_valid() -> bool {
+#[graphql_object(context = super::debugger::SBDebugger)]
+impl SBBlock {
+    #[graphql(name = "isValid")]
+    fn graphql_is_valid(&self) -> bool {
         self.is_valid()
     }
 
-    field is_inlined() -> bool {
+    #[graphql(name = "isInlined")]
+    fn graphql_is_inlined(&self) -> bool {
         self.is_inlined()
     }
 
-    field inlined_name() -> &str {
+    #[graphql(name = "inlinedName")]
+    fn graphql_inlined_name(&self) -> Option<String> {
         self.inlined_name()
     }
 
-    field inlined_call_site_file() -> Option<SBFileSpec> {
+    #[graphql(name = "inlinedCallSiteFile")]
+    fn graphql_inlined_call_site_file(&self) -> Option<SBFileSpec> {
         self.inlined_call_site_file()
     }
 
-    // TODO(bm) This should be u32
-    field inlined_call_site_line() -> Option<i32> {
-        self.inlined_call_site_line().map(|i| i as i32)
+    #[graphql(name = "inlinedCallSiteLine")]
+    fn graphql_inlined_call_site_line(&self) -> Option<U64> {
+        self.inlined_call_site_line().map(U64::from)
     }
 
-    // TODO(bm) This should be u32
-    field inlined_call_site_column() -> Option<i32> {
-        self.inlined_call_site_column().map(|i| i as i32)
+    #[graphql(name = "inlinedCallSiteColumn")]
+    fn graphql_inlined_call_site_column(&self) -> Option<U64> {
+        self.inlined_call_site_column().map(U64::from)
     }
-});
+}
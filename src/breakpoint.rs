@@ -5,13 +5,22 @@
 // except according to those terms.
 
 use super::breakpointlocation::SBBreakpointLocation;
-use super::lldb_addr_t;
+use super::error::InvalidRefError;
+#[cfg(feature = "graphql")]
+use super::graphql::U64;
 use super::stream::SBStream;
 use super::stringlist::SBStringList;
+use super::strings;
+use super::{lldb_addr_t, lldb_tid_t};
+use std::convert::TryFrom;
 use std::ffi::CString;
 use std::fmt;
+use std::hash::{Hash, Hasher};
 use sys;
 
+const LLDB_INVALID_THREAD_ID: lldb_tid_t = 0;
+const LLDB_INVALID_INDEX32: u32 = !0;
+
 /// A logical breakpoint and its associated settings.
 ///
 /// # To Hit or Not
@@ -68,14 +77,24 @@ pub struct SBBreakpoint {
 
 impl SBBreakpoint {
     /// Construct a new `SBBreakpoint`.
-    pub fn wrap(raw: sys::SBBreakpointRef) -> SBBreakpoint {
+    ///
+    /// # Safety
+    ///
+    /// `raw` must be a valid reference, which is disposed of when the
+    /// `SBBreakpoint` is dropped. Use [`maybe_wrap`] when it may be invalid.
+    ///
+    /// [`maybe_wrap`]: #method.maybe_wrap
+    pub unsafe fn wrap(raw: sys::SBBreakpointRef) -> SBBreakpoint {
         SBBreakpoint { raw }
     }
 
     /// Construct a new `Some(SBBreakpoint)` or `None`.
+    ///
+    /// `raw` is disposed of if it is invalid.
     pub fn maybe_wrap(raw: sys::SBBreakpointRef) -> Option<SBBreakpoint> {
-        if unsafe { sys::SBBreakpointIsValid(raw) != 0 } {
-            Some(SBBreakpoint { raw })
+        let wrapped = SBBreakpoint { raw };
+        if wrapped.is_valid() {
+            Some(wrapped)
         } else {
             None
         }
@@ -116,6 +135,18 @@ impl SBBreakpoint {
         unsafe { sys::SBBreakpointIsInternal(self.raw) != 0 }
     }
 
+    /// The number of locations that this breakpoint has been
+    /// resolved to.
+    pub fn num_locations(&self) -> u32 {
+        unsafe { sys::SBBreakpointGetNumLocations(self.raw) }
+    }
+
+    /// The number of locations that have been resolved to an
+    /// address.
+    pub fn num_resolved_locations(&self) -> u32 {
+        unsafe { sys::SBBreakpointGetNumResolvedLocations(self.raw) }
+    }
+
     #[allow(missing_docs)]
     pub fn hit_count(&self) -> u32 {
         unsafe { sys::SBBreakpointGetHitCount(self.raw) }
@@ -131,6 +162,89 @@ impl SBBreakpoint {
         unsafe { sys::SBBreakpointSetIgnoreCount(self.raw, count) }
     }
 
+    /// The expression which must be true for the breakpoint to stop,
+    /// if it has one.
+    pub fn condition(&self) -> Option<String> {
+        unsafe { strings::to_string(sys::SBBreakpointGetCondition(self.raw)) }
+    }
+
+    /// Only stop when `condition` evaluates to true, or always stop
+    /// when it is empty.
+    pub fn set_condition(&self, condition: &str) {
+        let condition = CString::new(condition).unwrap();
+        unsafe { sys::SBBreakpointSetCondition(self.raw, condition.as_ptr()) }
+    }
+
+    /// The ID of the thread that the breakpoint stops in, if it is
+    /// limited to one.
+    pub fn thread_id(&self) -> Option<lldb_tid_t> {
+        match unsafe { sys::SBBreakpointGetThreadID(self.raw) } {
+            LLDB_INVALID_THREAD_ID => None,
+            tid => Some(tid),
+        }
+    }
+
+    /// Only stop in the thread with the ID `thread_id`, or in any
+    /// thread when it is `None`.
+    pub fn set_thread_id(&self, thread_id: Option<lldb_tid_t>) {
+        let thread_id = thread_id.unwrap_or(LLDB_INVALID_THREAD_ID);
+        unsafe { sys::SBBreakpointSetThreadID(self.raw, thread_id) }
+    }
+
+    /// The index ID of the thread that the breakpoint stops in, if it
+    /// is limited to one.
+    pub fn thread_index(&self) -> Option<u32> {
+        match unsafe { sys::SBBreakpointGetThreadIndex(self.raw) } {
+            LLDB_INVALID_INDEX32 => None,
+            index => Some(index),
+        }
+    }
+
+    /// Only stop in the thread with the index ID `index`, as shown by
+    /// `thread list`, or in any thread when it is `None`.
+    pub fn set_thread_index(&self, index: Option<u32>) {
+        let index = index.unwrap_or(LLDB_INVALID_INDEX32);
+        unsafe { sys::SBBreakpointSetThreadIndex(self.raw, index) }
+    }
+
+    /// The name of the thread that the breakpoint stops in, if it is
+    /// limited to one.
+    pub fn thread_name(&self) -> Option<String> {
+        unsafe { strings::to_string(sys::SBBreakpointGetThreadName(self.raw)) }
+    }
+
+    /// Only stop in threads named `name`.
+    pub fn set_thread_name(&self, name: &str) {
+        let name = CString::new(name).unwrap();
+        unsafe { sys::SBBreakpointSetThreadName(self.raw, name.as_ptr()) }
+    }
+
+    /// The name of the queue that the breakpoint stops in, if it is
+    /// limited to one.
+    pub fn queue_name(&self) -> Option<String> {
+        unsafe { strings::to_string(sys::SBBreakpointGetQueueName(self.raw)) }
+    }
+
+    /// Only stop in threads running work from the dispatch queue named
+    /// `name`.
+    pub fn set_queue_name(&self, name: &str) {
+        let name = CString::new(name).unwrap();
+        unsafe { sys::SBBreakpointSetQueueName(self.raw, name.as_ptr()) }
+    }
+
+    /// The LLDB commands run when the breakpoint is hit.
+    pub fn command_line_commands(&self) -> SBStringList {
+        let commands = SBStringList::new();
+        unsafe { sys::SBBreakpointGetCommandLineCommands(self.raw, commands.raw) };
+        commands
+    }
+
+    /// Run the LLDB commands in `commands` each time the breakpoint is
+    /// hit, replacing any set before.
+    pub fn set_command_line_commands(&self, commands: &SBStringList) {
+        unsafe { sys::SBBreakpointSetCommandLineCommands(self.raw, commands.raw) }
+    }
+
     #[allow(missing_docs)]
     pub fn add_name(&self, name: &str) -> bool {
         let name = CString::new(name).unwrap();
@@ -185,6 +299,12 @@ impl SBBreakpoint {
             idx: 0,
         }
     }
+
+    fn description(&self) -> String {
+        let stream = SBStream::new();
+        unsafe { sys::SBBreakpointGetDescription(self.raw, stream.raw) };
+        stream.data()
+    }
 }
 
 impl Clone for SBBreakpoint {
@@ -197,9 +317,36 @@ impl Clone for SBBreakpoint {
 
 impl fmt::Debug for SBBreakpoint {
     fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
-        let stream = SBStream::new();
-        unsafe { sys::SBBreakpointGetDescription(self.raw, stream.raw) };
-        write!(fmt, "SBBreakpoint {{ {} }}", stream.data())
+        write!(fmt, "SBBreakpoint {{ {} }}", self.description())
+    }
+}
+
+impl fmt::Display for SBBreakpoint {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "{}", self.description())
+    }
+}
+
+/// Breakpoints are compared by their ID, which is unique within a target.
+impl PartialEq for SBBreakpoint {
+    fn eq(&self, other: &SBBreakpoint) -> bool {
+        self.id() == other.id()
+    }
+}
+
+impl Eq for SBBreakpoint {}
+
+impl Hash for SBBreakpoint {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.id().hash(state);
+    }
+}
+
+impl TryFrom<sys::SBBreakpointRef> for SBBreakpoint {
+    type Error = InvalidRefError;
+
+    fn try_from(raw: sys::SBBreakpointRef) -> Result<SBBreakpoint, InvalidRefError> {
+        SBBreakpoint::maybe_wrap(raw).ok_or(InvalidRefError)
     }
 }
 
@@ -245,47 +392,50 @@ impl<'d> Iterator for SBBreakpointLocationIter<'d> {
 impl<'d> ExactSizeIterator for SBBreakpointLocationIter<'d> {}
 
 #[cfg(feature = "graphql")]
-graphql_object!(SBBreakpoint: super::debugger::SBDebugger | &self | {
-    field is_valid() -> bool {
+#[graphql_object(context = super::debugger::SBDebugger)]
+impl SBBreakpoint {
+    #[graphql(name = "isValid")]
+    fn graphql_is_valid(&self) -> bool {
         self.is_valid()
     }
 
-    field id() -> i32 {
+    #[graphql(name = "id")]
+    fn graphql_id(&self) -> i32 {
         self.id()
     }
 
-    field is_enabled() -> bool {
+    #[graphql(name = "isEnabled")]
+    fn graphql_is_enabled(&self) -> bool {
         self.is_enabled()
     }
 
-    field is_oneshot() -> bool {
+    #[graphql(name = "isOneshot")]
+    fn graphql_is_oneshot(&self) -> bool {
         self.is_oneshot()
     }
 
-    field is_internal() -> bool {
+    #[graphql(name = "isInternal")]
+    fn graphql_is_internal(&self) -> bool {
         self.is_internal()
     }
 
-    field is_valid() -> bool {
-        self.is_valid()
+    #[graphql(name = "ignoreCount")]
+    fn graphql_ignore_count(&self) -> U64 {
+        self.ignore_count().into()
     }
 
-    // TODO(bm) This should be u32
-    field ignore_count() -> i32 {
-        self.ignore_count() as i32
+    #[graphql(name = "hitCount")]
+    fn graphql_hit_count(&self) -> U64 {
+        self.hit_count().into()
     }
 
-    // TODO(bm) This should be u32
-    field hit_count() -> i32 {
-        self.hit_count() as i32
+    #[graphql(name = "names")]
+    fn graphql_names(&self) -> Vec<String> {
+        self.names().iter().collect()
     }
 
-    // TODO(bm) Make this work. (Lifetimes.)
-    // field names() -> Vec<&str> {
-    //     self.names().iter().collect()
-    // }
-
-    field locations() -> Vec<SBBreakpointLocation> {
+    #[graphql(name = "locations")]
+    fn graphql_locations(&self) -> Vec<SBBreakpointLocation> {
         self.locations().collect()
     }
-});
+}
@@ -0,0 +1,408 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use super::breakpoint::SBBreakpoint;
+use super::error::SBError;
+use super::session::DebugSession;
+use super::stringlist::SBStringList;
+use super::strings;
+use super::target::SBTarget;
+use super::thread::SBThread;
+use super::{lldb_addr_t, lldb_tid_t, StopReason};
+
+type HitCallback = Box<dyn FnMut(&SBThread) + Send>;
+
+/// Create a breakpoint and configure it in one chain.
+///
+/// ```no_run
+/// # use lldb::{SBBreakpoint, SBError, SBTarget};
+/// # fn set(target: &SBTarget, tid: u64) -> Result<SBBreakpoint, SBError> {
+/// target
+///     .breakpoint_builder()
+///     .function("handle_request", None)
+///     .condition("request->id == 42")
+///     .thread_id(tid)
+///     .ignore_count(2)
+///     .oneshot(true)
+///     .build()
+/// # }
+/// ```
+///
+/// All of the settings are checked before anything is created, so
+/// when [`build`] returns an error the target is left unchanged,
+/// rather than with a half-configured breakpoint.
+///
+/// A Rust callback given to [`on_hit`] is run by a [`DebugSession`],
+/// so breakpoints with one are created with [`build_in`]. LLDB
+/// commands given to [`commands`] are run by LLDB itself.
+///
+/// [`build`]: #method.build
+/// [`build_in`]: #method.build_in
+/// [`on_hit`]: #method.on_hit
+/// [`commands`]: #method.commands
+/// [`DebugSession`]: struct.DebugSession.html
+pub struct BreakpointBuilder {
+    target: SBTarget,
+    settings: Settings,
+    on_hit: Option<HitCallback>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+struct Settings {
+    locations: Vec<Location>,
+    condition: Option<String>,
+    thread_id: Option<lldb_tid_t>,
+    thread_index: Option<u32>,
+    thread_name: Option<String>,
+    queue_name: Option<String>,
+    ignore_count: u32,
+    hardware: bool,
+    oneshot: bool,
+    disabled: bool,
+    commands: Vec<String>,
+    names: Vec<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Location {
+    Function(String, Option<String>),
+    Line(String, u32),
+    Address(lldb_addr_t),
+}
+
+impl BreakpointBuilder {
+    /// Start building a breakpoint for `target`.
+    pub fn new(target: &SBTarget) -> BreakpointBuilder {
+        BreakpointBuilder {
+            target: target.clone(),
+            settings: Settings::default(),
+            on_hit: None,
+        }
+    }
+
+    /// Break on the function or symbol `name`, optionally only in the
+    /// module named `module`.
+    pub fn function(mut self, name: &str, module: Option<&str>) -> Self {
+        self.settings.locations.push(Location::Function(
+            name.to_string(),
+            module.map(|m| m.to_string()),
+        ));
+        self
+    }
+
+    /// Break on `line` of the source file `file`.
+    pub fn line(mut self, file: &str, line: u32) -> Self {
+        self.settings
+            .locations
+            .push(Location::Line(file.to_string(), line));
+        self
+    }
+
+    /// Break at the load address `address`.
+    pub fn address(mut self, address: lldb_addr_t) -> Self {
+        self.settings.locations.push(Location::Address(address));
+        self
+    }
+
+    /// Only stop when the expression `condition` is true.
+    pub fn condition(mut self, condition: &str) -> Self {
+        self.settings.condition = Some(condition.to_string());
+        self
+    }
+
+    /// Only stop in the thread with the ID `thread_id`.
+    pub fn thread_id(mut self, thread_id: lldb_tid_t) -> Self {
+        self.settings.thread_id = Some(thread_id);
+        self
+    }
+
+    /// Only stop in the thread with the index ID `index`, as shown by
+    /// `thread list`.
+    pub fn thread_index(mut self, index: u32) -> Self {
+        self.settings.thread_index = Some(index);
+        self
+    }
+
+    /// Only stop in threads named `name`.
+    pub fn thread_name(mut self, name: &str) -> Self {
+        self.settings.thread_name = Some(name.to_string());
+        self
+    }
+
+    /// Only stop in threads running work from the dispatch queue
+    /// named `name`.
+    pub fn queue_name(mut self, name: &str) -> Self {
+        self.settings.queue_name = Some(name.to_string());
+        self
+    }
+
+    /// Don't stop for the first `count` hits.
+    pub fn ignore_count(mut self, count: u32) -> Self {
+        self.settings.ignore_count = count;
+        self
+    }
+
+    /// Use a hardware breakpoint, which doesn't modify the code of
+    /// the process, such as for code in read-only memory.
+    pub fn hardware(mut self, hardware: bool) -> Self {
+        self.settings.hardware = hardware;
+        self
+    }
+
+    /// Delete the breakpoint after it is hit for the first time.
+    pub fn oneshot(mut self, oneshot: bool) -> Self {
+        self.settings.oneshot = oneshot;
+        self
+    }
+
+    /// Create the breakpoint enabled, which is the default, or
+    /// disabled.
+    pub fn enabled(mut self, enabled: bool) -> Self {
+        self.settings.disabled = !enabled;
+        self
+    }
+
+    /// Run the LLDB commands in `commands` each time the breakpoint
+    /// is hit.
+    pub fn commands(mut self, commands: &[&str]) -> Self {
+        self.settings
+            .commands
+            .extend(commands.iter().map(|c| c.to_string()));
+        self
+    }
+
+    /// Add the name `name` to the breakpoint.
+    pub fn name(mut self, name: &str) -> Self {
+        self.settings.names.push(name.to_string());
+        self
+    }
+
+    /// Call `callback` with the thread which hit the breakpoint each
+    /// time the process stops there.
+    ///
+    /// Breakpoints with a callback must be created with [`build_in`].
+    ///
+    /// [`build_in`]: #method.build_in
+    pub fn on_hit<F>(mut self, callback: F) -> Self
+    where
+        F: FnMut(&SBThread) + Send + 'static,
+    {
+        self.on_hit = Some(Box::new(callback));
+        self
+    }
+
+    /// Check the settings and create the breakpoint.
+    pub fn build(self) -> Result<SBBreakpoint, SBError> {
+        if self.on_hit.is_some() {
+            return Err(SBError::from_message(
+                "a breakpoint with a callback must be created with build_in",
+            ));
+        }
+        create(&self.target, &self.settings)
+    }
+
+    /// Check the settings and create the breakpoint, running its
+    /// callback, if it has one, from the event loop of `session`.
+    ///
+    /// The callback stays registered with `session` as a stop hook,
+    /// and is no longer called once the breakpoint is deleted.
+    pub fn build_in(self, session: &DebugSession) -> Result<SBBreakpoint, SBError> {
+        let breakpoint = create(&self.target, &self.settings)?;
+        if let Some(mut callback) = self.on_hit {
+            let id = breakpoint.id();
+            session.add_stop_hook(move |process| {
+                for thread in process.threads() {
+                    if thread.stop_reason() == StopReason::Breakpoint
+                        && thread.stop_reason_data_count() > 0
+                        && thread.stop_reason_data_at_index(0) == id as u64
+                    {
+                        callback(&thread);
+                    }
+                }
+            });
+        }
+        Ok(breakpoint)
+    }
+}
+
+impl Settings {
+    /// Check that the settings describe exactly one breakpoint that
+    /// can be created.
+    fn validate(&self) -> Result<&Location, String> {
+        let location = match self.locations.as_slice() {
+            [] => return Err("no location was given for the breakpoint".to_string()),
+            [location] => location,
+            _ => return Err("more than one location was given for the breakpoint".to_string()),
+        };
+        match *location {
+            Location::Function(ref name, _) if name.is_empty() => {
+                return Err("the function name is empty".to_string())
+            }
+            Location::Line(ref file, _) if file.is_empty() => {
+                return Err("the file name is empty".to_string())
+            }
+            Location::Line(_, 0) => return Err("line numbers start at 1".to_string()),
+            _ => {}
+        }
+        if self.thread_id.is_some() && self.thread_index.is_some() {
+            return Err("only one of a thread ID and a thread index can be given".to_string());
+        }
+        let mut strings: Vec<&str> = vec![];
+        if let Location::Function(ref name, ref module) = *location {
+            strings.push(name);
+            strings.extend(module.as_deref());
+        }
+        if let Location::Line(ref file, _) = *location {
+            strings.push(file);
+        }
+        strings.extend(self.condition.as_deref());
+        strings.extend(self.thread_name.as_deref());
+        strings.extend(self.queue_name.as_deref());
+        strings.extend(self.commands.iter().map(|c| c.as_str()));
+        strings.extend(self.names.iter().map(|n| n.as_str()));
+        if let Some(string) = strings.iter().find(|s| s.contains('\0')) {
+            return Err(format!("{:?} contains a NUL byte", string));
+        }
+        Ok(location)
+    }
+
+    /// The `breakpoint set` command for a hardware breakpoint at
+    /// `location`, which can't be requested through the SB API.
+    fn hardware_command(location: &Location) -> String {
+        match *location {
+            Location::Function(ref name, ref module) => {
+                let mut command = format!(
+                    "breakpoint set --hardware --name {}",
+                    strings::quote_argument(name)
+                );
+                if let Some(module) = module {
+                    command.push_str(" --shlib ");
+                    command.push_str(&strings::quote_argument(module));
+                }
+                command
+            }
+            Location::Line(ref file, line) => format!(
+                "breakpoint set --hardware --file {} --line {}",
+                strings::quote_argument(file),
+                line
+            ),
+            Location::Address(address) => {
+                format!("breakpoint set --hardware --address {:#x}", address)
+            }
+        }
+    }
+}
+
+fn create(target: &SBTarget, settings: &Settings) -> Result<SBBreakpoint, SBError> {
+    let location = settings
+        .validate()
+        .map_err(|message| SBError::from_message(&message))?;
+    let breakpoint = if settings.hardware {
+        let output = target.run_command(&Settings::hardware_command(location))?;
+        parse_breakpoint_id(&output)
+            .and_then(|id| target.find_breakpoint_by_id(id))
+            .ok_or_else(|| {
+                SBError::from_message(&format!(
+                    "unexpected output from breakpoint set: {}",
+                    output
+                ))
+            })?
+    } else {
+        match *location {
+            Location::Function(ref name, ref module) => {
+                target.breakpoint_create_by_name(name, module.as_deref())
+            }
+            Location::Line(ref file, line) => target.breakpoint_create_by_location(file, line),
+            Location::Address(address) => target.breakpoint_create_by_address(address),
+        }
+    };
+    if !breakpoint.is_valid() {
+        return Err(SBError::from_message("the breakpoint could not be created"));
+    }
+
+    if let Some(condition) = &settings.condition {
+        breakpoint.set_condition(condition);
+    }
+    breakpoint.set_thread_id(settings.thread_id);
+    breakpoint.set_thread_index(settings.thread_index);
+    if let Some(name) = &settings.thread_name {
+        breakpoint.set_thread_name(name);
+    }
+    if let Some(name) = &settings.queue_name {
+        breakpoint.set_queue_name(name);
+    }
+    breakpoint.set_ignore_count(settings.ignore_count);
+    breakpoint.set_oneshot(settings.oneshot);
+    breakpoint.set_enabled(!settings.disabled);
+    if !settings.commands.is_empty() {
+        let commands: SBStringList = settings.commands.iter().collect();
+        breakpoint.set_command_line_commands(&commands);
+    }
+    for name in &settings.names {
+        if !breakpoint.add_name(name) {
+            target.delete_breakpoint(breakpoint.id());
+            return Err(SBError::from_message(&format!(
+                "{:?} is not a valid breakpoint name",
+                name
+            )));
+        }
+    }
+    Ok(breakpoint)
+}
+
+/// Parse the ID from the output of `breakpoint set`, which starts
+/// `Breakpoint 3: ...`.
+fn parse_breakpoint_id(output: &str) -> Option<i32> {
+    output
+        .trim_start()
+        .strip_prefix("Breakpoint ")?
+        .split(':')
+        .next()?
+        .parse()
+        .ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_breakpoint_id, Location, Settings};
+
+    #[test]
+    fn validate() {
+        let mut settings = Settings::default();
+        assert!(settings.validate().is_err());
+        settings
+            .locations
+            .push(Location::Function("main".to_string(), None));
+        assert_eq!(
+            settings.validate(),
+            Ok(&Location::Function("main".to_string(), None))
+        );
+        settings.thread_id = Some(1);
+        settings.thread_index = Some(1);
+        assert!(settings.validate().is_err());
+        settings.thread_index = None;
+        settings.condition = Some("x\0".to_string());
+        assert!(settings.validate().is_err());
+        settings.condition = None;
+        settings.locations.push(Location::Address(0x1000));
+        assert!(settings.validate().is_err());
+        settings.locations = vec![Location::Line("main.c".to_string(), 0)];
+        assert!(settings.validate().is_err());
+    }
+
+    #[test]
+    fn hardware_command() {
+        assert_eq!(
+            Settings::hardware_command(&Location::Line("my file.c".to_string(), 12)),
+            "breakpoint set --hardware --file \"my file.c\" --line 12"
+        );
+        assert_eq!(
+            parse_breakpoint_id("Breakpoint 7: where = a.out`main + 4"),
+            Some(7)
+        );
+        assert_eq!(parse_breakpoint_id("error: no such file"), None);
+    }
+}
@@ -6,6 +6,7 @@
 
 use super::breakpoint::SBBreakpoint;
 use super::target::SBTarget;
+use std::fmt;
 use sys;
 
 /// A list of [breakpoints].
@@ -19,11 +20,17 @@ pub struct SBBreakpointList {
 impl SBBreakpointList {
     /// Construct a new `SBBreakpointList`.
     pub fn new(target: &SBTarget) -> SBBreakpointList {
-        SBBreakpointList::wrap(unsafe { sys::CreateSBBreakpointList(target.raw) })
+        unsafe { SBBreakpointList::wrap(sys::CreateSBBreakpointList(target.raw)) }
     }
 
     /// Construct a new `SBBreakpointList`.
-    pub fn wrap(raw: sys::SBBreakpointListRef) -> SBBreakpointList {
+    ///
+    /// # Safety
+    ///
+    /// `raw` must be an `SBBreakpointListRef` from `CreateSBBreakpointList`
+    /// which isn't used elsewhere, as it is disposed of when the
+    /// `SBBreakpointList` is dropped.
+    pub unsafe fn wrap(raw: sys::SBBreakpointListRef) -> SBBreakpointList {
         SBBreakpointList { raw }
     }
 
@@ -74,6 +81,12 @@ impl Clone for SBBreakpointList {
     }
 }
 
+impl fmt::Debug for SBBreakpointList {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.debug_list().entries(self.iter()).finish()
+    }
+}
+
 impl Drop for SBBreakpointList {
     fn drop(&mut self) {
         unsafe { sys::DisposeSBBreakpointList(self.raw) };
@@ -81,7 +94,6 @@ impl Drop for SBBreakpointList {
 }
 
 unsafe impl Send for SBBreakpointList {}
-unsafe impl Sync for SBBreakpointList {}
 
 /// An iterator over the [breakpoints] in an [`SBBreakpointList`].
 ///
@@ -97,9 +109,12 @@ impl<'d> Iterator for SBBreakpointListIter<'d> {
 
     fn next(&mut self) -> Option<SBBreakpoint> {
         if self.idx < unsafe { sys::SBBreakpointListGetSize(self.breakpoint_list.raw) } {
-            let r = SBBreakpoint::wrap(unsafe {
-                sys::SBBreakpointListGetBreakpointAtIndex(self.breakpoint_list.raw, self.idx)
-            });
+            let r = unsafe {
+                SBBreakpoint::wrap(sys::SBBreakpointListGetBreakpointAtIndex(
+                    self.breakpoint_list.raw,
+                    self.idx,
+                ))
+            };
             self.idx += 1;
             Some(r)
         } else {
@@ -6,9 +6,14 @@
 
 use super::address::SBAddress;
 use super::breakpoint::SBBreakpoint;
+use super::error::InvalidRefError;
+#[cfg(feature = "graphql")]
+use super::graphql::U64;
 use super::stream::SBStream;
 use super::{lldb_addr_t, DescriptionLevel};
+use std::convert::TryFrom;
 use std::fmt;
+use std::hash::{Hash, Hasher};
 use sys;
 
 /// One unique instance (by address) of a logical breakpoint.
@@ -30,14 +35,24 @@ pub struct SBBreakpointLocation {
 
 impl SBBreakpointLocation {
     /// Construct a new `SBBreakpointLocation`.
-    pub fn wrap(raw: sys::SBBreakpointLocationRef) -> SBBreakpointLocation {
+    ///
+    /// # Safety
+    ///
+    /// `raw` must be a valid reference, which is disposed of when the
+    /// `SBBreakpointLocation` is dropped. Use [`maybe_wrap`] when it may be invalid.
+    ///
+    /// [`maybe_wrap`]: #method.maybe_wrap
+    pub unsafe fn wrap(raw: sys::SBBreakpointLocationRef) -> SBBreakpointLocation {
         SBBreakpointLocation { raw }
     }
 
     /// Construct a new `Some(SBBreakpointLocation)` or `None`.
+    ///
+    /// `raw` is disposed of if it is invalid.
     pub fn maybe_wrap(raw: sys::SBBreakpointLocationRef) -> Option<SBBreakpointLocation> {
-        if unsafe { sys::SBBreakpointLocationIsValid(raw) != 0 } {
-            Some(SBBreakpointLocation { raw })
+        let wrapped = SBBreakpointLocation { raw };
+        if wrapped.is_valid() {
+            Some(wrapped)
         } else {
             None
         }
@@ -95,7 +110,15 @@ impl SBBreakpointLocation {
 
     #[allow(missing_docs)]
     pub fn breakpoint(&self) -> SBBreakpoint {
-        SBBreakpoint::wrap(unsafe { sys::SBBreakpointLocationGetBreakpoint(self.raw) })
+        unsafe { SBBreakpoint::wrap(sys::SBBreakpointLocationGetBreakpoint(self.raw)) }
+    }
+
+    fn description(&self) -> String {
+        let stream = SBStream::new();
+        unsafe {
+            sys::SBBreakpointLocationGetDescription(self.raw, stream.raw, DescriptionLevel::Brief)
+        };
+        stream.data()
     }
 }
 
@@ -109,11 +132,38 @@ impl Clone for SBBreakpointLocation {
 
 impl fmt::Debug for SBBreakpointLocation {
     fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
-        let stream = SBStream::new();
-        unsafe {
-            sys::SBBreakpointLocationGetDescription(self.raw, stream.raw, DescriptionLevel::Brief)
-        };
-        write!(fmt, "SBBreakpointLocation {{ {} }}", stream.data())
+        write!(fmt, "SBBreakpointLocation {{ {} }}", self.description())
+    }
+}
+
+impl fmt::Display for SBBreakpointLocation {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "{}", self.description())
+    }
+}
+
+/// Breakpoint locations are compared by their ID and the ID of their breakpoint.
+impl PartialEq for SBBreakpointLocation {
+    fn eq(&self, other: &SBBreakpointLocation) -> bool {
+        (self.breakpoint().id(), self.id()) == (other.breakpoint().id(), other.id())
+    }
+}
+
+impl Eq for SBBreakpointLocation {}
+
+impl Hash for SBBreakpointLocation {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        (self.breakpoint().id(), self.id()).hash(state);
+    }
+}
+
+impl TryFrom<sys::SBBreakpointLocationRef> for SBBreakpointLocation {
+    type Error = InvalidRefError;
+
+    fn try_from(
+        raw: sys::SBBreakpointLocationRef,
+    ) -> Result<SBBreakpointLocation, InvalidRefError> {
+        SBBreakpointLocation::maybe_wrap(raw).ok_or(InvalidRefError)
     }
 }
 
@@ -127,38 +177,50 @@ unsafe impl Send for SBBreakpointLocation {}
 unsafe impl Sync for SBBreakpointLocation {}
 
 #[cfg(feature = "graphql")]
-graphql_object!(SBBreakpointLocation: super::debugger::SBDebugger | &self | {
-    field is_valid() -> bool {
+#[graphql_object(context = super::debugger::SBDebugger)]
+impl SBBreakpointLocation {
+    #[graphql(name = "isValid")]
+    fn graphql_is_valid(&self) -> bool {
         self.is_valid()
     }
 
-    field id() -> i32 {
+    #[graphql(name = "id")]
+    fn graphql_id(&self) -> i32 {
         self.id()
     }
 
-    field address() -> Option<SBAddress> {
+    #[graphql(name = "address")]
+    fn graphql_address(&self) -> Option<SBAddress> {
         self.address()
     }
 
-    // TODO(bm) This should be u64
-    field load_address() -> i32 {
-        self.load_address() as i32
+    #[graphql(name = "loadAddress")]
+    fn graphql_load_address(&self) -> U64 {
+        self.load_address().into()
     }
 
-    field is_enabled() -> bool {
+    #[graphql(name = "isEnabled")]
+    fn graphql_is_enabled(&self) -> bool {
         self.is_enabled()
     }
 
-    // TODO(bm) This should be u32
-    field ignore_count() -> i32 {
-        self.ignore_count() as i32
+    #[graphql(name = "ignoreCount")]
+    fn graphql_ignore_count(&self) -> U64 {
+        self.ignore_count().into()
     }
 
-    field is_resolved() -> bool {
+    #[graphql(name = "isResolved")]
+    fn graphql_is_resolved(&self) -> bool {
         self.is_resolved()
     }
 
-    field breakpoint() -> SBBreakpoint {
+    #[graphql(name = "hitCount")]
+    fn graphql_hit_count(&self) -> U64 {
+        self.hit_count().into()
+    }
+
+    #[graphql(name = "breakpoint")]
+    fn graphql_breakpoint(&self) -> SBBreakpoint {
         self.breakpoint()
     }
-});
+}
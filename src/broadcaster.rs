@@ -4,8 +4,10 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
+use super::error::InvalidRefError;
 use super::event::SBEvent;
 use super::listener::SBListener;
+use std::convert::TryFrom;
 use sys;
 
 /// An entity which can broadcast events.
@@ -23,18 +25,28 @@ pub struct SBBroadcaster {
 impl SBBroadcaster {
     /// Construct a new `SBBroadcaster`.
     pub fn new() -> SBBroadcaster {
-        SBBroadcaster::wrap(unsafe { sys::CreateSBBroadcaster() })
+        unsafe { SBBroadcaster::wrap(sys::CreateSBBroadcaster()) }
     }
 
     /// Construct a new `SBBroadcaster`.
-    pub fn wrap(raw: sys::SBBroadcasterRef) -> SBBroadcaster {
+    ///
+    /// # Safety
+    ///
+    /// `raw` must be a valid reference, which is disposed of when the
+    /// `SBBroadcaster` is dropped. Use [`maybe_wrap`] when it may be invalid.
+    ///
+    /// [`maybe_wrap`]: #method.maybe_wrap
+    pub unsafe fn wrap(raw: sys::SBBroadcasterRef) -> SBBroadcaster {
         SBBroadcaster { raw }
     }
 
     /// Construct a new `Some(SBBroadcaster)` or `None`.
+    ///
+    /// `raw` is disposed of if it is invalid.
     pub fn maybe_wrap(raw: sys::SBBroadcasterRef) -> Option<SBBroadcaster> {
-        if unsafe { sys::SBBroadcasterIsValid(raw) != 0 } {
-            Some(SBBroadcaster { raw })
+        let wrapped = SBBroadcaster { raw };
+        if wrapped.is_valid() {
+            Some(wrapped)
         } else {
             None
         }
@@ -92,6 +104,14 @@ impl Default for SBBroadcaster {
     }
 }
 
+impl TryFrom<sys::SBBroadcasterRef> for SBBroadcaster {
+    type Error = InvalidRefError;
+
+    fn try_from(raw: sys::SBBroadcasterRef) -> Result<SBBroadcaster, InvalidRefError> {
+        SBBroadcaster::maybe_wrap(raw).ok_or(InvalidRefError)
+    }
+}
+
 impl Drop for SBBroadcaster {
     fn drop(&mut self) {
         unsafe { sys::DisposeSBBroadcaster(self.raw) };
@@ -0,0 +1,149 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use super::breakpoint::SBBreakpoint;
+use super::error::SBError;
+use super::target::SBTarget;
+use std::collections::BTreeMap;
+
+/// Counts the calls to a set of functions, as a rough profiler which
+/// needs nothing built into the program.
+///
+/// Each function gets a breakpoint which continues automatically, so
+/// the process never stops for it, and LLDB counts the hits as it
+/// goes. The counts can be read at any time, including while the
+/// process is running:
+///
+/// ```no_run
+/// # use lldb::{CallCounter, SBError, SBTarget};
+/// # fn profile(target: &SBTarget) -> Result<(), SBError> {
+/// let counter = CallCounter::new(target, &["malloc", "free", "parse_request"])?;
+/// // Run the process for a while.
+/// for (function, count) in counter.counts() {
+///     println!("{:>10} {}", count, function);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+///
+/// Each hit still costs a round trip from the process to the debugger,
+/// so functions called millions of times slow the process down
+/// noticeably. The breakpoints are deleted when the counter is dropped.
+#[derive(Debug)]
+pub struct CallCounter {
+    target: SBTarget,
+    functions: BTreeMap<String, Counted>,
+}
+
+#[derive(Debug)]
+struct Counted {
+    breakpoint: SBBreakpoint,
+    /// The hit count of the breakpoint when the counter was last reset.
+    baseline: u32,
+}
+
+impl CallCounter {
+    /// Start counting the calls to each of `functions` in `target`.
+    ///
+    /// Functions are found by name, as with
+    /// [`SBTarget::breakpoint_create_by_name`], so calls to all of the
+    /// functions with a name are counted together. A name which
+    /// matches nothing yet is still counted once a module containing
+    /// it is loaded.
+    ///
+    /// [`SBTarget::breakpoint_create_by_name`]: struct.SBTarget.html#method.breakpoint_create_by_name
+    pub fn new(target: &SBTarget, functions: &[&str]) -> Result<CallCounter, SBError> {
+        let mut counter = CallCounter {
+            target: target.clone(),
+            functions: BTreeMap::new(),
+        };
+        for function in functions {
+            counter.add(function)?;
+        }
+        Ok(counter)
+    }
+
+    /// Start counting the calls to `function` as well. Adding a
+    /// function which is already counted does nothing.
+    pub fn add(&mut self, function: &str) -> Result<(), SBError> {
+        if self.functions.contains_key(function) {
+            return Ok(());
+        }
+        let breakpoint = self.target.breakpoint_create_by_name(function, None);
+        if !breakpoint.is_valid() {
+            return Err(SBError::from_message(&format!(
+                "no breakpoint could be set on {}",
+                function
+            )));
+        }
+        let auto_continue = self.target.run_command(&format!(
+            "breakpoint modify --auto-continue true {}",
+            breakpoint.id()
+        ));
+        if let Err(error) = auto_continue {
+            self.target.delete_breakpoint(breakpoint.id());
+            return Err(error);
+        }
+        self.functions.insert(
+            function.to_string(),
+            Counted {
+                baseline: breakpoint.hit_count(),
+                breakpoint,
+            },
+        );
+        Ok(())
+    }
+
+    /// Stop counting the calls to `function`, returning its count.
+    pub fn remove(&mut self, function: &str) -> Option<u32> {
+        let counted = self.functions.remove(function)?;
+        let count = counted.count();
+        self.target.delete_breakpoint(counted.breakpoint.id());
+        Some(count)
+    }
+
+    /// The number of calls to `function` since it was added or the
+    /// counter was last reset, or `None` if it isn't counted.
+    pub fn count(&self, function: &str) -> Option<u32> {
+        self.functions.get(function).map(Counted::count)
+    }
+
+    /// The number of calls to each function, by name.
+    pub fn counts(&self) -> BTreeMap<String, u32> {
+        self.functions
+            .iter()
+            .map(|(function, counted)| (function.clone(), counted.count()))
+            .collect()
+    }
+
+    /// The functions, most called first.
+    pub fn ranked(&self) -> Vec<(String, u32)> {
+        let mut ranked: Vec<(String, u32)> = self.counts().into_iter().collect();
+        ranked.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        ranked
+    }
+
+    /// Start counting from zero again.
+    pub fn reset(&mut self) {
+        for counted in self.functions.values_mut() {
+            counted.baseline = counted.breakpoint.hit_count();
+        }
+    }
+}
+
+impl Counted {
+    fn count(&self) -> u32 {
+        self.breakpoint.hit_count().saturating_sub(self.baseline)
+    }
+}
+
+impl Drop for CallCounter {
+    fn drop(&mut self) {
+        for counted in self.functions.values() {
+            self.target.delete_breakpoint(counted.breakpoint.id());
+        }
+    }
+}
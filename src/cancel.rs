@@ -0,0 +1,219 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use super::error::SBError;
+use std::error::Error;
+use std::fmt;
+use std::mem;
+use std::sync::{Arc, Mutex};
+
+/// A way to cancel long-running operations from another thread, such
+/// as in response to a Cancel button in a user interface.
+///
+/// The operations which accept a `CancelToken`, such as
+/// [`SBTarget::attach_cancellable`], register a way of interrupting
+/// LLDB with the token while they run. Calling [`cancel`] from any
+/// thread interrupts whatever is running, and makes it return
+/// [`CancelError::Cancelled`] unless it finished anyway.
+///
+/// ```no_run
+/// # use lldb::{CancelToken, SBAttachInfo, SBTarget};
+/// # use std::thread;
+/// # fn attach(target: &SBTarget) {
+/// let token = CancelToken::new();
+/// let cancel = token.clone();
+/// thread::spawn(move || {
+///     // Later, when the user presses Cancel:
+///     cancel.cancel();
+/// });
+/// let result = target.attach_cancellable(SBAttachInfo::new_with_pid(1234), &token);
+/// # }
+/// ```
+///
+/// A token stays cancelled once [`cancel`] has been called.
+///
+/// [`SBTarget::attach_cancellable`]: struct.SBTarget.html#method.attach_cancellable
+/// [`cancel`]: #method.cancel
+/// [`CancelError::Cancelled`]: enum.CancelError.html#variant.Cancelled
+#[derive(Clone, Default)]
+pub struct CancelToken {
+    state: Arc<Mutex<CancelState>>,
+}
+
+#[derive(Default)]
+struct CancelState {
+    cancelled: bool,
+    interrupts: Vec<(u64, Box<dyn Fn() + Send>)>,
+    next_id: u64,
+}
+
+impl CancelToken {
+    /// Create a token which hasn't been cancelled.
+    pub fn new() -> CancelToken {
+        CancelToken::default()
+    }
+
+    /// Cancel the operations using this token.
+    pub fn cancel(&self) {
+        let interrupts = {
+            let mut state = self.state.lock().unwrap();
+            state.cancelled = true;
+            mem::take(&mut state.interrupts)
+        };
+        // The token isn't locked while interrupting, so that the
+        // interrupts can use it.
+        for (_, interrupt) in interrupts {
+            interrupt();
+        }
+    }
+
+    /// Has [`cancel`] been called?
+    ///
+    /// [`cancel`]: #method.cancel
+    pub fn is_cancelled(&self) -> bool {
+        self.state.lock().unwrap().cancelled
+    }
+
+    /// Return [`CancelError::Cancelled`] if the token has been
+    /// cancelled. This is for checking the token between the steps of
+    /// an operation.
+    ///
+    /// [`CancelError::Cancelled`]: enum.CancelError.html#variant.Cancelled
+    pub fn check(&self) -> Result<(), CancelError> {
+        if self.is_cancelled() {
+            Err(CancelError::Cancelled)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Run `op`, calling `interrupt` if the token is cancelled while it
+    /// runs.
+    ///
+    /// `op` isn't run at all if the token has already been cancelled,
+    /// and its error is replaced with [`CancelError::Cancelled`] if the
+    /// token was cancelled while it ran. If `op` succeeds, its result
+    /// is returned even if the token was cancelled, as the cancel came
+    /// too late: throwing away a launched or attached process would
+    /// leave it running with nothing to look after it.
+    ///
+    /// [`CancelError::Cancelled`]: enum.CancelError.html#variant.Cancelled
+    pub fn run<T, F, I>(&self, op: F, interrupt: I) -> Result<T, CancelError>
+    where
+        F: FnOnce() -> Result<T, SBError>,
+        I: Fn() + Send + 'static,
+    {
+        let id = {
+            let mut state = self.state.lock().unwrap();
+            if state.cancelled {
+                return Err(CancelError::Cancelled);
+            }
+            let id = state.next_id;
+            state.next_id += 1;
+            state.interrupts.push((id, Box::new(interrupt)));
+            id
+        };
+        let result = op();
+        let mut state = self.state.lock().unwrap();
+        state.interrupts.retain(|&(other, _)| other != id);
+        match result {
+            Ok(value) => Ok(value),
+            Err(_) if state.cancelled => Err(CancelError::Cancelled),
+            Err(error) => Err(CancelError::Lldb(error)),
+        }
+    }
+}
+
+impl fmt::Debug for CancelToken {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "CancelToken {{ cancelled: {} }}", self.is_cancelled())
+    }
+}
+
+/// The error returned by operations which accept a [`CancelToken`].
+///
+/// [`CancelToken`]: struct.CancelToken.html
+#[derive(Debug)]
+pub enum CancelError {
+    /// The operation failed.
+    Lldb(SBError),
+    /// The operation was cancelled.
+    Cancelled,
+}
+
+impl fmt::Display for CancelError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            CancelError::Lldb(ref error) => fmt::Display::fmt(error, f),
+            CancelError::Cancelled => write!(f, "the operation was cancelled"),
+        }
+    }
+}
+
+impl Error for CancelError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match *self {
+            CancelError::Lldb(ref error) => Some(error),
+            CancelError::Cancelled => None,
+        }
+    }
+}
+
+impl From<SBError> for CancelError {
+    fn from(error: SBError) -> CancelError {
+        CancelError::Lldb(error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CancelError, CancelToken};
+    use SBError;
+
+    #[test]
+    fn keeps_results_which_finished() {
+        let token = CancelToken::new();
+        let result = token.run(
+            || {
+                token.cancel();
+                Ok(42)
+            },
+            || {},
+        );
+        assert_eq!(result.ok(), Some(42));
+        assert!(matches!(
+            token.run(|| Ok(1), || {}),
+            Err(CancelError::Cancelled)
+        ));
+    }
+
+    #[test]
+    fn reports_errors_after_cancelling_as_cancelled() {
+        let token = CancelToken::new();
+        let result: Result<(), CancelError> = token.run(
+            || {
+                token.cancel();
+                Err(SBError::new())
+            },
+            || {},
+        );
+        assert!(matches!(result, Err(CancelError::Cancelled)));
+    }
+
+    #[test]
+    fn interrupts_can_use_the_token() {
+        let token = CancelToken::new();
+        let interrupted = token.clone();
+        let result = token.run(
+            || {
+                token.cancel();
+                Ok(())
+            },
+            move || assert!(interrupted.is_cancelled()),
+        );
+        assert!(result.is_ok());
+    }
+}
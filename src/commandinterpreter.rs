@@ -4,6 +4,16 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
+use super::broadcaster::SBBroadcaster;
+use super::commandinterpreterrunoptions::SBCommandInterpreterRunOptions;
+use super::commandreturnobject::SBCommandReturnObject;
+use super::event::SBEvent;
+use super::filespec::SBFileSpec;
+use super::stringlist::SBStringList;
+use super::strings::{self, AsCStr};
+use super::target::SBTarget;
+use super::trace::traced;
+use std::ffi::CString;
 use sys;
 
 #[allow(missing_docs)]
@@ -14,10 +24,424 @@ pub struct SBCommandInterpreter {
 }
 
 impl SBCommandInterpreter {
+    /// Broadcast when the interpreter's thread should exit.
+    pub const BROADCAST_BIT_THREAD_SHOULD_EXIT: u32 = 1 << 0;
+    /// Broadcast when the prompt should be redrawn.
+    pub const BROADCAST_BIT_RESET_PROMPT: u32 = 1 << 1;
+    /// Broadcast when the `quit` command is run.
+    pub const BROADCAST_BIT_QUIT_COMMAND_RECEIVED: u32 = 1 << 2;
+    /// Broadcast with output written asynchronously, such as by
+    /// breakpoint commands.
+    pub const BROADCAST_BIT_ASYNCHRONOUS_OUTPUT_DATA: u32 = 1 << 3;
+    /// Broadcast with errors written asynchronously.
+    pub const BROADCAST_BIT_ASYNCHRONOUS_ERROR_DATA: u32 = 1 << 4;
+
     /// Construct a new `SBCommandInterpreter`.
-    pub fn wrap(raw: sys::SBCommandInterpreterRef) -> SBCommandInterpreter {
+    ///
+    /// # Safety
+    ///
+    /// `raw` must be an `SBCommandInterpreterRef` which isn't used
+    /// elsewhere, such as one from `SBDebuggerGetCommandInterpreter`, as
+    /// it is disposed of when the `SBCommandInterpreter` is dropped.
+    pub unsafe fn wrap(raw: sys::SBCommandInterpreterRef) -> SBCommandInterpreter {
         SBCommandInterpreter { raw }
     }
+
+    /// Check whether or not this is a valid `SBCommandInterpreter` value.
+    pub fn is_valid(&self) -> bool {
+        unsafe { sys::SBCommandInterpreterIsValid(self.raw) != 0 }
+    }
+
+    #[allow(missing_docs)]
+    pub fn broadcaster_class_name() -> &'static str {
+        unsafe { strings::to_static_str(sys::SBCommandInterpreterGetBroadcasterClass()) }
+    }
+
+    /// The broadcaster for the events described by the `BROADCAST_BIT_*`
+    /// constants.
+    ///
+    /// Listen for the asynchronous output bits to receive output that
+    /// isn't part of the result of a command, such as the output of
+    /// breakpoint commands:
+    ///
+    /// ```no_run
+    /// # use lldb::{SBCommandInterpreter, SBDebugger, SBEvent};
+    /// # fn pump(debugger: &SBDebugger) {
+    /// let interpreter = debugger.command_interpreter();
+    /// let listener = debugger.listener();
+    /// listener.start_listening_for_events(
+    ///     &interpreter.broadcaster(),
+    ///     SBCommandInterpreter::BROADCAST_BIT_ASYNCHRONOUS_OUTPUT_DATA
+    ///         | SBCommandInterpreter::BROADCAST_BIT_ASYNCHRONOUS_ERROR_DATA,
+    /// );
+    /// let mut event = SBEvent::new();
+    /// while listener.wait_for_event(1, &mut event) {
+    ///     if let Some(event) = SBCommandInterpreter::event_as_command_interpreter_event(&event) {
+    ///         if let Some(text) = event.output() {
+    ///             print!("{}", text);
+    ///         }
+    ///     }
+    /// }
+    /// # }
+    /// ```
+    pub fn broadcaster(&self) -> SBBroadcaster {
+        unsafe { SBBroadcaster::wrap(sys::SBCommandInterpreterGetBroadcaster(self.raw)) }
+    }
+
+    /// The command interpreter event in `event`, if it is one.
+    pub fn event_as_command_interpreter_event<'e>(
+        event: &'e SBEvent,
+    ) -> Option<SBCommandInterpreterEvent<'e>> {
+        if unsafe { sys::SBCommandInterpreterEventIsCommandInterpreterEvent(event.raw) != 0 } {
+            Some(SBCommandInterpreterEvent::new(event))
+        } else {
+            None
+        }
+    }
+
+    /// Run a single command, as if it had been typed into the `lldb`
+    /// command line.
+    ///
+    /// See [`AsCStr`] for avoiding the conversion of `command` on
+    /// each call.
+    ///
+    /// [`AsCStr`]: trait.AsCStr.html
+    pub fn handle_command<S: AsCStr + ?Sized>(
+        &self,
+        command: &S,
+        add_to_history: bool,
+    ) -> SBCommandReturnObject {
+        let result = SBCommandReturnObject::new();
+        self.handle_command_into(command, &result, add_to_history);
+        result
+    }
+
+    /// Run a single command, collecting its output into `result`,
+    /// which may have been set up to stream the output as it is
+    /// produced with [`SBCommandReturnObject::set_immediate_output_file`].
+    ///
+    /// [`SBCommandReturnObject::set_immediate_output_file`]: struct.SBCommandReturnObject.html#method.set_immediate_output_file
+    pub fn handle_command_into<S: AsCStr + ?Sized>(
+        &self,
+        command: &S,
+        result: &SBCommandReturnObject,
+        add_to_history: bool,
+    ) {
+        traced("SBCommandInterpreter::handle_command", || {
+            command.with_cstr(|command| unsafe {
+                sys::SBCommandInterpreterHandleCommand(
+                    self.raw,
+                    command.as_ptr(),
+                    result.raw,
+                    add_to_history as u8,
+                )
+            });
+            result
+        });
+    }
+
+    /// Run a single command against `target`, rather than the
+    /// debugger's selected target.
+    pub fn handle_command_for_target<S: AsCStr + ?Sized>(
+        &self,
+        command: &S,
+        target: &SBTarget,
+        add_to_history: bool,
+    ) -> SBCommandReturnObject {
+        traced("SBCommandInterpreter::handle_command", || {
+            let result = SBCommandReturnObject::new();
+            unsafe {
+                let context = sys::CreateSBExecutionContext2(target.raw);
+                command.with_cstr(|command| {
+                    sys::SBCommandInterpreterHandleCommand2(
+                        self.raw,
+                        command.as_ptr(),
+                        context,
+                        result.raw,
+                        add_to_history as u8,
+                    )
+                });
+                sys::DisposeSBExecutionContext(context);
+            }
+            result
+        })
+    }
+
+    /// Run the commands in a file, in the same way as the `command source`
+    /// command.
+    ///
+    /// The output of all of the commands is collected into the
+    /// returned result.
+    pub fn handle_commands_from_file(
+        &self,
+        file: &SBFileSpec,
+        options: &SBCommandInterpreterRunOptions,
+    ) -> SBCommandReturnObject {
+        let result = SBCommandReturnObject::new();
+        unsafe {
+            let context = sys::CreateSBExecutionContext();
+            sys::SBCommandInterpreterHandleCommandsFromFile(
+                self.raw,
+                file.raw,
+                context,
+                options.raw,
+                result.raw,
+            );
+            sys::DisposeSBExecutionContext(context);
+        }
+        result
+    }
+
+    /// Is there a command with the given name?
+    pub fn command_exists(&self, name: &str) -> bool {
+        let name = CString::new(name).unwrap();
+        unsafe { sys::SBCommandInterpreterCommandExists(self.raw, name.as_ptr()) != 0 }
+    }
+
+    /// Is there an alias with the given name?
+    pub fn alias_exists(&self, name: &str) -> bool {
+        let name = CString::new(name).unwrap();
+        unsafe { sys::SBCommandInterpreterAliasExists(self.raw, name.as_ptr()) != 0 }
+    }
+
+    /// Are there any commands?
+    pub fn has_commands(&self) -> bool {
+        unsafe { sys::SBCommandInterpreterHasCommands(self.raw) != 0 }
+    }
+
+    /// Are there any aliases?
+    pub fn has_aliases(&self) -> bool {
+        unsafe { sys::SBCommandInterpreterHasAliases(self.raw) != 0 }
+    }
+
+    /// List the available top-level commands, aliases and user-defined
+    /// commands along with their short help text.
+    ///
+    /// This is built from the output of the `help` command, as LLDB
+    /// has no API for enumerating commands.
+    pub fn commands(&self) -> Vec<CommandInfo> {
+        let result = self.handle_command("help", false);
+        parse_command_listing(&result.output().unwrap_or_default())
+    }
+
+    /// List the commands related to `word`, in the same way as the
+    /// `apropos` command.
+    pub fn apropos(&self, word: &str) -> Vec<CommandInfo> {
+        let command = format!("apropos {}", strings::quote_argument(word));
+        let result = self.handle_command(&command, false);
+        parse_command_listing(&result.output().unwrap_or_default())
+    }
+
+    /// Complete the command line `line` with the cursor at the byte
+    /// offset `cursor`, in the same way as pressing tab in the `lldb`
+    /// command line.
+    ///
+    /// At most `max_results` matches are returned, or all matches if
+    /// this is `None`.
+    ///
+    /// ```no_run
+    /// # use lldb::SBDebugger;
+    /// # fn complete(debugger: &SBDebugger) {
+    /// let interpreter = debugger.command_interpreter();
+    /// let completions = interpreter.handle_completion("br", 2, None);
+    /// for (m, description) in completions.matches.iter().zip(&completions.descriptions) {
+    ///     println!("{}\t{}", m, description);
+    /// }
+    /// # }
+    /// ```
+    pub fn handle_completion(
+        &self,
+        line: &str,
+        cursor: usize,
+        max_results: Option<usize>,
+    ) -> Completions {
+        let line = CString::new(line).unwrap();
+        let matches = SBStringList::new();
+        let descriptions = SBStringList::new();
+        let max_results = max_results.map(|m| m as i32).unwrap_or(-1);
+        unsafe {
+            {
+                sys::SBCommandInterpreterHandleCompletionWithDescriptions2(
+                    self.raw,
+                    line.as_ptr(),
+                    cursor as u32,
+                    0,
+                    max_results,
+                    matches.raw,
+                    descriptions.raw,
+                )
+            }
+        };
+        Completions::from_lists(&matches, &descriptions)
+    }
+}
+
+/// An event broadcast by an [`SBCommandInterpreter`].
+///
+/// [`SBCommandInterpreter`]: struct.SBCommandInterpreter.html
+pub struct SBCommandInterpreterEvent<'e> {
+    event: &'e SBEvent,
+}
+
+impl<'e> SBCommandInterpreterEvent<'e> {
+    #[allow(missing_docs)]
+    pub fn new(event: &'e SBEvent) -> Self {
+        SBCommandInterpreterEvent { event }
+    }
+
+    /// Is this asynchronous output text?
+    pub fn is_output(&self) -> bool {
+        self.event.event_type() & SBCommandInterpreter::BROADCAST_BIT_ASYNCHRONOUS_OUTPUT_DATA != 0
+    }
+
+    /// Is this asynchronous error text?
+    pub fn is_error(&self) -> bool {
+        self.event.event_type() & SBCommandInterpreter::BROADCAST_BIT_ASYNCHRONOUS_ERROR_DATA != 0
+    }
+
+    /// Was the `quit` command run?
+    pub fn is_quit(&self) -> bool {
+        self.event.event_type() & SBCommandInterpreter::BROADCAST_BIT_QUIT_COMMAND_RECEIVED != 0
+    }
+
+    /// The text carried by this event, if it is asynchronous output.
+    pub fn output(&self) -> Option<String> {
+        if self.is_output() {
+            self.text()
+        } else {
+            None
+        }
+    }
+
+    /// The text carried by this event, if it is asynchronous error text.
+    pub fn error(&self) -> Option<String> {
+        if self.is_error() {
+            self.text()
+        } else {
+            None
+        }
+    }
+
+    fn text(&self) -> Option<String> {
+        unsafe { strings::to_string(sys::SBEventGetCStringFromEvent(self.event.raw)) }
+    }
+}
+
+/// The kind of a command listed by [`SBCommandInterpreter::commands`].
+///
+/// [`SBCommandInterpreter::commands`]: struct.SBCommandInterpreter.html#method.commands
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum CommandKind {
+    /// A command built in to LLDB.
+    Builtin,
+    /// An alias for another command.
+    Alias,
+    /// A command added by the user, such as a script command.
+    User,
+}
+
+/// A command listed by [`SBCommandInterpreter::commands`] or
+/// [`SBCommandInterpreter::apropos`].
+///
+/// [`SBCommandInterpreter::commands`]: struct.SBCommandInterpreter.html#method.commands
+/// [`SBCommandInterpreter::apropos`]: struct.SBCommandInterpreter.html#method.apropos
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CommandInfo {
+    /// The name of the command.
+    pub name: String,
+    /// The short help text for the command.
+    pub help: String,
+    /// The kind of command.
+    pub kind: CommandKind,
+}
+
+/// Parse the command listing printed by `help` or `apropos`.
+///
+/// Sections are introduced by an unindented header line, and each
+/// command is an indented `name -- help` line. Long help text is
+/// wrapped onto further indented lines.
+fn parse_command_listing(text: &str) -> Vec<CommandInfo> {
+    let mut commands: Vec<CommandInfo> = vec![];
+    let mut kind = Some(CommandKind::Builtin);
+    for line in text.lines() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        if !line.starts_with(char::is_whitespace) {
+            let header = line.to_lowercase();
+            kind = if header.contains("settings") {
+                None
+            } else if header.contains("abbreviation") || header.contains("alias") {
+                Some(CommandKind::Alias)
+            } else if header.contains("user") {
+                Some(CommandKind::User)
+            } else {
+                Some(CommandKind::Builtin)
+            };
+            continue;
+        }
+        let kind = match kind {
+            Some(kind) => kind,
+            None => continue,
+        };
+        if let Some(idx) = line.find(" -- ") {
+            let name = line[..idx].trim();
+            if !name.is_empty() && !name.contains(char::is_whitespace) {
+                commands.push(CommandInfo {
+                    name: name.to_owned(),
+                    help: line[idx + 4..].trim().to_owned(),
+                    kind,
+                });
+                continue;
+            }
+        }
+        if let Some(command) = commands.last_mut() {
+            command.help.push(' ');
+            command.help.push_str(line.trim());
+        }
+    }
+    commands
+}
+
+/// The result of completing a command line with
+/// [`SBCommandInterpreter::handle_completion`].
+///
+/// [`SBCommandInterpreter::handle_completion`]: struct.SBCommandInterpreter.html#method.handle_completion
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Completions {
+    /// The text common to all of the matches which can be inserted
+    /// at the cursor. This is empty if there is no common text.
+    pub common_prefix: String,
+    /// The possible completions of the word at the cursor.
+    pub matches: Vec<String>,
+    /// A description of each match. This has the same length as
+    /// `matches`, with an empty string when there is no description.
+    pub descriptions: Vec<String>,
+}
+
+impl Completions {
+    /// Build from the lists populated by LLDB, where the first
+    /// element is the common prefix and the rest are the matches.
+    fn from_lists(matches: &SBStringList, descriptions: &SBStringList) -> Completions {
+        let mut matches = matches.iter().map(|s| s.to_owned());
+        let mut descriptions = descriptions.iter().map(|s| s.to_owned()).skip(1);
+        let common_prefix = matches.next().unwrap_or_default();
+        let matches: Vec<String> = matches.collect();
+        let descriptions = matches
+            .iter()
+            .map(|_| descriptions.next().unwrap_or_default())
+            .collect();
+        Completions {
+            common_prefix,
+            matches,
+            descriptions,
+        }
+    }
+
+    /// Is there anything to complete?
+    pub fn is_empty(&self) -> bool {
+        self.common_prefix.is_empty() && self.matches.is_empty()
+    }
 }
 
 impl Clone for SBCommandInterpreter {
@@ -38,4 +462,39 @@ unsafe impl Send for SBCommandInterpreter {}
 unsafe impl Sync for SBCommandInterpreter {}
 
 #[cfg(feature = "graphql")]
-graphql_object!(SBCommandInterpreter: super::debugger::SBDebugger | &self | {});
+#[graphql_object(context = super::debugger::SBDebugger)]
+impl SBCommandInterpreter {
+    #[graphql(name = "isValid")]
+    fn graphql_is_valid(&self) -> bool {
+        self.is_valid()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_command_listing, CommandKind};
+
+    #[test]
+    fn parses_help_listing() {
+        let commands = parse_command_listing(
+            "Debugger commands:
+  apropos           -- List debugger commands related to a word or phrase.
+  breakpoint        -- Commands for operating on breakpoints (see 'help b'
+                       for shorthand.)
+
+Current command abbreviations (type 'help command alias' for more info):
+  b         -- Set a breakpoint using one of several shorthand formats.
+
+For more information on any command, type 'help <command-name>'.
+",
+        );
+        assert_eq!(commands.len(), 3);
+        assert_eq!(commands[0].name, "apropos");
+        assert_eq!(
+            commands[1].help,
+            "Commands for operating on breakpoints (see 'help b' for shorthand.)"
+        );
+        assert_eq!(commands[2].name, "b");
+        assert_eq!(commands[2].kind, CommandKind::Alias);
+    }
+}
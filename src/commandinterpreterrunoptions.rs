@@ -0,0 +1,122 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use sys;
+
+/// Options controlling how a series of commands is run by the
+/// [command interpreter], such as when sourcing a command file.
+///
+/// [command interpreter]: struct.SBCommandInterpreter.html
+#[derive(Debug)]
+pub struct SBCommandInterpreterRunOptions {
+    /// The underlying raw `SBCommandInterpreterRunOptionsRef`.
+    pub raw: sys::SBCommandInterpreterRunOptionsRef,
+}
+
+impl SBCommandInterpreterRunOptions {
+    /// Construct a new `SBCommandInterpreterRunOptions`.
+    pub fn new() -> SBCommandInterpreterRunOptions {
+        unsafe { SBCommandInterpreterRunOptions::wrap(sys::CreateSBCommandInterpreterRunOptions()) }
+    }
+
+    /// Construct a new `SBCommandInterpreterRunOptions`.
+    ///
+    /// # Safety
+    ///
+    /// `raw` must be an `SBCommandInterpreterRunOptionsRef` from
+    /// `CreateSBCommandInterpreterRunOptions` which isn't used elsewhere,
+    /// as it is disposed of when the `SBCommandInterpreterRunOptions` is
+    /// dropped.
+    pub unsafe fn wrap(
+        raw: sys::SBCommandInterpreterRunOptionsRef,
+    ) -> SBCommandInterpreterRunOptions {
+        SBCommandInterpreterRunOptions { raw }
+    }
+
+    /// Whether to stop running commands when a command continues
+    /// the process.
+    pub fn stop_on_continue(&self) -> bool {
+        unsafe { sys::SBCommandInterpreterRunOptionsGetStopOnContinue(self.raw) != 0 }
+    }
+
+    /// Whether to stop running commands when a command continues
+    /// the process.
+    pub fn set_stop_on_continue(&self, stop: bool) {
+        unsafe { sys::SBCommandInterpreterRunOptionsSetStopOnContinue(self.raw, stop as u8) };
+    }
+
+    /// Whether to stop running commands when a command fails.
+    pub fn stop_on_error(&self) -> bool {
+        unsafe { sys::SBCommandInterpreterRunOptionsGetStopOnError(self.raw) != 0 }
+    }
+
+    /// Whether to stop running commands when a command fails.
+    pub fn set_stop_on_error(&self, stop: bool) {
+        unsafe { sys::SBCommandInterpreterRunOptionsSetStopOnError(self.raw, stop as u8) };
+    }
+
+    /// Whether to stop running commands when the process crashes.
+    pub fn stop_on_crash(&self) -> bool {
+        unsafe { sys::SBCommandInterpreterRunOptionsGetStopOnCrash(self.raw) != 0 }
+    }
+
+    /// Whether to stop running commands when the process crashes.
+    pub fn set_stop_on_crash(&self, stop: bool) {
+        unsafe { sys::SBCommandInterpreterRunOptionsSetStopOnCrash(self.raw, stop as u8) };
+    }
+
+    /// Whether to echo each command before running it.
+    pub fn echo_commands(&self) -> bool {
+        unsafe { sys::SBCommandInterpreterRunOptionsGetEchoCommands(self.raw) != 0 }
+    }
+
+    /// Whether to echo each command before running it.
+    pub fn set_echo_commands(&self, echo: bool) {
+        unsafe { sys::SBCommandInterpreterRunOptionsSetEchoCommands(self.raw, echo as u8) };
+    }
+
+    /// Whether to print the results of each command.
+    pub fn print_results(&self) -> bool {
+        unsafe { sys::SBCommandInterpreterRunOptionsGetPrintResults(self.raw) != 0 }
+    }
+
+    /// Whether to print the results of each command.
+    pub fn set_print_results(&self, print: bool) {
+        unsafe { sys::SBCommandInterpreterRunOptionsSetPrintResults(self.raw, print as u8) };
+    }
+
+    /// Whether to add each command to the command history.
+    pub fn add_to_history(&self) -> bool {
+        unsafe { sys::SBCommandInterpreterRunOptionsGetAddToHistory(self.raw) != 0 }
+    }
+
+    /// Whether to add each command to the command history.
+    pub fn set_add_to_history(&self, add: bool) {
+        unsafe { sys::SBCommandInterpreterRunOptionsSetAddToHistory(self.raw, add as u8) };
+    }
+}
+
+impl Clone for SBCommandInterpreterRunOptions {
+    fn clone(&self) -> SBCommandInterpreterRunOptions {
+        SBCommandInterpreterRunOptions {
+            raw: unsafe { sys::CreateSBCommandInterpreterRunOptions2(self.raw) },
+        }
+    }
+}
+
+impl Default for SBCommandInterpreterRunOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for SBCommandInterpreterRunOptions {
+    fn drop(&mut self) {
+        unsafe { sys::DisposeSBCommandInterpreterRunOptions(self.raw) };
+    }
+}
+
+unsafe impl Send for SBCommandInterpreterRunOptions {}
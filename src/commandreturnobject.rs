@@ -0,0 +1,244 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use super::error::InvalidRefError;
+use super::stream::SBStream;
+use super::strings;
+use std::cell::RefCell;
+use std::convert::TryFrom;
+use std::fmt;
+#[cfg(unix)]
+use std::os::unix::io::AsRawFd;
+#[cfg(unix)]
+use std::ptr;
+use std::sync::Arc;
+use sys;
+
+/// The result of running a command with the [command interpreter].
+///
+/// [command interpreter]: struct.SBCommandInterpreter.html
+pub struct SBCommandReturnObject {
+    /// The underlying raw `SBCommandReturnObjectRef`.
+    pub raw: sys::SBCommandReturnObjectRef,
+    /// The files that LLDB writes immediate output to, which must stay
+    /// open for as long as this, or a clone of it, might write to them.
+    immediate_files: RefCell<Vec<Arc<ImmediateFile>>>,
+}
+
+impl SBCommandReturnObject {
+    /// Construct a new `SBCommandReturnObject`.
+    pub fn new() -> SBCommandReturnObject {
+        unsafe { SBCommandReturnObject::wrap(sys::CreateSBCommandReturnObject()) }
+    }
+
+    /// Construct a new `SBCommandReturnObject`.
+    ///
+    /// # Safety
+    ///
+    /// `raw` must be a valid reference, which is disposed of when the
+    /// `SBCommandReturnObject` is dropped. Use [`maybe_wrap`] when it may be invalid.
+    ///
+    /// [`maybe_wrap`]: #method.maybe_wrap
+    pub unsafe fn wrap(raw: sys::SBCommandReturnObjectRef) -> SBCommandReturnObject {
+        SBCommandReturnObject {
+            raw,
+            immediate_files: RefCell::new(vec![]),
+        }
+    }
+
+    /// Construct a new `Some(SBCommandReturnObject)` or `None`.
+    ///
+    /// `raw` is disposed of if it is invalid.
+    pub fn maybe_wrap(raw: sys::SBCommandReturnObjectRef) -> Option<SBCommandReturnObject> {
+        let wrapped = SBCommandReturnObject {
+            raw,
+            immediate_files: RefCell::new(vec![]),
+        };
+        if wrapped.is_valid() {
+            Some(wrapped)
+        } else {
+            None
+        }
+    }
+
+    /// Check whether or not this is a valid `SBCommandReturnObject` value.
+    pub fn is_valid(&self) -> bool {
+        unsafe { sys::SBCommandReturnObjectIsValid(self.raw) != 0 }
+    }
+
+    /// Clear the output, error and status.
+    pub fn clear(&self) {
+        unsafe { sys::SBCommandReturnObjectClear(self.raw) };
+    }
+
+    /// The status of the command.
+    pub fn status(&self) -> sys::ReturnStatus {
+        unsafe { sys::SBCommandReturnObjectGetStatus(self.raw) }
+    }
+
+    /// Did the command succeed?
+    pub fn succeeded(&self) -> bool {
+        unsafe { sys::SBCommandReturnObjectSucceeded(self.raw) != 0 }
+    }
+
+    /// Did the command produce a result?
+    pub fn has_result(&self) -> bool {
+        unsafe { sys::SBCommandReturnObjectHasResult(self.raw) != 0 }
+    }
+
+    /// The output written by the command, if any.
+    pub fn output(&self) -> Option<String> {
+        unsafe { strings::to_string(sys::SBCommandReturnObjectGetOutput(self.raw)) }
+    }
+
+    /// The error message written by the command, if any.
+    pub fn error(&self) -> Option<String> {
+        unsafe { strings::to_string(sys::SBCommandReturnObjectGetError(self.raw)) }
+    }
+
+    /// Write the output of a command to `output` as it is produced,
+    /// as well as collecting it for [`output`].
+    ///
+    /// Commands which produce a lot of output, such as `bt all` in a
+    /// process with thousands of threads, can then be shown as they
+    /// go rather than all at once at the end. Pass this to
+    /// [`SBCommandInterpreter::handle_command_into`] to use it:
+    ///
+    /// ```no_run
+    /// # use lldb::{SBCommandReturnObject, SBDebugger};
+    /// # fn backtraces(debugger: &SBDebugger) {
+    /// let result = SBCommandReturnObject::new();
+    /// result.set_immediate_output_file(&std::io::stdout());
+    /// debugger
+    ///     .command_interpreter()
+    ///     .handle_command_into("bt all", &result, false);
+    /// # }
+    /// ```
+    ///
+    /// The file descriptor of `output` is duplicated, so ownership
+    /// remains with the caller. The duplicate is line buffered, and
+    /// closed once this and every clone of it have been dropped.
+    ///
+    /// Returns `false` if `output` could not be opened as a stream.
+    ///
+    /// [`output`]: #method.output
+    /// [`SBCommandInterpreter::handle_command_into`]: struct.SBCommandInterpreter.html#method.handle_command_into
+    #[cfg(unix)]
+    pub fn set_immediate_output_file<O: AsRawFd>(&self, output: &O) -> bool {
+        match ImmediateFile::open(output) {
+            Some(file) => {
+                unsafe { sys::SBCommandReturnObjectSetImmediateOutputFile(self.raw, file.0) };
+                self.immediate_files.borrow_mut().push(file);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Write the error messages of a command to `error` as they are
+    /// produced, as well as collecting them for [`error`].
+    ///
+    /// See [`set_immediate_output_file`].
+    ///
+    /// [`error`]: #method.error
+    /// [`set_immediate_output_file`]: #method.set_immediate_output_file
+    #[cfg(unix)]
+    pub fn set_immediate_error_file<E: AsRawFd>(&self, error: &E) -> bool {
+        match ImmediateFile::open(error) {
+            Some(file) => {
+                unsafe { sys::SBCommandReturnObjectSetImmediateErrorFile(self.raw, file.0) };
+                self.immediate_files.borrow_mut().push(file);
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn description(&self) -> String {
+        let stream = SBStream::new();
+        unsafe { sys::SBCommandReturnObjectGetDescription(self.raw, stream.raw) };
+        stream.data()
+    }
+}
+
+impl Clone for SBCommandReturnObject {
+    fn clone(&self) -> SBCommandReturnObject {
+        SBCommandReturnObject {
+            raw: unsafe { sys::CloneSBCommandReturnObject(self.raw) },
+            immediate_files: self.immediate_files.clone(),
+        }
+    }
+}
+
+impl Default for SBCommandReturnObject {
+    fn default() -> SBCommandReturnObject {
+        SBCommandReturnObject::new()
+    }
+}
+
+impl fmt::Debug for SBCommandReturnObject {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "SBCommandReturnObject {{ {} }}", self.description())
+    }
+}
+
+impl fmt::Display for SBCommandReturnObject {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "{}", self.description())
+    }
+}
+
+impl TryFrom<sys::SBCommandReturnObjectRef> for SBCommandReturnObject {
+    type Error = InvalidRefError;
+
+    fn try_from(
+        raw: sys::SBCommandReturnObjectRef,
+    ) -> Result<SBCommandReturnObject, InvalidRefError> {
+        SBCommandReturnObject::maybe_wrap(raw).ok_or(InvalidRefError)
+    }
+}
+
+impl Drop for SBCommandReturnObject {
+    fn drop(&mut self) {
+        unsafe { sys::DisposeSBCommandReturnObject(self.raw) };
+    }
+}
+
+unsafe impl Send for SBCommandReturnObject {}
+
+/// A `FILE` which LLDB writes the immediate output of a command to,
+/// closed when dropped.
+struct ImmediateFile(*mut libc::FILE);
+
+impl ImmediateFile {
+    /// Open a line buffered `FILE` for writing on a duplicate of the
+    /// file descriptor of `file`.
+    #[cfg(unix)]
+    fn open<F: AsRawFd>(file: &F) -> Option<Arc<ImmediateFile>> {
+        unsafe {
+            let fd = libc::dup(file.as_raw_fd());
+            if fd < 0 {
+                return None;
+            }
+            let f = libc::fdopen(fd, b"w\0".as_ptr() as *const libc::c_char);
+            if f.is_null() {
+                libc::close(fd);
+                return None;
+            }
+            libc::setvbuf(f, ptr::null_mut(), libc::_IOLBF, 0);
+            Some(Arc::new(ImmediateFile(f)))
+        }
+    }
+}
+
+impl Drop for ImmediateFile {
+    fn drop(&mut self) {
+        unsafe { libc::fclose(self.0) };
+    }
+}
+
+unsafe impl Send for ImmediateFile {}
+unsafe impl Sync for ImmediateFile {}
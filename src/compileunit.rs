@@ -4,9 +4,11 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
+use super::error::InvalidRefError;
 use super::filespec::SBFileSpec;
 use super::stream::SBStream;
 use super::LanguageType;
+use std::convert::TryFrom;
 use std::fmt;
 use sys;
 
@@ -18,14 +20,24 @@ pub struct SBCompileUnit {
 
 impl SBCompileUnit {
     /// Construct a new `SBCompileUnit`.
-    pub fn wrap(raw: sys::SBCompileUnitRef) -> SBCompileUnit {
+    ///
+    /// # Safety
+    ///
+    /// `raw` must be a valid reference, which is disposed of when the
+    /// `SBCompileUnit` is dropped. Use [`maybe_wrap`] when it may be invalid.
+    ///
+    /// [`maybe_wrap`]: #method.maybe_wrap
+    pub unsafe fn wrap(raw: sys::SBCompileUnitRef) -> SBCompileUnit {
         SBCompileUnit { raw }
     }
 
     /// Construct a new `Some(SBCompileUnit)` or `None`.
+    ///
+    /// `raw` is disposed of if it is invalid.
     pub fn maybe_wrap(raw: sys::SBCompileUnitRef) -> Option<SBCompileUnit> {
-        if unsafe { sys::SBCompileUnitIsValid(raw) != 0 } {
-            Some(SBCompileUnit { raw })
+        let wrapped = SBCompileUnit { raw };
+        if wrapped.is_valid() {
+            Some(wrapped)
         } else {
             None
         }
@@ -38,13 +50,19 @@ impl SBCompileUnit {
 
     /// The source file for the compile unit.
     pub fn filespec(&self) -> SBFileSpec {
-        SBFileSpec::wrap(unsafe { sys::SBCompileUnitGetFileSpec(self.raw) })
+        unsafe { SBFileSpec::wrap(sys::SBCompileUnitGetFileSpec(self.raw)) }
     }
 
     /// The language for the compile unit.
     pub fn language(&self) -> LanguageType {
         unsafe { sys::SBCompileUnitGetLanguage(self.raw) }
     }
+
+    fn description(&self) -> String {
+        let stream = SBStream::new();
+        unsafe { sys::SBCompileUnitGetDescription(self.raw, stream.raw) };
+        stream.data()
+    }
 }
 
 impl Clone for SBCompileUnit {
@@ -57,9 +75,21 @@ impl Clone for SBCompileUnit {
 
 impl fmt::Debug for SBCompileUnit {
     fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
-        let stream = SBStream::new();
-        unsafe { sys::SBCompileUnitGetDescription(self.raw, stream.raw) };
-        write!(fmt, "SBCompileUnit {{ {} }}", stream.data())
+        write!(fmt, "SBCompileUnit {{ {} }}", self.description())
+    }
+}
+
+impl fmt::Display for SBCompileUnit {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "{}", self.description())
+    }
+}
+
+impl TryFrom<sys::SBCompileUnitRef> for SBCompileUnit {
+    type Error = InvalidRefError;
+
+    fn try_from(raw: sys::SBCompileUnitRef) -> Result<SBCompileUnit, InvalidRefError> {
+        SBCompileUnit::maybe_wrap(raw).ok_or(InvalidRefError)
     }
 }
 
@@ -73,12 +103,15 @@ unsafe impl Send for SBCompileUnit {}
 unsafe impl Sync for SBCompileUnit {}
 
 #[cfg(feature = "graphql")]
-graphql_object!(SBCompileUnit: super::debugger::SBDebugger | &self | {
-    field is_valid() -> bool {
+#[graphql_object(context = super::debugger::SBDebugger)]
+impl SBCompileUnit {
+    #[graphql(name = "isValid")]
+    fn graphql_is_valid(&self) -> bool {
         self.is_valid()
     }
 
-    field filespec() -> SBFileSpec {
+    #[graphql(name = "filespec")]
+    fn graphql_filespec(&self) -> SBFileSpec {
         self.filespec()
     }
-});
+}
@@ -0,0 +1,123 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use super::lldb_pid_t;
+use super::process::SBProcess;
+use super::StateType;
+use std::fmt;
+
+/// Phrases in the exit description which LLDB gives a process when
+/// the connection to its remote debug server goes, rather than the
+/// process exiting.
+const LOST_CONNECTION_PHRASES: &[&str] = &[
+    "lost connection",
+    "connection closed",
+    "connection reset",
+    "connection timed out",
+    "debugserver died",
+];
+
+/// The connection to the remote debug server of a process was lost,
+/// from [`SBProcess::connection_lost`] or
+/// [`SBProcessEvent::connection_lost`].
+///
+/// LLDB doesn't have an event of its own for this. When `debugserver`,
+/// `lldb-server` or a gdbserver stub goes away, or stops answering
+/// packets within `plugin.process.gdb-remote.packet-timeout`, the
+/// process is marked as exited, with an exit description saying why.
+/// That is easily mistaken for the process itself exiting, so this
+/// picks those exits out, letting a tool reconnect rather than give
+/// up:
+///
+/// ```no_run
+/// # use lldb::{SBEvent, SBListener, SBProcess};
+/// # fn watch(listener: &SBListener) {
+/// let mut event = SBEvent::new();
+/// loop {
+///     if !listener.wait_for_event(1, &mut event) {
+///         continue;
+///     }
+///     if let Some(lost) = SBProcess::event_as_process_event(&event)
+///         .and_then(|process_event| process_event.connection_lost())
+///     {
+///         eprintln!("{}; reconnecting", lost);
+///         // Connect to the debug server again.
+///     }
+/// }
+/// # }
+/// ```
+///
+/// [`SBProcess::connection_lost`]: struct.SBProcess.html#method.connection_lost
+/// [`SBProcessEvent::connection_lost`]: struct.SBProcessEvent.html#method.connection_lost
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ConnectionLost {
+    /// The ID of the process, which may still be running on the
+    /// remote machine.
+    pub pid: lldb_pid_t,
+    /// The exit status LLDB gave the process, usually -1.
+    pub status: i32,
+    /// The exit description LLDB gave the process, such as
+    /// `lost connection`.
+    pub description: String,
+}
+
+impl ConnectionLost {
+    /// The `ConnectionLost` for `process`, if it has exited because
+    /// the connection to its debug server was lost.
+    pub(crate) fn from_process(process: &SBProcess) -> Option<ConnectionLost> {
+        if process.state() != StateType::Exited {
+            return None;
+        }
+        let description = process.exit_description()?;
+        if !is_lost_connection(&description) {
+            return None;
+        }
+        Some(ConnectionLost {
+            pid: process.process_id(),
+            status: process.exit_status(),
+            description,
+        })
+    }
+}
+
+impl fmt::Display for ConnectionLost {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "lost the connection to the debug server of process {} ({})",
+            self.pid, self.description
+        )
+    }
+}
+
+/// Does the exit description of a process say that the connection to
+/// its debug server was lost?
+fn is_lost_connection(description: &str) -> bool {
+    let description = description.to_lowercase();
+    LOST_CONNECTION_PHRASES
+        .iter()
+        .any(|phrase| description.contains(phrase))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::is_lost_connection;
+
+    #[test]
+    fn recognizes_lost_connections() {
+        assert!(is_lost_connection("lost connection"));
+        assert!(is_lost_connection("Lost connection to the remote"));
+        assert!(is_lost_connection("debugserver died with signal SIGKILL"));
+        assert!(is_lost_connection("Connection reset by peer"));
+    }
+
+    #[test]
+    fn leaves_ordinary_exits() {
+        assert!(!is_lost_connection(""));
+        assert!(!is_lost_connection("exited with status = 0 (0x00000000)"));
+        assert!(!is_lost_connection("Terminated due to signal 9"));
+    }
+}
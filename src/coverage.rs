@@ -0,0 +1,273 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Basic block coverage using breakpoints.
+//!
+//! [`Coverage`] disassembles functions to find their basic blocks and
+//! puts a one-shot breakpoint at the start of each. Running the
+//! process with [`Coverage::run`] removes each breakpoint as it is
+//! hit, so each block only costs one stop, and the blocks which were
+//! reached make up the coverage:
+//!
+//! ```no_run
+//! # use lldb::{SBDebugger, SBLaunchInfo};
+//! # use lldb::coverage::Coverage;
+//! SBDebugger::initialize();
+//! let debugger = SBDebugger::create(false);
+//! debugger.set_async(false);
+//! let target = debugger.create_target("parser", None, None, false).unwrap();
+//! let mut coverage = Coverage::new(&target);
+//! for module in target.modules() {
+//!     coverage.add_module(&module);
+//! }
+//! let launch_info = SBLaunchInfo::new();
+//! launch_info.set_arguments(&["input.txt"], false);
+//! let process = target.launch(launch_info).unwrap();
+//! coverage.run(&process).unwrap();
+//! for block in coverage.blocks().iter().filter(|block| block.hit) {
+//!     println!("{:#x} {:?}", block.address, block.function);
+//! }
+//! ```
+//!
+//! Blocks are found from the branches in the disassembly, so a jump
+//! through a table, whose targets don't appear in the disassembly,
+//! leaves the blocks it jumps to merged with the ones before them.
+
+use super::address::SBAddress;
+use super::error::SBError;
+use super::function::SBFunction;
+use super::instructionlist::SBInstructionList;
+use super::module::SBModule;
+use super::process::SBProcess;
+use super::symbol::SBSymbol;
+use super::target::SBTarget;
+use super::{lldb_addr_t, DisassemblyFlavor, StateType, StopReason, SymbolType};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+const LLDB_INVALID_ADDRESS: lldb_addr_t = !0;
+
+/// Collects which basic blocks of a set of functions are executed.
+///
+/// The debugger must be in synchronous mode when using [`run`].
+///
+/// [`run`]: #method.run
+pub struct Coverage {
+    target: SBTarget,
+    blocks: BTreeMap<(Option<String>, lldb_addr_t), CoverageBlock>,
+    breakpoints: HashMap<i32, (Option<String>, lldb_addr_t)>,
+}
+
+/// A basic block and whether it was executed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CoverageBlock {
+    /// The path of the module containing the block.
+    pub module: Option<String>,
+    /// The name of the function containing the block.
+    pub function: Option<String>,
+    /// The file address of the start of the block, which is the same
+    /// wherever the module is loaded.
+    pub address: lldb_addr_t,
+    /// The source file of the start of the block, if there is line
+    /// information.
+    pub file: Option<String>,
+    /// The source line of the start of the block, if there is line
+    /// information.
+    pub line: Option<u32>,
+    /// Whether the block was executed.
+    pub hit: bool,
+}
+
+impl Coverage {
+    /// Create a collector for the process of `target`.
+    pub fn new(target: &SBTarget) -> Coverage {
+        Coverage {
+            target: target.clone(),
+            blocks: BTreeMap::new(),
+            breakpoints: HashMap::new(),
+        }
+    }
+
+    /// Add the blocks of `function`, returning how many were added.
+    pub fn add_function(&mut self, function: &SBFunction) -> usize {
+        let instructions = function.get_instructions(&self.target, DisassemblyFlavor::Default);
+        self.add_instructions(&instructions, function.name())
+    }
+
+    /// Add the blocks of the code at `symbol`, for functions without
+    /// debug information, returning how many were added.
+    pub fn add_symbol(&mut self, symbol: &SBSymbol) -> usize {
+        let instructions = symbol.get_instructions(&self.target, DisassemblyFlavor::Default);
+        self.add_instructions(&instructions, symbol.name())
+    }
+
+    /// Add the blocks of every function in the symbol table of
+    /// `module`, returning how many were added.
+    pub fn add_module(&mut self, module: &SBModule) -> usize {
+        module
+            .symbols()
+            .filter(|symbol| symbol.symbol_type() == SymbolType::Code)
+            .map(|symbol| self.add_symbol(&symbol))
+            .sum()
+    }
+
+    fn add_instructions(
+        &mut self,
+        instructions: &SBInstructionList,
+        function: Option<String>,
+    ) -> usize {
+        let instructions: Vec<_> = instructions.iter().collect();
+        let addresses: Vec<SBAddress> = instructions.iter().map(|i| i.address()).collect();
+        let mut index_of = HashMap::new();
+        for (idx, address) in addresses.iter().enumerate() {
+            // Branch targets are shown as load addresses once the
+            // module is loaded, and as file addresses before.
+            index_of.insert(address.file_address(), idx);
+            let load_address = address.load_address(&self.target);
+            if load_address != LLDB_INVALID_ADDRESS {
+                index_of.insert(load_address, idx);
+            }
+        }
+
+        let mut starts = BTreeSet::new();
+        if !instructions.is_empty() {
+            starts.insert(0);
+        }
+        for (idx, instruction) in instructions.iter().enumerate() {
+            if !instruction.is_branch() {
+                continue;
+            }
+            if idx + 1 < instructions.len() {
+                starts.insert(idx + 1);
+            }
+            if let Some(&target) = branch_target(&instruction.operands(&self.target))
+                .as_ref()
+                .and_then(|address| index_of.get(address))
+            {
+                starts.insert(target);
+            }
+        }
+
+        let mut added = 0;
+        for idx in starts {
+            let address = &addresses[idx];
+            let module = address.module().map(|module| module.filespec().path());
+            let key = (module.clone(), address.file_address());
+            if self.blocks.contains_key(&key) {
+                continue;
+            }
+            let breakpoint = self.target.breakpoint_create_by_sbaddress(address);
+            breakpoint.set_oneshot(true);
+            self.breakpoints.insert(breakpoint.id(), key.clone());
+            let line_entry = address.line_entry();
+            self.blocks.insert(
+                key,
+                CoverageBlock {
+                    module,
+                    function: function.clone(),
+                    address: address.file_address(),
+                    file: line_entry.as_ref().map(|l| l.filespec().path()),
+                    line: line_entry.as_ref().map(|l| l.line()).filter(|&l| l != 0),
+                    hit: false,
+                },
+            );
+            added += 1;
+        }
+        added
+    }
+
+    /// Record the blocks that the threads of `process` are stopped
+    /// at.
+    ///
+    /// Returns `true` if the process stopped only because of the
+    /// breakpoints for blocks, so that it can be resumed.
+    pub fn record_stop(&mut self, process: &SBProcess) -> bool {
+        let mut recorded = false;
+        for thread in process.threads() {
+            match thread.stop_reason() {
+                StopReason::Breakpoint => {
+                    let id = thread.stop_reason_data_at_index(0) as i32;
+                    match self.breakpoints.remove(&id) {
+                        Some(key) => {
+                            if let Some(block) = self.blocks.get_mut(&key) {
+                                block.hit = true;
+                            }
+                            recorded = true;
+                        }
+                        None => return false,
+                    }
+                }
+                StopReason::None | StopReason::Invalid => {}
+                _ => return false,
+            }
+        }
+        recorded
+    }
+
+    /// Resume `process` until it exits or stops for some reason other
+    /// than reaching a block, recording the blocks it reaches.
+    ///
+    /// Returns the state that the process was left in.
+    pub fn run(&mut self, process: &SBProcess) -> Result<StateType, SBError> {
+        loop {
+            process.continue_execution()?;
+            let state = process.state();
+            if state != StateType::Stopped || !self.record_stop(process) {
+                return Ok(state);
+            }
+        }
+    }
+
+    /// The blocks, ordered by module and address.
+    pub fn blocks(&self) -> Vec<CoverageBlock> {
+        self.blocks.values().cloned().collect()
+    }
+
+    /// Whether each block was executed, by file address.
+    ///
+    /// When covering more than one module, use [`blocks`] instead, as
+    /// the file addresses of different modules can be the same.
+    ///
+    /// [`blocks`]: #method.blocks
+    pub fn hits(&self) -> BTreeMap<lldb_addr_t, bool> {
+        self.blocks
+            .values()
+            .map(|block| (block.address, block.hit))
+            .collect()
+    }
+
+    /// Remove the breakpoints for blocks which haven't been reached.
+    pub fn clear_breakpoints(&mut self) {
+        for (id, _) in self.breakpoints.drain() {
+            self.target.delete_breakpoint(id);
+        }
+    }
+}
+
+/// Find the address in the operands of a branch, such as
+/// `0x100003f80 ; <+32> at main.c:5`.
+fn branch_target(operands: &str) -> Option<lldb_addr_t> {
+    let start = operands.find("0x")? + 2;
+    let digits: String = operands[start..]
+        .chars()
+        .take_while(char::is_ascii_hexdigit)
+        .collect();
+    lldb_addr_t::from_str_radix(&digits, 16).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::branch_target;
+
+    #[test]
+    fn branch_targets() {
+        assert_eq!(branch_target("0x100003f80"), Some(0x1_0000_3f80));
+        assert_eq!(
+            branch_target("0x401126 ; <+22> at main.c:5:9"),
+            Some(0x401126)
+        );
+        assert_eq!(branch_target("x8"), None);
+    }
+}
@@ -0,0 +1,679 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! A [Debug Adapter Protocol] server.
+//!
+//! This lets editors which speak the Debug Adapter Protocol, such as
+//! VS Code and neovim, debug programs with LLDB through this crate.
+//! The server is built on a [`DebugSession`] and supports launching
+//! and attaching, source and function breakpoints, stack traces,
+//! scopes and variables, evaluating expressions and stepping.
+//!
+//! A debug adapter usually talks to the editor over its standard
+//! input and output:
+//!
+//! ```no_run
+//! use lldb::dap::DapServer;
+//! use std::io;
+//!
+//! DapServer::new(io::stdin(), io::stdout()).run().unwrap();
+//! ```
+//!
+//! [Debug Adapter Protocol]: https://microsoft.github.io/debug-adapter-protocol/
+//! [`DebugSession`]: ../struct.DebugSession.html
+
+use super::expressionoptions::SBExpressionOptions;
+use super::frame::SBFrame;
+use super::launchinfo::SBLaunchInfo;
+use super::process::SBProcess;
+use super::session::DebugSession;
+use super::thread::{SBThread, StepKind};
+use super::value::SBValue;
+use super::valuelist::SBValueList;
+use super::{lldb_pid_t, RunMode, StopReason};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::sync::{Arc, Mutex};
+
+/// A Debug Adapter Protocol server reading requests from `R` and
+/// writing responses and events to `W`.
+pub struct DapServer<R, W> {
+    input: BufReader<R>,
+    output: Arc<Mutex<Output<W>>>,
+    session: Option<DebugSession>,
+    pending: Option<PendingStart>,
+    source_breakpoints: HashMap<String, Vec<u32>>,
+    function_breakpoints: Vec<String>,
+    breakpoint_ids: HashMap<String, Vec<i32>>,
+    frames: Vec<SBFrame>,
+    variables: Vec<Variables>,
+}
+
+/// How to start the process once the client has finished
+/// configuring the session.
+enum PendingStart {
+    Launch(SBLaunchInfo),
+    Attach(lldb_pid_t),
+}
+
+/// Something which a `variablesReference` refers to.
+enum Variables {
+    List(SBValueList),
+    Children(SBValue),
+}
+
+/// The writing half of the server, shared with the callbacks on the
+/// session's event thread.
+struct Output<W> {
+    writer: W,
+    seq: u64,
+}
+
+impl<W: Write> Output<W> {
+    fn send(&mut self, mut message: Value) -> io::Result<()> {
+        self.seq += 1;
+        message["seq"] = Value::from(self.seq);
+        write_message(&mut self.writer, &message)
+    }
+
+    fn event(&mut self, event: &str, body: Value) -> io::Result<()> {
+        self.send(json!({ "type": "event", "event": event, "body": body }))
+    }
+}
+
+impl<R, W> DapServer<R, W>
+where
+    R: Read,
+    W: Write + Send + 'static,
+{
+    /// Create a server which reads from `input` and writes to
+    /// `output`.
+    pub fn new(input: R, output: W) -> DapServer<R, W> {
+        DapServer {
+            input: BufReader::new(input),
+            output: Arc::new(Mutex::new(Output {
+                writer: output,
+                seq: 0,
+            })),
+            session: None,
+            pending: None,
+            source_breakpoints: HashMap::new(),
+            function_breakpoints: Vec::new(),
+            breakpoint_ids: HashMap::new(),
+            frames: Vec::new(),
+            variables: Vec::new(),
+        }
+    }
+
+    /// Handle requests until the client disconnects or closes the
+    /// input.
+    pub fn run(mut self) -> io::Result<()> {
+        while let Some(request) = read_message(&mut self.input)? {
+            if request["type"] != "request" {
+                continue;
+            }
+            let command = request["command"].as_str().unwrap_or_default().to_string();
+            let arguments = &request["arguments"];
+            let result = self.handle(&command, arguments);
+            let mut response = json!({
+                "type": "response",
+                "request_seq": request["seq"],
+                "command": command,
+                "success": result.is_ok(),
+            });
+            match result {
+                Ok(body) => response["body"] = body,
+                Err(message) => response["message"] = Value::from(message),
+            }
+            self.output.lock().unwrap().send(response)?;
+
+            match command.as_str() {
+                "initialize" => self
+                    .output
+                    .lock()
+                    .unwrap()
+                    .event("initialized", json!({}))?,
+                "disconnect" => break,
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    fn handle(&mut self, command: &str, arguments: &Value) -> Result<Value, String> {
+        match command {
+            "initialize" => Ok(capabilities()),
+            "launch" => self.launch(arguments),
+            "attach" => self.attach(arguments),
+            "configurationDone" => self.configuration_done(),
+            "setBreakpoints" => self.set_breakpoints(arguments),
+            "setFunctionBreakpoints" => self.set_function_breakpoints(arguments),
+            "setExceptionBreakpoints" => Ok(json!({})),
+            "threads" => self.threads(),
+            "stackTrace" => self.stack_trace(arguments),
+            "scopes" => self.scopes(arguments),
+            "variables" => self.variables(arguments),
+            "evaluate" => self.evaluate(arguments),
+            "continue" => self.continue_execution(),
+            "next" => self.step(arguments, StepKind::Over, StepKind::InstructionOver),
+            "stepIn" => self.step(arguments, StepKind::Into, StepKind::Instruction),
+            "stepOut" => self.step(arguments, StepKind::Out, StepKind::Out),
+            "pause" => self.pause(),
+            "disconnect" => self.disconnect(arguments),
+            _ => Err(format!("Unsupported request: {}", command)),
+        }
+    }
+
+    fn launch(&mut self, arguments: &Value) -> Result<Value, String> {
+        let program = arguments["program"]
+            .as_str()
+            .ok_or("No program was given to launch")?;
+        self.start_session(program)?;
+
+        let launch_info = SBLaunchInfo::new();
+        let args = string_array(&arguments["args"]);
+        launch_info.set_arguments(&args.iter().map(|s| &**s).collect::<Vec<_>>(), false);
+        if let Some(cwd) = arguments["cwd"].as_str() {
+            launch_info.set_working_directory(cwd);
+        }
+        if let Some(env) = arguments["env"].as_object() {
+            let environment = env
+                .iter()
+                .filter_map(|(k, v)| v.as_str().map(|v| (k.clone(), v.to_string())))
+                .collect();
+            launch_info.set_environment(&environment, true);
+        }
+        launch_info.set_stop_at_entry(arguments["stopOnEntry"].as_bool().unwrap_or(false));
+        self.pending = Some(PendingStart::Launch(launch_info));
+        Ok(json!({}))
+    }
+
+    fn attach(&mut self, arguments: &Value) -> Result<Value, String> {
+        let pid = arguments["pid"]
+            .as_u64()
+            .ok_or("No pid was given to attach to")?;
+        self.start_session(arguments["program"].as_str().unwrap_or_default())?;
+        self.pending = Some(PendingStart::Attach(pid));
+        Ok(json!({}))
+    }
+
+    /// Create the session, forwarding its callbacks to the client as
+    /// events, and set any breakpoints that arrived before it.
+    fn start_session(&mut self, program: &str) -> Result<(), String> {
+        let session = DebugSession::new(program).map_err(|e| e.to_string())?;
+
+        let output = Arc::clone(&self.output);
+        session.on_stop(move |stop| {
+            let _ = output.lock().unwrap().event(
+                "stopped",
+                json!({
+                    "reason": stop_reason(stop.stop_reason),
+                    "threadId": stop.thread_id,
+                    "allThreadsStopped": true,
+                }),
+            );
+        });
+        for &(category, stderr) in &[("stdout", false), ("stderr", true)] {
+            let output = Arc::clone(&self.output);
+            let callback = move |text: &str| {
+                let _ = output
+                    .lock()
+                    .unwrap()
+                    .event("output", json!({ "category": category, "output": text }));
+            };
+            if stderr {
+                session.on_stderr(callback);
+            } else {
+                session.on_stdout(callback);
+            }
+        }
+        let output = Arc::clone(&self.output);
+        session.on_exit(move |status| {
+            let mut output = output.lock().unwrap();
+            let _ = output.event("exited", json!({ "exitCode": status }));
+            let _ = output.event("terminated", json!({}));
+        });
+
+        self.session = Some(session);
+        let sources: Vec<String> = self.source_breakpoints.keys().cloned().collect();
+        for source in sources {
+            self.create_source_breakpoints(&source);
+        }
+        self.create_function_breakpoints();
+        Ok(())
+    }
+
+    fn configuration_done(&mut self) -> Result<Value, String> {
+        let session = self.session.as_mut().ok_or("No program to debug")?;
+        match self.pending.take() {
+            Some(PendingStart::Launch(launch_info)) => session.start_launch(launch_info),
+            Some(PendingStart::Attach(pid)) => session.start_attach(pid),
+            None => Ok(()),
+        }
+        .map_err(|e| e.to_string())?;
+        Ok(json!({}))
+    }
+
+    fn set_breakpoints(&mut self, arguments: &Value) -> Result<Value, String> {
+        let path = arguments["source"]["path"]
+            .as_str()
+            .ok_or("No source path was given")?
+            .to_string();
+        let lines = arguments["breakpoints"]
+            .as_array()
+            .map(|breakpoints| {
+                breakpoints
+                    .iter()
+                    .filter_map(|b| b["line"].as_u64())
+                    .map(|line| line as u32)
+                    .collect()
+            })
+            .unwrap_or_default();
+        self.source_breakpoints.insert(path.clone(), lines);
+        let breakpoints = self.create_source_breakpoints(&path);
+        Ok(json!({ "breakpoints": breakpoints }))
+    }
+
+    fn set_function_breakpoints(&mut self, arguments: &Value) -> Result<Value, String> {
+        self.function_breakpoints = arguments["breakpoints"]
+            .as_array()
+            .map(|breakpoints| {
+                breakpoints
+                    .iter()
+                    .filter_map(|b| b["name"].as_str())
+                    .map(String::from)
+                    .collect()
+            })
+            .unwrap_or_default();
+        let breakpoints = self.create_function_breakpoints();
+        Ok(json!({ "breakpoints": breakpoints }))
+    }
+
+    /// Replace the breakpoints in the source file at `path` with the
+    /// ones the client asked for, describing the result.
+    fn create_source_breakpoints(&mut self, path: &str) -> Vec<Value> {
+        let lines = self.source_breakpoints[path].clone();
+        let session = match self.session {
+            Some(ref session) => session,
+            None => return lines.iter().map(|&line| unverified(line)).collect(),
+        };
+        for id in self.breakpoint_ids.remove(path).unwrap_or_default() {
+            session.target().delete_breakpoint(id);
+        }
+        let mut ids = Vec::new();
+        let breakpoints = lines
+            .iter()
+            .map(|&line| {
+                let breakpoint = session.break_at_line(path, line);
+                ids.push(breakpoint.id());
+                json!({
+                    "id": breakpoint.id(),
+                    "verified": breakpoint.num_resolved_locations() > 0,
+                    "line": line,
+                })
+            })
+            .collect();
+        self.breakpoint_ids.insert(path.to_string(), ids);
+        breakpoints
+    }
+
+    fn create_function_breakpoints(&mut self) -> Vec<Value> {
+        // Function breakpoints are kept under a key which can't be a
+        // source path.
+        let key = String::new();
+        let session = match self.session {
+            Some(ref session) => session,
+            None => {
+                return self
+                    .function_breakpoints
+                    .iter()
+                    .map(|_| json!({ "verified": false }))
+                    .collect()
+            }
+        };
+        for id in self.breakpoint_ids.remove(&key).unwrap_or_default() {
+            session.target().delete_breakpoint(id);
+        }
+        let mut ids = Vec::new();
+        let breakpoints = self
+            .function_breakpoints
+            .iter()
+            .map(|name| {
+                let breakpoint = session.break_at_function(name);
+                ids.push(breakpoint.id());
+                json!({
+                    "id": breakpoint.id(),
+                    "verified": breakpoint.num_resolved_locations() > 0,
+                })
+            })
+            .collect();
+        self.breakpoint_ids.insert(key, ids);
+        breakpoints
+    }
+
+    fn process(&self) -> Result<&SBProcess, String> {
+        self.session
+            .as_ref()
+            .and_then(DebugSession::process)
+            .ok_or_else(|| "No process is being debugged".to_string())
+    }
+
+    fn thread(&self, arguments: &Value) -> Result<SBThread, String> {
+        let process = self.process()?;
+        match arguments["threadId"].as_u64() {
+            Some(thread_id) => process
+                .thread_by_id(thread_id)
+                .ok_or_else(|| format!("No thread with ID {}", thread_id)),
+            None => process
+                .selected_thread()
+                .ok_or_else(|| "No thread is selected".to_string()),
+        }
+    }
+
+    fn threads(&self) -> Result<Value, String> {
+        let threads: Vec<Value> = match self.process() {
+            Ok(process) => process
+                .threads()
+                .map(|thread| {
+                    let name = thread
+                        .name()
+                        .unwrap_or_else(|| format!("Thread #{}", thread.index_id()));
+                    json!({ "id": thread.thread_id(), "name": name })
+                })
+                .collect(),
+            Err(_) => Vec::new(),
+        };
+        Ok(json!({ "threads": threads }))
+    }
+
+    fn stack_trace(&mut self, arguments: &Value) -> Result<Value, String> {
+        let thread = self.thread(arguments)?;
+        let start = arguments["startFrame"].as_u64().unwrap_or(0) as usize;
+        let levels = match arguments["levels"].as_u64() {
+            Some(levels) if levels > 0 => levels as usize,
+            _ => usize::MAX,
+        };
+        let frames: Vec<SBFrame> = thread.frames().collect();
+        let total = frames.len();
+        let stack_frames: Vec<Value> = frames
+            .into_iter()
+            .skip(start)
+            .take(levels)
+            .map(|frame| {
+                let pc = frame.pc();
+                let name = frame
+                    .display_function_name()
+                    .unwrap_or_else(|| format!("{:#x}", pc));
+                let mut stack_frame = json!({
+                    "name": name,
+                    "line": 0,
+                    "column": 0,
+                    "instructionPointerReference": format!("{:#x}", pc),
+                });
+                if let Some(line_entry) = frame.line_entry() {
+                    let file = line_entry.filespec();
+                    stack_frame["line"] = Value::from(line_entry.line());
+                    stack_frame["column"] = Value::from(line_entry.column());
+                    stack_frame["source"] = json!({
+                        "name": file.filename(),
+                        "path": file.path(),
+                    });
+                }
+                self.frames.push(frame);
+                stack_frame["id"] = Value::from(self.frames.len());
+                stack_frame
+            })
+            .collect();
+        Ok(json!({ "stackFrames": stack_frames, "totalFrames": total }))
+    }
+
+    fn frame(&self, frame_id: u64) -> Result<&SBFrame, String> {
+        (frame_id as usize)
+            .checked_sub(1)
+            .and_then(|index| self.frames.get(index))
+            .ok_or_else(|| format!("No frame with ID {}", frame_id))
+    }
+
+    fn scopes(&mut self, arguments: &Value) -> Result<Value, String> {
+        let frame = self
+            .frame(arguments["frameId"].as_u64().unwrap_or(0))?
+            .clone();
+        let scopes: Vec<Value> = vec![
+            ("Locals", "locals", frame.locals()),
+            ("Arguments", "arguments", frame.arguments()),
+            ("Registers", "registers", frame.registers()),
+        ]
+        .into_iter()
+        .map(|(name, hint, values)| {
+            json!({
+                "name": name,
+                "presentationHint": hint,
+                "variablesReference": self.add_variables(Variables::List(values)),
+                "expensive": false,
+            })
+        })
+        .collect();
+        Ok(json!({ "scopes": scopes }))
+    }
+
+    fn variables(&mut self, arguments: &Value) -> Result<Value, String> {
+        let reference = arguments["variablesReference"].as_u64().unwrap_or(0);
+        let values: Vec<SBValue> = match (reference as usize)
+            .checked_sub(1)
+            .and_then(|index| self.variables.get(index))
+        {
+            Some(Variables::List(list)) => list.iter().collect(),
+            Some(Variables::Children(value)) => (0..value.num_children())
+                .filter_map(|idx| value.child_at_index(idx))
+                .collect(),
+            None => return Err(format!("No variables with reference {}", reference)),
+        };
+        let variables: Vec<Value> = values
+            .into_iter()
+            .map(|value| {
+                let name = value.name();
+                let mut variable = self.describe_value(value);
+                variable["name"] = Value::from(name.unwrap_or_default());
+                variable
+            })
+            .collect();
+        Ok(json!({ "variables": variables }))
+    }
+
+    fn evaluate(&mut self, arguments: &Value) -> Result<Value, String> {
+        let expression = arguments["expression"]
+            .as_str()
+            .ok_or("No expression was given")?;
+        let value = match arguments["frameId"].as_u64() {
+            Some(frame_id) => {
+                let frame = self.frame(frame_id)?;
+                frame.evaluate_expression(expression, &frame.expression_options())
+            }
+            None => self
+                .session
+                .as_ref()
+                .ok_or("No program to debug")?
+                .target()
+                .evaluate_expression(expression, &SBExpressionOptions::new()),
+        };
+        if let Some(error) = value.error() {
+            if error.is_failure() {
+                return Err(error.to_string());
+            }
+        }
+        let mut result = self.describe_value(value);
+        result["result"] = result["value"].take();
+        Ok(result)
+    }
+
+    /// Describe `value` as a DAP `Variable`, without its name.
+    fn describe_value(&mut self, value: SBValue) -> Value {
+        let display = match (value.value(), value.summary()) {
+            (Some(value), Some(summary)) => format!("{} {}", value, summary),
+            (Some(text), None) | (None, Some(text)) => text,
+            (None, None) => String::new(),
+        };
+        let mut description = json!({
+            "value": display,
+            "type": value.display_type_name(),
+            "variablesReference": 0,
+        });
+        if value.num_children() > 0 {
+            description["variablesReference"] =
+                Value::from(self.add_variables(Variables::Children(value)));
+        }
+        description
+    }
+
+    fn add_variables(&mut self, variables: Variables) -> usize {
+        self.variables.push(variables);
+        self.variables.len()
+    }
+
+    /// Forget the frames and variables handed out to the client,
+    /// which are no longer valid once the process resumes.
+    fn resumed(&mut self) {
+        self.frames.clear();
+        self.variables.clear();
+    }
+
+    fn continue_execution(&mut self) -> Result<Value, String> {
+        self.process()?
+            .continue_execution()
+            .map_err(|e| e.to_string())?;
+        self.resumed();
+        Ok(json!({ "allThreadsContinued": true }))
+    }
+
+    fn step(
+        &mut self,
+        arguments: &Value,
+        line_kind: StepKind,
+        instruction_kind: StepKind,
+    ) -> Result<Value, String> {
+        let kind = if arguments["granularity"] == "instruction" {
+            instruction_kind
+        } else {
+            line_kind
+        };
+        self.thread(arguments)?
+            .step(kind, RunMode::OnlyDuringStepping)
+            .map_err(|e| e.to_string())?;
+        self.resumed();
+        Ok(json!({}))
+    }
+
+    fn pause(&mut self) -> Result<Value, String> {
+        self.process()?.stop().map_err(|e| e.to_string())?;
+        Ok(json!({}))
+    }
+
+    fn disconnect(&mut self, arguments: &Value) -> Result<Value, String> {
+        if let Some(mut session) = self.session.take() {
+            if session.process().is_some() {
+                // Without `terminateDebuggee`, dropping the session
+                // kills a launched process and detaches from an
+                // attached one.
+                match arguments["terminateDebuggee"].as_bool() {
+                    Some(true) => session.kill().map_err(|e| e.to_string())?,
+                    Some(false) => session.detach().map_err(|e| e.to_string())?,
+                    None => {}
+                }
+            }
+        }
+        self.resumed();
+        Ok(json!({}))
+    }
+}
+
+fn capabilities() -> Value {
+    json!({
+        "supportsConfigurationDoneRequest": true,
+        "supportsFunctionBreakpoints": true,
+        "supportsEvaluateForHovers": true,
+        "supportsSteppingGranularity": true,
+        "supportTerminateDebuggee": true,
+    })
+}
+
+fn stop_reason(reason: StopReason) -> &'static str {
+    match reason {
+        StopReason::Breakpoint => "breakpoint",
+        StopReason::PlanComplete | StopReason::Trace => "step",
+        StopReason::Signal | StopReason::Exception => "exception",
+        _ => "pause",
+    }
+}
+
+fn string_array(value: &Value) -> Vec<String> {
+    value
+        .as_array()
+        .map(|values| {
+            values
+                .iter()
+                .filter_map(Value::as_str)
+                .map(String::from)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn unverified(line: u32) -> Value {
+    json!({ "verified": false, "line": line })
+}
+
+/// Read a message framed with a `Content-Length` header, returning
+/// `None` at the end of the input.
+fn read_message<R: BufRead>(input: &mut R) -> io::Result<Option<Value>> {
+    let mut content_length = None;
+    loop {
+        let mut line = String::new();
+        if input.read_line(&mut line)? == 0 {
+            return Ok(None);
+        }
+        let line = line.trim_end();
+        if line.is_empty() {
+            break;
+        }
+        if let Some(length) = line.strip_prefix("Content-Length:") {
+            content_length = length.trim().parse::<usize>().ok();
+        }
+    }
+    let content_length = content_length
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "missing Content-Length"))?;
+    let mut body = vec![0; content_length];
+    input.read_exact(&mut body)?;
+    serde_json::from_slice(&body)
+        .map(Some)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+fn write_message<W: Write>(output: &mut W, message: &Value) -> io::Result<()> {
+    let body = message.to_string();
+    write!(output, "Content-Length: {}\r\n\r\n{}", body.len(), body)?;
+    output.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{read_message, write_message};
+    use serde_json::json;
+    use std::io::Cursor;
+
+    #[test]
+    fn framing() {
+        let message = json!({ "seq": 1, "type": "request", "command": "threads" });
+        let mut buffer = Vec::new();
+        write_message(&mut buffer, &message).unwrap();
+        write_message(&mut buffer, &message).unwrap();
+
+        let mut input = Cursor::new(buffer);
+        assert_eq!(read_message(&mut input).unwrap(), Some(message.clone()));
+        assert_eq!(read_message(&mut input).unwrap(), Some(message));
+        assert_eq!(read_message(&mut input).unwrap(), None);
+    }
+}
@@ -4,25 +4,167 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
+use super::error::{InvalidRefError, SBError};
+use super::stream::SBStream;
+use super::{lldb_offset_t, ByteOrder};
+use libc::c_void;
+use std::convert::TryFrom;
+use std::ffi::CString;
+use std::fmt;
+use std::sync::{Arc, Mutex};
 use sys;
 
 /// A block of data.
-#[derive(Debug)]
+///
+/// An `SBData` can be created from Rust data with an explicit byte
+/// order and address size. The data is always copied, into a buffer
+/// owned by LLDB or by the `SBData` itself, so the Rust data does not
+/// need to outlive the `SBData`:
+///
+/// ```no_run
+/// # use lldb::{ByteOrder, SBData, SBTarget};
+/// # fn make_value(target: &SBTarget) {
+/// let data = SBData::from_u64(0x1234, ByteOrder::Little, 8);
+/// let int_type = target.find_first_type("int").unwrap();
+/// let value = target.create_value_from_data("answer", &data, &int_type);
+/// # }
+/// ```
 pub struct SBData {
     /// The underlying raw `SBDataRef`.
     pub raw: sys::SBDataRef,
+    /// Copies of bytes given to [`set_bytes`], which LLDB refers to
+    /// rather than copying. Clones share the LLDB data, and so these.
+    ///
+    /// [`set_bytes`]: #method.set_bytes
+    buffers: Arc<Mutex<Vec<Arc<[u8]>>>>,
 }
 
 impl SBData {
+    /// Construct a new, empty `SBData`.
+    pub fn new() -> SBData {
+        unsafe { SBData::wrap(sys::CreateSBData()) }
+    }
+
+    /// Construct a new `SBData` containing a copy of `bytes`.
+    pub fn from_bytes(
+        bytes: &[u8],
+        byte_order: ByteOrder,
+        address_byte_size: u8,
+    ) -> Result<SBData, SBError> {
+        let data = SBData::new();
+        data.set_bytes(bytes, byte_order, address_byte_size)?;
+        Ok(data)
+    }
+
+    /// Construct a new `SBData` containing `value`, encoded with
+    /// the given byte order.
+    pub fn from_u64(value: u64, byte_order: ByteOrder, address_byte_size: u8) -> SBData {
+        let bytes = match byte_order {
+            ByteOrder::Big => value.to_be_bytes(),
+            _ => value.to_le_bytes(),
+        };
+        SBData::from_bytes(&bytes, byte_order, address_byte_size)
+            .expect("Setting data from a buffer should not fail")
+    }
+
+    /// Construct a new `SBData` containing a copy of `string`
+    /// followed by a terminating NUL byte.
+    pub fn from_string(string: &str, byte_order: ByteOrder, address_byte_size: u8) -> SBData {
+        let string = CString::new(string).unwrap();
+        unsafe {
+            SBData::wrap(sys::SBDataCreateDataFromCString(
+                byte_order,
+                u32::from(address_byte_size),
+                string.as_ptr(),
+            ))
+        }
+    }
+
+    /// Construct a new `SBData` containing a copy of `values`.
+    pub fn from_u64_slice(values: &[u64], byte_order: ByteOrder, address_byte_size: u8) -> SBData {
+        unsafe {
+            SBData::wrap(sys::SBDataCreateDataFromUInt64Array(
+                byte_order,
+                u32::from(address_byte_size),
+                values.as_ptr() as *mut _,
+                values.len(),
+            ))
+        }
+    }
+
+    /// Construct a new `SBData` containing a copy of `values`.
+    pub fn from_u32_slice(values: &[u32], byte_order: ByteOrder, address_byte_size: u8) -> SBData {
+        unsafe {
+            SBData::wrap(sys::SBDataCreateDataFromUInt32Array(
+                byte_order,
+                u32::from(address_byte_size),
+                values.as_ptr() as *mut _,
+                values.len(),
+            ))
+        }
+    }
+
+    /// Construct a new `SBData` containing a copy of `values`.
+    pub fn from_i64_slice(values: &[i64], byte_order: ByteOrder, address_byte_size: u8) -> SBData {
+        unsafe {
+            SBData::wrap(sys::SBDataCreateDataFromSInt64Array(
+                byte_order,
+                u32::from(address_byte_size),
+                values.as_ptr() as *mut _,
+                values.len(),
+            ))
+        }
+    }
+
+    /// Construct a new `SBData` containing a copy of `values`.
+    pub fn from_i32_slice(values: &[i32], byte_order: ByteOrder, address_byte_size: u8) -> SBData {
+        unsafe {
+            SBData::wrap(sys::SBDataCreateDataFromSInt32Array(
+                byte_order,
+                u32::from(address_byte_size),
+                values.as_ptr() as *mut _,
+                values.len(),
+            ))
+        }
+    }
+
+    /// Construct a new `SBData` containing a copy of `values`.
+    pub fn from_f64_slice(values: &[f64], byte_order: ByteOrder, address_byte_size: u8) -> SBData {
+        unsafe {
+            SBData::wrap(sys::SBDataCreateDataFromDoubleArray(
+                byte_order,
+                u32::from(address_byte_size),
+                values.as_ptr() as *mut _,
+                values.len(),
+            ))
+        }
+    }
+
     /// Construct a new `SBData`.
-    pub fn wrap(raw: sys::SBDataRef) -> SBData {
-        SBData { raw }
+    ///
+    /// # Safety
+    ///
+    /// `raw` must be a valid reference, which is disposed of when the
+    /// `SBData` is dropped. Use [`maybe_wrap`] when it may be invalid.
+    ///
+    /// [`maybe_wrap`]: #method.maybe_wrap
+    pub unsafe fn wrap(raw: sys::SBDataRef) -> SBData {
+        SBData {
+            raw,
+            buffers: Arc::default(),
+        }
     }
 
     /// Construct a new `Some(SBData)` or `None`.
+    ///
+    /// `raw` is disposed of if it is invalid.
     pub fn maybe_wrap(raw: sys::SBDataRef) -> Option<SBData> {
-        if unsafe { sys::SBDataIsValid(raw) != 0 } {
-            Some(SBData { raw })
+        let wrapped = SBData {
+            raw,
+            buffers: Arc::default(),
+        };
+        if wrapped.is_valid() {
+            Some(wrapped)
         } else {
             None
         }
@@ -32,16 +174,149 @@ impl SBData {
     pub fn is_valid(&self) -> bool {
         unsafe { sys::SBDataIsValid(self.raw) != 0 }
     }
+
+    /// Replace the contents of this `SBData` with a copy of `bytes`.
+    ///
+    /// LLDB only keeps a pointer to the bytes it is given here, so the
+    /// copy is owned by this `SBData`, and by its clones.
+    pub fn set_bytes(
+        &self,
+        bytes: &[u8],
+        byte_order: ByteOrder,
+        address_byte_size: u8,
+    ) -> Result<(), SBError> {
+        let buffer: Arc<[u8]> = Arc::from(bytes);
+        let error = SBError::new();
+        unsafe {
+            sys::SBDataSetData(
+                self.raw,
+                error.raw,
+                buffer.as_ptr() as *mut _,
+                buffer.len(),
+                byte_order,
+                address_byte_size,
+            )
+        };
+        if error.is_success() {
+            *self.buffers.lock().unwrap() = vec![buffer];
+            Ok(())
+        } else {
+            Err(error)
+        }
+    }
+
+    /// Copy the data starting at `offset` into `buffer` with a single
+    /// call into LLDB.
+    ///
+    /// This fails if there are fewer than `buffer.len()` bytes after
+    /// `offset`.
+    pub fn read_raw_data(&self, offset: lldb_offset_t, buffer: &mut [u8]) -> Result<(), SBError> {
+        let error = SBError::new();
+        unsafe {
+            sys::SBDataReadRawData(
+                self.raw,
+                error.raw,
+                offset,
+                buffer.as_mut_ptr() as *mut c_void,
+                buffer.len(),
+            )
+        };
+        if error.is_success() {
+            Ok(())
+        } else {
+            Err(error)
+        }
+    }
+
+    /// Copy all of the data into a `Vec`.
+    ///
+    /// This is much faster than reading the data an element at a time
+    /// for large arrays.
+    pub fn to_bytes(&self) -> Result<Vec<u8>, SBError> {
+        let mut bytes = vec![0; self.byte_size()];
+        if !bytes.is_empty() {
+            self.read_raw_data(0, &mut bytes)?;
+        }
+        Ok(bytes)
+    }
+
+    /// Append the contents of another `SBData` to this one.
+    pub fn append(&self, other: &SBData) -> bool {
+        if unsafe { sys::SBDataAppend(self.raw, other.raw) != 0 } {
+            // Appending to empty data refers to the other data rather
+            // than copying it.
+            if !Arc::ptr_eq(&self.buffers, &other.buffers) {
+                let other_buffers = other.buffers.lock().unwrap().clone();
+                self.buffers.lock().unwrap().extend(other_buffers);
+            }
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Clear the contents of this `SBData`.
+    pub fn clear(&self) {
+        unsafe { sys::SBDataClear(self.raw) };
+    }
+
+    /// The size of the data, in bytes.
+    pub fn byte_size(&self) -> usize {
+        unsafe { sys::SBDataGetByteSize(self.raw) as usize }
+    }
+
+    /// The byte order of the data.
+    pub fn byte_order(&self) -> ByteOrder {
+        unsafe { sys::SBDataGetByteOrder(self.raw) }
+    }
+
+    /// Set the byte order of the data.
+    pub fn set_byte_order(&self, byte_order: ByteOrder) {
+        unsafe { sys::SBDataSetByteOrder(self.raw, byte_order) };
+    }
+
+    /// The size of an address, in bytes, within the data.
+    pub fn address_byte_size(&self) -> u8 {
+        unsafe { sys::SBDataGetAddressByteSize(self.raw) }
+    }
+
+    /// Set the size of an address, in bytes, within the data.
+    pub fn set_address_byte_size(&self, address_byte_size: u8) {
+        unsafe { sys::SBDataSetAddressByteSize(self.raw, address_byte_size) };
+    }
 }
 
 impl Clone for SBData {
     fn clone(&self) -> SBData {
         SBData {
             raw: unsafe { sys::CloneSBData(self.raw) },
+            buffers: Arc::clone(&self.buffers),
         }
     }
 }
 
+impl Default for SBData {
+    fn default() -> SBData {
+        SBData::new()
+    }
+}
+
+impl fmt::Debug for SBData {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        let stream = SBStream::new();
+        unsafe { sys::SBDataGetDescription(self.raw, stream.raw, 0) };
+        write!(fmt, "SBData {{ {} }}", stream.data())
+    }
+}
+
+impl TryFrom<sys::SBDataRef> for SBData {
+    type Error = InvalidRefError;
+
+    fn try_from(raw: sys::SBDataRef) -> Result<SBData, InvalidRefError> {
+        SBData::maybe_wrap(raw).ok_or(InvalidRefError)
+    }
+}
+
 impl Drop for SBData {
     fn drop(&mut self) {
         unsafe { sys::DisposeSBData(self.raw) };
@@ -49,4 +324,25 @@ impl Drop for SBData {
 }
 
 unsafe impl Send for SBData {}
-unsafe impl Sync for SBData {}
+
+#[cfg(test)]
+mod tests {
+    use super::SBData;
+    use ByteOrder;
+
+    #[test]
+    fn keeps_a_copy_of_the_bytes() {
+        let bytes = vec![1, 2, 3, 4, 5];
+        let data = SBData::from_bytes(&bytes, ByteOrder::Little, 8).unwrap();
+        drop(bytes);
+        let clone = data.clone();
+        drop(data);
+        assert_eq!(clone.to_bytes().unwrap(), vec![1, 2, 3, 4, 5]);
+
+        let data = SBData::from_u64(0x1122_3344_5566_7788, ByteOrder::Little, 8);
+        assert_eq!(
+            data.to_bytes().unwrap(),
+            vec![0x88, 0x77, 0x66, 0x55, 0x44, 0x33, 0x22, 0x11]
+        );
+    }
+}
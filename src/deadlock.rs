@@ -0,0 +1,327 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Finding threads which are deadlocked on each other.
+//!
+//! [`analyze`] looks at the backtrace of every thread of a stopped
+//! process to find those which are blocked waiting for a lock, works
+//! out which thread holds each lock where it can, and reports the
+//! cycles in the resulting wait-for graph:
+//!
+//! ```no_run
+//! # use lldb::SBProcess;
+//! # use lldb::deadlock;
+//! # fn check(process: &SBProcess) {
+//! let report = deadlock::analyze(process);
+//! for cycle in &report.cycles {
+//!     println!("Deadlock between threads {:?}", cycle);
+//! }
+//! for thread in &report.blocked {
+//!     println!("{:#x} waits on {:?} held by {:?}", thread.thread_id, thread.lock, thread.owner);
+//! }
+//! # }
+//! ```
+//!
+//! Blocked threads are recognized by the names of the functions they
+//! are waiting in, covering the pthread functions of glibc and macOS
+//! and the locks of the Rust standard library.
+//!
+//! The owner of a lock can only be found for glibc's
+//! `pthread_mutex_t` and `pthread_rwlock_t`, which record the thread
+//! holding them for writing, and only when the address of the lock can
+//! be found from a `mutex` or `rwlock` variable in the waiting frames,
+//! which needs debug information for libc. Other locks don't record
+//! their owner, so threads waiting on them are reported without one
+//! and can't be part of a cycle.
+
+use super::expressionoptions::SBExpressionOptions;
+use super::frame::{FrameInfo, SBFrame};
+use super::process::SBProcess;
+use super::thread::SBThread;
+use super::{lldb_addr_t, lldb_tid_t, ByteOrder};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// The number of frames above the waiting function to search for the
+/// lock being waited on.
+const LOCK_SEARCH_DEPTH: usize = 4;
+
+/// The offset of `__owner` in glibc's `pthread_mutex_t`.
+const GLIBC_MUTEX_OWNER_OFFSET: lldb_addr_t = 8;
+
+/// The threads of a process which are blocked, and the deadlocks
+/// between them.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DeadlockReport {
+    /// The threads which are waiting on a lock or condition variable.
+    pub blocked: Vec<BlockedThread>,
+    /// The cycles of threads each waiting on a lock held by the next,
+    /// starting from the thread with the lowest ID.
+    pub cycles: Vec<Vec<lldb_tid_t>>,
+}
+
+/// A thread which is blocked waiting on a lock.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BlockedThread {
+    /// The ID of the thread.
+    pub thread_id: lldb_tid_t,
+    /// The index ID of the thread, as shown by `thread list`.
+    pub index_id: u32,
+    /// The name of the thread, if it has one.
+    pub name: Option<String>,
+    /// What the thread is waiting on.
+    pub kind: WaitKind,
+    /// The index of the frame of the function which is waiting.
+    pub wait_frame: u32,
+    /// The address of the lock, if it could be found.
+    pub lock: Option<lldb_addr_t>,
+    /// The ID of the thread holding the lock, if it could be found.
+    pub owner: Option<lldb_tid_t>,
+    /// The backtrace of the thread.
+    pub frames: Vec<FrameInfo>,
+}
+
+/// What a [`BlockedThread`] is waiting on.
+///
+/// [`BlockedThread`]: struct.BlockedThread.html
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WaitKind {
+    /// A mutex.
+    Mutex,
+    /// A reader-writer lock.
+    RwLock,
+    /// A condition variable.
+    CondVar,
+    /// Another thread to exit.
+    Join,
+}
+
+impl DeadlockReport {
+    /// Is any thread part of a deadlock?
+    pub fn is_deadlocked(&self) -> bool {
+        !self.cycles.is_empty()
+    }
+}
+
+/// Find the blocked threads of `process`, which must be stopped, and
+/// the deadlocks between them.
+pub fn analyze(process: &SBProcess) -> DeadlockReport {
+    let blocked: Vec<BlockedThread> = process
+        .threads()
+        .filter_map(|thread| blocked_thread(process, &thread))
+        .collect();
+    let waits_for = blocked
+        .iter()
+        .filter_map(|thread| thread.owner.map(|owner| (thread.thread_id, owner)))
+        .collect();
+    DeadlockReport {
+        cycles: find_cycles(&waits_for),
+        blocked,
+    }
+}
+
+fn blocked_thread(process: &SBProcess, thread: &SBThread) -> Option<BlockedThread> {
+    let frames = thread.collect_frames();
+    let (wait_frame, kind) = frames.iter().find_map(|frame| {
+        frame
+            .function_name
+            .as_ref()
+            .and_then(|name| wait_kind(name))
+            .map(|kind| (frame.index, kind))
+    })?;
+    let (lock, owner) = match kind {
+        WaitKind::Mutex | WaitKind::RwLock => {
+            let sbframes: Vec<SBFrame> = thread
+                .frames()
+                .take(wait_frame as usize + LOCK_SEARCH_DEPTH)
+                .collect();
+            match find_lock(&sbframes, kind) {
+                Some((address, frame)) => {
+                    (Some(address), lock_owner(process, &frame, kind, address))
+                }
+                None => (None, None),
+            }
+        }
+        WaitKind::CondVar | WaitKind::Join => (None, None),
+    };
+    Some(BlockedThread {
+        thread_id: thread.thread_id(),
+        index_id: thread.index_id(),
+        name: thread.name(),
+        kind,
+        wait_frame,
+        lock,
+        owner,
+        frames,
+    })
+}
+
+/// What a thread waiting in the function `name` is waiting on, if
+/// the function is one which blocks.
+fn wait_kind(name: &str) -> Option<WaitKind> {
+    const WAITS: &[(&str, WaitKind)] = &[
+        // glibc
+        ("__lll_lock_wait", WaitKind::Mutex),
+        ("pthread_mutex_lock", WaitKind::Mutex),
+        ("__pthread_mutex_lock", WaitKind::Mutex),
+        ("pthread_mutex_timedlock", WaitKind::Mutex),
+        ("pthread_rwlock_rdlock", WaitKind::RwLock),
+        ("pthread_rwlock_wrlock", WaitKind::RwLock),
+        ("pthread_cond_wait", WaitKind::CondVar),
+        ("pthread_cond_timedwait", WaitKind::CondVar),
+        ("pthread_join", WaitKind::Join),
+        // macOS
+        ("__psynch_mutexwait", WaitKind::Mutex),
+        ("__psynch_rw_rdlock", WaitKind::RwLock),
+        ("__psynch_rw_wrlock", WaitKind::RwLock),
+        ("__psynch_cvwait", WaitKind::CondVar),
+        // The Rust standard library
+        ("::Mutex::lock_contended", WaitKind::Mutex),
+        ("::RwLock::read_contended", WaitKind::RwLock),
+        ("::RwLock::write_contended", WaitKind::RwLock),
+        ("::Condvar::wait", WaitKind::CondVar),
+        ("::JoinInner<T>::join", WaitKind::Join),
+    ];
+    // Rust names are matched by their path, ignoring the crate and
+    // module, and C names exactly, ignoring any symbol version.
+    let name = name.split('@').next().unwrap_or(name);
+    WAITS
+        .iter()
+        .find(|&&(function, _)| {
+            if function.starts_with("::") {
+                name.contains(function)
+            } else {
+                name == function
+            }
+        })
+        .map(|&(_, kind)| kind)
+}
+
+/// Find the address of the lock being waited on from the variables of
+/// the frames of a waiting thread.
+fn find_lock(frames: &[SBFrame], kind: WaitKind) -> Option<(lldb_addr_t, SBFrame)> {
+    let variable = match kind {
+        WaitKind::RwLock => "rwlock",
+        _ => "mutex",
+    };
+    frames.iter().find_map(|frame| {
+        frame
+            .find_variable(variable)
+            .and_then(|value| value.value_as_unsigned().ok())
+            .filter(|&address| address != 0)
+            .map(|address| (address, frame.clone()))
+    })
+}
+
+/// Find the thread which holds the lock at `address`.
+fn lock_owner(
+    process: &SBProcess,
+    frame: &SBFrame,
+    kind: WaitKind,
+    address: lldb_addr_t,
+) -> Option<lldb_tid_t> {
+    let expression = match kind {
+        WaitKind::RwLock => format!("((pthread_rwlock_t *){:#x})->__data.__cur_writer", address),
+        _ => format!("((pthread_mutex_t *){:#x})->__data.__owner", address),
+    };
+    let owner = frame
+        .evaluate_expression(&expression, &SBExpressionOptions::new())
+        .value_as_signed()
+        .ok()
+        .or_else(|| {
+            // Without the libc headers' types, read the field directly.
+            if kind != WaitKind::Mutex || !is_glibc(process) {
+                return None;
+            }
+            let mut buffer = [0; 4];
+            match process.read_memory(address + GLIBC_MUTEX_OWNER_OFFSET, &mut buffer) {
+                Ok(4) => Some(i64::from(match process.byte_order() {
+                    ByteOrder::Big => i32::from_be_bytes(buffer),
+                    _ => i32::from_le_bytes(buffer),
+                })),
+                _ => None,
+            }
+        })?;
+    if owner > 0 {
+        Some(owner as lldb_tid_t)
+    } else {
+        None
+    }
+}
+
+fn is_glibc(process: &SBProcess) -> bool {
+    process
+        .target()
+        .triple()
+        .is_some_and(|triple| triple.contains("linux"))
+}
+
+/// Find the cycles in a wait-for graph, where each thread waits on at
+/// most one other.
+fn find_cycles(waits_for: &BTreeMap<lldb_tid_t, lldb_tid_t>) -> Vec<Vec<lldb_tid_t>> {
+    let mut cycles = Vec::new();
+    let mut visited = BTreeSet::new();
+    for &start in waits_for.keys() {
+        let mut path = Vec::new();
+        let mut thread = start;
+        while visited.insert(thread) {
+            path.push(thread);
+            match waits_for.get(&thread) {
+                Some(&next) => thread = next,
+                None => break,
+            }
+        }
+        // A cycle is only new if it closes on this path, rather than
+        // on one walked from an earlier start.
+        if let Some(pos) = path.iter().position(|&t| t == thread) {
+            let mut cycle = path.split_off(pos);
+            let min = cycle
+                .iter()
+                .enumerate()
+                .min_by_key(|&(_, t)| t)
+                .map(|(i, _)| i);
+            cycle.rotate_left(min.unwrap_or(0));
+            cycles.push(cycle);
+        }
+    }
+    cycles
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{find_cycles, wait_kind, WaitKind};
+
+    #[test]
+    fn wait_kinds() {
+        assert_eq!(wait_kind("__lll_lock_wait"), Some(WaitKind::Mutex));
+        assert_eq!(
+            wait_kind("pthread_cond_wait@@GLIBC_2.3.2"),
+            Some(WaitKind::CondVar)
+        );
+        assert_eq!(
+            wait_kind("std::sys::sync::mutex::futex::Mutex::lock_contended"),
+            Some(WaitKind::Mutex)
+        );
+        assert_eq!(wait_kind("pthread_mutex_unlock"), None);
+        assert_eq!(wait_kind("main"), None);
+    }
+
+    #[test]
+    fn cycles() {
+        let waits_for = vec![
+            (1, 2),
+            (2, 3),
+            (3, 2),
+            (4, 1),
+            (5, 6),
+            (7, 9),
+            (9, 8),
+            (8, 7),
+        ]
+        .into_iter()
+        .collect();
+        assert_eq!(find_cycles(&waits_for), vec![vec![2, 3], vec![7, 9, 8]]);
+    }
+}
@@ -5,16 +5,35 @@
 // except according to those terms.
 
 use super::commandinterpreter::SBCommandInterpreter;
+use super::commandreturnobject::SBCommandReturnObject;
+use super::demangle;
 use super::error::SBError;
+#[cfg(unix)]
+use super::event::SBEvent;
+use super::filespec::SBFileSpec;
 use super::listener::SBListener;
 use super::platform::SBPlatform;
+#[cfg(unix)]
+use super::process::SBProcess;
+use super::sourcemanager::SBSourceManager;
 use super::stream::SBStream;
+use super::stringlist::SBStringList;
+use super::strings;
 use super::structureddata::SBStructuredData;
 use super::target::SBTarget;
-use std::ffi::{CStr, CString};
+use super::trace::traced;
+use std::convert::TryFrom;
+use std::ffi::CString;
 use std::fmt;
+use std::fs;
 use std::iter;
+use std::os::raw::c_int;
+#[cfg(unix)]
+use std::os::unix::io::{AsRawFd, IntoRawFd as IntoRawFile, RawFd};
+#[cfg(windows)]
+use std::os::windows::io::IntoRawHandle as IntoRawFile;
 use std::ptr;
+use std::time::Duration;
 use sys;
 
 /// Creates [`SBTarget`]s, provides access to them and manages
@@ -161,6 +180,31 @@ impl SBDebugger {
         }
     }
 
+    /// Destroy a debugger, along with its targets.
+    ///
+    /// Dropping an `SBDebugger` only drops the handle: LLDB keeps the
+    /// debugger itself alive until it is destroyed.
+    pub fn destroy(debugger: &SBDebugger) {
+        unsafe { sys::SBDebuggerDestroy(debugger.raw) };
+    }
+
+    /// The unique ID of this debugger.
+    pub fn id(&self) -> u64 {
+        unsafe { sys::SBDebuggerGetID(self.raw) }
+    }
+
+    /// The debugger with the ID `id`, if it hasn't been destroyed.
+    pub fn find_debugger_with_id(id: u64) -> Option<SBDebugger> {
+        let id = c_int::try_from(id).ok()?;
+        let raw = unsafe { sys::SBDebuggerFindDebuggerWithID(id) };
+        if unsafe { sys::SBDebuggerIsValid(raw) != 0 } {
+            Some(SBDebugger { raw })
+        } else {
+            unsafe { sys::DisposeSBDebugger(raw) };
+            None
+        }
+    }
+
     /// Get whether or not the debugger is in async mode.
     ///
     /// When in async mode, the debugger returns immediately when
@@ -181,7 +225,89 @@ impl SBDebugger {
 
     #[allow(missing_docs)]
     pub fn command_interpreter(&self) -> SBCommandInterpreter {
-        SBCommandInterpreter::wrap(unsafe { sys::SBDebuggerGetCommandInterpreter(self.raw) })
+        unsafe { SBCommandInterpreter::wrap(sys::SBDebuggerGetCommandInterpreter(self.raw)) }
+    }
+
+    /// The source manager for this debugger.
+    pub fn source_manager(&self) -> SBSourceManager {
+        unsafe { SBSourceManager::wrap(sys::SBDebuggerGetSourceManager(self.raw)) }
+    }
+
+    /// Demangle `mangled` with LLDB's own demangler, as the debugger
+    /// shows it, using the `language cplusplus demangle` command.
+    ///
+    /// Whichever schemes the LLDB in use knows are demangled, on any
+    /// host: Itanium C++, Microsoft Visual C++, and Rust's legacy and
+    /// `v0` names for LLDB 13 or later. The name doesn't need to be in
+    /// a target. Returns `None` when it can't be demangled.
+    pub fn demangle(&self, mangled: &str) -> Option<String> {
+        let command = format!(
+            "language cplusplus demangle {}",
+            strings::quote_argument(mangled)
+        );
+        let result = self.command_interpreter().handle_command(&command, false);
+        if !result.succeeded() {
+            return None;
+        }
+        demangle::from_lldb_output(mangled, &result.output()?)
+    }
+
+    /// Run the commands in the file at `path`, such as an `.lldbinit`
+    /// style setup script, stopping at the first command which fails.
+    ///
+    /// `~` in `path` is expanded. The commands are run in the same way
+    /// as by [`run_commands`], and the result of each command that was
+    /// run is returned along with the command, so the failing command
+    /// (if any) is the last one. Reading the file can fail.
+    ///
+    /// [`run_commands`]: #method.run_commands
+    pub fn run_command_file(
+        &self,
+        path: &str,
+    ) -> Result<Vec<(String, SBCommandReturnObject)>, SBError> {
+        let path = SBFileSpec::from_path(path, true).path();
+        let commands = fs::read_to_string(&path).map_err(|error| {
+            SBError::from_message(&format!("couldn't read {}: {}", path, error))
+        })?;
+        Ok(self.run_commands(&commands))
+    }
+
+    /// Run each line of `commands` as a separate command, stopping
+    /// at the first command which fails.
+    ///
+    /// Blank lines and lines starting with `#` are skipped. The result
+    /// of each command that was run is returned along with the
+    /// command, so the failing command (if any) is the last one:
+    ///
+    /// ```no_run
+    /// # use lldb::SBDebugger;
+    /// # fn setup(debugger: &SBDebugger) {
+    /// let results = debugger.run_commands(
+    ///     "settings set target.x86-disassembly-flavor intel\n\
+    ///      breakpoint set --name main",
+    /// );
+    /// if let Some((command, result)) = results.last() {
+    ///     if !result.succeeded() {
+    ///         eprintln!("{} failed: {:?}", command, result.error());
+    ///     }
+    /// }
+    /// # }
+    /// ```
+    pub fn run_commands(&self, commands: &str) -> Vec<(String, SBCommandReturnObject)> {
+        let interpreter = self.command_interpreter();
+        let mut results = vec![];
+        for command in commands.lines().map(str::trim) {
+            if command.is_empty() || command.starts_with('#') {
+                continue;
+            }
+            let result = interpreter.handle_command(command, false);
+            let succeeded = result.succeeded();
+            results.push((command.to_owned(), result));
+            if !succeeded {
+                break;
+            }
+        }
+        results
     }
 
     /// Enable logging (defaults to `stderr`).
@@ -208,16 +334,100 @@ impl SBDebugger {
         ret != 0
     }
 
-    /// Get the LLDB version string.
-    pub fn version() -> String {
+    /// Handle a process event in the same way as the `lldb` driver.
+    ///
+    /// Any pending `stdout` and `stderr` output from the process is
+    /// written to `out` and `err`. State changes, such as the process
+    /// stopping (along with the stop reason and location) or exiting,
+    /// are reported to `out`.
+    ///
+    /// This is a convenient way for a simple driver to keep the
+    /// process I/O flowing without decoding each event itself:
+    ///
+    /// ```no_run
+    /// # use lldb::{SBDebugger, SBEvent, SBProcess};
+    /// # fn pump(debugger: &SBDebugger, process: &SBProcess) {
+    /// let listener = debugger.listener();
+    /// let mut event = SBEvent::new();
+    /// while listener.wait_for_event(1, &mut event) {
+    ///     debugger.handle_process_event(process, &event, &std::io::stdout(), &std::io::stderr());
+    /// }
+    /// # }
+    /// ```
+    ///
+    /// The file descriptors of `out` and `err` are duplicated for the
+    /// duration of the call, so ownership remains with the caller.
+    #[cfg(unix)]
+    pub fn handle_process_event<O: AsRawFd, E: AsRawFd>(
+        &self,
+        process: &SBProcess,
+        event: &SBEvent,
+        out: &O,
+        err: &E,
+    ) {
+        unsafe {
+            let out = dup_as_file(out.as_raw_fd());
+            let err = dup_as_file(err.as_raw_fd());
+            sys::SBDebuggerHandleProcessEvent(self.raw, process.raw, event.raw, out, err);
+            close_file(out);
+            close_file(err);
+        }
+    }
+
+    /// Read debugger input, such as commands for the command
+    /// interpreter, from `input`.
+    ///
+    /// The debugger takes ownership of `input` and closes it when
+    /// it is replaced or the debugger is destroyed.
+    ///
+    /// Returns `false` if `input` could not be opened as a stream.
+    pub fn set_input_file<F: IntoRawFile>(&self, input: F) -> bool {
+        unsafe {
+            let f = open_owned(input, b"r\0");
+            if !f.is_null() {
+                sys::SBDebuggerSetInputFileHandle(self.raw, f, 1);
+            }
+            !f.is_null()
+        }
+    }
+
+    /// Write debugger output, such as command results, to `output`.
+    ///
+    /// The debugger takes ownership of `output` and closes it when
+    /// it is replaced or the debugger is destroyed.
+    ///
+    /// Returns `false` if `output` could not be opened as a stream.
+    pub fn set_output_file<F: IntoRawFile>(&self, output: F) -> bool {
+        unsafe {
+            let f = open_owned(output, b"w\0");
+            if !f.is_null() {
+                sys::SBDebuggerSetOutputFileHandle(self.raw, f, 1);
+            }
+            !f.is_null()
+        }
+    }
+
+    /// Write debugger errors to `error`.
+    ///
+    /// The debugger takes ownership of `error` and closes it when
+    /// it is replaced or the debugger is destroyed.
+    ///
+    /// Returns `false` if `error` could not be opened as a stream.
+    pub fn set_error_file<F: IntoRawFile>(&self, error: F) -> bool {
         unsafe {
-            match CStr::from_ptr(sys::SBDebuggerGetVersionString()).to_str() {
-                Ok(s) => s.to_owned(),
-                _ => panic!("No version string?"),
+            let f = open_owned(error, b"w\0");
+            if !f.is_null() {
+                sys::SBDebuggerSetErrorFileHandle(self.raw, f, 1);
             }
+            !f.is_null()
         }
     }
 
+    /// Get the LLDB version string.
+    pub fn version() -> String {
+        unsafe { strings::to_string_lossy(sys::SBDebuggerGetVersionString()) }
+    }
+
     /// Create a target.
     ///
     /// The executable name may be an empty string to create
@@ -229,25 +439,27 @@ impl SBDebugger {
         platform_name: Option<&str>,
         add_dependent_modules: bool,
     ) -> Result<SBTarget, SBError> {
-        let executable = CString::new(executable).unwrap();
-        let target_triple = target_triple.map(|s| CString::new(s).unwrap());
-        let platform_name = platform_name.map(|s| CString::new(s).unwrap());
-        let error = SBError::new();
-        let target = unsafe {
-            sys::SBDebuggerCreateTarget(
-                self.raw,
-                executable.as_ptr(),
-                target_triple.map_or(ptr::null(), |s| s.as_ptr()),
-                platform_name.map_or(ptr::null(), |s| s.as_ptr()),
-                add_dependent_modules as u8,
-                error.raw,
-            )
-        };
-        if error.is_success() {
-            Ok(SBTarget::wrap(target))
-        } else {
-            Err(error)
-        }
+        traced("SBDebugger::create_target", || {
+            let executable = CString::new(executable).unwrap();
+            let target_triple = target_triple.map(|s| CString::new(s).unwrap());
+            let platform_name = platform_name.map(|s| CString::new(s).unwrap());
+            let error = SBError::new();
+            let target = unsafe {
+                sys::SBDebuggerCreateTarget(
+                    self.raw,
+                    executable.as_ptr(),
+                    target_triple.map_or(ptr::null(), |s| s.as_ptr()),
+                    platform_name.map_or(ptr::null(), |s| s.as_ptr()),
+                    add_dependent_modules as u8,
+                    error.raw,
+                )
+            };
+            if error.is_success() {
+                Ok(unsafe { SBTarget::wrap(target) })
+            } else {
+                Err(error)
+            }
+        })
     }
 
     /// Create a target from just an executable name.
@@ -279,7 +491,30 @@ impl SBDebugger {
     ///
     /// [SBListener]: struct.SBListener.html
     pub fn listener(&self) -> SBListener {
-        SBListener::wrap(unsafe { sys::SBDebuggerGetListener(self.raw) })
+        unsafe { SBListener::wrap(sys::SBDebuggerGetListener(self.raw)) }
+    }
+
+    /// Load a processor trace bundle, such as one saved with
+    /// [`ProcessorTrace::save`], from its description file, returning
+    /// the target created for it.
+    ///
+    /// The process of the target is a postmortem process whose threads
+    /// have the traces. Read them with [`SBThread::trace_cursor`].
+    ///
+    /// This needs LLDB 13 or later, and the `lldb-13` feature.
+    ///
+    /// [`ProcessorTrace::save`]: struct.ProcessorTrace.html#method.save
+    /// [`SBThread::trace_cursor`]: struct.SBThread.html#method.trace_cursor
+    #[cfg(feature = "lldb-13")]
+    pub fn load_trace(&self, description: &str) -> Result<SBTarget, SBError> {
+        let command = format!("trace load {}", strings::quote_argument(description));
+        let result = self.command_interpreter().handle_command(&command, false);
+        if !result.succeeded() {
+            return Err(SBError::from_message(&result.error().unwrap_or_default()));
+        }
+        // Loading a trace selects the target that it created.
+        self.selected_target()
+            .ok_or_else(|| SBError::from_message("loading the trace created no target"))
     }
 
     /// Get the currently selected [`SBTarget`].
@@ -307,6 +542,120 @@ impl SBDebugger {
         }
     }
 
+    /// The name of this debugger, which identifies it in
+    /// [`set_setting`] and [`setting`].
+    ///
+    /// [`set_setting`]: #method.set_setting
+    /// [`setting`]: #method.setting
+    pub fn instance_name(&self) -> String {
+        unsafe { strings::to_string_lossy(sys::SBDebuggerGetInstanceName(self.raw)) }
+    }
+
+    /// Set the setting `name`, as the `settings set` command does.
+    pub fn set_setting(&self, name: &str, value: &str) -> Result<(), SBError> {
+        let name = CString::new(name).unwrap();
+        let value = CString::new(value).unwrap();
+        let instance_name = CString::new(self.instance_name()).unwrap();
+        let error = unsafe {
+            SBError::wrap(sys::SBDebuggerSetInternalVariable(
+                name.as_ptr(),
+                value.as_ptr(),
+                instance_name.as_ptr(),
+            ))
+        };
+        if error.is_success() {
+            Ok(())
+        } else {
+            Err(error)
+        }
+    }
+
+    /// The value of the setting `name`, one line per entry for array
+    /// and dictionary settings, or `None` if there is no such setting.
+    pub fn setting(&self, name: &str) -> Option<Vec<String>> {
+        let name = CString::new(name).unwrap();
+        let instance_name = CString::new(self.instance_name()).unwrap();
+        SBStringList::maybe_wrap(unsafe {
+            sys::SBDebuggerGetInternalVariableValue(name.as_ptr(), instance_name.as_ptr())
+        })
+        .map(|values| values.iter().collect())
+    }
+
+    /// Download missing debug information from these debuginfod
+    /// servers, in order.
+    ///
+    /// An empty list turns off debuginfod, unless the
+    /// `DEBUGINFOD_URLS` environment variable is set.
+    ///
+    /// This needs LLDB 18 or later, and the `lldb-18` feature.
+    #[cfg(feature = "lldb-18")]
+    pub fn set_debuginfod_urls(&self, urls: &[&str]) -> Result<(), SBError> {
+        self.set_setting(
+            "plugin.symbol-locator.debuginfod.server-urls",
+            &urls.join(" "),
+        )
+    }
+
+    /// Keep the files downloaded from debuginfod servers in
+    /// `directory`, rather than in the default cache directory.
+    ///
+    /// This needs LLDB 18 or later, and the `lldb-18` feature.
+    #[cfg(feature = "lldb-18")]
+    pub fn set_symbol_cache_path(&self, directory: &str) -> Result<(), SBError> {
+        self.set_setting("plugin.symbol-locator.debuginfod.cache-path", directory)
+    }
+
+    /// Whether to look for debug information outside of the module's
+    /// own directory, such as in `/usr/lib/debug` or with Spotlight.
+    pub fn set_external_symbol_lookup(&self, enabled: bool) -> Result<(), SBError> {
+        self.set_setting(
+            "symbols.enable-external-lookup",
+            if enabled { "true" } else { "false" },
+        )
+    }
+
+    /// How long to wait for a remote debug server to answer a packet
+    /// before giving up on the connection, as a whole number of
+    /// seconds of at least one. The default is a few seconds, which
+    /// can be too short for a slow link, or for a debug server under
+    /// heavy load.
+    ///
+    /// A connection which times out shows up as a
+    /// [`ConnectionLost`] when the process is marked as exited.
+    ///
+    /// [`ConnectionLost`]: struct.ConnectionLost.html
+    pub fn set_gdb_remote_packet_timeout(&self, timeout: Duration) -> Result<(), SBError> {
+        let seconds = timeout.as_secs() + u64::from(timeout.subsec_nanos() > 0);
+        self.set_setting(
+            "plugin.process.gdb-remote.packet-timeout",
+            &seconds.max(1).to_string(),
+        )
+    }
+
+    /// How long to wait for a remote debug server to answer a packet.
+    /// See [`set_gdb_remote_packet_timeout`].
+    ///
+    /// [`set_gdb_remote_packet_timeout`]: #method.set_gdb_remote_packet_timeout
+    pub fn gdb_remote_packet_timeout(&self) -> Option<Duration> {
+        let value = self.setting("plugin.process.gdb-remote.packet-timeout")?;
+        let seconds = value.first()?.trim().parse().ok()?;
+        Some(Duration::from_secs(seconds))
+    }
+
+    /// When to download debug symbols with `dsymForUUID` or
+    /// `DebugSymbols.framework` on macOS.
+    ///
+    /// This needs LLDB 18 or later, and the `lldb-18` feature.
+    #[cfg(feature = "lldb-18")]
+    pub fn set_symbol_auto_download(&self, download: SymbolDownload) -> Result<(), SBError> {
+        let value = match download {
+            SymbolDownload::Off => "off",
+            SymbolDownload::Background => "background",
+            SymbolDownload::Foreground => "foreground",
+        };
+        self.set_setting("symbols.auto-download", value)
+    }
+
     /// Set the selected [`SBPlatform`].
     ///
     /// [`SBPlatform`]: struct.SBPlatform.html
@@ -331,6 +680,91 @@ impl SBDebugger {
             idx: 0,
         }
     }
+
+    fn description(&self) -> String {
+        let stream = SBStream::new();
+        unsafe { sys::SBDebuggerGetDescription(self.raw, stream.raw) };
+        stream.data()
+    }
+}
+
+/// When symbols are downloaded, for
+/// [`SBDebugger::set_symbol_auto_download`].
+///
+/// [`SBDebugger::set_symbol_auto_download`]: struct.SBDebugger.html#method.set_symbol_auto_download
+#[cfg(feature = "lldb-18")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SymbolDownload {
+    /// Never download symbols.
+    Off,
+    /// Download symbols in the background, so that they appear later
+    /// without blocking the debugger.
+    Background,
+    /// Download symbols as soon as a module is loaded, waiting for
+    /// them to arrive.
+    Foreground,
+}
+
+/// Open a `FILE` for writing on a duplicate of `fd`.
+///
+/// Returns a null pointer if the descriptor could not be duplicated
+/// or opened, which LLDB treats as "no output".
+#[cfg(unix)]
+unsafe fn dup_as_file(fd: RawFd) -> *mut libc::FILE {
+    let fd = libc::dup(fd);
+    if fd < 0 {
+        return ptr::null_mut();
+    }
+    open_file(fd, b"w\0")
+}
+
+/// Open a `FILE` with the given NUL terminated `mode` which owns `fd`.
+///
+/// If the `FILE` can't be opened, `fd` is closed and a null pointer
+/// is returned.
+#[cfg(unix)]
+unsafe fn open_file(fd: RawFd, mode: &[u8]) -> *mut libc::FILE {
+    let f = libc::fdopen(fd, mode.as_ptr() as *const libc::c_char);
+    if f.is_null() {
+        libc::close(fd);
+    }
+    f
+}
+
+/// Open a `FILE` with the given NUL terminated `mode` which owns
+/// `file`.
+#[cfg(unix)]
+unsafe fn open_owned<F: IntoRawFile>(file: F, mode: &[u8]) -> *mut libc::FILE {
+    open_file(file.into_raw_fd(), mode)
+}
+
+/// Open a `FILE` with the given NUL terminated `mode` which owns
+/// `file`, by way of a C runtime file descriptor for its handle.
+#[cfg(windows)]
+unsafe fn open_owned<F: IntoRawFile>(file: F, mode: &[u8]) -> *mut libc::FILE {
+    use std::fs::File;
+    use std::os::windows::io::FromRawHandle;
+
+    let handle = file.into_raw_handle();
+    let fd = libc::open_osfhandle(handle as libc::intptr_t, 0);
+    if fd < 0 {
+        // Close the handle, which still belongs to us.
+        drop(File::from_raw_handle(handle));
+        return ptr::null_mut();
+    }
+    let f = libc::fdopen(fd, mode.as_ptr() as *const libc::c_char);
+    if f.is_null() {
+        libc::close(fd);
+    }
+    f
+}
+
+/// Flush and close a `FILE` opened by `dup_as_file`.
+#[cfg(unix)]
+unsafe fn close_file(f: *mut libc::FILE) {
+    if !f.is_null() {
+        libc::fclose(f);
+    }
 }
 
 /// Iterate over the [targets] known to a [debugger].
@@ -375,9 +809,13 @@ impl Clone for SBDebugger {
 
 impl fmt::Debug for SBDebugger {
     fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
-        let stream = SBStream::new();
-        unsafe { sys::SBDebuggerGetDescription(self.raw, stream.raw) };
-        write!(fmt, "SBDebugger {{ {} }}", stream.data())
+        write!(fmt, "SBDebugger {{ {} }}", self.description())
+    }
+}
+
+impl fmt::Display for SBDebugger {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "{}", self.description())
     }
 }
 
@@ -403,9 +841,12 @@ impl<'d> Iterator for SBDebuggerPlatformIter<'d> {
 
     fn next(&mut self) -> Option<SBPlatform> {
         if self.idx < unsafe { sys::SBDebuggerGetNumPlatforms(self.debugger.raw) } {
-            let r = Some(SBPlatform::wrap(unsafe {
-                sys::SBDebuggerGetPlatformAtIndex(self.debugger.raw, self.idx)
-            }));
+            let r = Some(unsafe {
+                SBPlatform::wrap(sys::SBDebuggerGetPlatformAtIndex(
+                    self.debugger.raw,
+                    self.idx,
+                ))
+            });
             self.idx += 1;
             r
         } else {
@@ -432,9 +873,12 @@ impl<'d> Iterator for SBDebuggerAvailablePlatformIter<'d> {
 
     fn next(&mut self) -> Option<SBStructuredData> {
         if self.idx < unsafe { sys::SBDebuggerGetNumAvailablePlatforms(self.debugger.raw) } {
-            let r = Some(SBStructuredData::wrap(unsafe {
-                sys::SBDebuggerGetAvailablePlatformInfoAtIndex(self.debugger.raw, self.idx)
-            }));
+            let r = Some(unsafe {
+                SBStructuredData::wrap(sys::SBDebuggerGetAvailablePlatformInfoAtIndex(
+                    self.debugger.raw,
+                    self.idx,
+                ))
+            });
             self.idx += 1;
             r
         } else {
@@ -454,27 +898,33 @@ impl<'d> ExactSizeIterator for SBDebuggerAvailablePlatformIter<'d> {}
 impl ::juniper::Context for SBDebugger {}
 
 #[cfg(feature = "graphql")]
-graphql_object!(SBDebugger: SBDebugger | &self | {
-    field targets() -> Vec<SBTarget> {
+#[graphql_object(context = SBDebugger)]
+impl SBDebugger {
+    #[graphql(name = "targets")]
+    fn graphql_targets(&self) -> Vec<SBTarget> {
         self.targets().collect()
     }
 
-    field selected_target() -> Option<SBTarget> {
+    #[graphql(name = "selectedTarget")]
+    fn graphql_selected_target(&self) -> Option<SBTarget> {
         self.selected_target()
     }
 
-    field selected_platform() -> SBPlatform {
+    #[graphql(name = "selectedPlatform")]
+    fn graphql_selected_platform(&self) -> SBPlatform {
         self.selected_platform()
     }
 
-    field platforms() -> Vec<SBPlatform> {
+    #[graphql(name = "platforms")]
+    fn graphql_platforms(&self) -> Vec<SBPlatform> {
         self.platforms().collect()
     }
 
-    field available_platforms() -> Vec<SBStructuredData> {
+    #[graphql(name = "availablePlatforms")]
+    fn graphql_available_platforms(&self) -> Vec<SBStructuredData> {
         self.available_platforms().collect()
     }
-});
+}
 
 #[cfg(test)]
 mod tests {
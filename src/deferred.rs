@@ -0,0 +1,229 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use super::breakpoint::SBBreakpoint;
+use super::event::SBEvent;
+use super::filespeclist::SBFileSpecList;
+use super::listener::SBListener;
+use super::module::SBModule;
+use super::target::SBTarget;
+use std::path::Path;
+
+/// Breakpoints in libraries which haven't been loaded yet.
+///
+/// Each breakpoint is set, restricted to its library, once that
+/// library is loaded, such as by `dlopen`, and reported back as a
+/// [`ResolvedBreakpoint`]. Until then, the breakpoint doesn't exist,
+/// so it can't be hit in a different library with a function or file
+/// of the same name.
+///
+/// ```no_run
+/// # use lldb::{DeferredBreakpoints, SBEvent, SBListener, SBTarget};
+/// # fn run(target: &SBTarget, listener: &SBListener) {
+/// let mut deferred = DeferredBreakpoints::new(target);
+/// deferred.add_function("libplugin.so", "plugin_init");
+/// deferred.add_line("libplugin.so", "plugin.c", 42);
+/// deferred.listen(listener);
+///
+/// let mut event = SBEvent::new();
+/// while !deferred.is_empty() && listener.wait_for_event(10, &mut event) {
+///     for resolved in deferred.handle_event(&event) {
+///         println!(
+///             "Set breakpoint {} in {} with {} locations",
+///             resolved.breakpoint.id(),
+///             resolved.module,
+///             resolved.breakpoint.num_locations()
+///         );
+///     }
+/// }
+/// # }
+/// ```
+///
+/// [`ResolvedBreakpoint`]: struct.ResolvedBreakpoint.html
+pub struct DeferredBreakpoints {
+    target: SBTarget,
+    pending: Vec<Pending>,
+    next_id: usize,
+}
+
+struct Pending {
+    id: usize,
+    module: String,
+    location: DeferredLocation,
+}
+
+/// Where a deferred breakpoint goes in its library.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum DeferredLocation {
+    /// The start of a function.
+    Function(String),
+    /// A line of a source file.
+    Line {
+        /// The path, or the file name, of the source file.
+        file: String,
+        /// The line number.
+        line: u32,
+    },
+}
+
+/// A deferred breakpoint which has been set, because its library was
+/// loaded.
+#[derive(Debug)]
+pub struct ResolvedBreakpoint {
+    /// The ID returned when the breakpoint was added.
+    pub id: usize,
+    /// The path of the library which was loaded.
+    pub module: String,
+    /// Where the breakpoint was set.
+    pub location: DeferredLocation,
+    /// The breakpoint. It has no locations if the function or line
+    /// wasn't found in the library.
+    pub breakpoint: SBBreakpoint,
+}
+
+impl DeferredBreakpoints {
+    /// Create an empty set of deferred breakpoints for `target`.
+    pub fn new(target: &SBTarget) -> DeferredBreakpoints {
+        DeferredBreakpoints {
+            target: target.clone(),
+            pending: vec![],
+            next_id: 0,
+        }
+    }
+
+    /// Set a breakpoint on the function `name` once the library
+    /// `module` is loaded, returning an ID for the request.
+    ///
+    /// `module` is matched against the file name of each library
+    /// loaded, such as `libplugin.so`, or against its full path.
+    pub fn add_function(&mut self, module: &str, name: &str) -> usize {
+        self.add(module, DeferredLocation::Function(name.to_string()))
+    }
+
+    /// Set a breakpoint on `line` of `file` once the library `module`
+    /// is loaded, returning an ID for the request.
+    pub fn add_line(&mut self, module: &str, file: &str, line: u32) -> usize {
+        self.add(
+            module,
+            DeferredLocation::Line {
+                file: file.to_string(),
+                line,
+            },
+        )
+    }
+
+    fn add(&mut self, module: &str, location: DeferredLocation) -> usize {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.pending.push(Pending {
+            id,
+            module: module.to_string(),
+            location,
+        });
+        id
+    }
+
+    /// Stop waiting to set the breakpoint with the ID `id`, returning
+    /// whether it was still waiting.
+    pub fn cancel(&mut self, id: usize) -> bool {
+        let len = self.pending.len();
+        self.pending.retain(|pending| pending.id != id);
+        self.pending.len() != len
+    }
+
+    /// Are there no breakpoints left waiting for their library?
+    pub fn is_empty(&self) -> bool {
+        self.pending.is_empty()
+    }
+
+    /// The libraries and locations of the breakpoints which are still
+    /// waiting, by ID.
+    pub fn pending(&self) -> Vec<(usize, String, DeferredLocation)> {
+        self.pending
+            .iter()
+            .map(|pending| (pending.id, pending.module.clone(), pending.location.clone()))
+            .collect()
+    }
+
+    /// Make `listener` receive the target's events for modules being
+    /// loaded, to be passed to [`handle_event`].
+    ///
+    /// [`handle_event`]: #method.handle_event
+    pub fn listen(&self, listener: &SBListener) {
+        listener.start_listening_for_events(
+            &self.target.broadcaster(),
+            SBTarget::BROADCAST_BIT_MODULES_LOADED,
+        );
+    }
+
+    /// Set the breakpoints for the libraries loaded in `event`,
+    /// returning those which were set.
+    ///
+    /// Events other than modules being loaded are ignored.
+    pub fn handle_event(&mut self, event: &SBEvent) -> Vec<ResolvedBreakpoint> {
+        if event.event_type() & SBTarget::BROADCAST_BIT_MODULES_LOADED == 0 {
+            return vec![];
+        }
+        match SBTarget::event_as_target_event(event) {
+            Some(target_event) => {
+                let modules: Vec<SBModule> = target_event.modules().collect();
+                self.resolve(&modules)
+            }
+            None => vec![],
+        }
+    }
+
+    /// Set the breakpoints for libraries which are already loaded,
+    /// returning those which were set.
+    ///
+    /// Call this after attaching, when libraries may have been loaded
+    /// before listening started.
+    pub fn resolve_loaded(&mut self) -> Vec<ResolvedBreakpoint> {
+        let modules: Vec<SBModule> = self.target.modules().collect();
+        self.resolve(&modules)
+    }
+
+    fn resolve(&mut self, modules: &[SBModule]) -> Vec<ResolvedBreakpoint> {
+        let mut resolved = vec![];
+        for module in modules {
+            let path = module.filespec().path();
+            let (matching, rest) = self
+                .pending
+                .drain(..)
+                .partition(|pending| module_matches(&pending.module, &path));
+            self.pending = rest;
+            for pending in matching {
+                let breakpoint = match pending.location {
+                    DeferredLocation::Function(ref name) => {
+                        self.target.breakpoint_create_by_name(name, Some(&path))
+                    }
+                    DeferredLocation::Line { ref file, line } => {
+                        let modules = SBFileSpecList::new();
+                        modules.append(&module.filespec());
+                        self.target
+                            .breakpoint_create_by_location_in_modules(file, line, &modules)
+                    }
+                };
+                resolved.push(ResolvedBreakpoint {
+                    id: pending.id,
+                    module: path.clone(),
+                    location: pending.location,
+                    breakpoint,
+                });
+            }
+        }
+        resolved
+    }
+}
+
+/// Does the requested `module` name the library at `path`?
+fn module_matches(module: &str, path: &str) -> bool {
+    module == path
+        || Path::new(path)
+            .file_name()
+            .and_then(|name| name.to_str())
+            .is_some_and(|name| name == module)
+}
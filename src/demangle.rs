@@ -0,0 +1,294 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#[cfg(not(target_env = "msvc"))]
+use std::ffi::{CStr, CString};
+#[cfg(not(target_env = "msvc"))]
+use std::os::raw::{c_char, c_int};
+#[cfg(not(target_env = "msvc"))]
+use std::ptr;
+use std::str;
+
+#[cfg(not(target_env = "msvc"))]
+extern "C" {
+    // Provided by the C++ runtime which LLDB, and the glue code from
+    // `lldb-sys`, are linked against. MSVC's runtime doesn't have it.
+    fn __cxa_demangle(
+        mangled_name: *const c_char,
+        output_buffer: *mut c_char,
+        length: *mut usize,
+        status: *mut c_int,
+    ) -> *mut c_char;
+}
+
+#[cfg(target_env = "msvc")]
+#[link(name = "dbghelp")]
+extern "system" {
+    fn UnDecorateSymbolName(
+        name: *const std::os::raw::c_char,
+        output_string: *mut std::os::raw::c_char,
+        max_string_length: u32,
+        flags: u32,
+    ) -> u32;
+}
+
+/// Demangle a symbol name, such as `_ZN4core3fmt5write17h8f3c1a2b4d5e6f70E`
+/// or `_ZNSt6vectorIiSaIiEE9push_backERKi`, as the debugger shows it.
+///
+/// * Itanium C++ names are demangled with the demangler of the C++
+///   runtime that LLDB is linked with. MSVC's runtime doesn't have
+///   one, so these aren't demangled on `*-msvc` targets.
+/// * Rust's legacy names, which are Itanium names with some escaping,
+///   are demangled on every target, with the hash at the end removed.
+/// * Microsoft Visual C++ names, which start with `?`, are demangled
+///   with `UnDecorateSymbolName` on `*-msvc` targets only.
+///
+/// ```no_run
+/// assert_eq!(
+///     lldb::demangle("_ZN4core3fmt5write17h8f3c1a2b4d5e6f70E").as_deref(),
+///     Some("core::fmt::write"),
+/// );
+/// ```
+///
+/// Returns `None` when `mangled` isn't a mangled name, or can't be
+/// demangled on this target. This works without a debugger, using the
+/// demanglers which are already linked in. LLDB has demanglers for
+/// every scheme built in, including Rust's `v0` names, so when there
+/// is a debugger, prefer [`SBDebugger::demangle`], or the display
+/// names of [`SBSymbol`] and [`SBFunction`].
+///
+/// [`SBDebugger::demangle`]: struct.SBDebugger.html#method.demangle
+/// [`SBSymbol`]: struct.SBSymbol.html
+/// [`SBFunction`]: struct.SBFunction.html
+pub fn demangle(mangled: &str) -> Option<String> {
+    // Mach-O symbols start with an extra underscore.
+    let name = match mangled.strip_prefix('_') {
+        Some(rest) if rest.starts_with("_Z") => rest,
+        _ => mangled,
+    };
+    if name.starts_with("_Z") {
+        demangle_itanium(name)
+    } else if name.starts_with('?') {
+        demangle_msvc(name)
+    } else {
+        None
+    }
+}
+
+fn demangle_itanium(mangled: &str) -> Option<String> {
+    if let Some(path) = rust_legacy_path(mangled).and_then(|path| clean_rust_legacy(&path)) {
+        return Some(path);
+    }
+    let demangled = cxa_demangle(mangled)?;
+    Some(clean_rust_legacy(&demangled).unwrap_or(demangled))
+}
+
+#[cfg(not(target_env = "msvc"))]
+fn cxa_demangle(mangled: &str) -> Option<String> {
+    let mangled = CString::new(mangled).ok()?;
+    let mut status: c_int = 0;
+    unsafe {
+        let demangled = __cxa_demangle(
+            mangled.as_ptr(),
+            ptr::null_mut(),
+            ptr::null_mut(),
+            &mut status,
+        );
+        if demangled.is_null() {
+            return None;
+        }
+        let result = CStr::from_ptr(demangled).to_string_lossy().into_owned();
+        libc::free(demangled as *mut libc::c_void);
+        if status == 0 {
+            Some(result)
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(target_env = "msvc")]
+fn cxa_demangle(_mangled: &str) -> Option<String> {
+    None
+}
+
+#[cfg(target_env = "msvc")]
+fn demangle_msvc(mangled: &str) -> Option<String> {
+    let mangled = std::ffi::CString::new(mangled).ok()?;
+    let mut buffer = vec![0u8; 4096];
+    // UNDNAME_COMPLETE
+    let len = unsafe {
+        UnDecorateSymbolName(
+            mangled.as_ptr(),
+            buffer.as_mut_ptr() as *mut std::os::raw::c_char,
+            buffer.len() as u32,
+            0,
+        )
+    };
+    if len == 0 {
+        return None;
+    }
+    buffer.truncate(len as usize);
+    String::from_utf8(buffer).ok()
+}
+
+#[cfg(not(target_env = "msvc"))]
+fn demangle_msvc(_mangled: &str) -> Option<String> {
+    None
+}
+
+/// The demangled name from the output of LLDB's
+/// `language cplusplus demangle` command for `mangled`, which is
+/// `<mangled> ---> <demangled>` when it could be demangled.
+pub(crate) fn from_lldb_output(mangled: &str, output: &str) -> Option<String> {
+    let demangled = output
+        .trim_end()
+        .strip_prefix(mangled)?
+        .strip_prefix(" ---> ")?;
+    if demangled.is_empty() || demangled == mangled {
+        None
+    } else {
+        Some(demangled.to_string())
+    }
+}
+
+/// The path in a legacy Rust name, such as `core::fmt::write::h8f3c...`
+/// for `_ZN4core3fmt5write17h8f3c...E`, without demangling any C++.
+///
+/// Returns `None` for names which aren't just a nested name.
+fn rust_legacy_path(mangled: &str) -> Option<String> {
+    let mut rest = mangled.strip_prefix("_ZN")?;
+    let mut segments = vec![];
+    while rest != "E" {
+        let digits = rest.bytes().take_while(u8::is_ascii_digit).count();
+        let len: usize = rest[..digits].parse().ok()?;
+        rest = &rest[digits..];
+        segments.push(rest.get(..len)?);
+        rest = &rest[len..];
+    }
+    Some(segments.join("::"))
+}
+
+/// Turn the Itanium demangling of a legacy Rust name, which ends in a
+/// path segment like `h8f3c1a2b4d5e6f70` and escapes the characters
+/// Itanium doesn't allow, into the Rust path.
+///
+/// Returns `None` for names which aren't Rust names.
+fn clean_rust_legacy(demangled: &str) -> Option<String> {
+    let (path, hash) = demangled.rsplit_once("::")?;
+    let is_hash = hash.len() == 17
+        && hash.starts_with('h')
+        && hash[1..].chars().all(|c| c.is_ascii_hexdigit());
+    if !is_hash {
+        return None;
+    }
+
+    let mut segments = vec![];
+    for segment in path.split("::") {
+        // A segment which would start with `$` has `_` added before it.
+        let segment = if segment.starts_with("_$") {
+            &segment[1..]
+        } else {
+            segment
+        };
+        segments.push(unescape(segment)?);
+    }
+    Some(segments.join("::"))
+}
+
+/// Undo the escaping of a segment of a legacy Rust name, such as
+/// `$LT$impl$u20$core..fmt..Debug$GT$`.
+fn unescape(segment: &str) -> Option<String> {
+    let mut result = String::with_capacity(segment.len());
+    let mut rest = segment;
+    while !rest.is_empty() {
+        if let Some(after) = rest.strip_prefix('$') {
+            let end = after.find('$')?;
+            let escape = &after[..end];
+            let c = match escape {
+                "SP" => '@',
+                "BP" => '*',
+                "RF" => '&',
+                "LT" => '<',
+                "GT" => '>',
+                "LP" => '(',
+                "RP" => ')',
+                "C" => ',',
+                _ => {
+                    let code = escape.strip_prefix('u')?;
+                    std::char::from_u32(u32::from_str_radix(code, 16).ok()?)?
+                }
+            };
+            result.push(c);
+            rest = &after[end + 1..];
+        } else if let Some(after) = rest.strip_prefix("..") {
+            result.push_str("::");
+            rest = after;
+        } else {
+            let c = rest.chars().next().unwrap();
+            result.push(c);
+            rest = &rest[c.len_utf8()..];
+        }
+    }
+    Some(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{clean_rust_legacy, from_lldb_output, rust_legacy_path};
+
+    #[test]
+    fn reads_lldb_output() {
+        assert_eq!(
+            from_lldb_output(
+                "_RNvCs1234_7mycrate3foo",
+                "_RNvCs1234_7mycrate3foo ---> mycrate::foo\n"
+            )
+            .as_deref(),
+            Some("mycrate::foo"),
+        );
+        assert_eq!(from_lldb_output("main", ""), None);
+        assert_eq!(from_lldb_output("_Zfoo", "_Zbar ---> bar\n"), None);
+    }
+
+    #[test]
+    fn finds_rust_legacy_paths() {
+        assert_eq!(
+            rust_legacy_path("_ZN4core3fmt5write17h8f3c1a2b4d5e6f70E").as_deref(),
+            Some("core::fmt::write::h8f3c1a2b4d5e6f70"),
+        );
+        assert_eq!(rust_legacy_path("_ZN3foo3barEv"), None);
+    }
+
+    #[test]
+    fn cleans_rust_legacy_names() {
+        assert_eq!(
+            clean_rust_legacy("core::fmt::write::h8f3c1a2b4d5e6f70").as_deref(),
+            Some("core::fmt::write"),
+        );
+        assert_eq!(
+            clean_rust_legacy(
+                "_$LT$alloc..vec..Vec$LT$T$GT$$u20$as$u20$core..ops..drop..Drop$GT$::drop::h0123456789abcdef"
+            )
+            .as_deref(),
+            Some("<alloc::vec::Vec<T> as core::ops::drop::Drop>::drop"),
+        );
+        assert_eq!(
+            clean_rust_legacy("foo::_$u7b$$u7b$closure$u7d$$u7d$::h0123456789abcdef").as_deref(),
+            Some("foo::{{closure}}"),
+        );
+    }
+
+    #[test]
+    fn leaves_cxx_names() {
+        assert_eq!(
+            clean_rust_legacy("std::vector<int>::push_back(int const&)"),
+            None
+        );
+        assert_eq!(clean_rust_legacy("main"), None);
+        assert_eq!(clean_rust_legacy("ns::h12"), None);
+    }
+}
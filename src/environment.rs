@@ -0,0 +1,281 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use std::collections::btree_map::{self, BTreeMap};
+use std::env;
+use std::fmt;
+use std::iter::FromIterator;
+
+/// A set of environment variables, for building the environment of a
+/// process to launch and checking the one it actually got.
+///
+/// Launching with the debugger's own environment, a few variables
+/// changed and a few removed:
+///
+/// ```no_run
+/// # use lldb::{Environment, SBLaunchInfo, SBProcess, SBError, SBTarget};
+/// # fn launch(target: &SBTarget) -> Result<SBProcess, SBError> {
+/// let environment = Environment::inherit()
+///     .set("RUST_LOG", "debug")
+///     .remove("LD_PRELOAD");
+/// let launch_info = SBLaunchInfo::builder()
+///     .environment_vars(&environment)
+///     .stop_at_entry(true)
+///     .build();
+/// let process = target.launch(launch_info)?;
+/// let diff = environment.diff(&process.environment()?);
+/// if !diff.is_empty() {
+///     eprintln!("the environment was changed on launch:\n{}", diff);
+/// }
+/// # Ok(process)
+/// # }
+/// ```
+///
+/// Variables are kept in order of name. Those that aren't valid
+/// Unicode are converted lossily.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Environment {
+    vars: BTreeMap<String, String>,
+}
+
+impl Environment {
+    /// An empty environment.
+    pub fn new() -> Environment {
+        Environment::default()
+    }
+
+    /// The environment of this process, the one running the debugger.
+    pub fn inherit() -> Environment {
+        env::vars_os()
+            .map(|(name, value)| {
+                (
+                    name.to_string_lossy().into_owned(),
+                    value.to_string_lossy().into_owned(),
+                )
+            })
+            .collect()
+    }
+
+    /// Parse entries of the form `NAME=VALUE`, such as from
+    /// [`SBLaunchInfo::environment_entries`]. An entry without `=` is
+    /// a variable with an empty value. Later entries replace earlier
+    /// ones with the same name.
+    ///
+    /// [`SBLaunchInfo::environment_entries`]: struct.SBLaunchInfo.html#method.environment_entries
+    pub fn from_entries<I, S>(entries: I) -> Environment
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        entries
+            .into_iter()
+            .map(|entry| {
+                let entry = entry.as_ref();
+                match entry.find('=') {
+                    Some(i) => (entry[..i].to_string(), entry[i + 1..].to_string()),
+                    None => (entry.to_string(), String::new()),
+                }
+            })
+            .collect()
+    }
+
+    /// Set the variable `name` to `value`.
+    pub fn set(mut self, name: &str, value: &str) -> Self {
+        self.vars.insert(name.to_string(), value.to_string());
+        self
+    }
+
+    /// Remove the variable `name`, if it is set.
+    pub fn remove(mut self, name: &str) -> Self {
+        self.vars.remove(name);
+        self
+    }
+
+    /// Set every variable in `overrides`, replacing any with the same
+    /// names.
+    pub fn merge<I, K, V>(mut self, overrides: I) -> Self
+    where
+        I: IntoIterator<Item = (K, V)>,
+        K: Into<String>,
+        V: Into<String>,
+    {
+        self.vars.extend(
+            overrides
+                .into_iter()
+                .map(|(name, value)| (name.into(), value.into())),
+        );
+        self
+    }
+
+    /// Remove every variable named in `names`.
+    pub fn remove_all<I, S>(mut self, names: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        for name in names {
+            self.vars.remove(name.as_ref());
+        }
+        self
+    }
+
+    /// The value of the variable `name`, if it is set.
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.vars.get(name).map(|value| value.as_str())
+    }
+
+    /// Is the variable `name` set?
+    pub fn contains(&self, name: &str) -> bool {
+        self.vars.contains_key(name)
+    }
+
+    /// The number of variables.
+    pub fn len(&self) -> usize {
+        self.vars.len()
+    }
+
+    /// Are there no variables?
+    pub fn is_empty(&self) -> bool {
+        self.vars.is_empty()
+    }
+
+    /// Iterate over the names and values of the variables, in order of
+    /// name.
+    pub fn iter(&self) -> btree_map::Iter<'_, String, String> {
+        self.vars.iter()
+    }
+
+    /// The variables as entries of the form `NAME=VALUE`, as taken by
+    /// [`SBLaunchInfo::set_environment_entries`].
+    ///
+    /// [`SBLaunchInfo::set_environment_entries`]: struct.SBLaunchInfo.html#method.set_environment_entries
+    pub fn entries(&self) -> Vec<String> {
+        self.vars
+            .iter()
+            .map(|(name, value)| format!("{}={}", name, value))
+            .collect()
+    }
+
+    /// Compare this environment, the one that was asked for, with
+    /// `actual`, the one that a process got.
+    pub fn diff(&self, actual: &Environment) -> EnvironmentDiff {
+        let mut diff = EnvironmentDiff::default();
+        for (name, value) in &self.vars {
+            match actual.vars.get(name) {
+                None => diff.missing.push((name.clone(), value.clone())),
+                Some(actual) if actual != value => {
+                    diff.changed
+                        .push((name.clone(), value.clone(), actual.clone()))
+                }
+                Some(_) => {}
+            }
+        }
+        for (name, value) in &actual.vars {
+            if !self.vars.contains_key(name) {
+                diff.unexpected.push((name.clone(), value.clone()));
+            }
+        }
+        diff
+    }
+}
+
+impl<K: Into<String>, V: Into<String>> FromIterator<(K, V)> for Environment {
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Environment {
+        Environment::new().merge(iter)
+    }
+}
+
+impl<'a> IntoIterator for &'a Environment {
+    type Item = (&'a String, &'a String);
+    type IntoIter = btree_map::Iter<'a, String, String>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// The differences between the environment that was asked for and
+/// the one a process got, from [`Environment::diff`].
+///
+/// [`Environment::diff`]: struct.Environment.html#method.diff
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct EnvironmentDiff {
+    /// The variables that were asked for but aren't set, with the
+    /// values asked for.
+    pub missing: Vec<(String, String)>,
+    /// The variables that are set but weren't asked for, with their
+    /// values.
+    pub unexpected: Vec<(String, String)>,
+    /// The variables with a different value than was asked for, with
+    /// the value asked for and then the actual one.
+    pub changed: Vec<(String, String, String)>,
+}
+
+impl EnvironmentDiff {
+    /// Did the process get exactly the environment asked for?
+    pub fn is_empty(&self) -> bool {
+        self.missing.is_empty() && self.unexpected.is_empty() && self.changed.is_empty()
+    }
+}
+
+impl fmt::Display for EnvironmentDiff {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (name, value) in &self.missing {
+            writeln!(f, "- {}={}", name, value)?;
+        }
+        for (name, value) in &self.unexpected {
+            writeln!(f, "+ {}={}", name, value)?;
+        }
+        for (name, requested, actual) in &self.changed {
+            writeln!(f, "~ {}={} (asked for {})", name, actual, requested)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Environment;
+
+    #[test]
+    fn merge() {
+        let environment = Environment::from_entries(["HOME=/root", "PATH=/bin", "EMPTY", "A=b=c"])
+            .merge(vec![("PATH", "/usr/bin")])
+            .remove_all(["HOME"]);
+        assert_eq!(environment.get("PATH"), Some("/usr/bin"));
+        assert_eq!(environment.get("EMPTY"), Some(""));
+        assert_eq!(environment.get("A"), Some("b=c"));
+        assert!(!environment.contains("HOME"));
+        assert_eq!(
+            environment.entries(),
+            vec!["A=b=c", "EMPTY=", "PATH=/usr/bin"]
+        );
+    }
+
+    #[test]
+    fn diff() {
+        let requested = Environment::new()
+            .set("A", "1")
+            .set("B", "$HOME")
+            .set("C", "3");
+        let actual = Environment::new()
+            .set("A", "1")
+            .set("B", "/root")
+            .set("D", "4");
+        let diff = requested.diff(&actual);
+        assert_eq!(diff.missing, vec![("C".to_string(), "3".to_string())]);
+        assert_eq!(diff.unexpected, vec![("D".to_string(), "4".to_string())]);
+        assert_eq!(
+            diff.changed,
+            vec![("B".to_string(), "$HOME".to_string(), "/root".to_string())]
+        );
+        assert_eq!(
+            diff.to_string(),
+            "- C=3\n+ D=4\n~ B=/root (asked for $HOME)\n"
+        );
+        assert!(requested.diff(&requested).is_empty());
+    }
+}
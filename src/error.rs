@@ -5,9 +5,13 @@
 // except according to those terms.
 
 use super::stream::SBStream;
+use super::strings;
 use super::ErrorType;
-use std::ffi::CStr;
+use std::convert::TryFrom;
+use std::error;
+use std::ffi::CString;
 use std::fmt;
+use std::io;
 use sys;
 
 /// A container for holding any error code.
@@ -19,18 +23,28 @@ pub struct SBError {
 impl SBError {
     /// Construct a new `SBError`.
     pub fn new() -> SBError {
-        SBError::wrap(unsafe { sys::CreateSBError() })
+        unsafe { SBError::wrap(sys::CreateSBError()) }
     }
 
     /// Construct a new `SBError`.
-    pub fn wrap(raw: sys::SBErrorRef) -> SBError {
+    ///
+    /// # Safety
+    ///
+    /// `raw` must be a valid reference, which is disposed of when the
+    /// `SBError` is dropped. Use [`maybe_wrap`] when it may be invalid.
+    ///
+    /// [`maybe_wrap`]: #method.maybe_wrap
+    pub unsafe fn wrap(raw: sys::SBErrorRef) -> SBError {
         SBError { raw }
     }
 
     /// Construct a new `Some(SBError)` or `None`.
+    ///
+    /// `raw` is disposed of if it is invalid.
     pub fn maybe_wrap(raw: sys::SBErrorRef) -> Option<SBError> {
-        if unsafe { sys::SBErrorIsValid(raw) != 0 } {
-            Some(SBError { raw })
+        let wrapped = SBError { raw };
+        if wrapped.is_valid() {
+            Some(wrapped)
         } else {
             None
         }
@@ -42,13 +56,22 @@ impl SBError {
     }
 
     /// Any textual error message associated with the error.
-    pub fn error_string(&self) -> &str {
-        unsafe {
-            match CStr::from_ptr(sys::SBErrorGetCString(self.raw)).to_str() {
-                Ok(s) => s,
-                _ => panic!("Invalid string?"),
-            }
-        }
+    pub fn error_string(&self) -> String {
+        unsafe { strings::to_string_lossy(sys::SBErrorGetCString(self.raw)) }
+    }
+
+    /// Construct a new generic error with the given message, for
+    /// failures found on the Rust side rather than by LLDB.
+    pub fn from_message(message: &str) -> SBError {
+        let mut error = SBError::new();
+        error.set_error_string(message);
+        error
+    }
+
+    /// Set this to be a generic error with the given message.
+    pub fn set_error_string(&mut self, error_string: &str) {
+        let error_string = CString::new(error_string).unwrap();
+        unsafe { sys::SBErrorSetErrorString(self.raw, error_string.as_ptr()) };
     }
 
     /// Does this error represent a failure?
@@ -95,6 +118,28 @@ impl fmt::Debug for SBError {
     }
 }
 
+impl fmt::Display for SBError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(&Error::from(self), fmt)
+    }
+}
+
+impl error::Error for SBError {}
+
+impl From<SBError> for io::Error {
+    fn from(error: SBError) -> io::Error {
+        io::Error::from(Error::from(error))
+    }
+}
+
+impl TryFrom<sys::SBErrorRef> for SBError {
+    type Error = InvalidRefError;
+
+    fn try_from(raw: sys::SBErrorRef) -> Result<SBError, InvalidRefError> {
+        SBError::maybe_wrap(raw).ok_or(InvalidRefError)
+    }
+}
+
 impl Drop for SBError {
     fn drop(&mut self) {
         unsafe { sys::DisposeSBError(self.raw) };
@@ -103,3 +148,304 @@ impl Drop for SBError {
 
 unsafe impl Send for SBError {}
 unsafe impl Sync for SBError {}
+
+/// An error from LLDB, classified by the kind of error code it carries.
+///
+/// This is built from an [`SBError`] so that errors can be matched
+/// on by category rather than by comparing messages:
+///
+/// ```no_run
+/// # use lldb::{Error, SBProcess};
+/// # fn stop(process: &SBProcess) {
+/// match process.stop().map_err(Error::from) {
+///     Ok(()) => {}
+///     Err(Error::Posix { errno, .. }) => eprintln!("stop failed with errno {}", errno),
+///     Err(e) => eprintln!("stop failed: {}", e),
+/// }
+/// # }
+/// ```
+///
+/// An `Error` (or an `SBError`) can also be converted into an
+/// `io::Error`, so that `?` can be used in functions returning an
+/// `io::Result`.
+///
+/// In each case, the message reported by LLDB is preserved. If LLDB
+/// didn't provide a message, a description of the code is used
+/// instead where one is known.
+///
+/// [`SBError`]: struct.SBError.html
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Error {
+    /// An error without a specific error code.
+    Generic {
+        /// The error message.
+        message: String,
+    },
+    /// A Mach kernel error, from a `kern_return_t`.
+    MachKernel {
+        /// The `kern_return_t` value.
+        code: u32,
+        /// The error message.
+        message: String,
+    },
+    /// A POSIX error, from an `errno` value.
+    Posix {
+        /// The `errno` value.
+        errno: i32,
+        /// The error message.
+        message: String,
+    },
+    /// An error from evaluating an expression. The code is an
+    /// [`ExpressionResults`] value.
+    ///
+    /// [`ExpressionResults`]: enum.ExpressionResults.html
+    Expression {
+        /// The error code.
+        code: u32,
+        /// The error message.
+        message: String,
+    },
+    /// A Win32 error, from `GetLastError`.
+    Win32 {
+        /// The Win32 error code.
+        code: u32,
+        /// The error message.
+        message: String,
+    },
+    /// An error of an unknown type.
+    Unknown {
+        /// The error code.
+        code: u32,
+        /// The error message.
+        message: String,
+    },
+}
+
+impl Error {
+    /// The error message.
+    pub fn message(&self) -> &str {
+        match *self {
+            Error::Generic { ref message }
+            | Error::MachKernel { ref message, .. }
+            | Error::Posix { ref message, .. }
+            | Error::Expression { ref message, .. }
+            | Error::Win32 { ref message, .. }
+            | Error::Unknown { ref message, .. } => message,
+        }
+    }
+
+    /// The type of the error.
+    pub fn error_type(&self) -> ErrorType {
+        match *self {
+            Error::Generic { .. } => ErrorType::Generic,
+            Error::MachKernel { .. } => ErrorType::MachKernel,
+            Error::Posix { .. } => ErrorType::POSIX,
+            Error::Expression { .. } => ErrorType::Expression,
+            Error::Win32 { .. } => ErrorType::Win32,
+            Error::Unknown { .. } => ErrorType::Invalid,
+        }
+    }
+
+    /// The error code of the host operating system, if this is one.
+    ///
+    /// This is the `errno` value of a POSIX error on Unix, and the code
+    /// of a Win32 error on Windows, matching
+    /// `io::Error::raw_os_error`.
+    pub fn raw_os_error(&self) -> Option<i32> {
+        match *self {
+            #[cfg(not(windows))]
+            Error::Posix { errno, .. } => Some(errno),
+            #[cfg(windows)]
+            Error::Win32 { code, .. } => Some(code as i32),
+            _ => None,
+        }
+    }
+}
+
+impl<'a> From<&'a SBError> for Error {
+    fn from(error: &'a SBError) -> Error {
+        let code = error.error();
+        let message = error.error_string();
+        match error.error_type() {
+            ErrorType::MachKernel => Error::MachKernel {
+                code,
+                message: or_else(message, || kern_return_name(code).map(str::to_owned)),
+            },
+            ErrorType::POSIX => {
+                let errno = code as i32;
+                Error::Posix {
+                    errno,
+                    message: or_else(message, || {
+                        Some(io::Error::from_raw_os_error(errno).to_string())
+                    }),
+                }
+            }
+            ErrorType::Expression => Error::Expression { code, message },
+            ErrorType::Win32 => Error::Win32 {
+                code,
+                message: or_else(message, || win32_error_message(code)),
+            },
+            ErrorType::Generic => Error::Generic { message },
+            ErrorType::Invalid => Error::Unknown { code, message },
+        }
+    }
+}
+
+impl From<SBError> for Error {
+    fn from(error: SBError) -> Error {
+        Error::from(&error)
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        let message = self.message();
+        match *self {
+            Error::MachKernel { code, .. } if message.is_empty() => {
+                write!(fmt, "kernel error {}", code)
+            }
+            Error::Expression { code, .. } if message.is_empty() => {
+                write!(fmt, "expression error {}", code)
+            }
+            Error::Win32 { code, .. } if message.is_empty() => {
+                write!(fmt, "Win32 error {}", code)
+            }
+            Error::Unknown { code, .. } if message.is_empty() => write!(fmt, "error {}", code),
+            _ => write!(fmt, "{}", message),
+        }
+    }
+}
+
+impl error::Error for Error {}
+
+/// Errors from the host operating system, which are POSIX errors on
+/// Unix and Win32 errors on Windows, are mapped to the matching
+/// `io::ErrorKind`. All other
+/// errors have a kind of `io::ErrorKind::Other`. The original error is
+/// kept as the inner error.
+impl From<Error> for io::Error {
+    fn from(error: Error) -> io::Error {
+        let kind = match error.raw_os_error() {
+            Some(errno) => io::Error::from_raw_os_error(errno).kind(),
+            None => io::ErrorKind::Other,
+        };
+        io::Error::new(kind, error)
+    }
+}
+
+/// The error returned when converting a raw reference that isn't
+/// valid into one of the `SB*` wrappers with `TryFrom`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct InvalidRefError;
+
+impl fmt::Display for InvalidRefError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "invalid reference to an LLDB object")
+    }
+}
+
+impl error::Error for InvalidRefError {}
+
+fn or_else<F: FnOnce() -> Option<String>>(message: String, fallback: F) -> String {
+    if message.is_empty() {
+        fallback().unwrap_or(message)
+    } else {
+        message
+    }
+}
+
+/// A description of a Win32 error code.
+///
+/// On Windows, this is the system's message for the code. Elsewhere,
+/// such as when debugging a Windows minidump or a remote Windows
+/// host, the name of the more common codes is used.
+#[cfg(windows)]
+fn win32_error_message(code: u32) -> Option<String> {
+    Some(io::Error::from_raw_os_error(code as i32).to_string())
+}
+
+#[cfg(not(windows))]
+fn win32_error_message(code: u32) -> Option<String> {
+    let name = match code {
+        1 => "ERROR_INVALID_FUNCTION",
+        2 => "ERROR_FILE_NOT_FOUND",
+        3 => "ERROR_PATH_NOT_FOUND",
+        5 => "ERROR_ACCESS_DENIED",
+        6 => "ERROR_INVALID_HANDLE",
+        8 => "ERROR_NOT_ENOUGH_MEMORY",
+        14 => "ERROR_OUTOFMEMORY",
+        32 => "ERROR_SHARING_VIOLATION",
+        50 => "ERROR_NOT_SUPPORTED",
+        87 => "ERROR_INVALID_PARAMETER",
+        122 => "ERROR_INSUFFICIENT_BUFFER",
+        193 => "ERROR_BAD_EXE_FORMAT",
+        299 => "ERROR_PARTIAL_COPY",
+        487 => "ERROR_INVALID_ADDRESS",
+        740 => "ERROR_ELEVATION_REQUIRED",
+        998 => "ERROR_NOACCESS",
+        1460 => "ERROR_TIMEOUT",
+        _ => return None,
+    };
+    Some(name.to_string())
+}
+
+/// The name of a `kern_return_t` value, as defined in
+/// `<mach/kern_return.h>`.
+fn kern_return_name(code: u32) -> Option<&'static str> {
+    let name = match code {
+        1 => "KERN_INVALID_ADDRESS",
+        2 => "KERN_PROTECTION_FAILURE",
+        3 => "KERN_NO_SPACE",
+        4 => "KERN_INVALID_ARGUMENT",
+        5 => "KERN_FAILURE",
+        6 => "KERN_RESOURCE_SHORTAGE",
+        7 => "KERN_NOT_RECEIVER",
+        8 => "KERN_NO_ACCESS",
+        9 => "KERN_MEMORY_FAILURE",
+        10 => "KERN_MEMORY_ERROR",
+        11 => "KERN_ALREADY_IN_SET",
+        12 => "KERN_NOT_IN_SET",
+        13 => "KERN_NAME_EXISTS",
+        14 => "KERN_ABORTED",
+        15 => "KERN_INVALID_NAME",
+        16 => "KERN_INVALID_TASK",
+        17 => "KERN_INVALID_RIGHT",
+        18 => "KERN_INVALID_VALUE",
+        19 => "KERN_UREFS_OVERFLOW",
+        20 => "KERN_INVALID_CAPABILITY",
+        21 => "KERN_RIGHT_EXISTS",
+        22 => "KERN_INVALID_HOST",
+        23 => "KERN_MEMORY_PRESENT",
+        24 => "KERN_MEMORY_DATA_MOVED",
+        25 => "KERN_MEMORY_RESTART_COPY",
+        26 => "KERN_INVALID_PROCESSOR_SET",
+        27 => "KERN_POLICY_LIMIT",
+        28 => "KERN_INVALID_POLICY",
+        29 => "KERN_INVALID_OBJECT",
+        30 => "KERN_ALREADY_WAITING",
+        31 => "KERN_DEFAULT_SET",
+        32 => "KERN_EXCEPTION_PROTECTED",
+        33 => "KERN_INVALID_LEDGER",
+        34 => "KERN_INVALID_MEMORY_CONTROL",
+        35 => "KERN_INVALID_SECURITY",
+        36 => "KERN_NOT_DEPRESSED",
+        37 => "KERN_TERMINATED",
+        38 => "KERN_LOCK_SET_DESTROYED",
+        39 => "KERN_LOCK_UNSTABLE",
+        40 => "KERN_LOCK_OWNED",
+        41 => "KERN_LOCK_OWNED_SELF",
+        42 => "KERN_SEMAPHORE_DESTROYED",
+        43 => "KERN_RPC_SERVER_TERMINATED",
+        44 => "KERN_RPC_TERMINATE_ORPHAN",
+        45 => "KERN_RPC_CONTINUE_ORPHAN",
+        46 => "KERN_NOT_SUPPORTED",
+        47 => "KERN_NODE_DOWN",
+        48 => "KERN_NOT_WAITING",
+        49 => "KERN_OPERATION_TIMED_OUT",
+        50 => "KERN_CODESIGN_ERROR",
+        51 => "KERN_POLICY_STATIC",
+        52 => "KERN_INSUFFICIENT_BUFFER_SIZE",
+        _ => return None,
+    };
+    Some(name)
+}
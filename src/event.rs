@@ -5,8 +5,10 @@
 // except according to those terms.
 
 use super::broadcaster::SBBroadcaster;
+use super::error::InvalidRefError;
 use super::stream::SBStream;
-use std::ffi::CStr;
+use super::strings;
+use std::convert::TryFrom;
 use std::fmt;
 use sys;
 
@@ -18,14 +20,24 @@ pub struct SBEvent {
 
 impl SBEvent {
     /// Construct a new `SBEvent`.
-    pub fn wrap(raw: sys::SBEventRef) -> SBEvent {
+    ///
+    /// # Safety
+    ///
+    /// `raw` must be a valid reference, which is disposed of when the
+    /// `SBEvent` is dropped. Use [`maybe_wrap`] when it may be invalid.
+    ///
+    /// [`maybe_wrap`]: #method.maybe_wrap
+    pub unsafe fn wrap(raw: sys::SBEventRef) -> SBEvent {
         SBEvent { raw }
     }
 
     /// Construct a new `Some(SBEvent)` or `None`.
+    ///
+    /// `raw` is disposed of if it is invalid.
     pub fn maybe_wrap(raw: sys::SBEventRef) -> Option<SBEvent> {
-        if unsafe { sys::SBEventIsValid(raw) != 0 } {
-            Some(SBEvent { raw })
+        let wrapped = SBEvent { raw };
+        if wrapped.is_valid() {
+            Some(wrapped)
         } else {
             None
         }
@@ -33,7 +45,7 @@ impl SBEvent {
 
     #[allow(missing_docs)]
     pub fn new() -> SBEvent {
-        Self::wrap(unsafe { sys::CreateSBEvent() })
+        unsafe { Self::wrap(sys::CreateSBEvent()) }
     }
 
     /// Check whether or not this is a valid `SBEvent` value.
@@ -42,13 +54,8 @@ impl SBEvent {
     }
 
     #[allow(missing_docs)]
-    pub fn data_flavor(&self) -> &str {
-        unsafe {
-            match CStr::from_ptr(sys::SBEventGetDataFlavor(self.raw)).to_str() {
-                Ok(s) => s,
-                _ => panic!("Invalid string?"),
-            }
-        }
+    pub fn data_flavor(&self) -> Option<String> {
+        unsafe { strings::to_string(sys::SBEventGetDataFlavor(self.raw)) }
     }
 
     #[allow(missing_docs)]
@@ -58,23 +65,24 @@ impl SBEvent {
 
     #[allow(missing_docs)]
     pub fn broadcaster(&self) -> SBBroadcaster {
-        SBBroadcaster::wrap(unsafe { sys::SBEventGetBroadcaster(self.raw) })
+        unsafe { SBBroadcaster::wrap(sys::SBEventGetBroadcaster(self.raw)) }
     }
 
     #[allow(missing_docs)]
-    pub fn broadcaster_class(&self) -> &str {
-        unsafe {
-            match CStr::from_ptr(sys::SBEventGetBroadcasterClass(self.raw)).to_str() {
-                Ok(s) => s,
-                _ => panic!("Invalid string?"),
-            }
-        }
+    pub fn broadcaster_class(&self) -> Option<String> {
+        unsafe { strings::to_string(sys::SBEventGetBroadcasterClass(self.raw)) }
     }
 
     #[allow(missing_docs)]
     pub fn broadcaster_matches_ref(&self, broadcaster: &SBBroadcaster) -> bool {
         unsafe { sys::SBEventBroadcasterMatchesRef(self.raw, broadcaster.raw) != 0 }
     }
+
+    fn description(&self) -> String {
+        let stream = SBStream::new();
+        unsafe { sys::SBEventGetDescription(self.raw, stream.raw) };
+        stream.data()
+    }
 }
 
 impl Clone for SBEvent {
@@ -87,9 +95,21 @@ impl Clone for SBEvent {
 
 impl fmt::Debug for SBEvent {
     fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
-        let stream = SBStream::new();
-        unsafe { sys::SBEventGetDescription(self.raw, stream.raw) };
-        write!(fmt, "SBEvent {{ {} }}", stream.data())
+        write!(fmt, "SBEvent {{ {} }}", self.description())
+    }
+}
+
+impl fmt::Display for SBEvent {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "{}", self.description())
+    }
+}
+
+impl TryFrom<sys::SBEventRef> for SBEvent {
+    type Error = InvalidRefError;
+
+    fn try_from(raw: sys::SBEventRef) -> Result<SBEvent, InvalidRefError> {
+        SBEvent::maybe_wrap(raw).ok_or(InvalidRefError)
     }
 }
 
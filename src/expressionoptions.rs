@@ -4,6 +4,7 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
+use super::LanguageType;
 use sys;
 
 #[allow(missing_docs)]
@@ -16,11 +17,17 @@ pub struct SBExpressionOptions {
 impl SBExpressionOptions {
     /// Construct a new `SBExpressionOptions`.
     pub fn new() -> SBExpressionOptions {
-        SBExpressionOptions::wrap(unsafe { sys::CreateSBExpressionOptions() })
+        unsafe { SBExpressionOptions::wrap(sys::CreateSBExpressionOptions()) }
     }
 
     /// Construct a new `SBExpressionOptions`.
-    pub fn wrap(raw: sys::SBExpressionOptionsRef) -> SBExpressionOptions {
+    ///
+    /// # Safety
+    ///
+    /// `raw` must be an `SBExpressionOptionsRef` from
+    /// `CreateSBExpressionOptions` which isn't used elsewhere, as it is
+    /// disposed of when the `SBExpressionOptions` is dropped.
+    pub unsafe fn wrap(raw: sys::SBExpressionOptionsRef) -> SBExpressionOptions {
         SBExpressionOptions { raw }
     }
 
@@ -43,6 +50,25 @@ impl SBExpressionOptions {
     pub fn set_ignore_breakpoints(&self, ignore: bool) {
         unsafe { sys::SBExpressionOptionsSetIgnoreBreakpoints(self.raw, ignore as u8) };
     }
+
+    /// Whether results are not kept in persistent variables such as
+    /// `$0`.
+    pub fn suppress_persistent_result(&self) -> bool {
+        unsafe { sys::SBExpressionOptionsGetSuppressPersistentResult(self.raw) != 0 }
+    }
+
+    /// Don't keep results in persistent variables such as `$0`, for
+    /// expressions run for their own purposes, such as by a watch
+    /// window, which shouldn't use up the numbers seen by the user.
+    pub fn set_suppress_persistent_result(&self, suppress: bool) {
+        unsafe { sys::SBExpressionOptionsSetSuppressPersistentResult(self.raw, suppress as u8) };
+    }
+
+    /// Parse expressions as `language`, rather than in the language
+    /// of the frame they are evaluated in.
+    pub fn set_language(&self, language: LanguageType) {
+        unsafe { sys::SBExpressionOptionsSetLanguage(self.raw, language) };
+    }
 }
 
 impl Clone for SBExpressionOptions {
@@ -66,4 +92,3 @@ impl Drop for SBExpressionOptions {
 }
 
 unsafe impl Send for SBExpressionOptions {}
-unsafe impl Sync for SBExpressionOptions {}
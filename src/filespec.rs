@@ -4,9 +4,14 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
+use super::error::InvalidRefError;
 use super::stream::SBStream;
-use std::ffi::CStr;
+use super::strings;
+use std::convert::TryFrom;
+use std::ffi::CString;
 use std::fmt;
+use std::os::raw::c_char;
+use std::path::{Path, PathBuf};
 use sys;
 
 /// A file specification that divides the path into a
@@ -14,21 +19,68 @@ use sys;
 ///
 /// The string values of the paths are put into uniqued string pools
 /// for fast comparisons and efficient memory usage.
+///
+/// A file spec can be created from a [`Path`] and converted back
+/// into a [`PathBuf`]:
+///
+/// ```no_run
+/// # use lldb::SBFileSpec;
+/// # use std::path::Path;
+/// let file_spec = SBFileSpec::from(Path::new("/usr/bin/true"));
+/// assert_eq!(file_spec.filename().as_deref(), Some("true"));
+/// assert_eq!(file_spec.to_path_buf(), Path::new("/usr/bin/true"));
+/// ```
+///
+/// [`Path`]: https://doc.rust-lang.org/std/path/struct.Path.html
+/// [`PathBuf`]: https://doc.rust-lang.org/std/path/struct.PathBuf.html
 pub struct SBFileSpec {
     /// The underlying raw `SBFileSpecRef`.
     pub raw: sys::SBFileSpecRef,
 }
 
 impl SBFileSpec {
+    /// Construct a new, empty `SBFileSpec`.
+    pub fn new() -> SBFileSpec {
+        unsafe { SBFileSpec::wrap(sys::CreateSBFileSpec()) }
+    }
+
+    /// Construct a new `SBFileSpec` for a path.
+    ///
+    /// If `resolve` is `true`, then the path will be resolved, expanding
+    /// a leading `~` and making relative paths absolute.
+    pub fn from_path(path: &str, resolve: bool) -> SBFileSpec {
+        let path = CString::new(path).unwrap();
+        unsafe { SBFileSpec::wrap(sys::CreateSBFileSpec3(path.as_ptr(), resolve as u8)) }
+    }
+
+    /// Resolve a path, expanding a leading `~` and making relative
+    /// paths absolute.
+    pub fn resolve_path(path: &str) -> String {
+        let path = CString::new(path).unwrap();
+        read_path(|buf, len| unsafe {
+            sys::SBFileSpecResolvePath(path.as_ptr(), buf, len) as usize
+        })
+    }
+
     /// Construct a new `SBFileSpec`.
-    pub fn wrap(raw: sys::SBFileSpecRef) -> SBFileSpec {
+    ///
+    /// # Safety
+    ///
+    /// `raw` must be a valid reference, which is disposed of when the
+    /// `SBFileSpec` is dropped. Use [`maybe_wrap`] when it may be invalid.
+    ///
+    /// [`maybe_wrap`]: #method.maybe_wrap
+    pub unsafe fn wrap(raw: sys::SBFileSpecRef) -> SBFileSpec {
         SBFileSpec { raw }
     }
 
     /// Construct a new `Some(SBFileSpec)` or `None`.
+    ///
+    /// `raw` is disposed of if it is invalid.
     pub fn maybe_wrap(raw: sys::SBFileSpecRef) -> Option<SBFileSpec> {
-        if unsafe { sys::SBFileSpecIsValid(raw) != 0 } {
-            Some(SBFileSpec { raw })
+        let wrapped = SBFileSpec { raw };
+        if wrapped.is_valid() {
+            Some(wrapped)
         } else {
             None
         }
@@ -45,23 +97,63 @@ impl SBFileSpec {
     }
 
     /// The path file name.
-    pub fn filename(&self) -> &str {
-        unsafe {
-            match CStr::from_ptr(sys::SBFileSpecGetFilename(self.raw)).to_str() {
-                Ok(s) => s,
-                _ => panic!("Invalid string?"),
-            }
-        }
+    pub fn filename(&self) -> Option<String> {
+        unsafe { strings::to_string(sys::SBFileSpecGetFilename(self.raw)) }
+    }
+
+    /// Set the path file name.
+    pub fn set_filename(&mut self, filename: &str) {
+        let filename = CString::new(filename).unwrap();
+        unsafe { sys::SBFileSpecSetFilename(self.raw, filename.as_ptr()) };
     }
 
     /// The path directory name.
-    pub fn directory(&self) -> &str {
-        unsafe {
-            match CStr::from_ptr(sys::SBFileSpecGetDirectory(self.raw)).to_str() {
-                Ok(s) => s,
-                _ => panic!("Invalid string?"),
-            }
+    pub fn directory(&self) -> Option<String> {
+        unsafe { strings::to_string(sys::SBFileSpecGetDirectory(self.raw)) }
+    }
+
+    /// Set the path directory name.
+    pub fn set_directory(&mut self, directory: &str) {
+        let directory = CString::new(directory).unwrap();
+        unsafe { sys::SBFileSpecSetDirectory(self.raw, directory.as_ptr()) };
+    }
+
+    /// The full path, combining the directory and file name.
+    pub fn path(&self) -> String {
+        read_path(|buf, len| unsafe { sys::SBFileSpecGetPath(self.raw, buf, len) as usize })
+    }
+
+    /// The full path, combining the directory and file name.
+    pub fn to_path_buf(&self) -> PathBuf {
+        PathBuf::from(self.path())
+    }
+
+    /// Search for the executable in the directories listed in
+    /// the `PATH` environment variable, if this file spec only
+    /// has a file name.
+    ///
+    /// Returns `true` if the executable was found, in which case
+    /// this file spec is updated with the full path.
+    pub fn resolve_executable_location(&self) -> bool {
+        unsafe { sys::SBFileSpecResolveExecutableLocation(self.raw) != 0 }
+    }
+}
+
+/// Read a path from a function which writes a NUL terminated path into
+/// a buffer and returns the length of the path that was written.
+fn read_path<F>(read: F) -> String
+where
+    F: Fn(*mut c_char, usize) -> usize,
+{
+    let mut buf: Vec<u8> = vec![0; 1024];
+    loop {
+        let len = read(buf.as_mut_ptr() as *mut c_char, buf.len());
+        if len + 1 < buf.len() {
+            buf.truncate(len);
+            return String::from_utf8_lossy(&buf).into_owned();
         }
+        let new_len = buf.len() * 2;
+        buf.resize(new_len, 0);
     }
 }
 
@@ -73,6 +165,37 @@ impl Clone for SBFileSpec {
     }
 }
 
+impl Default for SBFileSpec {
+    fn default() -> SBFileSpec {
+        SBFileSpec::new()
+    }
+}
+
+/// On Windows, the `\\?\` prefix which `fs::canonicalize` adds to
+/// paths is removed, as LLDB doesn't understand it.
+impl<'a> From<&'a Path> for SBFileSpec {
+    fn from(path: &'a Path) -> SBFileSpec {
+        let path = path.to_string_lossy();
+        if cfg!(windows) {
+            SBFileSpec::from_path(&strip_verbatim_prefix(&path), false)
+        } else {
+            SBFileSpec::from_path(&path, false)
+        }
+    }
+}
+
+/// Turn a Windows verbatim path, such as `\\?\C:\foo` or
+/// `\\?\UNC\server\share`, into the ordinary form.
+fn strip_verbatim_prefix(path: &str) -> String {
+    if let Some(rest) = path.strip_prefix(r"\\?\UNC\") {
+        format!(r"\\{}", rest)
+    } else if let Some(rest) = path.strip_prefix(r"\\?\") {
+        rest.to_string()
+    } else {
+        path.to_string()
+    }
+}
+
 impl fmt::Debug for SBFileSpec {
     fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
         let stream = SBStream::new();
@@ -81,6 +204,21 @@ impl fmt::Debug for SBFileSpec {
     }
 }
 
+/// Displays the full path.
+impl fmt::Display for SBFileSpec {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "{}", self.path())
+    }
+}
+
+impl TryFrom<sys::SBFileSpecRef> for SBFileSpec {
+    type Error = InvalidRefError;
+
+    fn try_from(raw: sys::SBFileSpecRef) -> Result<SBFileSpec, InvalidRefError> {
+        SBFileSpec::maybe_wrap(raw).ok_or(InvalidRefError)
+    }
+}
+
 impl Drop for SBFileSpec {
     fn drop(&mut self) {
         unsafe { sys::DisposeSBFileSpec(self.raw) };
@@ -91,20 +229,30 @@ unsafe impl Send for SBFileSpec {}
 unsafe impl Sync for SBFileSpec {}
 
 #[cfg(feature = "graphql")]
-graphql_object!(SBFileSpec: super::debugger::SBDebugger | &self | {
-    field is_valid() -> bool {
+#[graphql_object(context = super::debugger::SBDebugger)]
+impl SBFileSpec {
+    #[graphql(name = "isValid")]
+    fn graphql_is_valid(&self) -> bool {
         self.is_valid()
     }
 
-    field exists() -> bool {
+    #[graphql(name = "exists")]
+    fn graphql_exists(&self) -> bool {
         self.exists()
     }
 
-    field filename() -> &str {
+    #[graphql(name = "filename")]
+    fn graphql_filename(&self) -> Option<String> {
         self.filename()
     }
 
-    field directory() -> &str {
+    #[graphql(name = "directory")]
+    fn graphql_directory(&self) -> Option<String> {
         self.directory()
     }
-});
+
+    #[graphql(name = "path")]
+    fn graphql_path(&self) -> String {
+        self.path()
+    }
+}
@@ -7,6 +7,8 @@
 use super::filespec::SBFileSpec;
 use super::stream::SBStream;
 use std::fmt;
+use std::iter;
+use std::path::Path;
 use sys;
 
 /// A list of [filespecs].
@@ -20,11 +22,17 @@ pub struct SBFileSpecList {
 impl SBFileSpecList {
     /// Construct a new `SBFileSpecList`
     pub fn new() -> SBFileSpecList {
-        SBFileSpecList::wrap(unsafe { sys::CreateSBFileSpecList() })
+        unsafe { SBFileSpecList::wrap(sys::CreateSBFileSpecList()) }
     }
 
     /// Construct a new `SBFileSpecList`.
-    pub fn wrap(raw: sys::SBFileSpecListRef) -> SBFileSpecList {
+    ///
+    /// # Safety
+    ///
+    /// `raw` must be an `SBFileSpecListRef` which isn't used elsewhere,
+    /// such as one from `CreateSBFileSpecList`, as it is disposed of when
+    /// the `SBFileSpecList` is dropped.
+    pub unsafe fn wrap(raw: sys::SBFileSpecListRef) -> SBFileSpecList {
         SBFileSpecList { raw }
     }
 
@@ -38,11 +46,48 @@ impl SBFileSpecList {
         unsafe { sys::SBFileSpecListAppendIfUnique(self.raw, file.raw) };
     }
 
+    /// The number of filespecs in this list.
+    pub fn len(&self) -> usize {
+        unsafe { sys::SBFileSpecListGetSize(self.raw) as usize }
+    }
+
     /// Is this filespec list empty?
     pub fn is_empty(&self) -> bool {
         unsafe { sys::SBFileSpecListGetSize(self.raw) == 0 }
     }
 
+    /// Get the filespec at the given index, if there is one.
+    pub fn get(&self, idx: usize) -> Option<SBFileSpec> {
+        if idx < self.len() {
+            SBFileSpec::maybe_wrap(unsafe {
+                sys::SBFileSpecListGetFileSpecAtIndex(self.raw, idx as u32)
+            })
+        } else {
+            None
+        }
+    }
+
+    /// Find the index of a filespec in this list, starting the search
+    /// at `start_idx`.
+    ///
+    /// If `full` is `true`, then both the directory and file name must
+    /// match. Otherwise, only the file name needs to match.
+    pub fn find_file_index(
+        &self,
+        start_idx: usize,
+        file: &SBFileSpec,
+        full: bool,
+    ) -> Option<usize> {
+        let idx = unsafe {
+            sys::SBFileSpecListFindFileIndex(self.raw, start_idx as u32, file.raw, full as u8)
+        };
+        if idx != u32::MAX {
+            Some(idx as usize)
+        } else {
+            None
+        }
+    }
+
     /// Clear this filespec list.
     pub fn clear(&self) {
         unsafe { sys::SBFileSpecListClear(self.raw) };
@@ -79,6 +124,16 @@ impl Default for SBFileSpecList {
     }
 }
 
+impl<'a> iter::FromIterator<&'a Path> for SBFileSpecList {
+    fn from_iter<I: IntoIterator<Item = &'a Path>>(paths: I) -> SBFileSpecList {
+        let list = SBFileSpecList::new();
+        for path in paths {
+            list.append(&SBFileSpec::from(path));
+        }
+        list
+    }
+}
+
 impl Drop for SBFileSpecList {
     fn drop(&mut self) {
         unsafe { sys::DisposeSBFileSpecList(self.raw) };
@@ -86,7 +141,6 @@ impl Drop for SBFileSpecList {
 }
 
 unsafe impl Send for SBFileSpecList {}
-unsafe impl Sync for SBFileSpecList {}
 
 /// An iterator over the [filespecs] in an [`SBFileSpecList`].
 ///
@@ -102,9 +156,12 @@ impl<'d> Iterator for SBFileSpecListIter<'d> {
 
     fn next(&mut self) -> Option<SBFileSpec> {
         if self.idx < unsafe { sys::SBFileSpecListGetSize(self.filespec_list.raw) as usize } {
-            let r = SBFileSpec::wrap(unsafe {
-                sys::SBFileSpecListGetFileSpecAtIndex(self.filespec_list.raw, self.idx as u32)
-            });
+            let r = unsafe {
+                SBFileSpec::wrap(sys::SBFileSpecListGetFileSpecAtIndex(
+                    self.filespec_list.raw,
+                    self.idx as u32,
+                ))
+            };
             self.idx += 1;
             Some(r)
         } else {
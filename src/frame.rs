@@ -5,24 +5,42 @@
 // except according to those terms.
 
 use super::address::SBAddress;
+use super::argvalue::ArgValue;
 use super::block::SBBlock;
+use super::cancel::{CancelError, CancelToken};
 use super::compileunit::SBCompileUnit;
+use super::error::{InvalidRefError, SBError};
 use super::expressionoptions::SBExpressionOptions;
+use super::filespec::SBFileSpec;
 use super::function::SBFunction;
+#[cfg(feature = "graphql")]
+use super::graphql::U64;
 use super::lineentry::SBLineEntry;
-use super::lldb_addr_t;
 use super::module::SBModule;
+use super::registers::RegisterSnapshot;
+use super::stophandle::StopHandle;
 use super::stream::SBStream;
+use super::strings::{self, AsCStr};
 use super::symbol::SBSymbol;
 use super::symbolcontext::SBSymbolContext;
 use super::thread::SBThread;
+use super::timeout::{with_timeout_or_interrupt, TimedOut};
+use super::trace::traced;
+use super::types::SBType;
 use super::value::SBValue;
 use super::valuelist::SBValueList;
 use super::variablesoptions::SBVariablesOptions;
-use std::ffi::{CStr, CString};
+use super::{lldb_addr_t, BasicType, FunctionNameType, LanguageType, SymbolContextItem};
+use std::convert::TryFrom;
+use std::ffi::CString;
 use std::fmt;
+use std::time::Duration;
 use sys;
 
+/// The error code for an expression which succeeded without
+/// producing a value, as LLDB's `UserExpression::kNoResult`.
+pub(crate) const NO_RESULT: u32 = 0x1001;
+
 /// One of the stack frames associated with a thread.
 pub struct SBFrame {
     /// The underlying raw `SBFrameRef`.
@@ -31,14 +49,24 @@ pub struct SBFrame {
 
 impl SBFrame {
     /// Construct a new `SBFrame`.
-    pub fn wrap(raw: sys::SBFrameRef) -> SBFrame {
+    ///
+    /// # Safety
+    ///
+    /// `raw` must be a valid reference, which is disposed of when the
+    /// `SBFrame` is dropped. Use [`maybe_wrap`] when it may be invalid.
+    ///
+    /// [`maybe_wrap`]: #method.maybe_wrap
+    pub unsafe fn wrap(raw: sys::SBFrameRef) -> SBFrame {
         SBFrame { raw }
     }
 
     /// Construct a new `Some(SBFrame)` or `None`.
+    ///
+    /// `raw` is disposed of if it is invalid.
     pub fn maybe_wrap(raw: sys::SBFrameRef) -> Option<SBFrame> {
-        if unsafe { sys::SBFrameIsValid(raw) != 0 } {
-            Some(SBFrame { raw })
+        let wrapped = SBFrame { raw };
+        if wrapped.is_valid() {
+            Some(wrapped)
         } else {
             None
         }
@@ -93,7 +121,7 @@ impl SBFrame {
 
     /// The program counter (PC) as a section offset address (`SBAddress`).
     pub fn pc_address(&self) -> SBAddress {
-        SBAddress::wrap(unsafe { sys::SBFrameGetPCAddress(self.raw) })
+        unsafe { SBAddress::wrap(sys::SBFrameGetPCAddress(self.raw)) }
     }
 
     /// The symbol context for this frame's current pc value.
@@ -106,32 +134,58 @@ impl SBFrame {
     ///   is needed by the caller. These flags have constants starting
     ///   with `SYMBOL_CONTEXT_ITEM_`.
     pub fn symbol_context(&self, resolve_scope: u32) -> SBSymbolContext {
-        SBSymbolContext::wrap(unsafe { sys::SBFrameGetSymbolContext(self.raw, resolve_scope) })
+        unsafe { SBSymbolContext::wrap(sys::SBFrameGetSymbolContext(self.raw, resolve_scope)) }
     }
 
     /// The `SBModule` for this stack frame.
     pub fn module(&self) -> SBModule {
-        SBModule::wrap(unsafe { sys::SBFrameGetModule(self.raw) })
+        unsafe { SBModule::wrap(sys::SBFrameGetModule(self.raw)) }
     }
 
     /// The `SBCompileUnit` for this stack frame.
     pub fn compile_unit(&self) -> SBCompileUnit {
-        SBCompileUnit::wrap(unsafe { sys::SBFrameGetCompileUnit(self.raw) })
+        unsafe { SBCompileUnit::wrap(sys::SBFrameGetCompileUnit(self.raw)) }
+    }
+
+    /// The language of the code of this stack frame, or
+    /// `LanguageType::Unknown` if it can't be worked out.
+    ///
+    /// The language is guessed from the function, which tells apart
+    /// C++, Rust and Swift functions compiled into the same module,
+    /// falling back to the language of the compile unit.
+    pub fn language(&self) -> LanguageType {
+        match unsafe { sys::SBFrameGuessLanguage(self.raw) } {
+            LanguageType::Unknown => self.compile_unit().language(),
+            language => language,
+        }
+    }
+
+    /// Options for evaluating expressions in this frame, parsing them
+    /// in the frame's [`language`] when it is known.
+    ///
+    /// [`language`]: #method.language
+    pub fn expression_options(&self) -> SBExpressionOptions {
+        let options = SBExpressionOptions::new();
+        match self.language() {
+            LanguageType::Unknown => {}
+            language => options.set_language(language),
+        }
+        options
     }
 
     /// The `SBFunction` for this stack frame.
     pub fn function(&self) -> SBFunction {
-        SBFunction::wrap(unsafe { sys::SBFrameGetFunction(self.raw) })
+        unsafe { SBFunction::wrap(sys::SBFrameGetFunction(self.raw)) }
     }
 
     /// The `SBSymbol` for this stack frame.
     pub fn symbol(&self) -> SBSymbol {
-        SBSymbol::wrap(unsafe { sys::SBFrameGetSymbol(self.raw) })
+        unsafe { SBSymbol::wrap(sys::SBFrameGetSymbol(self.raw)) }
     }
 
     /// Get the deepest block that contains the frame PC.
     pub fn block(&self) -> SBBlock {
-        SBBlock::wrap(unsafe { sys::SBFrameGetBlock(self.raw) })
+        unsafe { SBBlock::wrap(sys::SBFrameGetBlock(self.raw)) }
     }
 
     /// Get the appropriate function name for this frame. Inlined functions in
@@ -148,23 +202,13 @@ impl SBFrame {
     /// * NULL
     ///
     /// See also `is_inlined`.
-    pub fn function_name(&self) -> Option<&str> {
-        unsafe {
-            match CStr::from_ptr(sys::SBFrameGetFunctionName(self.raw).as_ref()?).to_str() {
-                Ok(s) => Some(s),
-                _ => None,
-            }
-        }
+    pub fn function_name(&self) -> Option<String> {
+        unsafe { strings::to_string(sys::SBFrameGetFunctionName(self.raw)) }
     }
 
     #[allow(missing_docs)]
-    pub fn display_function_name(&self) -> Option<&str> {
-        unsafe {
-            match CStr::from_ptr(sys::SBFrameGetDisplayFunctionName(self.raw)).to_str() {
-                Ok(s) => Some(s),
-                _ => None,
-            }
-        }
+    pub fn display_function_name(&self) -> Option<String> {
+        unsafe { strings::to_string(sys::SBFrameGetDisplayFunctionName(self.raw)) }
     }
 
     /// Return `true` if this frame represents an inlined function.
@@ -172,14 +216,240 @@ impl SBFrame {
         unsafe { sys::SBFrameIsInlined(self.raw) != 0 }
     }
 
+    /// The name of the inlined function this frame represents, if it
+    /// is inlined.
+    pub fn inlined_name(&self) -> Option<String> {
+        self.inlined_block().and_then(|block| block.inlined_name())
+    }
+
+    /// The file containing the call which this frame's function was
+    /// inlined at, if it is inlined.
+    pub fn inlined_call_site_file(&self) -> Option<SBFileSpec> {
+        self.inlined_block()
+            .and_then(|block| block.inlined_call_site_file())
+    }
+
+    /// The line of the call which this frame's function was inlined
+    /// at, if it is inlined.
+    pub fn inlined_call_site_line(&self) -> Option<u32> {
+        self.inlined_block()
+            .and_then(|block| block.inlined_call_site_line())
+    }
+
+    /// The column of the call which this frame's function was inlined
+    /// at, if it is inlined and the column is known.
+    pub fn inlined_call_site_column(&self) -> Option<u32> {
+        self.inlined_block()
+            .and_then(|block| block.inlined_call_site_column())
+    }
+
+    fn inlined_block(&self) -> Option<SBBlock> {
+        if self.is_inlined() {
+            Some(self.frame_block()).filter(SBBlock::is_valid)
+        } else {
+            None
+        }
+    }
+
+    /// The functions being run at this frame's PC, starting with the
+    /// innermost, as the `bt` command shows them. All but the last
+    /// were inlined into the one following them.
+    ///
+    /// This expands a physical frame, as an unwinder finds it, into
+    /// its logical frames. The frames listed by [`SBThread`] are
+    /// already expanded, and each of the frames for the same physical
+    /// frame gives the same list.
+    ///
+    /// [`SBThread`]: struct.SBThread.html
+    pub fn inline_frames(&self) -> Vec<InlineFrame> {
+        let address = self.pc_address();
+        let mut frames = vec![];
+
+        // The PC's location is in the innermost function, and each
+        // inlined block records where it was called from in the
+        // function around it.
+        let mut location = address.line_entry().map(|entry| {
+            (
+                Some(entry.filespec()).filter(SBFileSpec::is_valid),
+                Some(entry.line()).filter(|&line| line > 0),
+                Some(entry.column()).filter(|&column| column > 0),
+            )
+        });
+        let mut block = address
+            .block()
+            .and_then(|block| block.containing_inlined_block());
+        while let Some(inlined) = block {
+            let (file, line, column) = location.take().unwrap_or((None, None, None));
+            frames.push(InlineFrame {
+                function_name: inlined.inlined_name(),
+                file,
+                line,
+                column,
+                is_inlined: true,
+            });
+            location = Some((
+                inlined.inlined_call_site_file(),
+                inlined.inlined_call_site_line(),
+                inlined.inlined_call_site_column(),
+            ));
+            block = inlined
+                .parent()
+                .and_then(|parent| parent.containing_inlined_block());
+        }
+
+        let function_name = match (address.function(), address.symbol()) {
+            (Some(function), _) => function.display_name(),
+            (None, Some(symbol)) => symbol.display_name(),
+            (None, None) => self.function_name(),
+        };
+        let (file, line, column) = location.unwrap_or((None, None, None));
+        frames.push(InlineFrame {
+            function_name,
+            file,
+            line,
+            column,
+            is_inlined: false,
+        });
+        frames
+    }
+
     /// Evaluate an expression within the context of this frame.
     pub fn evaluate_expression(&self, expression: &str, options: &SBExpressionOptions) -> SBValue {
-        let expression = CString::new(expression).unwrap();
-        SBValue::wrap(unsafe {
-            sys::SBFrameEvaluateExpression(self.raw, expression.as_ptr(), options.raw)
+        traced("SBFrame::evaluate_expression", || {
+            let expression = CString::new(expression).unwrap();
+            unsafe {
+                SBValue::wrap(sys::SBFrameEvaluateExpression(
+                    self.raw,
+                    expression.as_ptr(),
+                    options.raw,
+                ))
+            }
         })
     }
 
+    /// Evaluate an expression within the context of this frame,
+    /// interrupting it if it hasn't finished after `timeout`.
+    pub fn evaluate_expression_with_timeout(
+        &self,
+        expression: &str,
+        options: &SBExpressionOptions,
+        timeout: Duration,
+    ) -> Result<SBValue, TimedOut> {
+        let frame = self.clone();
+        let expression = expression.to_string();
+        let options = options.clone();
+        let process = self.thread().process();
+        with_timeout_or_interrupt(
+            timeout,
+            move || frame.evaluate_expression(&expression, &options),
+            || process.send_async_interrupt(),
+        )
+    }
+
+    /// Evaluate an expression within the context of this frame,
+    /// interrupting it if `token` is cancelled.
+    pub fn evaluate_expression_cancellable(
+        &self,
+        expression: &str,
+        options: &SBExpressionOptions,
+        token: &CancelToken,
+    ) -> Result<SBValue, CancelError> {
+        let process = self.thread().process();
+        token.run(
+            || Ok(self.evaluate_expression(expression, options)),
+            move || process.send_async_interrupt(),
+        )
+    }
+
+    /// Call the function `name` in the process, in the context of this
+    /// frame, with `args`.
+    ///
+    /// When there is debug information for the function, the number
+    /// of arguments is checked against its parameters, as is passing
+    /// a pointer or string where the function doesn't take a pointer.
+    /// Otherwise, the function is called as if it took the C types of
+    /// the arguments and returned a `long`.
+    ///
+    /// ```no_run
+    /// # use lldb::{ArgValue, SBError, SBFrame};
+    /// # fn print_zones(frame: &SBFrame) -> Result<(), SBError> {
+    /// frame.call_function("malloc_zone_print", &[ArgValue::Pointer(0), ArgValue::Bool(false)])?;
+    /// let length = frame.call_function("strlen", &["hello".into()])?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn call_function(&self, name: &str, args: &[ArgValue]) -> Result<SBValue, SBError> {
+        let fail = |message: String| Err(SBError::from_message(&message));
+        let is_name_char = |c: char| c.is_ascii_alphanumeric() || c == '_' || c == ':' || c == '$';
+        if name.is_empty() || !name.chars().all(is_name_char) {
+            return fail(format!("`{}` is not a function name", name));
+        }
+        let arguments = args
+            .iter()
+            .map(ArgValue::to_string)
+            .collect::<Vec<_>>()
+            .join(", ");
+        let function_type = self
+            .thread()
+            .process()
+            .target()
+            .find_functions(name, FunctionNameType::Auto as u32)
+            .iter()
+            .map(|context| context.function())
+            .find(|function| function.is_valid())
+            // This is the type of the function itself.
+            .map(|function| function.return_type())
+            .filter(SBType::is_function_type);
+        let mut returns_void = false;
+        let expression = match function_type {
+            Some(function_type) => {
+                let params: Vec<SBType> = function_type.function_argument_types().iter().collect();
+                let variadic = function_type
+                    .name()
+                    .is_some_and(|name| name.contains("..."));
+                if args.len() < params.len() || (!variadic && args.len() > params.len()) {
+                    return fail(format!(
+                        "`{}` takes {} arguments but {} were given",
+                        name,
+                        params.len(),
+                        args.len()
+                    ));
+                }
+                for (idx, (arg, param)) in args.iter().zip(&params).enumerate() {
+                    let param = param.canonical_type().unwrap_or_else(|| param.clone());
+                    if arg.is_pointer() && !param.is_pointer_type() {
+                        return fail(format!(
+                            "argument {} of `{}` is a {}, not a pointer",
+                            idx + 1,
+                            name,
+                            param.display_type_name().unwrap_or_default()
+                        ));
+                    }
+                }
+                returns_void = function_type
+                    .function_return_type()
+                    .is_some_and(|t| t.basic_type() == BasicType::Void);
+                format!("{}({})", name, arguments)
+            }
+            None => {
+                let prototype = args
+                    .iter()
+                    .map(ArgValue::c_type)
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                format!("((long (*)({})){})({})", prototype, name, arguments)
+            }
+        };
+        let value = self.evaluate_expression(&expression, &SBExpressionOptions::new());
+        match value.error() {
+            // A call to a `void` function succeeds without producing a
+            // value, which LLDB reports with this error code.
+            Some(ref error) if returns_void && error.error() == NO_RESULT => Ok(value),
+            Some(error) if error.is_failure() => Err(error),
+            _ => Ok(value),
+        }
+    }
+
     /// Gets the lexical block that defines the stack frame. Another way to think
     /// of this is it will return the block that contains all of the variables
     /// for a stack frame. Inlined functions are represented as `SBBlock` objects
@@ -193,7 +463,7 @@ impl SBFrame {
     /// block that defines this frame. If the PC isn't currently in an inlined
     /// function, the lexical block that defines the function is returned.
     pub fn frame_block(&self) -> SBBlock {
-        SBBlock::wrap(unsafe { sys::SBFrameGetFrameBlock(self.raw) })
+        unsafe { SBBlock::wrap(sys::SBFrameGetFrameBlock(self.raw)) }
     }
 
     /// The line table entry (`SBLineEntry`) for this stack frame.
@@ -203,22 +473,23 @@ impl SBFrame {
 
     /// The thread that is executing this stack frame.
     pub fn thread(&self) -> SBThread {
-        SBThread::wrap(unsafe { sys::SBFrameGetThread(self.raw) })
+        unsafe { SBThread::wrap(sys::SBFrameGetThread(self.raw)) }
+    }
+
+    /// A handle to the frame which can only be used until the process
+    /// runs again or exits.
+    pub fn stop_handle(&self) -> StopHandle<SBFrame> {
+        StopHandle::new(self.clone(), self.thread().process(), SBFrame::is_valid)
     }
 
     /// The disassembly of this function, presented as a string.
-    pub fn disassemble(&self) -> &str {
-        unsafe {
-            match CStr::from_ptr(sys::SBFrameDisassemble(self.raw)).to_str() {
-                Ok(s) => s,
-                _ => panic!("Invalid string?"),
-            }
-        }
+    pub fn disassemble(&self) -> String {
+        unsafe { strings::to_string_lossy(sys::SBFrameDisassemble(self.raw)) }
     }
 
     /// The values for variables matching the specified options.
     pub fn variables(&self, options: &SBVariablesOptions) -> SBValueList {
-        SBValueList::wrap(unsafe { sys::SBFrameGetVariables(self.raw, options.raw) })
+        unsafe { SBValueList::wrap(sys::SBFrameGetVariables(self.raw, options.raw)) }
     }
 
     /// The values for all variables in this stack frame.
@@ -263,13 +534,59 @@ impl SBFrame {
 
     /// The values for the CPU registers for this stack frame.
     pub fn registers(&self) -> SBValueList {
-        SBValueList::wrap(unsafe { sys::SBFrameGetRegisters(self.raw) })
+        unsafe { SBValueList::wrap(sys::SBFrameGetRegisters(self.raw)) }
+    }
+
+    /// Capture the values of all of the registers of this frame.
+    pub fn register_snapshot(&self) -> RegisterSnapshot {
+        RegisterSnapshot::capture(self)
     }
 
     /// The value for a particular register, if present.
-    pub fn find_register(&self, name: &str) -> Option<SBValue> {
-        let name = CString::new(name).unwrap();
-        SBValue::maybe_wrap(unsafe { sys::SBFrameFindRegister(self.raw, name.as_ptr()) })
+    pub fn find_register<S: AsCStr + ?Sized>(&self, name: &S) -> Option<SBValue> {
+        name.with_cstr(|name| {
+            SBValue::maybe_wrap(unsafe { sys::SBFrameFindRegister(self.raw, name.as_ptr()) })
+        })
+    }
+
+    /// Find a local, argument or static variable which is in scope in
+    /// this frame by name.
+    ///
+    /// See [`AsCStr`] for avoiding the conversion of `name` on each
+    /// call.
+    ///
+    /// [`AsCStr`]: trait.AsCStr.html
+    pub fn find_variable<S: AsCStr + ?Sized>(&self, name: &S) -> Option<SBValue> {
+        name.with_cstr(|name| {
+            SBValue::maybe_wrap(unsafe { sys::SBFrameFindVariable(self.raw, name.as_ptr()) })
+        })
+    }
+
+    /// Gather the location of this frame.
+    ///
+    /// This resolves the symbol context of the frame once, rather
+    /// than once for each of [`module`], [`line_entry`] and so on.
+    ///
+    /// [`module`]: #method.module
+    /// [`line_entry`]: #method.line_entry
+    pub fn info(&self) -> FrameInfo {
+        let scope = SymbolContextItem::MODULE | SymbolContextItem::LINE_ENTRY;
+        let context = self.symbol_context(scope.bits());
+        let module = context.module();
+        let line_entry = context.line_entry();
+        FrameInfo {
+            index: self.frame_id(),
+            pc: self.pc(),
+            function_name: self.function_name(),
+            module: if module.is_valid() {
+                Some(module.filespec().path())
+            } else {
+                None
+            },
+            file: line_entry.as_ref().map(|l| l.filespec().path()),
+            line: line_entry.as_ref().map(SBLineEntry::line),
+            column: line_entry.as_ref().map(SBLineEntry::column),
+        }
     }
 
     /// The parent frame that invoked this frame, if available.
@@ -282,6 +599,55 @@ impl SBFrame {
             None
         }
     }
+
+    fn description(&self) -> String {
+        let stream = SBStream::new();
+        unsafe { sys::SBFrameGetDescription(self.raw, stream.raw) };
+        stream.data()
+    }
+}
+
+/// The location of a stack frame, as plain data.
+///
+/// This is created with [`SBFrame::info`] or, for a whole stack,
+/// [`SBThread::collect_frames`].
+///
+/// [`SBFrame::info`]: struct.SBFrame.html#method.info
+/// [`SBThread::collect_frames`]: struct.SBThread.html#method.collect_frames
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FrameInfo {
+    /// The index of the frame in the stack.
+    pub index: u32,
+    /// The program counter.
+    pub pc: lldb_addr_t,
+    /// The name of the function, if known.
+    pub function_name: Option<String>,
+    /// The path of the module containing the code, if known.
+    pub module: Option<String>,
+    /// The path of the source file, if there is line information.
+    pub file: Option<String>,
+    /// The source line, if there is line information.
+    pub line: Option<u32>,
+    /// The source column, if there is line information.
+    pub column: Option<u32>,
+}
+
+/// A function being run at a frame's PC, from
+/// [`SBFrame::inline_frames`].
+///
+/// [`SBFrame::inline_frames`]: struct.SBFrame.html#method.inline_frames
+#[derive(Clone, Debug)]
+pub struct InlineFrame {
+    /// The name of the function, if known.
+    pub function_name: Option<String>,
+    /// The source file of the location in the function.
+    pub file: Option<SBFileSpec>,
+    /// The line of the location in the function, if known.
+    pub line: Option<u32>,
+    /// The column of the location in the function, if known.
+    pub column: Option<u32>,
+    /// Was the function inlined into the next one?
+    pub is_inlined: bool,
 }
 
 impl Clone for SBFrame {
@@ -294,9 +660,30 @@ impl Clone for SBFrame {
 
 impl fmt::Debug for SBFrame {
     fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
-        let stream = SBStream::new();
-        unsafe { sys::SBFrameGetDescription(self.raw, stream.raw) };
-        write!(fmt, "SBFrame {{ {} }}", stream.data())
+        write!(fmt, "SBFrame {{ {} }}", self.description())
+    }
+}
+
+impl fmt::Display for SBFrame {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "{}", self.description())
+    }
+}
+
+/// Frames are equal if they refer to the same frame of the same thread.
+impl PartialEq for SBFrame {
+    fn eq(&self, other: &SBFrame) -> bool {
+        unsafe { sys::SBFrameIsEqual(self.raw, other.raw) != 0 }
+    }
+}
+
+impl Eq for SBFrame {}
+
+impl TryFrom<sys::SBFrameRef> for SBFrame {
+    type Error = InvalidRefError;
+
+    fn try_from(raw: sys::SBFrameRef) -> Result<SBFrame, InvalidRefError> {
+        SBFrame::maybe_wrap(raw).ok_or(InvalidRefError)
     }
 }
 
@@ -310,81 +697,120 @@ unsafe impl Send for SBFrame {}
 unsafe impl Sync for SBFrame {}
 
 #[cfg(feature = "graphql")]
-graphql_object!(SBFrame: super::debugger::SBDebugger | &self | {
-    field is_valid() -> bool {
+#[graphql_object(context = super::debugger::SBDebugger)]
+impl SBFrame {
+    #[graphql(name = "isValid")]
+    fn graphql_is_valid(&self) -> bool {
         self.is_valid()
     }
 
-    // TODO(bm): This should be u32
-    field frame_id() -> i32 {
-        self.frame_id() as i32
+    #[graphql(name = "frameId")]
+    fn graphql_frame_id(&self) -> U64 {
+        self.frame_id().into()
     }
 
-    // TODO(bm) This should be u64
-    field cfa() -> Option<i32> {
-        self.cfa().map(|i| i as i32)
+    #[graphql(name = "cfa")]
+    fn graphql_cfa(&self) -> Option<U64> {
+        self.cfa().map(U64::from)
     }
 
-    // TODO(bm) This should be u64
-    field pc() -> i32 {
-        self.pc() as i32
+    #[graphql(name = "pc")]
+    fn graphql_pc(&self) -> U64 {
+        self.pc().into()
     }
 
-    // TODO(bm) This should be u64
-    field sp() -> i32 {
-        self.sp() as i32
+    #[graphql(name = "sp")]
+    fn graphql_sp(&self) -> U64 {
+        self.sp().into()
     }
 
-    // TODO(bm) This should be u64
-    field fp() -> i32 {
-        self.fp() as i32
+    #[graphql(name = "fp")]
+    fn graphql_fp(&self) -> U64 {
+        self.fp().into()
     }
 
-    field pc_address() -> SBAddress {
+    #[graphql(name = "pcAddress")]
+    fn graphql_pc_address(&self) -> SBAddress {
         self.pc_address()
     }
 
-    field module() -> SBModule {
+    #[graphql(name = "module")]
+    fn graphql_module(&self) -> SBModule {
         self.module()
     }
 
-    field compile_unit() -> SBCompileUnit {
+    #[graphql(name = "compileUnit")]
+    fn graphql_compile_unit(&self) -> SBCompileUnit {
         self.compile_unit()
     }
 
-    field function() -> SBFunction {
+    #[graphql(name = "function")]
+    fn graphql_function(&self) -> SBFunction {
         self.function()
     }
 
-    field symbol() -> SBSymbol {
+    #[graphql(name = "symbol")]
+    fn graphql_symbol(&self) -> SBSymbol {
         self.symbol()
     }
 
-    field block() -> SBBlock {
+    #[graphql(name = "block")]
+    fn graphql_block(&self) -> SBBlock {
         self.block()
     }
 
-    field function_name() -> Option<&str> {
+    #[graphql(name = "functionName")]
+    fn graphql_function_name(&self) -> Option<String> {
         self.function_name()
     }
 
-    field display_function_name() -> Option<&str> {
+    #[graphql(name = "displayFunctionName")]
+    fn graphql_display_function_name(&self) -> Option<String> {
         self.display_function_name()
     }
 
-    field is_inlined() -> bool {
+    #[graphql(name = "isInlined")]
+    fn graphql_is_inlined(&self) -> bool {
         self.is_inlined()
     }
 
-    field frame_block() -> SBBlock {
+    #[graphql(name = "frameBlock")]
+    fn graphql_frame_block(&self) -> SBBlock {
         self.frame_block()
     }
 
-    field line_entry() -> Option<SBLineEntry> {
+    #[graphql(name = "lineEntry")]
+    fn graphql_line_entry(&self) -> Option<SBLineEntry> {
         self.line_entry()
     }
 
-    field thread() -> SBThread {
+    #[graphql(name = "thread")]
+    fn graphql_thread(&self) -> SBThread {
         self.thread()
     }
-});
+
+    #[graphql(name = "arguments")]
+    fn graphql_arguments(&self) -> Vec<SBValue> {
+        self.arguments().iter().collect()
+    }
+
+    #[graphql(name = "locals")]
+    fn graphql_locals(&self) -> Vec<SBValue> {
+        self.locals().iter().collect()
+    }
+
+    #[graphql(name = "statics")]
+    fn graphql_statics(&self) -> Vec<SBValue> {
+        self.statics().iter().collect()
+    }
+
+    #[graphql(name = "registers")]
+    fn graphql_registers(&self) -> Vec<SBValue> {
+        self.registers().iter().collect()
+    }
+
+    #[graphql(name = "parentFrame")]
+    fn graphql_parent_frame(&self) -> Option<SBFrame> {
+        self.parent_frame()
+    }
+}
@@ -5,13 +5,19 @@
 // except according to those terms.
 
 use super::address::SBAddress;
+use super::addressrange::{AddressRange, AddressRangeList};
 use super::block::SBBlock;
+use super::error::InvalidRefError;
+#[cfg(feature = "graphql")]
+use super::graphql::U64;
 use super::instructionlist::SBInstructionList;
 use super::stream::SBStream;
+use super::strings;
 use super::target::SBTarget;
 use super::types::SBType;
 use super::{DisassemblyFlavor, LanguageType};
-use std::ffi::{CStr, CString};
+use std::convert::TryFrom;
+use std::ffi::CString;
 use std::fmt;
 use std::ptr;
 use sys;
@@ -24,14 +30,24 @@ pub struct SBFunction {
 
 impl SBFunction {
     /// Construct a new `SBFunction`.
-    pub fn wrap(raw: sys::SBFunctionRef) -> SBFunction {
+    ///
+    /// # Safety
+    ///
+    /// `raw` must be a valid reference, which is disposed of when the
+    /// `SBFunction` is dropped. Use [`maybe_wrap`] when it may be invalid.
+    ///
+    /// [`maybe_wrap`]: #method.maybe_wrap
+    pub unsafe fn wrap(raw: sys::SBFunctionRef) -> SBFunction {
         SBFunction { raw }
     }
 
     /// Construct a new `Some(SBFunction)` or `None`.
+    ///
+    /// `raw` is disposed of if it is invalid.
     pub fn maybe_wrap(raw: sys::SBFunctionRef) -> Option<SBFunction> {
-        if unsafe { sys::SBFunctionIsValid(raw) != 0 } {
-            Some(SBFunction { raw })
+        let wrapped = SBFunction { raw };
+        if wrapped.is_valid() {
+            Some(wrapped)
         } else {
             None
         }
@@ -43,33 +59,18 @@ impl SBFunction {
     }
 
     /// The name of this function.
-    pub fn name(&self) -> &str {
-        unsafe {
-            match CStr::from_ptr(sys::SBFunctionGetName(self.raw)).to_str() {
-                Ok(s) => s,
-                _ => panic!("Invalid string?"),
-            }
-        }
+    pub fn name(&self) -> Option<String> {
+        unsafe { strings::to_string(sys::SBFunctionGetName(self.raw)) }
     }
 
     /// The display name for the function, as it should be seen in a UI.
-    pub fn display_name(&self) -> &str {
-        unsafe {
-            match CStr::from_ptr(sys::SBFunctionGetDisplayName(self.raw)).to_str() {
-                Ok(s) => s,
-                _ => panic!("Invalid string?"),
-            }
-        }
+    pub fn display_name(&self) -> Option<String> {
+        unsafe { strings::to_string(sys::SBFunctionGetDisplayName(self.raw)) }
     }
 
     /// The mangled (linkage) name for this function.
-    pub fn mangled_name(&self) -> &str {
-        unsafe {
-            match CStr::from_ptr(sys::SBFunctionGetMangledName(self.raw)).to_str() {
-                Ok(s) => s,
-                _ => panic!("Invalid string?"),
-            }
-        }
+    pub fn mangled_name(&self) -> Option<String> {
+        unsafe { strings::to_string(sys::SBFunctionGetMangledName(self.raw)) }
     }
 
     ///
@@ -83,23 +84,46 @@ impl SBFunction {
             DisassemblyFlavor::Default => None,
             DisassemblyFlavor::Intel => CString::new("intel").ok(),
         };
-        SBInstructionList::wrap(unsafe {
-            sys::SBFunctionGetInstructions2(
+        unsafe {
+            SBInstructionList::wrap(sys::SBFunctionGetInstructions2(
                 self.raw,
                 target.raw,
                 flavor.map_or(ptr::null(), |s| s.as_ptr()),
-            )
-        })
+            ))
+        }
     }
 
     /// Get the address of the start of this function.
     pub fn start_address(&self) -> SBAddress {
-        SBAddress::wrap(unsafe { sys::SBFunctionGetStartAddress(self.raw) })
+        unsafe { SBAddress::wrap(sys::SBFunctionGetStartAddress(self.raw)) }
     }
 
     /// Get the address of the end of this function.
     pub fn end_address(&self) -> SBAddress {
-        SBAddress::wrap(unsafe { sys::SBFunctionGetEndAddress(self.raw) })
+        unsafe { SBAddress::wrap(sys::SBFunctionGetEndAddress(self.raw)) }
+    }
+
+    /// The address ranges of the code of this function, as load
+    /// addresses in `target`.
+    ///
+    /// Optimized functions can be split into several ranges, such as
+    /// when their cold paths have been moved away from the rest of
+    /// their code, so an address between [`start_address`] and
+    /// [`end_address`] isn't necessarily in the function.
+    ///
+    /// [`start_address`]: #method.start_address
+    /// [`end_address`]: #method.end_address
+    pub fn ranges(&self, target: &SBTarget) -> AddressRangeList {
+        let ranges = self.block().ranges(target);
+        if !ranges.is_empty() {
+            return ranges;
+        }
+        let mut ranges = AddressRangeList::new();
+        ranges.append(AddressRange::from_bounds(
+            self.start_address().load_address(target),
+            self.end_address().load_address(target),
+        ));
+        ranges
     }
 
     /// Get the size of the function prologue, in bytes.
@@ -109,12 +133,12 @@ impl SBFunction {
 
     /// The return type for this function.
     pub fn return_type(&self) -> SBType {
-        SBType::wrap(unsafe { sys::SBFunctionGetType(self.raw) })
+        unsafe { SBType::wrap(sys::SBFunctionGetType(self.raw)) }
     }
 
     /// Get the top level lexical block for this function.
     pub fn block(&self) -> SBBlock {
-        SBBlock::wrap(unsafe { sys::SBFunctionGetBlock(self.raw) })
+        unsafe { SBBlock::wrap(sys::SBFunctionGetBlock(self.raw)) }
     }
 
     /// The language that this function was written in.
@@ -132,6 +156,12 @@ impl SBFunction {
     pub fn is_optimized(&self) -> bool {
         unsafe { sys::SBFunctionGetIsOptimized(self.raw) != 0 }
     }
+
+    fn description(&self) -> String {
+        let stream = SBStream::new();
+        unsafe { sys::SBFunctionGetDescription(self.raw, stream.raw) };
+        stream.data()
+    }
 }
 
 impl Clone for SBFunction {
@@ -144,9 +174,21 @@ impl Clone for SBFunction {
 
 impl fmt::Debug for SBFunction {
     fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
-        let stream = SBStream::new();
-        unsafe { sys::SBFunctionGetDescription(self.raw, stream.raw) };
-        write!(fmt, "SBFunction {{ {} }}", stream.data())
+        write!(fmt, "SBFunction {{ {} }}", self.description())
+    }
+}
+
+impl fmt::Display for SBFunction {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "{}", self.description())
+    }
+}
+
+impl TryFrom<sys::SBFunctionRef> for SBFunction {
+    type Error = InvalidRefError;
+
+    fn try_from(raw: sys::SBFunctionRef) -> Result<SBFunction, InvalidRefError> {
+        SBFunction::maybe_wrap(raw).ok_or(InvalidRefError)
     }
 }
 
@@ -160,45 +202,55 @@ unsafe impl Send for SBFunction {}
 unsafe impl Sync for SBFunction {}
 
 #[cfg(feature = "graphql")]
-graphql_object!(SBFunction: super::debugger::SBDebugger | &self | {
-    field is_valid() -> bool {
+#[graphql_object(context = super::debugger::SBDebugger)]
+impl SBFunction {
+    #[graphql(name = "isValid")]
+    fn graphql_is_valid(&self) -> bool {
         self.is_valid()
     }
 
-    field name() -> &str {
+    #[graphql(name = "name")]
+    fn graphql_name(&self) -> Option<String> {
         self.name()
     }
 
-    field display_name() -> &str {
+    #[graphql(name = "displayName")]
+    fn graphql_display_name(&self) -> Option<String> {
         self.display_name()
     }
 
-    field mangled_name() -> &str {
+    #[graphql(name = "mangledName")]
+    fn graphql_mangled_name(&self) -> Option<String> {
         self.mangled_name()
     }
 
-    field start_address() -> SBAddress {
+    #[graphql(name = "startAddress")]
+    fn graphql_start_address(&self) -> SBAddress {
         self.start_address()
     }
 
-    field end_address() -> SBAddress {
+    #[graphql(name = "endAddress")]
+    fn graphql_end_address(&self) -> SBAddress {
         self.end_address()
     }
 
-    // TODO(bm) This should be a u32
-    field prologue_byte_size() -> i32 {
-        self.prologue_byte_size() as i32
+    #[graphql(name = "prologueByteSize")]
+    fn graphql_prologue_byte_size(&self) -> U64 {
+        self.prologue_byte_size().into()
     }
 
-    field return_type() -> SBType {
+    #[graphql(name = "returnType")]
+    fn graphql_return_type(&self) -> SBType {
         self.return_type()
     }
 
-    field block() -> SBBlock {
+    #[graphql(name = "block")]
+    fn graphql_block(&self) -> SBBlock {
         self.block()
     }
 
-    field is_optimized() -> bool {
+    #[graphql(name = "isOptimized")]
+    fn graphql_is_optimized(&self) -> bool {
         self.is_optimized()
     }
-});
+}
@@ -0,0 +1,557 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Querying the debugger with GraphQL.
+//!
+//! With the `graphql` feature enabled, most of the types in this
+//! crate are exposed as GraphQL objects using [`juniper`]. The
+//! [`SBDebugger`] is the root of the query and is also used as the
+//! context, so a query can walk from the debugger to its targets,
+//! processes, threads, frames, values, modules and breakpoints:
+//!
+//! ```no_run
+//! # use lldb::SBDebugger;
+//! # fn dump(debugger: &SBDebugger) {
+//! let result = lldb::graphql::execute(
+//!     debugger,
+//!     "{ selectedTarget { process { threads { threadId frames { functionName } } } } }",
+//! );
+//! println!("{:?}", result);
+//! # }
+//! ```
+//!
+//! Addresses, IDs and other unsigned integers are [`U64`] scalars,
+//! written as strings, as they don't fit in GraphQL's `Int`.
+//!
+//! The [`Mutation`] root controls the debugger: launching and
+//! attaching to processes, resuming and stepping them, setting
+//! breakpoints and evaluating expressions.
+//!
+//! The [`Subscription`] root streams events from the process as
+//! they happen, so that a frontend can stay up to date without
+//! polling.
+//!
+//! [`juniper`]: https://docs.rs/juniper
+//! [`U64`]: struct.U64.html
+//! [`Mutation`]: struct.Mutation.html
+//! [`Subscription`]: struct.Subscription.html
+//! [`SBDebugger`]: ../struct.SBDebugger.html
+
+use super::attachinfo::SBAttachInfo;
+use super::breakpoint::SBBreakpoint;
+use super::breakpointlocation::SBBreakpointLocation;
+use super::debugger::SBDebugger;
+use super::event::SBEvent;
+use super::expressionoptions::SBExpressionOptions;
+use super::launchinfo::SBLaunchInfo;
+use super::listener::SBListener;
+use super::process::SBProcess;
+use super::thread::{SBThread, StepKind};
+use super::value::SBValue;
+use super::{RunMode, StateType, StopReason};
+use futures::channel::mpsc;
+use futures::{future, Stream, StreamExt};
+use juniper::meta::MetaType;
+use juniper::{
+    Arguments, BoxFuture, DefaultScalarValue, ExecutionError, Executor, FieldError, FieldResult,
+    GraphQLError, GraphQLSubscriptionValue, GraphQLType, GraphQLValue, Registry, RootNode, Value,
+    ValuesStream, Variables,
+};
+use std::pin::Pin;
+use std::thread;
+
+/// The GraphQL schema, rooted at an [`SBDebugger`].
+///
+/// [`SBDebugger`]: ../struct.SBDebugger.html
+pub type Schema = RootNode<'static, SBDebugger, Mutation, Subscription>;
+
+/// Create the schema for querying `debugger`.
+pub fn schema(debugger: &SBDebugger) -> Schema {
+    Schema::new(debugger.clone(), Mutation, Subscription)
+}
+
+/// Run a GraphQL query against `debugger`.
+pub fn execute(
+    debugger: &SBDebugger,
+    query: &str,
+) -> Result<(Value, Vec<ExecutionError<DefaultScalarValue>>), GraphQLError> {
+    juniper::execute_sync(query, None, &schema(debugger), &Variables::new(), debugger)
+}
+
+/// An unsigned integer, such as an address, a size or an ID.
+///
+/// GraphQL's `Int` is a signed 32-bit integer, which can't hold these
+/// without truncating them, so they are written as decimal strings.
+/// Either a string or a non-negative `Int` is accepted as an argument.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, GraphQLScalar)]
+#[graphql(with = u64_scalar, parse_token(String, i32))]
+pub struct U64(pub u64);
+
+mod u64_scalar {
+    use super::U64;
+    use juniper::{InputValue, ScalarValue, Value};
+    use std::convert::TryFrom;
+
+    pub(super) fn to_output<S: ScalarValue>(value: &U64) -> Value<S> {
+        Value::scalar(value.0.to_string())
+    }
+
+    pub(super) fn from_input<S: ScalarValue>(input: &InputValue<S>) -> Result<U64, String> {
+        if let Some(s) = input.as_string_value() {
+            s.parse()
+                .map(U64)
+                .map_err(|_| format!("Expected an unsigned integer, found: {}", input))
+        } else if let Some(i) = input.as_int_value() {
+            u64::try_from(i)
+                .map(U64)
+                .map_err(|_| format!("Expected an unsigned integer, found: {}", input))
+        } else {
+            Err(format!("Expected `String` or `Int`, found: {}", input))
+        }
+    }
+}
+
+impl From<u64> for U64 {
+    fn from(value: u64) -> U64 {
+        U64(value)
+    }
+}
+
+impl From<u32> for U64 {
+    fn from(value: u32) -> U64 {
+        U64(u64::from(value))
+    }
+}
+
+impl From<usize> for U64 {
+    fn from(value: usize) -> U64 {
+        U64(value as u64)
+    }
+}
+
+/// The root of GraphQL mutations.
+///
+/// Unless stated otherwise, mutations act on the process of the
+/// currently selected target.
+pub struct Mutation;
+
+#[graphql_object(context = SBDebugger)]
+impl Mutation {
+    /// Create a target for `executable`, select it and launch it.
+    fn launch(
+        context: &SBDebugger,
+        executable: String,
+        arguments: Option<Vec<String>>,
+        stop_at_entry: Option<bool>,
+    ) -> FieldResult<SBProcess> {
+        let target = context
+            .create_target_simple(&executable)
+            .ok_or_else(|| format!("Unable to create a target for {}", executable))?;
+        context.set_selected_target(&target);
+        let launch_info = SBLaunchInfo::new();
+        if let Some(arguments) = arguments {
+            let arguments: Vec<&str> = arguments.iter().map(|a| a.as_str()).collect();
+            launch_info.set_arguments(&arguments, false);
+        }
+        launch_info.set_stop_at_entry(stop_at_entry.unwrap_or(false));
+        Ok(target.launch(launch_info)?)
+    }
+
+    /// Attach to a process, either by `pid` or by `name`. When
+    /// `waitFor` is `true`, wait for the next process with that name
+    /// to be launched.
+    ///
+    /// If no target is selected, an empty one is created.
+    fn attach(
+        context: &SBDebugger,
+        pid: Option<U64>,
+        name: Option<String>,
+        wait_for: Option<bool>,
+    ) -> FieldResult<SBProcess> {
+        let attach_info = attach_info(context, pid, name, wait_for)?;
+        let target = match context.selected_target() {
+            Some(target) => target,
+            None => {
+                let target = context
+                    .create_target_simple("")
+                    .ok_or("Unable to create a target")?;
+                context.set_selected_target(&target);
+                target
+            }
+        };
+        Ok(target.attach(attach_info)?)
+    }
+
+    /// Resume the process.
+    #[graphql(name = "continue")]
+    fn continue_execution(context: &SBDebugger) -> FieldResult<SBProcess> {
+        let process = selected_process(context)?;
+        process.continue_execution()?;
+        Ok(process)
+    }
+
+    /// Stop the process.
+    fn stop(context: &SBDebugger) -> FieldResult<SBProcess> {
+        let process = selected_process(context)?;
+        process.stop()?;
+        Ok(process)
+    }
+
+    /// Step a thread, by default the selected thread.
+    fn step(
+        context: &SBDebugger,
+        kind: StepKind,
+        thread_index_id: Option<i32>,
+    ) -> FieldResult<SBThread> {
+        let process = selected_process(context)?;
+        let thread = match thread_index_id {
+            Some(id) => process
+                .thread_by_index_id(id as u32)
+                .ok_or_else(|| format!("There is no thread with index ID {}", id))?,
+            None => process
+                .selected_thread()
+                .ok_or("There is no selected thread")?,
+        };
+        thread.step(kind, RunMode::OnlyDuringStepping)?;
+        Ok(thread)
+    }
+
+    /// Set a breakpoint on the selected target, either on a function
+    /// `name`, optionally restricted to `module`, or on a `line` of a
+    /// source `file`.
+    fn set_breakpoint(
+        context: &SBDebugger,
+        name: Option<String>,
+        module: Option<String>,
+        file: Option<String>,
+        line: Option<i32>,
+    ) -> FieldResult<SBBreakpoint> {
+        let target = context
+            .selected_target()
+            .ok_or("There is no selected target")?;
+        let breakpoint = match (name, file, line) {
+            (Some(name), None, None) => target.breakpoint_create_by_name(&name, module.as_deref()),
+            (None, Some(file), Some(line)) => {
+                target.breakpoint_create_by_location(&file, line as u32)
+            }
+            _ => return Err("Either name, or file and line, must be given".into()),
+        };
+        if !breakpoint.is_valid() {
+            return Err("Unable to create the breakpoint".into());
+        }
+        Ok(breakpoint)
+    }
+
+    /// Evaluate an expression in the selected frame or, if the
+    /// process isn't stopped, in the selected target.
+    fn evaluate_expression(context: &SBDebugger, expression: String) -> FieldResult<SBValue> {
+        let target = context
+            .selected_target()
+            .ok_or("There is no selected target")?;
+        let frame = target
+            .process()
+            .filter(SBProcess::is_stopped)
+            .and_then(|process| process.selected_thread())
+            .and_then(|thread| thread.selected_frame());
+        let value = match frame {
+            Some(frame) => frame.evaluate_expression(&expression, &frame.expression_options()),
+            None => target.evaluate_expression(&expression, &SBExpressionOptions::new()),
+        };
+        match value.error() {
+            Some(ref error) if error.is_failure() => Err(error.to_string().into()),
+            _ => Ok(value),
+        }
+    }
+}
+
+// This lives outside of the `Mutation` impl as `syn` parses `async`
+// as a keyword, which it isn't in this edition.
+fn attach_info(
+    debugger: &SBDebugger,
+    pid: Option<U64>,
+    name: Option<String>,
+    wait_for: Option<bool>,
+) -> FieldResult<SBAttachInfo> {
+    match (pid, name) {
+        (Some(pid), None) => Ok(SBAttachInfo::new_with_pid(pid.0)),
+        (None, Some(name)) => Ok(SBAttachInfo::new_with_path(
+            &name,
+            wait_for.unwrap_or(false),
+            debugger.async(),
+        )),
+        _ => Err("Exactly one of pid and name must be given".into()),
+    }
+}
+
+fn selected_process(debugger: &SBDebugger) -> FieldResult<SBProcess> {
+    debugger
+        .selected_target()
+        .and_then(|target| target.process())
+        .ok_or_else(|| "There is no process".into())
+}
+
+/// The root of GraphQL subscriptions.
+///
+/// Each subscription follows the process of the currently selected
+/// target, listening to it on a thread of its own. The stream ends
+/// when the process exits or is detached, or when the subscriber
+/// goes away.
+///
+/// The debugger should be in asynchronous mode, as otherwise the
+/// process events are consumed while waiting for the process to stop.
+pub struct Subscription;
+
+type EventStream<T> = Pin<Box<dyn Stream<Item = T> + Send>>;
+
+// The `graphql_subscription` attribute requires `async fn`, which
+// isn't available in this edition, so the subscription root is
+// implemented by hand.
+impl GraphQLType for Subscription {
+    fn name(_: &()) -> Option<&str> {
+        Some("Subscription")
+    }
+
+    fn meta<'r>(info: &(), registry: &mut Registry<'r>) -> MetaType<'r>
+    where
+        DefaultScalarValue: 'r,
+    {
+        let fields = [
+            registry
+                .field::<ProcessStateChange>("processState", info)
+                .description("Changes in the state of the process."),
+            registry
+                .field::<ProcessOutput>("output", info)
+                .description("Output written by the process."),
+            registry
+                .field::<BreakpointHit>("breakpointHits", info)
+                .description("Threads stopping at breakpoints."),
+        ];
+        registry
+            .build_object_type::<Subscription>(info, &fields)
+            .into_meta()
+    }
+}
+
+impl GraphQLValue for Subscription {
+    type Context = SBDebugger;
+    type TypeInfo = ();
+
+    fn type_name<'i>(&self, info: &'i ()) -> Option<&'i str> {
+        <Subscription as GraphQLType>::name(info)
+    }
+}
+
+impl GraphQLSubscriptionValue for Subscription {
+    fn resolve_field_into_stream<'s, 'i, 'fi, 'args, 'e, 'ref_e, 'res, 'f>(
+        &'s self,
+        _: &'i (),
+        field_name: &'fi str,
+        _: Arguments<'args>,
+        executor: &'ref_e Executor<'ref_e, 'e, SBDebugger>,
+    ) -> BoxFuture<'f, Result<Value<ValuesStream<'res>>, FieldError>>
+    where
+        's: 'f,
+        'fi: 'f,
+        'args: 'f,
+        'ref_e: 'f,
+        'res: 'f,
+        'i: 'res,
+        'e: 'res,
+    {
+        let debugger = executor.context();
+        let result = match field_name {
+            "processState" => {
+                Subscription::process_state(debugger).map(|s| resolve_stream(executor, s))
+            }
+            "output" => Subscription::output(debugger).map(|s| resolve_stream(executor, s)),
+            "breakpointHits" => {
+                Subscription::breakpoint_hits(debugger).map(|s| resolve_stream(executor, s))
+            }
+            _ => Err(FieldError::from(format!(
+                "Field \"{}\" not found on type \"Subscription\"",
+                field_name
+            ))),
+        };
+        Box::pin(future::ready(result))
+    }
+}
+
+impl Subscription {
+    fn process_state(context: &SBDebugger) -> Result<EventStream<ProcessStateChange>, FieldError> {
+        process_events(context, 0, |event, _| {
+            SBProcess::event_as_process_event(event)
+                .map(|event| ProcessStateChange {
+                    process: event.process(),
+                    state: format!("{:?}", event.state()),
+                    restarted: event.restarted(),
+                })
+                .into_iter()
+                .collect()
+        })
+    }
+
+    fn output(context: &SBDebugger) -> Result<EventStream<ProcessOutput>, FieldError> {
+        let mask = SBProcess::BROADCAST_BIT_STDOUT | SBProcess::BROADCAST_BIT_STDERR;
+        process_events(context, mask, |event, process| {
+            let mut output = vec![];
+            if event.event_type() & SBProcess::BROADCAST_BIT_STDOUT != 0 {
+                output.push(ProcessOutput {
+                    stream: OutputStream::Stdout,
+                    text: process.read_stdout(),
+                });
+            }
+            if event.event_type() & SBProcess::BROADCAST_BIT_STDERR != 0 {
+                output.push(ProcessOutput {
+                    stream: OutputStream::Stderr,
+                    text: process.read_stderr(),
+                });
+            }
+            output.retain(|o| !o.text.is_empty());
+            output
+        })
+    }
+
+    fn breakpoint_hits(context: &SBDebugger) -> Result<EventStream<BreakpointHit>, FieldError> {
+        let target = context.selected_target();
+        process_events(context, 0, move |event, process| {
+            let stopped = SBProcess::event_as_process_event(event)
+                .is_some_and(|e| e.state() == StateType::Stopped && !e.restarted());
+            let target = match target {
+                Some(ref target) if stopped => target,
+                _ => return vec![],
+            };
+            process
+                .threads()
+                .filter(|thread| {
+                    thread.stop_reason() == StopReason::Breakpoint
+                        && thread.stop_reason_data_count() >= 2
+                })
+                .filter_map(|thread| {
+                    let breakpoint_id = thread.stop_reason_data_at_index(0) as i32;
+                    let location_id = thread.stop_reason_data_at_index(1) as i32;
+                    let breakpoint = target.find_breakpoint_by_id(breakpoint_id)?;
+                    let location = breakpoint.find_location_by_id(location_id);
+                    Some(BreakpointHit {
+                        thread,
+                        breakpoint,
+                        location,
+                    })
+                })
+                .collect()
+        })
+    }
+}
+
+/// A change in the state of a process.
+#[derive(GraphQLObject)]
+#[graphql(context = SBDebugger)]
+pub struct ProcessStateChange {
+    /// The process whose state changed.
+    pub process: SBProcess,
+    /// The new state, such as `Stopped` or `Exited`.
+    pub state: String,
+    /// Whether the process stopped and was then automatically
+    /// restarted, such as by a breakpoint condition.
+    pub restarted: bool,
+}
+
+/// Which output stream some [`ProcessOutput`] was written to.
+///
+/// [`ProcessOutput`]: struct.ProcessOutput.html
+#[derive(Clone, Copy, Debug, PartialEq, Eq, GraphQLEnum)]
+pub enum OutputStream {
+    /// The standard output.
+    Stdout,
+    /// The standard error.
+    Stderr,
+}
+
+/// Output written by a process.
+#[derive(GraphQLObject)]
+#[graphql(context = SBDebugger)]
+pub struct ProcessOutput {
+    /// The stream that the output was written to.
+    pub stream: OutputStream,
+    /// The output.
+    pub text: String,
+}
+
+/// A thread stopping at a breakpoint.
+#[derive(GraphQLObject)]
+#[graphql(context = SBDebugger)]
+pub struct BreakpointHit {
+    /// The thread that stopped.
+    pub thread: SBThread,
+    /// The breakpoint that was hit.
+    pub breakpoint: SBBreakpoint,
+    /// The location of the breakpoint that was hit.
+    pub location: Option<SBBreakpointLocation>,
+}
+
+/// Resolve each item of `stream` against the selection set of the
+/// subscription.
+#[allow(clippy::result_large_err)]
+fn resolve_stream<'e, 'res, T>(
+    executor: &Executor<'_, 'e, SBDebugger>,
+    stream: EventStream<T>,
+) -> Value<ValuesStream<'res>>
+where
+    T: GraphQLValue<Context = SBDebugger, TypeInfo = ()> + 'static,
+    'e: 'res,
+{
+    let executor = executor.as_owned_executor();
+    Value::Scalar(Box::pin(stream.map(move |item| {
+        let executor = executor.as_executor();
+        executor
+            .resolve_with_ctx(&(), &item)
+            .map_err(|e| executor.new_error(e))
+    })))
+}
+
+/// Listen for events from the process of the selected target,
+/// mapping each event with `f` on a separate thread.
+///
+/// State changes are always listened for, so that the stream can end
+/// when the process does.
+fn process_events<T, F>(
+    debugger: &SBDebugger,
+    event_mask: u32,
+    mut f: F,
+) -> Result<EventStream<T>, FieldError>
+where
+    T: Send + 'static,
+    F: FnMut(&SBEvent, &SBProcess) -> Vec<T> + Send + 'static,
+{
+    let process = debugger
+        .selected_target()
+        .and_then(|target| target.process())
+        .ok_or_else(|| FieldError::from("There is no process to listen to."))?;
+    let event_mask = event_mask | SBProcess::BROADCAST_BIT_STATE_CHANGED;
+    let listener = SBListener::new();
+    process.broadcaster().add_listener(&listener, event_mask);
+
+    let (sender, receiver) = mpsc::unbounded();
+    thread::spawn(move || {
+        let mut event = SBEvent::new();
+        while !sender.is_closed() {
+            if !listener.wait_for_event(1, &mut event) {
+                continue;
+            }
+            for item in f(&event, &process) {
+                if sender.unbounded_send(item).is_err() {
+                    break;
+                }
+            }
+            let finished = SBProcess::event_as_process_event(&event)
+                .is_some_and(|e| matches!(e.state(), StateType::Exited | StateType::Detached));
+            if finished {
+                break;
+            }
+        }
+        process.broadcaster().remove_listener(&listener, event_mask);
+    });
+    Ok(Box::pin(receiver))
+}
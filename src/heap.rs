@@ -0,0 +1,358 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Finding the allocations on the heap of a process.
+//!
+//! [`enumerate`] reads the heap of a stopped process and walks the
+//! chunks of its allocator, for tools which look for leaks or
+//! measure fragmentation:
+//!
+//! ```no_run
+//! # use lldb::{SBError, SBProcess};
+//! # use lldb::heap;
+//! # fn show(process: &SBProcess) -> Result<(), SBError> {
+//! let heap = heap::enumerate(process)?;
+//! let summary = heap.summary();
+//! println!(
+//!     "{} allocations using {} bytes, {} bytes free in {} blocks",
+//!     summary.allocated_count, summary.allocated_bytes, summary.free_bytes, summary.free_count
+//! );
+//! for allocation in heap.allocations.iter().filter(|a| a.size >= 1 << 20) {
+//!     println!("{:#x}: {} bytes", allocation.address, allocation.size);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Only the main arena of glibc's `malloc` is walked, which is the
+//! `[heap]` region grown with `brk`. Allocations made by other
+//! threads' arenas, and large allocations which `malloc` maps with
+//! `mmap`, are in anonymous regions of their own and aren't found.
+//! Chunks held in glibc's thread caches and fast bins are still
+//! marked as in use, so freed memory waiting there is reported as
+//! allocated.
+//!
+//! [`usable_size`] asks the allocator itself about one allocation,
+//! through `malloc_usable_size` or, on Apple platforms, `malloc_size`.
+
+use super::addressrange::AddressRange;
+use super::error::SBError;
+use super::expressionoptions::SBExpressionOptions;
+use super::process::{decode_pointer, SBProcess};
+use super::{lldb_addr_t, ByteOrder};
+
+/// The name that Linux gives the region of the heap grown with `brk`.
+const HEAP_REGION_NAME: &str = "[heap]";
+
+/// A block of memory on the heap.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Allocation {
+    /// The address of the block, as returned by `malloc`.
+    pub address: lldb_addr_t,
+    /// The number of bytes which can be used, which may be more than
+    /// were asked for.
+    pub size: u64,
+}
+
+impl Allocation {
+    /// The addresses of the block.
+    pub fn range(&self) -> AddressRange {
+        AddressRange::new(self.address, self.size)
+    }
+}
+
+/// The blocks found on the heap of a process.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Heap {
+    /// The regions of memory which were walked.
+    pub regions: Vec<AddressRange>,
+    /// The blocks which are allocated, in order of address.
+    pub allocations: Vec<Allocation>,
+    /// The blocks which are free, in order of address, including the
+    /// space at the end of the heap which hasn't been handed out yet.
+    pub free: Vec<Allocation>,
+}
+
+/// Totals for a [`Heap`].
+///
+/// [`Heap`]: struct.Heap.html
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct HeapSummary {
+    /// The number of allocated blocks.
+    pub allocated_count: usize,
+    /// The usable bytes in allocated blocks.
+    pub allocated_bytes: u64,
+    /// The number of free blocks.
+    pub free_count: usize,
+    /// The bytes in free blocks.
+    pub free_bytes: u64,
+    /// The size of the largest free block.
+    pub largest_free: u64,
+    /// The fraction of free memory outside of the largest free block,
+    /// from 0 when all of it can be used for one allocation, up to 1.
+    pub fragmentation: f64,
+}
+
+impl Heap {
+    /// The allocation containing `address`, if there is one.
+    pub fn allocation_containing(&self, address: lldb_addr_t) -> Option<&Allocation> {
+        // The allocations are sorted and don't overlap.
+        let index = match self
+            .allocations
+            .binary_search_by_key(&address, |allocation| allocation.address)
+        {
+            Ok(index) => index,
+            Err(0) => return None,
+            Err(index) => index - 1,
+        };
+        self.allocations
+            .get(index)
+            .filter(|allocation| allocation.range().contains(address))
+    }
+
+    /// Add up the allocated and free blocks.
+    pub fn summary(&self) -> HeapSummary {
+        let free_bytes = self.free.iter().map(|block| block.size).sum();
+        let largest_free = self.free.iter().map(|block| block.size).max().unwrap_or(0);
+        HeapSummary {
+            allocated_count: self.allocations.len(),
+            allocated_bytes: self.allocations.iter().map(|block| block.size).sum(),
+            free_count: self.free.len(),
+            free_bytes,
+            largest_free,
+            fragmentation: if free_bytes == 0 {
+                0.0
+            } else {
+                1.0 - largest_free as f64 / free_bytes as f64
+            },
+        }
+    }
+}
+
+/// Find the allocated and free blocks on the heap of `process`, which
+/// must be stopped.
+///
+/// Only glibc's `malloc` is supported, on Linux.
+pub fn enumerate(process: &SBProcess) -> Result<Heap, SBError> {
+    let triple = process.target().triple().unwrap_or_default();
+    if !triple.contains("linux") || triple.contains("android") {
+        return Err(SBError::from_message(&format!(
+            "the heap can only be walked for glibc on Linux, not {}",
+            triple
+        )));
+    }
+    let pointer_size = process.address_byte_size() as usize;
+    let byte_order = process.byte_order();
+    let mut heap = Heap::default();
+    for region in process.memory_regions().iter() {
+        if !region.is_writable() || region.name().as_deref() != Some(HEAP_REGION_NAME) {
+            continue;
+        }
+        let range = region.range();
+        let mut bytes = vec![0; range.byte_size() as usize];
+        let read = process.read_memory(range.base_address(), &mut bytes)?;
+        bytes.truncate(read);
+        for (block, in_use) in
+            walk_glibc_chunks(&bytes, range.base_address(), pointer_size, byte_order)
+        {
+            if in_use {
+                heap.allocations.push(block);
+            } else {
+                heap.free.push(block);
+            }
+        }
+        heap.regions.push(range);
+    }
+    if heap.regions.is_empty() {
+        return Err(SBError::from_message("the process has no [heap] region"));
+    }
+    Ok(heap)
+}
+
+/// Ask the allocator how many bytes can be used in the block at
+/// `address`, which must have been returned by `malloc`.
+///
+/// This runs `malloc_usable_size`, or `malloc_size` on Apple
+/// platforms, in the process, which must be stopped.
+pub fn usable_size(process: &SBProcess, address: lldb_addr_t) -> Result<u64, SBError> {
+    let apple = process
+        .target()
+        .triple()
+        .is_some_and(|triple| triple.contains("apple"));
+    let function = if apple {
+        "malloc_size"
+    } else {
+        "malloc_usable_size"
+    };
+    let options = SBExpressionOptions::new();
+    options.set_ignore_breakpoints(true);
+    options.set_unwind_on_error(true);
+    let value = process.target().evaluate_expression(
+        &format!("(unsigned long){}((void *){:#x})", function, address),
+        &options,
+    );
+    match value.error() {
+        Some(error) if error.is_failure() => Err(error),
+        _ => value.value_as_unsigned(),
+    }
+}
+
+/// Walk the chunks of a glibc `malloc` heap in `bytes`, which were
+/// read from `base`, returning each block with whether it is in use.
+///
+/// Each chunk starts with the size of the previous chunk, used only
+/// while that one is free, then its own size, whose lowest bit says
+/// whether the previous chunk is in use. The user's block starts
+/// after these two words, and can extend over the first word of the
+/// next chunk. The last chunk is the top chunk, which is free space
+/// that hasn't been handed out yet.
+fn walk_glibc_chunks(
+    bytes: &[u8],
+    base: lldb_addr_t,
+    pointer_size: usize,
+    byte_order: ByteOrder,
+) -> Vec<(Allocation, bool)> {
+    const PREV_INUSE: u64 = 1;
+    const SIZE_BITS: u64 = 7;
+    let word = pointer_size as u64;
+    let min_chunk = 4 * word;
+    let read_size = |offset: u64| -> Option<u64> {
+        let start = (offset + word) as usize;
+        bytes
+            .get(start..start + pointer_size)
+            .map(|b| decode_pointer(b, byte_order))
+    };
+
+    // The first chunk is aligned so that the user's block is aligned
+    // to two words.
+    let align = 2 * word;
+    let mut offset = (align - base % align) % align;
+    let mut chunks: Vec<(u64, u64)> = vec![];
+    while let Some(field) = read_size(offset) {
+        let size = field & !SIZE_BITS;
+        if size < min_chunk || offset + size > bytes.len() as u64 {
+            // The top chunk may be reported as reaching past what was
+            // read, and anything else means the heap isn't understood.
+            if size >= min_chunk {
+                chunks.push((offset, bytes.len() as u64 - offset));
+            }
+            break;
+        }
+        chunks.push((offset, size));
+        offset += size;
+    }
+
+    let mut blocks = Vec::with_capacity(chunks.len());
+    for (i, &(offset, size)) in chunks.iter().enumerate() {
+        let block = |size: u64| Allocation {
+            address: base + offset + 2 * word,
+            size,
+        };
+        match chunks.get(i + 1) {
+            Some(&(next, _)) => {
+                let in_use = read_size(next).is_some_and(|field| field & PREV_INUSE != 0);
+                if in_use {
+                    blocks.push((block(size - word), true));
+                } else {
+                    blocks.push((block(size - 2 * word), false));
+                }
+            }
+            None => blocks.push((block(size - 2 * word), false)),
+        }
+    }
+    blocks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{walk_glibc_chunks, Allocation, Heap};
+    use ByteOrder;
+
+    fn chunk(bytes: &mut Vec<u8>, size: u64, prev_in_use: bool) {
+        let start = bytes.len();
+        bytes.extend_from_slice(&0u64.to_le_bytes());
+        bytes.extend_from_slice(&(size | prev_in_use as u64).to_le_bytes());
+        bytes.resize(start + size as usize, 0xaa);
+    }
+
+    #[test]
+    fn glibc_chunks() {
+        let mut bytes = vec![];
+        chunk(&mut bytes, 0x20, true);
+        chunk(&mut bytes, 0x30, true);
+        chunk(&mut bytes, 0x40, false);
+        chunk(&mut bytes, 0x100, true);
+        let blocks = walk_glibc_chunks(&bytes, 0x1000, 8, ByteOrder::Little);
+        assert_eq!(
+            blocks,
+            vec![
+                (
+                    Allocation {
+                        address: 0x1010,
+                        size: 0x18
+                    },
+                    true
+                ),
+                (
+                    Allocation {
+                        address: 0x1030,
+                        size: 0x20
+                    },
+                    false
+                ),
+                (
+                    Allocation {
+                        address: 0x1060,
+                        size: 0x38
+                    },
+                    true
+                ),
+                (
+                    Allocation {
+                        address: 0x10a0,
+                        size: 0xf0
+                    },
+                    false
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn summary() {
+        let heap = Heap {
+            regions: vec![],
+            allocations: vec![
+                Allocation {
+                    address: 0x1000,
+                    size: 0x10,
+                },
+                Allocation {
+                    address: 0x1040,
+                    size: 0x20,
+                },
+            ],
+            free: vec![
+                Allocation {
+                    address: 0x1020,
+                    size: 0x10,
+                },
+                Allocation {
+                    address: 0x1080,
+                    size: 0x30,
+                },
+            ],
+        };
+        assert_eq!(heap.allocation_containing(0x1048), heap.allocations.get(1));
+        assert_eq!(heap.allocation_containing(0x1010), None);
+        assert_eq!(heap.allocation_containing(0x0fff), None);
+        let summary = heap.summary();
+        assert_eq!(summary.allocated_bytes, 0x30);
+        assert_eq!(summary.free_bytes, 0x40);
+        assert_eq!(summary.largest_free, 0x30);
+        assert_eq!(summary.fragmentation, 0.25);
+    }
+}
@@ -6,9 +6,13 @@
 
 use super::address::SBAddress;
 use super::data::SBData;
+use super::error::InvalidRefError;
+use super::frame::SBFrame;
 use super::stream::SBStream;
+use super::strings;
 use super::target::SBTarget;
-use std::ffi::CStr;
+use std::convert::TryFrom;
+use std::ffi::CString;
 use std::fmt;
 use sys;
 
@@ -20,14 +24,24 @@ pub struct SBInstruction {
 
 impl SBInstruction {
     /// Construct a new `SBInstruction`.
-    pub fn wrap(raw: sys::SBInstructionRef) -> SBInstruction {
+    ///
+    /// # Safety
+    ///
+    /// `raw` must be a valid reference, which is disposed of when the
+    /// `SBInstruction` is dropped. Use [`maybe_wrap`] when it may be invalid.
+    ///
+    /// [`maybe_wrap`]: #method.maybe_wrap
+    pub unsafe fn wrap(raw: sys::SBInstructionRef) -> SBInstruction {
         SBInstruction { raw }
     }
 
     /// Construct a new `Some(SBInstruction)` or `None`.
+    ///
+    /// `raw` is disposed of if it is invalid.
     pub fn maybe_wrap(raw: sys::SBInstructionRef) -> Option<SBInstruction> {
-        if unsafe { sys::SBInstructionIsValid(raw) != 0 } {
-            Some(SBInstruction { raw })
+        let wrapped = SBInstruction { raw };
+        if wrapped.is_valid() {
+            Some(wrapped)
         } else {
             None
         }
@@ -40,42 +54,27 @@ impl SBInstruction {
 
     /// Get the address of the instruction.
     pub fn address(&self) -> SBAddress {
-        SBAddress::wrap(unsafe { sys::SBInstructionGetAddress(self.raw) })
+        unsafe { SBAddress::wrap(sys::SBInstructionGetAddress(self.raw)) }
     }
 
     #[allow(missing_docs)]
-    pub fn mnemonic(&self, target: &SBTarget) -> &str {
-        unsafe {
-            match CStr::from_ptr(sys::SBInstructionGetMnemonic(self.raw, target.raw)).to_str() {
-                Ok(s) => s,
-                _ => panic!("Invalid string?"),
-            }
-        }
+    pub fn mnemonic(&self, target: &SBTarget) -> String {
+        unsafe { strings::to_string_lossy(sys::SBInstructionGetMnemonic(self.raw, target.raw)) }
     }
 
     #[allow(missing_docs)]
-    pub fn operands(&self, target: &SBTarget) -> &str {
-        unsafe {
-            match CStr::from_ptr(sys::SBInstructionGetOperands(self.raw, target.raw)).to_str() {
-                Ok(s) => s,
-                _ => panic!("Invalid string?"),
-            }
-        }
+    pub fn operands(&self, target: &SBTarget) -> String {
+        unsafe { strings::to_string_lossy(sys::SBInstructionGetOperands(self.raw, target.raw)) }
     }
 
     #[allow(missing_docs)]
-    pub fn comment(&self, target: &SBTarget) -> &str {
-        unsafe {
-            match CStr::from_ptr(sys::SBInstructionGetComment(self.raw, target.raw)).to_str() {
-                Ok(s) => s,
-                _ => panic!("Invalid string?"),
-            }
-        }
+    pub fn comment(&self, target: &SBTarget) -> String {
+        unsafe { strings::to_string_lossy(sys::SBInstructionGetComment(self.raw, target.raw)) }
     }
 
     #[allow(missing_docs)]
     pub fn data(&self, target: &SBTarget) -> SBData {
-        SBData::wrap(unsafe { sys::SBInstructionGetData(self.raw, target.raw) })
+        unsafe { SBData::wrap(sys::SBInstructionGetData(self.raw, target.raw)) }
     }
 
     #[allow(missing_docs)]
@@ -92,6 +91,158 @@ impl SBInstruction {
     pub fn has_delay_slot(&self) -> bool {
         unsafe { sys::SBInstructionHasDelaySlot(self.raw) != 0 }
     }
+
+    /// How the instruction changes the flow of control, such as
+    /// whether it is a call, a return or a conditional jump.
+    ///
+    /// This version of LLDB doesn't report this itself, so it is
+    /// worked out from the mnemonic and operands, for x86, ARM and
+    /// AArch64. Other branches are [`Unknown`].
+    ///
+    /// [`Unknown`]: enum.InstructionControlFlowKind.html#variant.Unknown
+    pub fn control_flow_kind(&self, target: &SBTarget) -> InstructionControlFlowKind {
+        classify(
+            &self.mnemonic(target),
+            &self.operands(target),
+            self.is_branch(),
+        )
+    }
+
+    /// Emulate the instruction against the registers and memory of
+    /// `frame`, changing them as running it would.
+    ///
+    /// `evaluate_options` is a mask of [`EmulateInstructionOptions`].
+    /// Returns whether the instruction could be emulated, which
+    /// depends on there being an emulator for the architecture.
+    ///
+    /// [`EmulateInstructionOptions`]: enum.EmulateInstructionOptions.html
+    pub fn emulate_with_frame(&self, frame: &SBFrame, evaluate_options: u32) -> bool {
+        unsafe { sys::SBInstructionEmulateWithFrame(self.raw, frame.raw, evaluate_options) != 0 }
+    }
+
+    /// Print, to standard output, the steps taken by emulating the
+    /// instruction for the architecture `triple`, such as
+    /// `arm64-apple-ios`.
+    ///
+    /// Returns whether the instruction could be emulated.
+    pub fn dump_emulation(&self, triple: &str) -> bool {
+        let triple = CString::new(triple).unwrap();
+        unsafe { sys::SBInstructionDumpEmulation(self.raw, triple.as_ptr()) != 0 }
+    }
+
+    fn description(&self) -> String {
+        let stream = SBStream::new();
+        unsafe { sys::SBInstructionGetDescription(self.raw, stream.raw) };
+        stream.data()
+    }
+}
+
+/// How an instruction changes the flow of control, from
+/// [`SBInstruction::control_flow_kind`].
+///
+/// [`SBInstruction::control_flow_kind`]: struct.SBInstruction.html#method.control_flow_kind
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum InstructionControlFlowKind {
+    /// The kind of the instruction isn't known.
+    Unknown,
+    /// The instruction doesn't change the flow of control.
+    Other,
+    /// A call to a function.
+    Call,
+    /// A return from a function.
+    Return,
+    /// A jump which is always taken.
+    Jump,
+    /// A jump which may or may not be taken.
+    CondJump,
+    /// A call into another privilege level, such as a system call.
+    FarCall,
+    /// A return from another privilege level.
+    FarReturn,
+    /// A jump into another segment.
+    FarJump,
+}
+
+impl InstructionControlFlowKind {
+    /// Is this a call, of either kind?
+    pub fn is_call(self) -> bool {
+        self == InstructionControlFlowKind::Call || self == InstructionControlFlowKind::FarCall
+    }
+
+    /// Is this a return, of either kind?
+    pub fn is_return(self) -> bool {
+        self == InstructionControlFlowKind::Return || self == InstructionControlFlowKind::FarReturn
+    }
+
+    /// Is this a jump, of any kind?
+    pub fn is_jump(self) -> bool {
+        matches!(
+            self,
+            InstructionControlFlowKind::Jump
+                | InstructionControlFlowKind::CondJump
+                | InstructionControlFlowKind::FarJump
+        )
+    }
+}
+
+/// The conditions of x86 conditional jumps, such as `jne`.
+const X86_CONDITIONS: &[&str] = &[
+    "a", "ae", "b", "be", "c", "e", "g", "ge", "l", "le", "na", "nae", "nb", "nbe", "nc", "ne",
+    "ng", "nge", "nl", "nle", "no", "np", "ns", "nz", "o", "p", "pe", "po", "s", "z",
+];
+
+/// Work out the control flow kind of an instruction from its
+/// disassembly, for x86, ARM, AArch64, RISC-V and MIPS.
+fn classify(mnemonic: &str, operands: &str, is_branch: bool) -> InstructionControlFlowKind {
+    use self::InstructionControlFlowKind::*;
+
+    let mnemonic = mnemonic.trim().to_ascii_lowercase();
+    let operands = operands.trim().to_ascii_lowercase();
+    // Drop x86 prefixes, such as `rep` or `notrack`.
+    let mnemonic = mnemonic.rsplit(' ').next().unwrap_or("");
+    if mnemonic.is_empty() {
+        return Unknown;
+    }
+    match mnemonic {
+        "call" | "callq" | "calll" | "bl" | "blx" | "blr" | "blraa" | "blraaz" | "blrab"
+        | "blrabz" => return Call,
+        "lcall" | "lcalll" | "lcallq" | "syscall" | "sysenter" | "int" | "int3" | "into"
+        | "svc" | "hvc" | "smc" => return FarCall,
+        "ret" | "retq" | "retl" | "retn" | "retaa" | "retab" => return Return,
+        "lret" | "lretl" | "lretq" | "retf" | "iret" | "iretd" | "iretq" | "sysret" | "sysretq"
+        | "sysexit" | "eret" => return FarReturn,
+        "jmp" | "jmpq" | "jmpl" | "b" | "br" | "braa" | "braaz" | "brab" | "brabz" => return Jump,
+        "ljmp" | "ljmpl" | "ljmpq" => return FarJump,
+        "bx" if operands == "lr" => return Return,
+        "bx" => return Jump,
+        "cbz" | "cbnz" | "tbz" | "tbnz" | "jcxz" | "jecxz" | "jrcxz" | "loop" | "loope"
+        | "loopne" => return CondJump,
+        "pop" | "ldm" | "ldmia" if operands.contains("pc") => return Return,
+        // RISC-V and MIPS.
+        "j" | "tail" => return Jump,
+        "jal" | "jalr" => return Call,
+        "jr" if operands == "ra" || operands == "$ra" => return Return,
+        "jr" => return Jump,
+        _ => {}
+    }
+    let x86_condition = mnemonic.strip_prefix('j');
+    if x86_condition.is_some_and(|c| X86_CONDITIONS.contains(&c)) || mnemonic.starts_with("b.") {
+        return CondJump;
+    }
+    if is_branch {
+        // ARM, RISC-V and MIPS conditional branches, such as `beq`
+        // and `bnez`, and anything else LLDB knows to be a branch.
+        const CONDITIONS: &[&str] = &[
+            "eq", "ne", "cs", "hs", "cc", "lo", "mi", "pl", "vs", "vc", "hi", "ls", "ge", "lt",
+            "gt", "le", "ltu", "geu", "gtu", "leu", "eqz", "nez", "gez", "gtz", "lez", "ltz",
+        ];
+        let condition = mnemonic.strip_prefix('b').map(|c| c.trim_end_matches(".w"));
+        if condition.is_some_and(|c| CONDITIONS.contains(&c)) {
+            return CondJump;
+        }
+        return Unknown;
+    }
+    Other
 }
 
 impl Clone for SBInstruction {
@@ -104,9 +255,21 @@ impl Clone for SBInstruction {
 
 impl fmt::Debug for SBInstruction {
     fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
-        let stream = SBStream::new();
-        unsafe { sys::SBInstructionGetDescription(self.raw, stream.raw) };
-        write!(fmt, "SBInstruction {{ {} }}", stream.data())
+        write!(fmt, "SBInstruction {{ {} }}", self.description())
+    }
+}
+
+impl fmt::Display for SBInstruction {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "{}", self.description())
+    }
+}
+
+impl TryFrom<sys::SBInstructionRef> for SBInstruction {
+    type Error = InvalidRefError;
+
+    fn try_from(raw: sys::SBInstructionRef) -> Result<SBInstruction, InvalidRefError> {
+        SBInstruction::maybe_wrap(raw).ok_or(InvalidRefError)
     }
 }
 
@@ -118,3 +281,48 @@ impl Drop for SBInstruction {
 
 unsafe impl Send for SBInstruction {}
 unsafe impl Sync for SBInstruction {}
+
+#[cfg(test)]
+mod tests {
+    use super::classify;
+    use super::InstructionControlFlowKind::*;
+
+    #[test]
+    fn classifies_x86() {
+        assert_eq!(classify("callq", "0x401000", true), Call);
+        assert_eq!(classify("retq", "", true), Return);
+        assert_eq!(classify("jmp", "0x401000", true), Jump);
+        assert_eq!(classify("jne", "0x401000", true), CondJump);
+        assert_eq!(classify("jae", "0x401000", true), CondJump);
+        assert_eq!(classify("jmpl", "*%eax", true), Jump);
+        assert_eq!(classify("jrcxz", "0x401000", true), CondJump);
+        assert_eq!(classify("syscall", "", false), FarCall);
+        assert_eq!(classify("notrack jmpq", "*%rax", true), Jump);
+        assert_eq!(classify("movq", "%rsp, %rbp", false), Other);
+    }
+
+    #[test]
+    fn classifies_arm() {
+        assert_eq!(classify("bl", "0x1000", true), Call);
+        assert_eq!(classify("ret", "", true), Return);
+        assert_eq!(classify("b.ne", "0x1000", true), CondJump);
+        assert_eq!(classify("cbz", "x0, 0x1000", true), CondJump);
+        assert_eq!(classify("bx", "lr", true), Return);
+        assert_eq!(classify("beq", "0x1000", true), CondJump);
+        assert_eq!(classify("pop", "{r4, pc}", true), Return);
+        assert_eq!(classify("bic", "r0, r0, #1", false), Other);
+        assert_eq!(classify("", "", false), Unknown);
+    }
+
+    #[test]
+    fn classifies_risc_v_and_mips() {
+        assert_eq!(classify("j", "0x1000", true), Jump);
+        assert_eq!(classify("jal", "0x1000", true), Call);
+        assert_eq!(classify("jalr", "a5", true), Call);
+        assert_eq!(classify("jr", "ra", true), Return);
+        assert_eq!(classify("jr", "$ra", true), Return);
+        assert_eq!(classify("jr", "a5", true), Jump);
+        assert_eq!(classify("bnez", "a0, 0x1000", true), CondJump);
+        assert_eq!(classify("bltu", "a0, a1, 0x1000", true), CondJump);
+    }
+}
@@ -4,8 +4,11 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
+use super::error::InvalidRefError;
 use super::instruction::SBInstruction;
 use super::stream::SBStream;
+use std::convert::TryFrom;
+use std::ffi::CString;
 use std::fmt;
 use sys;
 
@@ -19,14 +22,24 @@ pub struct SBInstructionList {
 
 impl SBInstructionList {
     /// Construct a new `SBInstructionList`.
-    pub fn wrap(raw: sys::SBInstructionListRef) -> SBInstructionList {
+    ///
+    /// # Safety
+    ///
+    /// `raw` must be a valid reference, which is disposed of when the
+    /// `SBInstructionList` is dropped. Use [`maybe_wrap`] when it may be invalid.
+    ///
+    /// [`maybe_wrap`]: #method.maybe_wrap
+    pub unsafe fn wrap(raw: sys::SBInstructionListRef) -> SBInstructionList {
         SBInstructionList { raw }
     }
 
     /// Construct a new `Some(SBInstructionList)` or `None`.
+    ///
+    /// `raw` is disposed of if it is invalid.
     pub fn maybe_wrap(raw: sys::SBInstructionListRef) -> Option<SBInstructionList> {
-        if unsafe { sys::SBInstructionListIsValid(raw) != 0 } {
-            Some(SBInstructionList { raw })
+        let wrapped = SBInstructionList { raw };
+        if wrapped.is_valid() {
+            Some(wrapped)
         } else {
             None
         }
@@ -52,6 +65,17 @@ impl SBInstructionList {
         unsafe { sys::SBInstructionListAppendInstruction(self.raw, instruction.raw) };
     }
 
+    /// Print, to standard output, the steps taken by emulating each
+    /// of the instructions for the architecture `triple`.
+    ///
+    /// Returns whether every instruction could be emulated.
+    pub fn dump_emulation_for_all_instructions(&self, triple: &str) -> bool {
+        let triple = CString::new(triple).unwrap();
+        unsafe {
+            sys::SBInstructionListDumpEmulationForAllInstructions(self.raw, triple.as_ptr()) != 0
+        }
+    }
+
     /// Iterate over this instruction list.
     pub fn iter(&self) -> SBInstructionListIter {
         SBInstructionListIter {
@@ -77,6 +101,14 @@ impl fmt::Debug for SBInstructionList {
     }
 }
 
+impl TryFrom<sys::SBInstructionListRef> for SBInstructionList {
+    type Error = InvalidRefError;
+
+    fn try_from(raw: sys::SBInstructionListRef) -> Result<SBInstructionList, InvalidRefError> {
+        SBInstructionList::maybe_wrap(raw).ok_or(InvalidRefError)
+    }
+}
+
 impl Drop for SBInstructionList {
     fn drop(&mut self) {
         unsafe { sys::DisposeSBInstructionList(self.raw) };
@@ -84,7 +116,6 @@ impl Drop for SBInstructionList {
 }
 
 unsafe impl Send for SBInstructionList {}
-unsafe impl Sync for SBInstructionList {}
 
 /// An iterator over the [instructions] in an [`SBInstructionList`].
 ///
@@ -100,12 +131,12 @@ impl<'d> Iterator for SBInstructionListIter<'d> {
 
     fn next(&mut self) -> Option<SBInstruction> {
         if self.idx < unsafe { sys::SBInstructionListGetSize(self.instruction_list.raw) as usize } {
-            let r = SBInstruction::wrap(unsafe {
-                sys::SBInstructionListGetInstructionAtIndex(
+            let r = unsafe {
+                SBInstruction::wrap(sys::SBInstructionListGetInstructionAtIndex(
                     self.instruction_list.raw,
                     self.idx as u32,
-                )
-            });
+                ))
+            };
             self.idx += 1;
             Some(r)
         } else {
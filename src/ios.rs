@@ -0,0 +1,121 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Debugging apps on iOS devices.
+//!
+//! [`IosDevice`] selects the `remote-ios` platform, which finds the
+//! system libraries of the device in Xcode's device support
+//! directories, and optionally connects it to a platform server on the
+//! device. Apps can then be installed and attached to by name,
+//! including waiting for an app to be launched from the home screen:
+//!
+//! ```no_run
+//! # use lldb::{RemoteUrl, SBDebugger};
+//! # use lldb::ios::IosDevice;
+//! SBDebugger::initialize();
+//! let debugger = SBDebugger::create(false);
+//! let device = IosDevice::connect(&debugger, Some(&RemoteUrl::tcp("localhost", 6000))).unwrap();
+//! device.install("build/Debug-iphoneos/Demo.app", "/private/var/mobile/Demo.app").unwrap();
+//! let (target, process) = device
+//!     .attach_by_name(Some("build/Debug-iphoneos/Demo.app"), "Demo", true)
+//!     .unwrap();
+//! ```
+//!
+//! The other Apple device platforms, such as `remote-tvos` and
+//! `remote-watchos`, work the same way through
+//! [`IosDevice::with_platform`].
+//!
+//! [`IosDevice`]: struct.IosDevice.html
+//! [`IosDevice::with_platform`]: struct.IosDevice.html#method.with_platform
+
+use super::attachinfo::SBAttachInfo;
+use super::debugger::SBDebugger;
+use super::error::SBError;
+use super::filespec::SBFileSpec;
+use super::platform::SBPlatform;
+use super::process::SBProcess;
+use super::remoteurl::RemoteUrl;
+use super::target::SBTarget;
+
+/// The name of the platform for iOS devices.
+pub const REMOTE_IOS: &str = "remote-ios";
+
+/// An iOS device, or another Apple device with a platform of its own.
+pub struct IosDevice {
+    debugger: SBDebugger,
+    platform: SBPlatform,
+}
+
+impl IosDevice {
+    /// Select the `remote-ios` platform for `debugger`, connecting it
+    /// to the platform server at `url` if there is one.
+    pub fn connect(debugger: &SBDebugger, url: Option<&RemoteUrl>) -> Result<IosDevice, SBError> {
+        IosDevice::with_platform(debugger, REMOTE_IOS, url)
+    }
+
+    /// Select the platform named `platform_name`, such as
+    /// `remote-tvos`, for `debugger`, connecting it to the platform
+    /// server at `url` if there is one.
+    ///
+    /// The platform becomes the selected platform of `debugger`, so
+    /// that targets created afterwards use it.
+    pub fn with_platform(
+        debugger: &SBDebugger,
+        platform_name: &str,
+        url: Option<&RemoteUrl>,
+    ) -> Result<IosDevice, SBError> {
+        let platform = SBPlatform::new(platform_name).ok_or_else(|| {
+            SBError::from_message(&format!("this LLDB has no {} platform", platform_name))
+        })?;
+        if let Some(url) = url {
+            platform.connect_remote(url)?;
+        }
+        debugger.set_selected_platform(&platform);
+        Ok(IosDevice {
+            debugger: debugger.clone(),
+            platform,
+        })
+    }
+
+    /// The platform for the device.
+    pub fn platform(&self) -> &SBPlatform {
+        &self.platform
+    }
+
+    /// Install the app bundle or program at `local` on the device at
+    /// `remote`.
+    pub fn install(&self, local: &str, remote: &str) -> Result<(), SBError> {
+        self.platform.install(
+            &SBFileSpec::from_path(local, true),
+            &SBFileSpec::from_path(remote, false),
+        )
+    }
+
+    /// Attach to the process named `name` on the device.
+    ///
+    /// When `wait_for` is true, this waits for the next process with
+    /// that name to be launched, such as by tapping the app's icon,
+    /// and attaches to it before it runs, instead of attaching to one
+    /// which is already running.
+    ///
+    /// `executable` is a local copy of the app or program, for its
+    /// symbols.
+    pub fn attach_by_name(
+        &self,
+        executable: Option<&str>,
+        name: &str,
+        wait_for: bool,
+    ) -> Result<(SBTarget, SBProcess), SBError> {
+        let target = self.debugger.create_target(
+            executable.unwrap_or(""),
+            None,
+            Some(&self.platform.name()),
+            true,
+        )?;
+        let process = target.attach(SBAttachInfo::new_with_path(name, wait_for, false))?;
+        Ok((target, process))
+    }
+}
@@ -4,10 +4,20 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
+use super::environment::Environment;
 use super::filespec::SBFileSpec;
 use super::listener::SBListener;
+#[cfg(unix)]
+use super::pty::PseudoTerminal;
+use super::strings;
+#[cfg(feature = "lldb-13")]
+use super::structureddata::SBStructuredData;
 use super::{lldb_pid_t, LaunchFlags};
-use std::ffi::{CStr, CString};
+#[cfg(feature = "lldb-13")]
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::ffi::CString;
+use std::iter;
 use std::ptr;
 use sys;
 
@@ -15,22 +25,64 @@ use sys;
 ///
 /// See [`SBTarget::launch`].
 ///
+/// The arguments, environment, working directory and launch flags
+/// can be set individually, or all at once using an
+/// [`SBLaunchInfoBuilder`]:
+///
+/// ```no_run
+/// # use lldb::{SBLaunchInfo, SBProcess, SBError, SBTarget};
+/// # fn launch(target: &SBTarget) -> Result<SBProcess, SBError> {
+/// let launch_info = SBLaunchInfo::builder()
+///     .arguments(&["--verbose", "input.txt"])
+///     .environment_entry("RUST_BACKTRACE", "1")
+///     .working_directory("/tmp")
+///     .stop_at_entry(true)
+///     .build();
+/// target.launch(launch_info)
+/// # }
+/// ```
+///
 /// [`SBTarget::launch`]: struct.SBTarget.html#method.launch
+/// [`SBLaunchInfoBuilder`]: struct.SBLaunchInfoBuilder.html
 #[derive(Debug)]
 pub struct SBLaunchInfo {
     /// The underlying raw `SBLaunchInfoRef`.
     pub raw: sys::SBLaunchInfoRef,
+    /// The class of a scripted process, which this version of LLDB's
+    /// API can't set on `raw`.
+    #[cfg(feature = "lldb-13")]
+    scripted_process_class_name: RefCell<Option<String>>,
+    /// The arguments given to the class of a scripted process.
+    #[cfg(feature = "lldb-13")]
+    scripted_process_dictionary: RefCell<Option<SBStructuredData>>,
 }
 
 impl SBLaunchInfo {
     /// Construct a new `SBLaunchInfo`.
     pub fn new() -> SBLaunchInfo {
-        SBLaunchInfo::wrap(unsafe { sys::CreateSBLaunchInfo(ptr::null_mut()) })
+        unsafe { SBLaunchInfo::wrap(sys::CreateSBLaunchInfo(ptr::null_mut())) }
     }
 
     /// Construct a new `SBLaunchInfo`.
-    pub fn wrap(raw: sys::SBLaunchInfoRef) -> SBLaunchInfo {
-        SBLaunchInfo { raw }
+    ///
+    /// # Safety
+    ///
+    /// `raw` must be an `SBLaunchInfoRef` which isn't used elsewhere,
+    /// such as one from `CreateSBLaunchInfo` or `SBTargetGetLaunchInfo`,
+    /// as it is disposed of when the `SBLaunchInfo` is dropped.
+    pub unsafe fn wrap(raw: sys::SBLaunchInfoRef) -> SBLaunchInfo {
+        SBLaunchInfo {
+            raw,
+            #[cfg(feature = "lldb-13")]
+            scripted_process_class_name: RefCell::new(None),
+            #[cfg(feature = "lldb-13")]
+            scripted_process_dictionary: RefCell::new(None),
+        }
+    }
+
+    /// Start building a new `SBLaunchInfo`.
+    pub fn builder() -> SBLaunchInfoBuilder {
+        SBLaunchInfoBuilder::new()
     }
 
     #[allow(missing_docs)]
@@ -116,12 +168,100 @@ impl SBLaunchInfo {
     /// process events. Calling this function allows a different
     /// listener to be used to listen for process events.
     ///
+    /// The listener is attached to the process before it is launched,
+    /// so it will receive every event from the process, including the
+    /// initial stop. Listening for process events after
+    /// [`SBTarget::launch`] has returned can miss that stop.
+    ///
     /// [`SBDebugger`]: struct.SBDebugger.html
     /// [`SBTarget`]: struct.SBTarget.html
+    /// [`SBTarget::launch`]: struct.SBTarget.html#method.launch
     pub fn set_listener(&self, listener: &SBListener) {
         unsafe { sys::SBLaunchInfoSetListener(self.raw, listener.raw) };
     }
 
+    /// Get an iterator over the arguments that will be passed
+    /// to the process.
+    pub fn arguments(&self) -> SBLaunchInfoArgumentIter {
+        SBLaunchInfoArgumentIter {
+            launch_info: self,
+            idx: 0,
+        }
+    }
+
+    /// Set the arguments that will be passed to the process.
+    ///
+    /// If `append` is `true`, the arguments are added to any existing
+    /// arguments. Otherwise, they replace them.
+    pub fn set_arguments(&self, args: &[&str], append: bool) {
+        let args: Vec<_> = args.iter().map(|&s| CString::new(s).unwrap()).collect();
+        let mut args_ptr = null_terminated(&args);
+        unsafe { sys::SBLaunchInfoSetArguments(self.raw, args_ptr.as_mut_ptr(), append as u8) };
+    }
+
+    /// Get an iterator over the environment entries that will be
+    /// used for the process. Each entry is of the form `NAME=VALUE`.
+    pub fn environment_entries(&self) -> SBLaunchInfoEnvironmentEntryIter {
+        SBLaunchInfoEnvironmentEntryIter {
+            launch_info: self,
+            idx: 0,
+        }
+    }
+
+    /// Set the environment entries that will be used for the process.
+    ///
+    /// Each entry should be of the form `NAME=VALUE`.
+    ///
+    /// If `append` is `true`, the entries are added to any existing
+    /// entries. Otherwise, they replace them.
+    pub fn set_environment_entries(&self, entries: &[&str], append: bool) {
+        let entries: Vec<_> = entries.iter().map(|&s| CString::new(s).unwrap()).collect();
+        let mut entries_ptr = null_terminated(&entries);
+        unsafe {
+            sys::SBLaunchInfoSetEnvironmentEntries(self.raw, entries_ptr.as_mut_ptr(), append as u8)
+        };
+    }
+
+    /// Set the environment that will be used for the process from
+    /// a map of names to values.
+    ///
+    /// If `append` is `true`, the entries are added to any existing
+    /// entries. Otherwise, they replace them.
+    pub fn set_environment(&self, environment: &HashMap<String, String>, append: bool) {
+        let entries: Vec<_> = environment
+            .iter()
+            .map(|(k, v)| format!("{}={}", k, v))
+            .collect();
+        let entries: Vec<_> = entries.iter().map(|s| s.as_str()).collect();
+        self.set_environment_entries(&entries, append);
+    }
+
+    /// The environment that will be used for the process.
+    pub fn environment_vars(&self) -> Environment {
+        Environment::from_entries(self.environment_entries())
+    }
+
+    /// Replace the environment that will be used for the process,
+    /// such as with one built from [`Environment::inherit`].
+    ///
+    /// [`Environment::inherit`]: struct.Environment.html#method.inherit
+    pub fn set_environment_vars(&self, environment: &Environment) {
+        let entries = environment.entries();
+        let entries: Vec<_> = entries.iter().map(|s| s.as_str()).collect();
+        self.set_environment_entries(&entries, false);
+    }
+
+    /// The working directory that the process will be launched in, if set.
+    pub fn working_directory(&self) -> Option<String> {
+        unsafe { strings::to_string(sys::SBLaunchInfoGetWorkingDirectory(self.raw)) }
+    }
+
+    /// Set the working directory that the process will be launched in.
+    pub fn set_working_directory(&self, working_directory: &str) {
+        let working_directory = CString::new(working_directory).unwrap();
+        unsafe { sys::SBLaunchInfoSetWorkingDirectory(self.raw, working_directory.as_ptr()) };
+    }
+
     #[allow(missing_docs)]
     pub fn launch_flags(&self) -> LaunchFlags {
         LaunchFlags::from_bits_truncate(unsafe { sys::SBLaunchInfoGetLaunchFlags(self.raw) })
@@ -132,14 +272,70 @@ impl SBLaunchInfo {
         unsafe { sys::SBLaunchInfoSetLaunchFlags(self.raw, launch_flags.bits()) }
     }
 
+    fn set_launch_flag(&self, flag: LaunchFlags, enabled: bool) {
+        let mut flags = self.launch_flags();
+        flags.set(flag, enabled);
+        self.set_launch_flags(flags);
+    }
+
+    /// Will the process stop at the program entry point?
+    pub fn stop_at_entry(&self) -> bool {
+        self.launch_flags().contains(LaunchFlags::STOP_AT_ENTRY)
+    }
+
+    /// Stop at the program entry point rather than automatically
+    /// continuing after launching.
+    pub fn set_stop_at_entry(&self, stop_at_entry: bool) {
+        self.set_launch_flag(LaunchFlags::STOP_AT_ENTRY, stop_at_entry);
+    }
+
+    /// Will address space layout randomization (ASLR) be disabled?
+    pub fn disable_aslr(&self) -> bool {
+        self.launch_flags().contains(LaunchFlags::DISABLE_ASLR)
+    }
+
+    /// Disable address space layout randomization (ASLR) for the process.
+    pub fn set_disable_aslr(&self, disable_aslr: bool) {
+        self.set_launch_flag(LaunchFlags::DISABLE_ASLR, disable_aslr);
+    }
+
+    /// Will stdio be disabled for the process?
+    pub fn disable_stdio(&self) -> bool {
+        self.launch_flags().contains(LaunchFlags::DISABLE_STDIO)
+    }
+
+    /// Disable stdio for the process, as is common for GUI applications.
+    pub fn set_disable_stdio(&self, disable_stdio: bool) {
+        self.set_launch_flag(LaunchFlags::DISABLE_STDIO, disable_stdio);
+    }
+
+    /// Will the process be launched inside a shell?
+    pub fn launch_in_shell(&self) -> bool {
+        self.launch_flags().contains(LaunchFlags::LAUNCH_IN_SHELL)
+    }
+
+    /// Launch the process inside a shell.
+    ///
+    /// The shell to use can be set with [`set_shell`].
+    ///
+    /// [`set_shell`]: #method.set_shell
+    pub fn set_launch_in_shell(&self, launch_in_shell: bool) {
+        self.set_launch_flag(LaunchFlags::LAUNCH_IN_SHELL, launch_in_shell);
+    }
+
+    /// Will the TTY used by the process be closed when it exits?
+    pub fn close_tty_on_exit(&self) -> bool {
+        self.launch_flags().contains(LaunchFlags::CLOSE_TTY_ON_EXIT)
+    }
+
+    /// Close the TTY used by the process when it exits.
+    pub fn set_close_tty_on_exit(&self, close_tty_on_exit: bool) {
+        self.set_launch_flag(LaunchFlags::CLOSE_TTY_ON_EXIT, close_tty_on_exit);
+    }
+
     #[allow(missing_docs)]
-    pub fn process_plugin_name(&self) -> Option<&str> {
-        unsafe {
-            match CStr::from_ptr(sys::SBLaunchInfoGetProcessPluginName(self.raw)).to_str() {
-                Ok(s) => Some(s),
-                _ => None,
-            }
-        }
+    pub fn process_plugin_name(&self) -> Option<String> {
+        unsafe { strings::to_string(sys::SBLaunchInfoGetProcessPluginName(self.raw)) }
     }
 
     #[allow(missing_docs)]
@@ -148,28 +344,76 @@ impl SBLaunchInfo {
         unsafe { sys::SBLaunchInfoSetProcessPluginName(self.raw, plugin.as_ptr()) };
     }
 
-    #[allow(missing_docs)]
-    pub fn shell(&self) -> Option<&str> {
-        unsafe {
-            match CStr::from_ptr(sys::SBLaunchInfoGetShell(self.raw)).to_str() {
-                Ok(s) => Some(s),
-                _ => None,
-            }
-        }
+    /// The Python class which provides the process, if this launches
+    /// a scripted process.
+    ///
+    /// See [`set_scripted_process_class_name`].
+    ///
+    /// [`set_scripted_process_class_name`]: #method.set_scripted_process_class_name
+    #[cfg(feature = "lldb-13")]
+    pub fn scripted_process_class_name(&self) -> Option<String> {
+        self.scripted_process_class_name.borrow().clone()
     }
 
-    #[allow(missing_docs)]
+    /// Launch a scripted process, whose threads, registers and memory
+    /// are provided by the Python class `class_name`, rather than a
+    /// real program. See [`SBTarget::launch_scripted_process`].
+    ///
+    /// This version of LLDB's API can't set the class on the
+    /// underlying `SBLaunchInfo`, so it is kept here, and
+    /// [`SBTarget::launch`] then launches the process from Python.
+    /// Only the class and its arguments are used for such a launch.
+    ///
+    /// [`SBTarget::launch_scripted_process`]: struct.SBTarget.html#method.launch_scripted_process
+    /// [`SBTarget::launch`]: struct.SBTarget.html#method.launch
+    #[cfg(feature = "lldb-13")]
+    pub fn set_scripted_process_class_name(&self, class_name: &str) {
+        *self.scripted_process_class_name.borrow_mut() = Some(class_name.to_string());
+    }
+
+    /// The arguments given to the class of a scripted process, if
+    /// they have been set.
+    #[cfg(feature = "lldb-13")]
+    pub fn scripted_process_dictionary(&self) -> Option<SBStructuredData> {
+        self.scripted_process_dictionary.borrow().clone()
+    }
+
+    /// Set the arguments given to the class of a scripted process,
+    /// as its `SBStructuredData` argument.
+    #[cfg(feature = "lldb-13")]
+    pub fn set_scripted_process_dictionary(&self, args: &SBStructuredData) {
+        *self.scripted_process_dictionary.borrow_mut() = Some(args.clone());
+    }
+
+    /// The shell that will be used to launch the process, if set.
+    pub fn shell(&self) -> Option<String> {
+        unsafe { strings::to_string(sys::SBLaunchInfoGetShell(self.raw)) }
+    }
+
+    /// Set the path to the shell that will be used to launch the process
+    /// when [launching in a shell], such as `/bin/bash`.
+    ///
+    /// If this isn't set, the default shell for the platform is used.
+    ///
+    /// [launching in a shell]: #method.set_launch_in_shell
     pub fn set_shell(&self, shell: &str) {
         let shell = CString::new(shell).unwrap();
         unsafe { sys::SBLaunchInfoSetShell(self.raw, shell.as_ptr()) };
     }
 
-    #[allow(missing_docs)]
+    /// Will the arguments be expanded by a shell?
     pub fn shell_expand_arguments(&self) -> bool {
         unsafe { sys::SBLaunchInfoGetShellExpandArguments(self.raw) != 0 }
     }
 
-    #[allow(missing_docs)]
+    /// Expand the arguments the same way as a shell would, so
+    /// that globs, variables and the like in the arguments behave
+    /// as they do when launching the process from a terminal.
+    ///
+    /// Unlike [launching in a shell], the process is not run as a
+    /// child of the shell.
+    ///
+    /// [launching in a shell]: #method.set_launch_in_shell
     pub fn set_shell_expand_arguments(&self, expand: bool) {
         unsafe { sys::SBLaunchInfoSetShellExpandArguments(self.raw, expand as u8) };
     }
@@ -184,17 +428,46 @@ impl SBLaunchInfo {
         unsafe { sys::SBLaunchInfoSetResumeCount(self.raw, resume_count) };
     }
 
-    #[allow(missing_docs)]
+    /// Close the file descriptor `fd` in the process before it starts.
+    ///
+    /// Returns `false` if the action could not be added.
     pub fn add_close_file_action(&self, fd: i32) -> bool {
         unsafe { sys::SBLaunchInfoAddCloseFileAction(self.raw, fd) != 0 }
     }
 
-    #[allow(missing_docs)]
+    /// Make the file descriptor `dup_fd` in the process a duplicate
+    /// of `fd`, which is inherited from the debugger.
+    ///
+    /// This is the equivalent of calling `dup2(fd, dup_fd)` in the
+    /// process before it starts. For example, to have the process
+    /// write its `stdout` to the same place as the debugger's `stderr`:
+    ///
+    /// ```no_run
+    /// # use lldb::SBLaunchInfo;
+    /// let launch_info = SBLaunchInfo::new();
+    /// launch_info.add_duplicate_file_action(2, 1);
+    /// ```
+    ///
+    /// Returns `false` if the action could not be added.
     pub fn add_duplicate_file_action(&self, fd: i32, dup_fd: i32) -> bool {
         unsafe { sys::SBLaunchInfoAddDuplicateFileAction(self.raw, fd, dup_fd) != 0 }
     }
 
-    #[allow(missing_docs)]
+    /// Open the file at `path` as the file descriptor `fd` in the
+    /// process before it starts.
+    ///
+    /// This can be used to redirect the standard streams of the
+    /// process to or from files:
+    ///
+    /// ```no_run
+    /// # use lldb::SBLaunchInfo;
+    /// let launch_info = SBLaunchInfo::new();
+    /// launch_info.add_open_file_action(0, "input.txt", true, false);
+    /// launch_info.add_open_file_action(1, "output.txt", false, true);
+    /// launch_info.add_open_file_action(2, "errors.txt", false, true);
+    /// ```
+    ///
+    /// Returns `false` if the action could not be added.
     pub fn add_open_file_action(&self, fd: i32, path: &str, read: bool, write: bool) -> bool {
         let path = CString::new(path).unwrap();
         unsafe {
@@ -203,21 +476,33 @@ impl SBLaunchInfo {
         }
     }
 
-    #[allow(missing_docs)]
+    /// Connect the file descriptor `fd` in the process to `/dev/null`,
+    /// suppressing any input or output.
+    ///
+    /// Returns `false` if the action could not be added.
     pub fn add_suppress_file_action(&self, fd: i32, read: bool, write: bool) -> bool {
         unsafe {
             sys::SBLaunchInfoAddSuppressFileAction(self.raw, fd, read as u8, write as u8) != 0
         }
     }
 
+    /// Connect the standard input, output and error of the process to
+    /// the terminal `pty`, so that it behaves as it does when run from
+    /// a shell.
+    ///
+    /// This replaces any other actions for those file descriptors.
+    /// Returns `false` if the actions could not be added.
+    #[cfg(unix)]
+    pub fn set_pseudo_terminal(&self, pty: &PseudoTerminal) -> bool {
+        let path = pty.slave_path();
+        self.add_open_file_action(0, path, true, false)
+            && self.add_open_file_action(1, path, false, true)
+            && self.add_open_file_action(2, path, false, true)
+    }
+
     #[allow(missing_docs)]
-    pub fn launch_event_data(&self) -> Option<&str> {
-        unsafe {
-            match CStr::from_ptr(sys::SBLaunchInfoGetLaunchEventData(self.raw)).to_str() {
-                Ok(s) => Some(s),
-                _ => None,
-            }
-        }
+    pub fn launch_event_data(&self) -> Option<String> {
+        unsafe { strings::to_string(sys::SBLaunchInfoGetLaunchEventData(self.raw)) }
     }
 
     #[allow(missing_docs)]
@@ -236,10 +521,298 @@ impl SBLaunchInfo {
     }
 }
 
+/// Build a null terminated array of pointers to the given strings.
+fn null_terminated(strings: &[CString]) -> Vec<*const ::std::os::raw::c_char> {
+    strings
+        .iter()
+        .map(|s| s.as_ptr())
+        .chain(iter::once(ptr::null()))
+        .collect()
+}
+
+/// Iterate over the arguments in an [`SBLaunchInfo`].
+///
+/// [`SBLaunchInfo`]: struct.SBLaunchInfo.html
+pub struct SBLaunchInfoArgumentIter<'d> {
+    launch_info: &'d SBLaunchInfo,
+    idx: u32,
+}
+
+impl<'d> Iterator for SBLaunchInfoArgumentIter<'d> {
+    type Item = String;
+
+    fn next(&mut self) -> Option<String> {
+        if self.idx < unsafe { sys::SBLaunchInfoGetNumArguments(self.launch_info.raw) } {
+            let r = unsafe {
+                strings::to_string_lossy(sys::SBLaunchInfoGetArgumentAtIndex(
+                    self.launch_info.raw,
+                    self.idx,
+                ))
+            };
+            self.idx += 1;
+            Some(r)
+        } else {
+            None
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let sz = unsafe { sys::SBLaunchInfoGetNumArguments(self.launch_info.raw) } as usize;
+        (sz - self.idx as usize, Some(sz))
+    }
+}
+
+impl<'d> ExactSizeIterator for SBLaunchInfoArgumentIter<'d> {}
+
+/// Iterate over the environment entries in an [`SBLaunchInfo`].
+///
+/// [`SBLaunchInfo`]: struct.SBLaunchInfo.html
+pub struct SBLaunchInfoEnvironmentEntryIter<'d> {
+    launch_info: &'d SBLaunchInfo,
+    idx: u32,
+}
+
+impl<'d> Iterator for SBLaunchInfoEnvironmentEntryIter<'d> {
+    type Item = String;
+
+    fn next(&mut self) -> Option<String> {
+        if self.idx < unsafe { sys::SBLaunchInfoGetNumEnvironmentEntries(self.launch_info.raw) } {
+            let r = unsafe {
+                strings::to_string_lossy(sys::SBLaunchInfoGetEnvironmentEntryAtIndex(
+                    self.launch_info.raw,
+                    self.idx,
+                ))
+            };
+            self.idx += 1;
+            Some(r)
+        } else {
+            None
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let sz =
+            unsafe { sys::SBLaunchInfoGetNumEnvironmentEntries(self.launch_info.raw) } as usize;
+        (sz - self.idx as usize, Some(sz))
+    }
+}
+
+impl<'d> ExactSizeIterator for SBLaunchInfoEnvironmentEntryIter<'d> {}
+
+/// A builder for an [`SBLaunchInfo`].
+///
+/// This is created via [`SBLaunchInfo::builder`].
+///
+/// [`SBLaunchInfo`]: struct.SBLaunchInfo.html
+/// [`SBLaunchInfo::builder`]: struct.SBLaunchInfo.html#method.builder
+#[derive(Debug, Default)]
+pub struct SBLaunchInfoBuilder {
+    launch_info: SBLaunchInfo,
+}
+
+impl SBLaunchInfoBuilder {
+    /// Construct a new `SBLaunchInfoBuilder`.
+    pub fn new() -> SBLaunchInfoBuilder {
+        SBLaunchInfoBuilder {
+            launch_info: SBLaunchInfo::new(),
+        }
+    }
+
+    /// Set the listener that will receive events from the process.
+    ///
+    /// See [`SBLaunchInfo::set_listener`].
+    ///
+    /// [`SBLaunchInfo::set_listener`]: struct.SBLaunchInfo.html#method.set_listener
+    pub fn listener(self, listener: &SBListener) -> Self {
+        self.launch_info.set_listener(listener);
+        self
+    }
+
+    /// Append an argument to pass to the process.
+    pub fn argument(self, argument: &str) -> Self {
+        self.launch_info.set_arguments(&[argument], true);
+        self
+    }
+
+    /// Append arguments to pass to the process.
+    pub fn arguments(self, arguments: &[&str]) -> Self {
+        self.launch_info.set_arguments(arguments, true);
+        self
+    }
+
+    /// Add an environment variable for the process.
+    pub fn environment_entry(self, name: &str, value: &str) -> Self {
+        let entry = format!("{}={}", name, value);
+        self.launch_info.set_environment_entries(&[&entry], true);
+        self
+    }
+
+    /// Add environment variables for the process.
+    pub fn environment(self, environment: &HashMap<String, String>) -> Self {
+        self.launch_info.set_environment(environment, true);
+        self
+    }
+
+    /// Replace the environment for the process, including any
+    /// variables added so far.
+    ///
+    /// See [`SBLaunchInfo::set_environment_vars`].
+    ///
+    /// [`SBLaunchInfo::set_environment_vars`]: struct.SBLaunchInfo.html#method.set_environment_vars
+    pub fn environment_vars(self, environment: &Environment) -> Self {
+        self.launch_info.set_environment_vars(environment);
+        self
+    }
+
+    /// Set the working directory for the process.
+    pub fn working_directory(self, working_directory: &str) -> Self {
+        self.launch_info.set_working_directory(working_directory);
+        self
+    }
+
+    /// Set the launch flags for the process, replacing any flags
+    /// that have already been set.
+    pub fn launch_flags(self, launch_flags: LaunchFlags) -> Self {
+        self.launch_info.set_launch_flags(launch_flags);
+        self
+    }
+
+    /// Stop at the program entry point.
+    ///
+    /// See [`SBLaunchInfo::set_stop_at_entry`].
+    ///
+    /// [`SBLaunchInfo::set_stop_at_entry`]: struct.SBLaunchInfo.html#method.set_stop_at_entry
+    pub fn stop_at_entry(self, stop_at_entry: bool) -> Self {
+        self.launch_info.set_stop_at_entry(stop_at_entry);
+        self
+    }
+
+    /// Disable address space layout randomization (ASLR).
+    ///
+    /// See [`SBLaunchInfo::set_disable_aslr`].
+    ///
+    /// [`SBLaunchInfo::set_disable_aslr`]: struct.SBLaunchInfo.html#method.set_disable_aslr
+    pub fn disable_aslr(self, disable_aslr: bool) -> Self {
+        self.launch_info.set_disable_aslr(disable_aslr);
+        self
+    }
+
+    /// Disable stdio for the process.
+    ///
+    /// See [`SBLaunchInfo::set_disable_stdio`].
+    ///
+    /// [`SBLaunchInfo::set_disable_stdio`]: struct.SBLaunchInfo.html#method.set_disable_stdio
+    pub fn disable_stdio(self, disable_stdio: bool) -> Self {
+        self.launch_info.set_disable_stdio(disable_stdio);
+        self
+    }
+
+    /// Launch the process inside a shell.
+    ///
+    /// See [`SBLaunchInfo::set_launch_in_shell`].
+    ///
+    /// [`SBLaunchInfo::set_launch_in_shell`]: struct.SBLaunchInfo.html#method.set_launch_in_shell
+    pub fn launch_in_shell(self, launch_in_shell: bool) -> Self {
+        self.launch_info.set_launch_in_shell(launch_in_shell);
+        self
+    }
+
+    /// Set the shell used to launch the process.
+    ///
+    /// See [`SBLaunchInfo::set_shell`].
+    ///
+    /// [`SBLaunchInfo::set_shell`]: struct.SBLaunchInfo.html#method.set_shell
+    pub fn shell(self, shell: &str) -> Self {
+        self.launch_info.set_shell(shell);
+        self
+    }
+
+    /// Expand the arguments the same way as a shell would.
+    ///
+    /// See [`SBLaunchInfo::set_shell_expand_arguments`].
+    ///
+    /// [`SBLaunchInfo::set_shell_expand_arguments`]: struct.SBLaunchInfo.html#method.set_shell_expand_arguments
+    pub fn shell_expand_arguments(self, expand: bool) -> Self {
+        self.launch_info.set_shell_expand_arguments(expand);
+        self
+    }
+
+    /// Close the TTY used by the process when it exits.
+    ///
+    /// See [`SBLaunchInfo::set_close_tty_on_exit`].
+    ///
+    /// [`SBLaunchInfo::set_close_tty_on_exit`]: struct.SBLaunchInfo.html#method.set_close_tty_on_exit
+    pub fn close_tty_on_exit(self, close_tty_on_exit: bool) -> Self {
+        self.launch_info.set_close_tty_on_exit(close_tty_on_exit);
+        self
+    }
+
+    /// Connect the standard streams of the process to a
+    /// pseudo-terminal.
+    ///
+    /// See [`SBLaunchInfo::set_pseudo_terminal`].
+    ///
+    /// [`SBLaunchInfo::set_pseudo_terminal`]: struct.SBLaunchInfo.html#method.set_pseudo_terminal
+    #[cfg(unix)]
+    pub fn pseudo_terminal(self, pty: &PseudoTerminal) -> Self {
+        self.launch_info.set_pseudo_terminal(pty);
+        self
+    }
+
+    /// Close a file descriptor in the process.
+    ///
+    /// See [`SBLaunchInfo::add_close_file_action`].
+    ///
+    /// [`SBLaunchInfo::add_close_file_action`]: struct.SBLaunchInfo.html#method.add_close_file_action
+    pub fn close_file(self, fd: i32) -> Self {
+        self.launch_info.add_close_file_action(fd);
+        self
+    }
+
+    /// Duplicate a file descriptor from the debugger into the process.
+    ///
+    /// See [`SBLaunchInfo::add_duplicate_file_action`].
+    ///
+    /// [`SBLaunchInfo::add_duplicate_file_action`]: struct.SBLaunchInfo.html#method.add_duplicate_file_action
+    pub fn duplicate_file(self, fd: i32, dup_fd: i32) -> Self {
+        self.launch_info.add_duplicate_file_action(fd, dup_fd);
+        self
+    }
+
+    /// Open a file as a file descriptor in the process.
+    ///
+    /// See [`SBLaunchInfo::add_open_file_action`].
+    ///
+    /// [`SBLaunchInfo::add_open_file_action`]: struct.SBLaunchInfo.html#method.add_open_file_action
+    pub fn open_file(self, fd: i32, path: &str, read: bool, write: bool) -> Self {
+        self.launch_info.add_open_file_action(fd, path, read, write);
+        self
+    }
+
+    /// Suppress a file descriptor in the process.
+    ///
+    /// See [`SBLaunchInfo::add_suppress_file_action`].
+    ///
+    /// [`SBLaunchInfo::add_suppress_file_action`]: struct.SBLaunchInfo.html#method.add_suppress_file_action
+    pub fn suppress_file(self, fd: i32, read: bool, write: bool) -> Self {
+        self.launch_info.add_suppress_file_action(fd, read, write);
+        self
+    }
+
+    /// Finish building the `SBLaunchInfo`.
+    pub fn build(self) -> SBLaunchInfo {
+        self.launch_info
+    }
+}
+
 impl Clone for SBLaunchInfo {
     fn clone(&self) -> SBLaunchInfo {
         SBLaunchInfo {
             raw: unsafe { sys::CloneSBLaunchInfo(self.raw) },
+            #[cfg(feature = "lldb-13")]
+            scripted_process_class_name: self.scripted_process_class_name.clone(),
+            #[cfg(feature = "lldb-13")]
+            scripted_process_dictionary: self.scripted_process_dictionary.clone(),
         }
     }
 }
@@ -257,4 +830,3 @@ impl Drop for SBLaunchInfo {
 }
 
 unsafe impl Send for SBLaunchInfo {}
-unsafe impl Sync for SBLaunchInfo {}
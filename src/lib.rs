@@ -48,6 +48,58 @@
 //! Support for building this has not yet been provided for Windows.
 //! Contributions are welcome!
 //!
+//! ### LLDB Versions
+//!
+//! By default, this crate only uses the parts of the LLDB API which
+//! are available in every version supported by `lldb-sys`. Newer parts
+//! of the API are enabled by the `lldb-13` to `lldb-18` features, each
+//! of which enables those before it, so enable the one matching the
+//! oldest LLDB that you need to run with:
+//!
+//! ```toml
+//! lldb = { version = "0.0.8", features = ["lldb-15"] }
+//! ```
+//!
+//! An API which is behind one of these features isn't compiled in
+//! without it, so that code using it fails to build rather than
+//! failing at run time against an older LLDB. That includes APIs built
+//! on commands and settings which older versions don't have:
+//!
+//! * `lldb-13`: [`SBTarget::launch_scripted_process`] and loading
+//!   processor traces with [`SBDebugger::load_trace`].
+//! * `lldb-14`: turning off `symbols.load-on-demand` when preloading
+//!   symbols with [`SBTarget::preload_symbols`].
+//! * `lldb-15`: processor tracing with [`SBProcess::start_trace`] and
+//!   [`SBThread::start_trace`].
+//! * `lldb-16`: reading processor traces with
+//!   [`SBThread::trace_cursor`].
+//! * `lldb-17`: [`SBTarget::attach_scripted_process`].
+//! * `lldb-18`: [`SBDebugger::set_debuginfod_urls`],
+//!   [`SBDebugger::set_symbol_cache_path`] and
+//!   [`SBDebugger::set_symbol_auto_download`].
+//!
+//! The `lldb-sys` version which this crate uses doesn't have any of the
+//! newer parts of the LLDB API itself, such as `SBFile`, `SBTrace` or
+//! `SBSaveCoreOptions`, so the features above are all built on
+//! commands and settings.
+//!
+//! ### Profiling Calls into LLDB
+//!
+//! With the `trace-ffi` feature, the calls into LLDB which can take a
+//! long time, such as creating targets, launching and attaching,
+//! resuming and stepping, reading memory, evaluating expressions,
+//! running commands and waiting for events, are made inside a
+//! [`tracing`] span. Each span is at the `DEBUG` level with the target
+//! `lldb::ffi`, and records the name of the call, such as
+//! `SBFrame::evaluate_expression`, its duration in microseconds as
+//! `duration_us` and, when it fails, the error message as `error`.
+//!
+//! Only those calls are traced. Accessors and other calls which return
+//! quickly, which are most of the API, are made directly, as a span
+//! would cost more than the call itself.
+//!
+//! [`tracing`]: https://docs.rs/tracing
+//!
 //! ## Usage
 //!
 //! The primary entry point is [`SBDebugger`]. This will be how you
@@ -81,6 +133,23 @@
 //!   instructions and a source file location. [`SBCompileUnit`] contains
 //!   [`SBLineEntry`]s.
 //!
+//! ## Thread Safety
+//!
+//! Every type here is `Send`. A type is also `Sync` unless it can be
+//! changed through a shared reference without LLDB taking a lock.
+//!
+//! That makes handles to objects that LLDB protects with its own locks,
+//! such as [`SBDebugger`], [`SBTarget`], [`SBProcess`] and [`SBValue`],
+//! `Sync`. So are values such as [`SBError`] and [`SBFileSpec`], whose
+//! few methods which change the value itself take `&mut self`.
+//!
+//! Values which are built up or filled in through shared references,
+//! such as [`SBStream`], [`SBCommandReturnObject`], [`SBData`], the
+//! `SB*List` types, [`SBModuleSpec`] and the option types like
+//! [`SBLaunchInfo`], are `Send` but not `Sync`. To share one between
+//! threads, put it in a `Mutex`, or use [`assert_thread_safe`] when it
+//! is known not to be modified.
+//!
 //! ## Support and Maintenance
 //!
 //! I am developing this library largely on my own so far. I am able
@@ -106,6 +175,24 @@
 //! [`SBFunction`]: struct.SBFunction.html
 //! [`SBBlock`]: struct.SBBlock.html
 //! [`SBLineEntry`]: struct.SBLineEntry.html
+//! [`SBStream`]: struct.SBStream.html
+//! [`SBError`]: struct.SBError.html
+//! [`SBFileSpec`]: struct.SBFileSpec.html
+//! [`SBData`]: struct.SBData.html
+//! [`SBModuleSpec`]: struct.SBModuleSpec.html
+//! [`SBCommandReturnObject`]: struct.SBCommandReturnObject.html
+//! [`SBLaunchInfo`]: struct.SBLaunchInfo.html
+//! [`SBTarget::launch_scripted_process`]: struct.SBTarget.html#method.launch_scripted_process
+//! [`SBTarget::attach_scripted_process`]: struct.SBTarget.html#method.attach_scripted_process
+//! [`SBTarget::preload_symbols`]: struct.SBTarget.html#method.preload_symbols
+//! [`SBProcess::start_trace`]: struct.SBProcess.html#method.start_trace
+//! [`SBThread::start_trace`]: struct.SBThread.html#method.start_trace
+//! [`SBThread::trace_cursor`]: struct.SBThread.html#method.trace_cursor
+//! [`SBDebugger::load_trace`]: struct.SBDebugger.html#method.load_trace
+//! [`SBDebugger::set_debuginfod_urls`]: struct.SBDebugger.html#method.set_debuginfod_urls
+//! [`SBDebugger::set_symbol_cache_path`]: struct.SBDebugger.html#method.set_symbol_cache_path
+//! [`SBDebugger::set_symbol_auto_download`]: struct.SBDebugger.html#method.set_symbol_auto_download
+//! [`assert_thread_safe`]: fn.assert_thread_safe.html
 
 #![allow(clippy::upper_case_acronyms)]
 #![warn(missing_docs)]
@@ -116,11 +203,20 @@
     unused_qualifications
 )]
 
+extern crate libc;
 extern crate lldb_sys as sys;
 
+#[cfg(feature = "graphql")]
+extern crate futures;
 #[cfg(feature = "graphql")]
 #[macro_use]
 extern crate juniper;
+#[cfg(feature = "serde")]
+extern crate serde;
+#[cfg(feature = "serde_json")]
+extern crate serde_json;
+#[cfg(feature = "tracing")]
+extern crate tracing;
 
 pub use sys::{lldb_addr_t, lldb_offset_t, lldb_pid_t, lldb_tid_t, lldb_user_id_t};
 
@@ -131,22 +227,40 @@ pub use sys::{
     FrameComparison, FunctionNameType, GdbSignal, InputReaderAction, InputReaderGranularity,
     InstrumentationRuntimeType, LanguageType, LaunchFlags, MatchType, MemberFunctionKind, PathType,
     Permissions, QueueItemKind, QueueKind, RegisterKind, ReturnStatus, RunMode, ScriptLanguage,
-    SectionType, StateType, StopReason, SymbolContextItem, SymbolType, TemplateArgumentKind,
-    TypeClass, TypeFlags, TypeOptions, TypeSummaryCapping, ValueType, WatchpointEventType,
-    WatchpointKind,
+    SectionType, StateType, StopReason, StructuredDataType, SymbolContextItem, SymbolType,
+    TemplateArgumentKind, TypeClass, TypeFlags, TypeOptions, TypeSummaryCapping, ValueType,
+    WatchpointEventType, WatchpointKind,
 };
 
 mod address;
+mod addressrange;
+pub mod android;
+mod argvalue;
 mod attachinfo;
+mod backend;
+pub mod baremetal;
 mod block;
 mod breakpoint;
+mod breakpointbuilder;
 mod breakpointlist;
 mod breakpointlocation;
 mod broadcaster;
+mod callcounter;
+mod cancel;
 mod commandinterpreter;
+mod commandinterpreterrunoptions;
+mod commandreturnobject;
 mod compileunit;
+mod connectionlost;
+pub mod coverage;
+#[cfg(feature = "dap")]
+pub mod dap;
 mod data;
+pub mod deadlock;
 mod debugger;
+mod deferred;
+mod demangle;
+mod environment;
 mod error;
 mod event;
 mod expressionoptions;
@@ -154,85 +268,166 @@ mod filespec;
 mod filespeclist;
 mod frame;
 mod function;
+#[cfg(feature = "graphql")]
+pub mod graphql;
+pub mod heap;
 mod instruction;
 mod instructionlist;
+pub mod ios;
 mod launchinfo;
 mod lineentry;
 mod listener;
+mod memoryregioninfo;
+mod memoryregioninfolist;
+mod memoryscan;
 mod module;
 mod modulespec;
 mod platform;
 mod process;
 mod processinfo;
+#[cfg(feature = "lldb-15")]
+mod processortrace;
+mod processstate;
+#[cfg(unix)]
+mod pty;
 mod queue;
 mod queueitem;
+mod registers;
+mod remoteurl;
+mod savecoreoptions;
 mod section;
+mod session;
+#[cfg(feature = "snapshots")]
+pub mod snapshots;
+mod sourcemanager;
+#[cfg(feature = "json")]
+mod statistics;
+mod stophandle;
 mod stream;
 mod stringlist;
+mod strings;
 mod structureddata;
 mod symbol;
 mod symbolcontext;
 mod symbolcontextlist;
+pub mod symbolication;
+mod symbolpreload;
 mod target;
 mod thread;
+mod threadsafe;
+mod timeout;
+mod trace;
+#[cfg(feature = "lldb-16")]
+mod tracecursor;
+mod tracer;
+#[cfg(feature = "triage")]
+pub mod triage;
 mod typelist;
 mod types;
 mod value;
 mod valuelist;
+mod variablecache;
 mod variablesoptions;
 mod watchpoint;
+mod watchset;
 
 pub use self::address::SBAddress;
+pub use self::addressrange::{AddressRange, AddressRangeList};
+pub use self::argvalue::ArgValue;
 pub use self::attachinfo::SBAttachInfo;
+pub use self::backend::{Backend, BackendError, MockBackend};
 pub use self::block::SBBlock;
 pub use self::breakpoint::{SBBreakpoint, SBBreakpointLocationIter};
+pub use self::breakpointbuilder::BreakpointBuilder;
 pub use self::breakpointlist::{SBBreakpointList, SBBreakpointListIter};
 pub use self::breakpointlocation::SBBreakpointLocation;
 pub use self::broadcaster::SBBroadcaster;
-pub use self::commandinterpreter::SBCommandInterpreter;
+pub use self::callcounter::CallCounter;
+pub use self::cancel::{CancelError, CancelToken};
+pub use self::commandinterpreter::{
+    CommandInfo, CommandKind, Completions, SBCommandInterpreter, SBCommandInterpreterEvent,
+};
+pub use self::commandinterpreterrunoptions::SBCommandInterpreterRunOptions;
+pub use self::commandreturnobject::SBCommandReturnObject;
 pub use self::compileunit::SBCompileUnit;
+pub use self::connectionlost::ConnectionLost;
 pub use self::data::SBData;
+#[cfg(feature = "lldb-18")]
+pub use self::debugger::SymbolDownload;
 pub use self::debugger::{SBDebugger, SBDebuggerTargetIter};
-pub use self::error::SBError;
+pub use self::deferred::{DeferredBreakpoints, DeferredLocation, ResolvedBreakpoint};
+pub use self::demangle::demangle;
+pub use self::environment::{Environment, EnvironmentDiff};
+pub use self::error::{Error, InvalidRefError, SBError};
 pub use self::event::SBEvent;
 pub use self::expressionoptions::SBExpressionOptions;
 pub use self::filespec::SBFileSpec;
 pub use self::filespeclist::{SBFileSpecList, SBFileSpecListIter};
-pub use self::frame::SBFrame;
+pub use self::frame::{FrameInfo, InlineFrame, SBFrame};
 pub use self::function::SBFunction;
-pub use self::instruction::SBInstruction;
+pub use self::instruction::{InstructionControlFlowKind, SBInstruction};
 pub use self::instructionlist::{SBInstructionList, SBInstructionListIter};
-pub use self::launchinfo::SBLaunchInfo;
+pub use self::launchinfo::{
+    SBLaunchInfo, SBLaunchInfoArgumentIter, SBLaunchInfoBuilder, SBLaunchInfoEnvironmentEntryIter,
+};
 pub use self::lineentry::SBLineEntry;
 pub use self::listener::SBListener;
-pub use self::module::{SBModule, SBModuleSectionIter};
+pub use self::memoryregioninfo::SBMemoryRegionInfo;
+pub use self::memoryregioninfolist::{SBMemoryRegionInfoList, SBMemoryRegionInfoListIter};
+pub use self::memoryscan::{parse_signature, MemoryScan, ScanProgress};
+pub use self::module::{SBModule, SBModuleSectionIter, SBModuleSymbolIter};
 pub use self::modulespec::SBModuleSpec;
 pub use self::platform::SBPlatform;
 pub use self::process::{
-    SBProcess, SBProcessEvent, SBProcessEventRestartedReasonIter, SBProcessQueueIter,
-    SBProcessThreadIter,
+    ChainError, ContinueToLocation, ContinueToStop, SBProcess, SBProcessEvent,
+    SBProcessEventRestartedReasonIter, SBProcessQueueIter, SBProcessThreadIter, StopSnapshot,
 };
-pub use self::processinfo::SBProcessInfo;
+pub use self::processinfo::{ProcessInfo, SBProcessInfo};
+#[cfg(feature = "lldb-15")]
+pub use self::processortrace::ProcessorTrace;
+pub use self::processstate::ProcessState;
+#[cfg(unix)]
+pub use self::pty::PseudoTerminal;
 pub use self::queue::{SBQueue, SBQueueQueueItemIter, SBQueueThreadIter};
 pub use self::queueitem::SBQueueItem;
+pub use self::registers::{RegisterChange, RegisterSnapshot, RegisterValue};
+pub use self::remoteurl::RemoteUrl;
+pub use self::savecoreoptions::{CoreStyle, SaveCoreOptions};
 pub use self::section::{SBSection, SBSectionSubSectionIter};
+pub use self::session::{DebugSession, SessionError};
+pub use self::sourcemanager::SBSourceManager;
+#[cfg(feature = "json")]
+pub use self::statistics::{
+    BreakpointStatistics, ModuleStatistics, SessionStatistics, SuccessFailureCount,
+    TargetStatistics,
+};
+pub use self::stophandle::{StaleHandle, StopHandle};
 pub use self::stream::SBStream;
 pub use self::stringlist::{SBStringList, SBStringListIter};
+pub use self::strings::AsCStr;
 pub use self::structureddata::SBStructuredData;
 pub use self::symbol::SBSymbol;
 pub use self::symbolcontext::SBSymbolContext;
 pub use self::symbolcontextlist::SBSymbolContextList;
+pub use self::symbolpreload::{PreloadProgress, PreloadedModule, SymbolPreload};
 pub use self::target::{
-    SBTarget, SBTargetBreakpointIter, SBTargetEvent, SBTargetEventModuleIter, SBTargetModuleIter,
-    SBTargetWatchpointIter,
+    LaunchError, SBTarget, SBTargetBreakpointIter, SBTargetEvent, SBTargetEventModuleIter,
+    SBTargetModuleIter, SBTargetWatchpointIter,
 };
-pub use self::thread::{SBThread, SBThreadEvent, SBThreadFrameIter};
+pub use self::thread::{SBThread, SBThreadEvent, SBThreadFrameIter, StepKind, ThreadEvent};
+pub use self::threadsafe::{assert_thread_safe, AssertThreadSafe};
+pub use self::timeout::{with_timeout, with_timeout_or_interrupt, TimedOut, TimeoutError};
+#[cfg(feature = "lldb-16")]
+pub use self::tracecursor::{TraceCursor, TraceItem, TraceItemKind};
+pub use self::tracer::{TraceEnd, TraceStep, Tracer};
 pub use self::typelist::{SBTypeList, SBTypeListIter};
 pub use self::types::SBType;
 pub use self::value::SBValue;
 pub use self::valuelist::{SBValueList, SBValueListIter};
+pub use self::variablecache::{CachedValue, VariableCache};
 pub use self::variablesoptions::SBVariablesOptions;
-pub use self::watchpoint::SBWatchpoint;
+pub use self::watchpoint::{SBWatchpoint, SBWatchpointEvent, WatchpointEvent};
+pub use self::watchset::{WatchEvent, WatchSet};
 
 /// Which syntax should be used in disassembly?
 ///
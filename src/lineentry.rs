@@ -5,8 +5,12 @@
 // except according to those terms.
 
 use super::address::SBAddress;
+use super::error::InvalidRefError;
 use super::filespec::SBFileSpec;
+#[cfg(feature = "graphql")]
+use super::graphql::U64;
 use super::stream::SBStream;
+use std::convert::TryFrom;
 use std::fmt;
 use sys;
 
@@ -19,14 +23,24 @@ pub struct SBLineEntry {
 
 impl SBLineEntry {
     /// Construct a new `SBLineEntry`.
-    pub fn wrap(raw: sys::SBLineEntryRef) -> SBLineEntry {
+    ///
+    /// # Safety
+    ///
+    /// `raw` must be a valid reference, which is disposed of when the
+    /// `SBLineEntry` is dropped. Use [`maybe_wrap`] when it may be invalid.
+    ///
+    /// [`maybe_wrap`]: #method.maybe_wrap
+    pub unsafe fn wrap(raw: sys::SBLineEntryRef) -> SBLineEntry {
         SBLineEntry { raw }
     }
 
     /// Construct a new `Some(SBLineEntry)` or `None`.
+    ///
+    /// `raw` is disposed of if it is invalid.
     pub fn maybe_wrap(raw: sys::SBLineEntryRef) -> Option<SBLineEntry> {
-        if unsafe { sys::SBLineEntryIsValid(raw) != 0 } {
-            Some(SBLineEntry { raw })
+        let wrapped = SBLineEntry { raw };
+        if wrapped.is_valid() {
+            Some(wrapped)
         } else {
             None
         }
@@ -39,17 +53,17 @@ impl SBLineEntry {
 
     /// The start address for this line entry.
     pub fn start_address(&self) -> SBAddress {
-        SBAddress::wrap(unsafe { sys::SBLineEntryGetStartAddress(self.raw) })
+        unsafe { SBAddress::wrap(sys::SBLineEntryGetStartAddress(self.raw)) }
     }
 
     /// The end address for this line entry.
     pub fn end_address(&self) -> SBAddress {
-        SBAddress::wrap(unsafe { sys::SBLineEntryGetEndAddress(self.raw) })
+        unsafe { SBAddress::wrap(sys::SBLineEntryGetEndAddress(self.raw)) }
     }
 
     /// The file (`SBFileSpec`) for this line entry.
     pub fn filespec(&self) -> SBFileSpec {
-        SBFileSpec::wrap(unsafe { sys::SBLineEntryGetFileSpec(self.raw) })
+        unsafe { SBFileSpec::wrap(sys::SBLineEntryGetFileSpec(self.raw)) }
     }
 
     /// The 1-based line number for this line entry.
@@ -67,6 +81,12 @@ impl SBLineEntry {
     pub fn column(&self) -> u32 {
         unsafe { sys::SBLineEntryGetColumn(self.raw) }
     }
+
+    fn description(&self) -> String {
+        let stream = SBStream::new();
+        unsafe { sys::SBLineEntryGetDescription(self.raw, stream.raw) };
+        stream.data()
+    }
 }
 
 impl Clone for SBLineEntry {
@@ -79,9 +99,21 @@ impl Clone for SBLineEntry {
 
 impl fmt::Debug for SBLineEntry {
     fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
-        let stream = SBStream::new();
-        unsafe { sys::SBLineEntryGetDescription(self.raw, stream.raw) };
-        write!(fmt, "SBLineEntry {{ {} }}", stream.data())
+        write!(fmt, "SBLineEntry {{ {} }}", self.description())
+    }
+}
+
+impl fmt::Display for SBLineEntry {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "{}", self.description())
+    }
+}
+
+impl TryFrom<sys::SBLineEntryRef> for SBLineEntry {
+    type Error = InvalidRefError;
+
+    fn try_from(raw: sys::SBLineEntryRef) -> Result<SBLineEntry, InvalidRefError> {
+        SBLineEntry::maybe_wrap(raw).ok_or(InvalidRefError)
     }
 }
 
@@ -95,30 +127,35 @@ unsafe impl Send for SBLineEntry {}
 unsafe impl Sync for SBLineEntry {}
 
 #[cfg(feature = "graphql")]
-graphql_object!(SBLineEntry: super::debugger::SBDebugger | &self | {
-    field is_valid() -> bool {
+#[graphql_object(context = super::debugger::SBDebugger)]
+impl SBLineEntry {
+    #[graphql(name = "isValid")]
+    fn graphql_is_valid(&self) -> bool {
         self.is_valid()
     }
 
-    field start_address() -> SBAddress {
+    #[graphql(name = "startAddress")]
+    fn graphql_start_address(&self) -> SBAddress {
         self.start_address()
     }
 
-    field end_address() -> SBAddress {
+    #[graphql(name = "endAddress")]
+    fn graphql_end_address(&self) -> SBAddress {
         self.end_address()
     }
 
-    field filespec() -> SBFileSpec {
+    #[graphql(name = "filespec")]
+    fn graphql_filespec(&self) -> SBFileSpec {
         self.filespec()
     }
 
-    // TODO(bm) This should be u32
-    field line() -> i32 {
-        self.line() as i32
+    #[graphql(name = "line")]
+    fn graphql_line(&self) -> U64 {
+        self.line().into()
     }
 
-    // TODO(bm) This should be u32
-    field column() -> i32 {
-        self.column() as i32
+    #[graphql(name = "column")]
+    fn graphql_column(&self) -> U64 {
+        self.column().into()
     }
-});
+}
@@ -5,9 +5,14 @@
 // except according to those terms.
 
 use super::broadcaster::SBBroadcaster;
+use super::cancel::{CancelError, CancelToken};
 use super::debugger::SBDebugger;
+use super::error::InvalidRefError;
 use super::event::SBEvent;
+use super::trace::traced;
+use std::convert::TryFrom;
 use std::ffi::CString;
+use std::time::Instant;
 use sys;
 
 /// Listen for debugger events.
@@ -20,18 +25,28 @@ pub struct SBListener {
 impl SBListener {
     /// Construct a new `SBListener`.
     pub fn new() -> SBListener {
-        SBListener::wrap(unsafe { sys::CreateSBListener() })
+        unsafe { SBListener::wrap(sys::CreateSBListener()) }
     }
 
     /// Construct a new `SBListener`.
-    pub fn wrap(raw: sys::SBListenerRef) -> SBListener {
+    ///
+    /// # Safety
+    ///
+    /// `raw` must be a valid reference, which is disposed of when the
+    /// `SBListener` is dropped. Use [`maybe_wrap`] when it may be invalid.
+    ///
+    /// [`maybe_wrap`]: #method.maybe_wrap
+    pub unsafe fn wrap(raw: sys::SBListenerRef) -> SBListener {
         SBListener { raw }
     }
 
     /// Construct a new `Some(SBListener)` or `None`.
+    ///
+    /// `raw` is disposed of if it is invalid.
     pub fn maybe_wrap(raw: sys::SBListenerRef) -> Option<SBListener> {
-        if unsafe { sys::SBListenerIsValid(raw) != 0 } {
-            Some(SBListener { raw })
+        let wrapped = SBListener { raw };
+        if wrapped.is_valid() {
+            Some(wrapped)
         } else {
             None
         }
@@ -90,7 +105,44 @@ impl SBListener {
 
     #[allow(missing_docs)]
     pub fn wait_for_event(&self, num_seconds: u32, event: &mut SBEvent) -> bool {
-        unsafe { sys::SBListenerWaitForEvent(self.raw, num_seconds, event.raw) != 0 }
+        traced("SBListener::wait_for_event", || unsafe {
+            sys::SBListenerWaitForEvent(self.raw, num_seconds, event.raw) != 0
+        })
+    }
+
+    /// Wait for an event until one arrives or `token` is cancelled.
+    ///
+    /// The token is checked at least once a second.
+    pub fn wait_for_event_cancellable(
+        &self,
+        event: &mut SBEvent,
+        token: &CancelToken,
+    ) -> Result<(), CancelError> {
+        self.wait_for_event_until(None, event, || token.check())
+            .map(|_| ())
+    }
+
+    /// Wait for an event until `deadline`, or for as long as it takes
+    /// if there is no deadline, returning whether one arrived.
+    ///
+    /// LLDB only waits in whole seconds, so this waits a second at a
+    /// time. `check` is called before each wait, so that an error from
+    /// it abandons the wait.
+    pub(crate) fn wait_for_event_until<E, F: FnMut() -> Result<(), E>>(
+        &self,
+        deadline: Option<Instant>,
+        event: &mut SBEvent,
+        mut check: F,
+    ) -> Result<bool, E> {
+        loop {
+            check()?;
+            if deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+                return Ok(false);
+            }
+            if self.wait_for_event(1, event) {
+                return Ok(true);
+            }
+        }
     }
 
     #[allow(missing_docs)]
@@ -215,6 +267,14 @@ impl Default for SBListener {
     }
 }
 
+impl TryFrom<sys::SBListenerRef> for SBListener {
+    type Error = InvalidRefError;
+
+    fn try_from(raw: sys::SBListenerRef) -> Result<SBListener, InvalidRefError> {
+        SBListener::maybe_wrap(raw).ok_or(InvalidRefError)
+    }
+}
+
 impl Drop for SBListener {
     fn drop(&mut self) {
         unsafe { sys::DisposeSBListener(self.raw) };
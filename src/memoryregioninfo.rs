@@ -0,0 +1,113 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use super::addressrange::AddressRange;
+use super::lldb_addr_t;
+use super::strings;
+use std::fmt;
+use sys;
+
+/// A region of the address space of a process, with its protection.
+pub struct SBMemoryRegionInfo {
+    /// The underlying raw `SBMemoryRegionInfoRef`.
+    pub raw: sys::SBMemoryRegionInfoRef,
+}
+
+impl SBMemoryRegionInfo {
+    /// Construct a new, empty `SBMemoryRegionInfo`.
+    pub fn new() -> SBMemoryRegionInfo {
+        unsafe { SBMemoryRegionInfo::wrap(sys::CreateSBMemoryRegionInfo()) }
+    }
+
+    /// Construct a new `SBMemoryRegionInfo`.
+    ///
+    /// # Safety
+    ///
+    /// `raw` must be a valid reference, which is disposed of when the
+    /// `SBMemoryRegionInfo` is dropped.
+    pub unsafe fn wrap(raw: sys::SBMemoryRegionInfoRef) -> SBMemoryRegionInfo {
+        SBMemoryRegionInfo { raw }
+    }
+
+    /// The first address of the region.
+    pub fn region_base(&self) -> lldb_addr_t {
+        unsafe { sys::SBMemoryRegionInfoGetRegionBase(self.raw) }
+    }
+
+    /// The address just past the end of the region.
+    pub fn region_end(&self) -> lldb_addr_t {
+        unsafe { sys::SBMemoryRegionInfoGetRegionEnd(self.raw) }
+    }
+
+    /// The addresses covered by the region.
+    pub fn range(&self) -> AddressRange {
+        AddressRange::from_bounds(self.region_base(), self.region_end())
+    }
+
+    #[allow(missing_docs)]
+    pub fn is_readable(&self) -> bool {
+        unsafe { sys::SBMemoryRegionInfoIsReadable(self.raw) != 0 }
+    }
+
+    #[allow(missing_docs)]
+    pub fn is_writable(&self) -> bool {
+        unsafe { sys::SBMemoryRegionInfoIsWritable(self.raw) != 0 }
+    }
+
+    #[allow(missing_docs)]
+    pub fn is_executable(&self) -> bool {
+        unsafe { sys::SBMemoryRegionInfoIsExecutable(self.raw) != 0 }
+    }
+
+    /// Is the region mapped into the process? Addresses which aren't
+    /// in any mapping are reported as an unmapped region reaching up
+    /// to the next mapping.
+    pub fn is_mapped(&self) -> bool {
+        unsafe { sys::SBMemoryRegionInfoIsMapped(self.raw) != 0 }
+    }
+
+    /// The name of the region, such as the path of a mapped file, if
+    /// it has one.
+    pub fn name(&self) -> Option<String> {
+        unsafe { strings::to_string(sys::SBMemoryRegionInfoGetName(self.raw)) }
+    }
+}
+
+impl Clone for SBMemoryRegionInfo {
+    fn clone(&self) -> SBMemoryRegionInfo {
+        SBMemoryRegionInfo {
+            raw: unsafe { sys::CloneSBMemoryRegionInfo(self.raw) },
+        }
+    }
+}
+
+impl Default for SBMemoryRegionInfo {
+    fn default() -> SBMemoryRegionInfo {
+        SBMemoryRegionInfo::new()
+    }
+}
+
+impl fmt::Debug for SBMemoryRegionInfo {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.debug_struct("SBMemoryRegionInfo")
+            .field("range", &format_args!("{}", self.range()))
+            .field("readable", &self.is_readable())
+            .field("writable", &self.is_writable())
+            .field("executable", &self.is_executable())
+            .field("mapped", &self.is_mapped())
+            .field("name", &self.name())
+            .finish()
+    }
+}
+
+impl Drop for SBMemoryRegionInfo {
+    fn drop(&mut self) {
+        unsafe { sys::DisposeSBMemoryRegionInfo(self.raw) };
+    }
+}
+
+unsafe impl Send for SBMemoryRegionInfo {}
+unsafe impl Sync for SBMemoryRegionInfo {}
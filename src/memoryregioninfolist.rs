@@ -0,0 +1,131 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use super::memoryregioninfo::SBMemoryRegionInfo;
+use std::fmt;
+use sys;
+
+/// A list of [memory regions].
+///
+/// [memory regions]: struct.SBMemoryRegionInfo.html
+pub struct SBMemoryRegionInfoList {
+    /// The underlying raw `SBMemoryRegionInfoListRef`.
+    pub raw: sys::SBMemoryRegionInfoListRef,
+}
+
+impl SBMemoryRegionInfoList {
+    /// Construct a new, empty `SBMemoryRegionInfoList`.
+    pub fn new() -> SBMemoryRegionInfoList {
+        unsafe { SBMemoryRegionInfoList::wrap(sys::CreateSBMemoryRegionInfoList()) }
+    }
+
+    /// Construct a new `SBMemoryRegionInfoList`.
+    ///
+    /// # Safety
+    ///
+    /// `raw` must be a valid reference, which is disposed of when the
+    /// `SBMemoryRegionInfoList` is dropped.
+    pub unsafe fn wrap(raw: sys::SBMemoryRegionInfoListRef) -> SBMemoryRegionInfoList {
+        SBMemoryRegionInfoList { raw }
+    }
+
+    #[allow(missing_docs)]
+    pub fn append(&self, region: &SBMemoryRegionInfo) {
+        unsafe { sys::SBMemoryRegionInfoListAppend(self.raw, region.raw) };
+    }
+
+    /// The number of regions in this list.
+    pub fn len(&self) -> usize {
+        unsafe { sys::SBMemoryRegionInfoListGetSize(self.raw) as usize }
+    }
+
+    /// Is this list empty?
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Get the region at the given index, if there is one.
+    pub fn get(&self, idx: usize) -> Option<SBMemoryRegionInfo> {
+        let region = SBMemoryRegionInfo::new();
+        let found = idx < self.len()
+            && unsafe {
+                sys::SBMemoryRegionInfoListGetMemoryRegionAtIndex(self.raw, idx as u32, region.raw)
+            } != 0;
+        if found {
+            Some(region)
+        } else {
+            None
+        }
+    }
+
+    /// Clear this list.
+    pub fn clear(&self) {
+        unsafe { sys::SBMemoryRegionInfoListClear(self.raw) };
+    }
+
+    /// Iterate over the regions in this list.
+    pub fn iter(&self) -> SBMemoryRegionInfoListIter<'_> {
+        SBMemoryRegionInfoListIter {
+            region_list: self,
+            idx: 0,
+        }
+    }
+}
+
+impl Clone for SBMemoryRegionInfoList {
+    fn clone(&self) -> SBMemoryRegionInfoList {
+        SBMemoryRegionInfoList {
+            raw: unsafe { sys::CloneSBMemoryRegionInfoList(self.raw) },
+        }
+    }
+}
+
+impl fmt::Debug for SBMemoryRegionInfoList {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl Default for SBMemoryRegionInfoList {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for SBMemoryRegionInfoList {
+    fn drop(&mut self) {
+        unsafe { sys::DisposeSBMemoryRegionInfoList(self.raw) };
+    }
+}
+
+unsafe impl Send for SBMemoryRegionInfoList {}
+
+/// An iterator over the [memory regions] in an
+/// [`SBMemoryRegionInfoList`].
+///
+/// [memory regions]: struct.SBMemoryRegionInfo.html
+/// [`SBMemoryRegionInfoList`]: struct.SBMemoryRegionInfoList.html
+pub struct SBMemoryRegionInfoListIter<'d> {
+    region_list: &'d SBMemoryRegionInfoList,
+    idx: usize,
+}
+
+impl<'d> Iterator for SBMemoryRegionInfoListIter<'d> {
+    type Item = SBMemoryRegionInfo;
+
+    fn next(&mut self) -> Option<SBMemoryRegionInfo> {
+        let region = self.region_list.get(self.idx)?;
+        self.idx += 1;
+        Some(region)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let sz = self.region_list.len();
+        (sz - self.idx, Some(sz))
+    }
+}
+
+impl<'d> ExactSizeIterator for SBMemoryRegionInfoListIter<'d> {}
@@ -0,0 +1,319 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use super::cancel::CancelToken;
+use super::error::SBError;
+use super::lldb_addr_t;
+use super::memoryregioninfo::SBMemoryRegionInfo;
+use super::process::SBProcess;
+use std::collections::VecDeque;
+use std::fmt;
+
+/// How much memory is read and searched at a time.
+const SCAN_CHUNK_SIZE: usize = 1 << 20;
+
+type RegionFilter<'a> = Box<dyn FnMut(&SBMemoryRegionInfo) -> bool + 'a>;
+type ProgressCallback<'a> = Box<dyn FnMut(&ScanProgress) + 'a>;
+
+/// A search of the memory of a process for a byte pattern, created by
+/// [`SBProcess::scan_for_pattern`].
+///
+/// This is an iterator over the addresses of the matches, in order.
+/// Memory is read a chunk at a time as the iterator is advanced, so
+/// scanning a large address space doesn't hold it all in memory, and
+/// stopping early doesn't read the rest. Memory which can't be read is
+/// skipped.
+///
+/// ```no_run
+/// # use lldb::{parse_signature, SBError, SBProcess};
+/// # fn scan(process: &SBProcess) -> Result<(), SBError> {
+/// let (pattern, mask) = parse_signature("48 8b 05 ?? ?? ?? ?? 48 85 c0").unwrap();
+/// let matches: Vec<u64> = process
+///     .scan_for_pattern(&pattern, Some(&mask), |region| region.is_executable())?
+///     .on_progress(|progress| eprint!("\r{:.0}%", progress.fraction() * 100.0))
+///     .collect();
+/// # Ok(())
+/// # }
+/// ```
+///
+/// [`SBProcess::scan_for_pattern`]: struct.SBProcess.html#method.scan_for_pattern
+pub struct MemoryScan<'a> {
+    process: &'a SBProcess,
+    pattern: Vec<u8>,
+    mask: Vec<u8>,
+    filter: Option<RegionFilter<'a>>,
+    progress: Option<ProgressCallback<'a>>,
+    token: Option<CancelToken>,
+    regions: Vec<(lldb_addr_t, lldb_addr_t)>,
+    region: usize,
+    position: lldb_addr_t,
+    scanned_bytes: u64,
+    total_bytes: u64,
+    matches: VecDeque<lldb_addr_t>,
+    buffer: Vec<u8>,
+    cancelled: bool,
+}
+
+/// How far a [`MemoryScan`] has got.
+///
+/// [`MemoryScan`]: struct.MemoryScan.html
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ScanProgress {
+    /// The bytes searched so far.
+    pub scanned_bytes: u64,
+    /// The bytes in all of the regions being searched.
+    pub total_bytes: u64,
+    /// The address that the search has reached.
+    pub address: lldb_addr_t,
+}
+
+impl ScanProgress {
+    /// The fraction of the memory which has been searched, from 0 to
+    /// 1.
+    pub fn fraction(&self) -> f64 {
+        if self.total_bytes == 0 {
+            1.0
+        } else {
+            self.scanned_bytes as f64 / self.total_bytes as f64
+        }
+    }
+}
+
+impl<'a> MemoryScan<'a> {
+    pub(crate) fn new<F>(
+        process: &'a SBProcess,
+        pattern: &[u8],
+        mask: Option<&[u8]>,
+        region_filter: F,
+    ) -> Result<MemoryScan<'a>, SBError>
+    where
+        F: FnMut(&SBMemoryRegionInfo) -> bool + 'a,
+    {
+        let mask = match mask {
+            Some(mask) if mask.len() != pattern.len() => {
+                return Err(SBError::from_message(&format!(
+                    "the mask is {} bytes long, but the pattern is {}",
+                    mask.len(),
+                    pattern.len()
+                )))
+            }
+            Some(mask) => mask.to_vec(),
+            None => vec![0xff; pattern.len()],
+        };
+        Ok(MemoryScan {
+            process,
+            pattern: pattern.to_vec(),
+            mask,
+            filter: Some(Box::new(region_filter)),
+            progress: None,
+            token: None,
+            regions: vec![],
+            region: 0,
+            position: 0,
+            scanned_bytes: 0,
+            total_bytes: 0,
+            matches: VecDeque::new(),
+            buffer: vec![],
+            cancelled: false,
+        })
+    }
+
+    /// Call `callback` after each chunk of memory is searched.
+    pub fn on_progress<F>(mut self, callback: F) -> Self
+    where
+        F: FnMut(&ScanProgress) + 'a,
+    {
+        self.progress = Some(Box::new(callback));
+        self
+    }
+
+    /// Stop the scan when `token` is cancelled. The token is checked
+    /// before each chunk of memory is read.
+    pub fn cancel_token(mut self, token: &CancelToken) -> Self {
+        self.token = Some(token.clone());
+        self
+    }
+
+    /// Did the scan stop because its [`CancelToken`] was cancelled?
+    ///
+    /// [`CancelToken`]: struct.CancelToken.html
+    pub fn was_cancelled(&self) -> bool {
+        self.cancelled
+    }
+
+    /// Find the regions to search, the first time the scan is
+    /// advanced.
+    fn start(&mut self) {
+        if let Some(mut filter) = self.filter.take() {
+            self.regions = self
+                .process
+                .memory_regions()
+                .iter()
+                .filter(|region| region.is_mapped() && region.is_readable())
+                .filter(|region| filter(region))
+                .map(|region| (region.region_base(), region.region_end()))
+                .filter(|&(base, end)| end > base)
+                .collect();
+            self.total_bytes = self.regions.iter().map(|&(base, end)| end - base).sum();
+            self.position = self.regions.first().map_or(0, |&(base, _)| base);
+        }
+    }
+
+    /// Search the next chunk of memory, returning false when there
+    /// is none left.
+    fn scan_chunk(&mut self) -> bool {
+        let (_, end) = match self.regions.get(self.region) {
+            Some(&region) => region,
+            None => return false,
+        };
+        if self
+            .token
+            .as_ref()
+            .is_some_and(|token| token.is_cancelled())
+        {
+            self.cancelled = true;
+            return false;
+        }
+        // Chunks overlap by one byte less than the pattern, so that
+        // matches across their boundaries are found, but only matches
+        // starting in this chunk are reported.
+        let chunk_end = end.min(self.position + SCAN_CHUNK_SIZE as lldb_addr_t);
+        let read_end = end.min(chunk_end + self.pattern.len() as lldb_addr_t - 1);
+        self.buffer.resize((read_end - self.position) as usize, 0);
+        let read = self
+            .process
+            .read_memory(self.position, &mut self.buffer)
+            .unwrap_or(0);
+        self.buffer.truncate(read);
+        let limit = (chunk_end - self.position) as usize;
+        let position = self.position;
+        self.matches.extend(
+            find_matches(&self.buffer, &self.pattern, &self.mask, limit)
+                .into_iter()
+                .map(|offset| position + offset as lldb_addr_t),
+        );
+
+        self.scanned_bytes += chunk_end - self.position;
+        if chunk_end < end {
+            self.position = chunk_end;
+        } else {
+            self.region += 1;
+            if let Some(&(base, _)) = self.regions.get(self.region) {
+                self.position = base;
+            }
+        }
+        if let Some(progress) = self.progress.as_mut() {
+            progress(&ScanProgress {
+                scanned_bytes: self.scanned_bytes,
+                total_bytes: self.total_bytes,
+                address: chunk_end,
+            });
+        }
+        true
+    }
+}
+
+impl<'a> Iterator for MemoryScan<'a> {
+    type Item = lldb_addr_t;
+
+    fn next(&mut self) -> Option<lldb_addr_t> {
+        if self.pattern.is_empty() {
+            return None;
+        }
+        self.start();
+        loop {
+            if let Some(address) = self.matches.pop_front() {
+                return Some(address);
+            }
+            if !self.scan_chunk() {
+                return None;
+            }
+        }
+    }
+}
+
+impl<'a> fmt::Debug for MemoryScan<'a> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.debug_struct("MemoryScan")
+            .field("pattern", &self.pattern)
+            .field("mask", &self.mask)
+            .field("scanned_bytes", &self.scanned_bytes)
+            .field("total_bytes", &self.total_bytes)
+            .finish()
+    }
+}
+
+/// Parse a signature written as hex bytes separated by spaces, with
+/// `?` or `??` for bytes which can be anything, such as
+/// `48 8b 05 ?? ?? ?? ??`, into a pattern and mask for
+/// [`SBProcess::scan_for_pattern`].
+///
+/// [`SBProcess::scan_for_pattern`]: struct.SBProcess.html#method.scan_for_pattern
+pub fn parse_signature(signature: &str) -> Option<(Vec<u8>, Vec<u8>)> {
+    let mut pattern = vec![];
+    let mut mask = vec![];
+    for byte in signature.split_whitespace() {
+        if byte == "?" || byte == "??" {
+            pattern.push(0);
+            mask.push(0);
+        } else if byte.len() == 2 {
+            pattern.push(u8::from_str_radix(byte, 16).ok()?);
+            mask.push(0xff);
+        } else {
+            return None;
+        }
+    }
+    Some((pattern, mask))
+}
+
+/// The offsets in `haystack` before `limit` at which `pattern`
+/// matches, comparing only the bits set in `mask`.
+fn find_matches(haystack: &[u8], pattern: &[u8], mask: &[u8], limit: usize) -> Vec<usize> {
+    if haystack.len() < pattern.len() {
+        return vec![];
+    }
+    let last = (haystack.len() - pattern.len() + 1).min(limit);
+    (0..last)
+        .filter(|&offset| {
+            haystack[offset..offset + pattern.len()]
+                .iter()
+                .zip(pattern)
+                .zip(mask)
+                .all(|((&byte, &expected), &mask)| byte & mask == expected & mask)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{find_matches, parse_signature};
+
+    #[test]
+    fn signatures() {
+        assert_eq!(
+            parse_signature("48 8B ?? ? c3"),
+            Some((vec![0x48, 0x8b, 0, 0, 0xc3], vec![0xff, 0xff, 0, 0, 0xff]))
+        );
+        assert_eq!(parse_signature("48 8"), None);
+        assert_eq!(parse_signature("zz"), None);
+    }
+
+    #[test]
+    fn matches() {
+        let haystack = b"abcXbcdabc";
+        assert_eq!(find_matches(haystack, b"abc", &[0xff; 3], 10), vec![0, 7]);
+        assert_eq!(find_matches(haystack, b"abc", &[0xff; 3], 7), vec![0]);
+        assert_eq!(
+            find_matches(haystack, b"a?c", &[0xff, 0, 0xff], 10),
+            vec![0, 7]
+        );
+        assert_eq!(
+            find_matches(haystack, b"?bc", &[0, 0xff, 0xff], 10),
+            vec![0, 3, 7]
+        );
+        assert!(find_matches(b"ab", b"abc", &[0xff; 3], 10).is_empty());
+    }
+}
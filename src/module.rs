@@ -4,13 +4,20 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
+use super::address::SBAddress;
+use super::error::InvalidRefError;
 use super::filespec::SBFileSpec;
 use super::section::SBSection;
 use super::stream::SBStream;
+use super::strings;
+use super::symbol::SBSymbol;
 use super::symbolcontextlist::SBSymbolContextList;
 use super::SymbolType;
+use std::convert::TryFrom;
 use std::ffi::CString;
 use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::ptr;
 use sys;
 
 /// An executable image and its associated object and symbol files.
@@ -21,14 +28,24 @@ pub struct SBModule {
 
 impl SBModule {
     /// Construct a new `SBModule`.
-    pub fn wrap(raw: sys::SBModuleRef) -> SBModule {
+    ///
+    /// # Safety
+    ///
+    /// `raw` must be a valid reference, which is disposed of when the
+    /// `SBModule` is dropped. Use [`maybe_wrap`] when it may be invalid.
+    ///
+    /// [`maybe_wrap`]: #method.maybe_wrap
+    pub unsafe fn wrap(raw: sys::SBModuleRef) -> SBModule {
         SBModule { raw }
     }
 
     /// Construct a new `Some(SBModule)` or `None`.
+    ///
+    /// `raw` is disposed of if it is invalid.
     pub fn maybe_wrap(raw: sys::SBModuleRef) -> Option<SBModule> {
-        if unsafe { sys::SBModuleIsValid(raw) != 0 } {
-            Some(SBModule { raw })
+        let wrapped = SBModule { raw };
+        if wrapped.is_valid() {
+            Some(wrapped)
         } else {
             None
         }
@@ -44,7 +61,34 @@ impl SBModule {
     /// This can differ from the path on the platform since we might
     /// be doing remote debugging.
     pub fn filespec(&self) -> SBFileSpec {
-        SBFileSpec::wrap(unsafe { sys::SBModuleGetFileSpec(self.raw) })
+        unsafe { SBFileSpec::wrap(sys::SBModuleGetFileSpec(self.raw)) }
+    }
+
+    /// The UUID of this module as a string, if it has one.
+    pub fn uuid_string(&self) -> Option<String> {
+        unsafe { strings::to_string(sys::SBModuleGetUUIDString(self.raw)) }
+    }
+
+    /// The target triple of this module, such as
+    /// `x86_64-pc-windows-msvc`.
+    pub fn triple(&self) -> Option<String> {
+        unsafe { strings::to_string(sys::SBModuleGetTriple(self.raw)) }
+    }
+
+    /// The version of this module, such as `[10, 0, 19041, 1]`, if it
+    /// records one.
+    ///
+    /// This comes from the version resource of PE files and the
+    /// module list of minidumps, and from `LC_ID_DYLIB` for Mach-O
+    /// libraries.
+    pub fn version(&self) -> Vec<u32> {
+        let count = unsafe { sys::SBModuleGetVersion(self.raw, ptr::null_mut(), 0) };
+        let mut versions = vec![0; count as usize];
+        let count = unsafe {
+            sys::SBModuleGetVersion(self.raw, versions.as_mut_ptr(), versions.len() as u32)
+        };
+        versions.truncate(count as usize);
+        versions
     }
 
     /// The file for the module as it is known on the remote system on
@@ -57,7 +101,34 @@ impl SBModule {
     /// `/tmp/lldb/platform-cache/remote.host.computer/usr/lib/liba.dylib`
     /// The file could also be cached in a local developer kit directory.
     pub fn platform_filespec(&self) -> SBFileSpec {
-        SBFileSpec::wrap(unsafe { sys::SBModuleGetPlatformFileSpec(self.raw) })
+        unsafe { SBFileSpec::wrap(sys::SBModuleGetPlatformFileSpec(self.raw)) }
+    }
+
+    /// The file which the debug information of the module was loaded
+    /// from, which is the module itself when it isn't separate.
+    ///
+    /// This loads the debug information if it hasn't been loaded yet,
+    /// which can include downloading it.
+    pub fn symbol_filespec(&self) -> Option<SBFileSpec> {
+        SBFileSpec::maybe_wrap(unsafe { sys::SBModuleGetSymbolFileSpec(self.raw) })
+    }
+
+    /// The address of the header of the object file, which is where
+    /// the module starts, if it has one.
+    pub fn object_file_header_address(&self) -> Option<SBAddress> {
+        SBAddress::maybe_wrap(unsafe { sys::SBModuleGetObjectFileHeaderAddress(self.raw) })
+    }
+
+    /// The number of compile units in the debug information of the
+    /// module.
+    pub fn num_compile_units(&self) -> u32 {
+        unsafe { sys::SBModuleGetNumCompileUnits(self.raw) }
+    }
+
+    /// Set where the module is found on the platform being debugged,
+    /// such as where it was copied to on a remote device.
+    pub fn set_platform_filespec(&self, platform_file: &SBFileSpec) -> bool {
+        unsafe { sys::SBModuleSetPlatformFileSpec(self.raw, platform_file.raw) != 0 }
     }
 
     #[allow(missing_docs)]
@@ -76,20 +147,45 @@ impl SBModule {
         }
     }
 
+    /// Get an iterator over the [symbols] in this module's symbol
+    /// table.
+    ///
+    /// [symbols]: struct.SBSymbol.html
+    pub fn symbols(&self) -> SBModuleSymbolIter {
+        SBModuleSymbolIter {
+            module: self,
+            idx: 0,
+        }
+    }
+
     #[allow(missing_docs)]
     pub fn find_functions(&self, name: &str, name_type_mask: u32) -> SBSymbolContextList {
         let name = CString::new(name).unwrap();
-        SBSymbolContextList::wrap(unsafe {
-            sys::SBModuleFindFunctions(self.raw, name.as_ptr(), name_type_mask)
-        })
+        unsafe {
+            SBSymbolContextList::wrap(sys::SBModuleFindFunctions(
+                self.raw,
+                name.as_ptr(),
+                name_type_mask,
+            ))
+        }
     }
 
     #[allow(missing_docs)]
     pub fn find_symbols(&self, name: &str, symbol_type: SymbolType) -> SBSymbolContextList {
         let name = CString::new(name).unwrap();
-        SBSymbolContextList::wrap(unsafe {
-            sys::SBModuleFindSymbols(self.raw, name.as_ptr(), symbol_type)
-        })
+        unsafe {
+            SBSymbolContextList::wrap(sys::SBModuleFindSymbols(
+                self.raw,
+                name.as_ptr(),
+                symbol_type,
+            ))
+        }
+    }
+
+    fn description(&self) -> String {
+        let stream = SBStream::new();
+        unsafe { sys::SBModuleGetDescription(self.raw, stream.raw) };
+        stream.data()
     }
 }
 
@@ -107,9 +203,9 @@ impl<'d> Iterator for SBModuleSectionIter<'d> {
 
     fn next(&mut self) -> Option<SBSection> {
         if self.idx < unsafe { sys::SBModuleGetNumSections(self.module.raw) as usize } {
-            let r = Some(SBSection::wrap(unsafe {
-                sys::SBModuleGetSectionAtIndex(self.module.raw, self.idx)
-            }));
+            let r = Some(unsafe {
+                SBSection::wrap(sys::SBModuleGetSectionAtIndex(self.module.raw, self.idx))
+            });
             self.idx += 1;
             r
         } else {
@@ -125,6 +221,38 @@ impl<'d> Iterator for SBModuleSectionIter<'d> {
 
 impl<'d> ExactSizeIterator for SBModuleSectionIter<'d> {}
 
+/// Iterate over the [symbols] in a [module].
+///
+/// [symbols]: struct.SBSymbol.html
+/// [module]: struct.SBModule.html
+pub struct SBModuleSymbolIter<'d> {
+    module: &'d SBModule,
+    idx: usize,
+}
+
+impl<'d> Iterator for SBModuleSymbolIter<'d> {
+    type Item = SBSymbol;
+
+    fn next(&mut self) -> Option<SBSymbol> {
+        if self.idx < unsafe { sys::SBModuleGetNumSymbols(self.module.raw) as usize } {
+            let r = Some(unsafe {
+                SBSymbol::wrap(sys::SBModuleGetSymbolAtIndex(self.module.raw, self.idx))
+            });
+            self.idx += 1;
+            r
+        } else {
+            None
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let sz = unsafe { sys::SBModuleGetNumSymbols(self.module.raw) } as usize;
+        (sz - self.idx, Some(sz))
+    }
+}
+
+impl<'d> ExactSizeIterator for SBModuleSymbolIter<'d> {}
+
 impl Clone for SBModule {
     fn clone(&self) -> SBModule {
         SBModule {
@@ -135,9 +263,37 @@ impl Clone for SBModule {
 
 impl fmt::Debug for SBModule {
     fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
-        let stream = SBStream::new();
-        unsafe { sys::SBModuleGetDescription(self.raw, stream.raw) };
-        write!(fmt, "SBModule {{ {} }}", stream.data())
+        write!(fmt, "SBModule {{ {} }}", self.description())
+    }
+}
+
+impl fmt::Display for SBModule {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "{}", self.description())
+    }
+}
+
+/// Modules are compared by their UUID and path.
+impl PartialEq for SBModule {
+    fn eq(&self, other: &SBModule) -> bool {
+        (self.uuid_string(), self.filespec().path())
+            == (other.uuid_string(), other.filespec().path())
+    }
+}
+
+impl Eq for SBModule {}
+
+impl Hash for SBModule {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        (self.uuid_string(), self.filespec().path()).hash(state);
+    }
+}
+
+impl TryFrom<sys::SBModuleRef> for SBModule {
+    type Error = InvalidRefError;
+
+    fn try_from(raw: sys::SBModuleRef) -> Result<SBModule, InvalidRefError> {
+        SBModule::maybe_wrap(raw).ok_or(InvalidRefError)
     }
 }
 
@@ -151,20 +307,30 @@ unsafe impl Send for SBModule {}
 unsafe impl Sync for SBModule {}
 
 #[cfg(feature = "graphql")]
-graphql_object!(SBModule: super::debugger::SBDebugger | &self | {
-    field is_valid() -> bool {
+#[graphql_object(context = super::debugger::SBDebugger)]
+impl SBModule {
+    #[graphql(name = "isValid")]
+    fn graphql_is_valid(&self) -> bool {
         self.is_valid()
     }
 
-    field filespec() -> SBFileSpec {
+    #[graphql(name = "uuidString")]
+    fn graphql_uuid_string(&self) -> Option<String> {
+        self.uuid_string()
+    }
+
+    #[graphql(name = "filespec")]
+    fn graphql_filespec(&self) -> SBFileSpec {
         self.filespec()
     }
 
-    field platform_filespec() -> SBFileSpec {
+    #[graphql(name = "platformFilespec")]
+    fn graphql_platform_filespec(&self) -> SBFileSpec {
         self.platform_filespec()
     }
 
-    field sections() -> Vec<SBSection> {
+    #[graphql(name = "sections")]
+    fn graphql_sections(&self) -> Vec<SBSection> {
         self.sections().collect()
     }
-});
+}
@@ -4,9 +4,14 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
+use super::error::InvalidRefError;
 use super::filespec::SBFileSpec;
 use super::stream::SBStream;
+use super::strings;
+use std::convert::TryFrom;
+use std::ffi::CString;
 use std::fmt;
+use std::slice;
 use sys;
 
 /// A description of an `SBModule`.
@@ -17,14 +22,24 @@ pub struct SBModuleSpec {
 
 impl SBModuleSpec {
     /// Construct a new `SBModuleSpec`.
-    pub fn wrap(raw: sys::SBModuleSpecRef) -> SBModuleSpec {
+    ///
+    /// # Safety
+    ///
+    /// `raw` must be a valid reference, which is disposed of when the
+    /// `SBModuleSpec` is dropped. Use [`maybe_wrap`] when it may be invalid.
+    ///
+    /// [`maybe_wrap`]: #method.maybe_wrap
+    pub unsafe fn wrap(raw: sys::SBModuleSpecRef) -> SBModuleSpec {
         SBModuleSpec { raw }
     }
 
     /// Construct a new `Some(SBModuleSpec)` or `None`.
+    ///
+    /// `raw` is disposed of if it is invalid.
     pub fn maybe_wrap(raw: sys::SBModuleSpecRef) -> Option<SBModuleSpec> {
-        if unsafe { sys::SBModuleSpecIsValid(raw) != 0 } {
-            Some(SBModuleSpec { raw })
+        let wrapped = SBModuleSpec { raw };
+        if wrapped.is_valid() {
+            Some(wrapped)
         } else {
             None
         }
@@ -40,7 +55,7 @@ impl SBModuleSpec {
     /// This can differ from the path on the platform since we might
     /// be doing remote debugging.
     pub fn filespec(&self) -> SBFileSpec {
-        SBFileSpec::wrap(unsafe { sys::SBModuleSpecGetFileSpec(self.raw) })
+        unsafe { SBFileSpec::wrap(sys::SBModuleSpecGetFileSpec(self.raw)) }
     }
 
     /// Set the file for the module on the host system that is running LLDB.
@@ -58,7 +73,7 @@ impl SBModuleSpec {
     /// `/tmp/lldb/platform-cache/remote.host.computer/usr/lib/liba.dylib`
     /// The file could also be cached in a local developer kit directory.
     pub fn platform_filespec(&self) -> SBFileSpec {
-        SBFileSpec::wrap(unsafe { sys::SBModuleSpecGetPlatformFileSpec(self.raw) })
+        unsafe { SBFileSpec::wrap(sys::SBModuleSpecGetPlatformFileSpec(self.raw)) }
     }
 
     /// Set the file for the module as it is known on the remote system which
@@ -78,33 +93,43 @@ impl SBModuleSpec {
     }
 
     #[allow(missing_docs)]
-    pub fn object_name(&self) -> &str {
-        unimplemented!();
+    pub fn object_name(&self) -> Option<String> {
+        unsafe { strings::to_string(sys::SBModuleSpecGetObjectName(self.raw)) }
     }
 
     #[allow(missing_docs)]
-    pub fn set_object_name(&self, _object_name: &str) {
-        unimplemented!();
+    pub fn set_object_name(&self, object_name: &str) {
+        let object_name = CString::new(object_name).unwrap();
+        unsafe { sys::SBModuleSpecSetObjectName(self.raw, object_name.as_ptr()) };
     }
 
     #[allow(missing_docs)]
-    pub fn triple(&self) -> &str {
-        unimplemented!();
+    pub fn triple(&self) -> Option<String> {
+        unsafe { strings::to_string(sys::SBModuleSpecGetTriple(self.raw)) }
     }
 
     #[allow(missing_docs)]
-    pub fn set_triple(&self, _object_name: &str) {
-        unimplemented!();
+    pub fn set_triple(&self, triple: &str) {
+        let triple = CString::new(triple).unwrap();
+        unsafe { sys::SBModuleSpecSetTriple(self.raw, triple.as_ptr()) };
     }
 
     #[allow(missing_docs)]
-    pub fn uuid_bytes(&self) -> &str {
-        unimplemented!();
+    pub fn uuid_bytes(&self) -> Vec<u8> {
+        unsafe {
+            let bytes = sys::SBModuleSpecGetUUIDBytes(self.raw);
+            let len = sys::SBModuleSpecGetUUIDLength(self.raw) as usize;
+            if bytes.is_null() {
+                vec![]
+            } else {
+                slice::from_raw_parts(bytes, len).to_vec()
+            }
+        }
     }
 
     #[allow(missing_docs)]
-    pub fn set_uuid_bytes(&self, _object_name: &str) {
-        unimplemented!();
+    pub fn set_uuid_bytes(&self, uuid: &[u8]) -> bool {
+        unsafe { sys::SBModuleSpecSetUUIDBytes(self.raw, uuid.as_ptr(), uuid.len()) != 0 }
     }
 }
 
@@ -124,6 +149,14 @@ impl fmt::Debug for SBModuleSpec {
     }
 }
 
+impl TryFrom<sys::SBModuleSpecRef> for SBModuleSpec {
+    type Error = InvalidRefError;
+
+    fn try_from(raw: sys::SBModuleSpecRef) -> Result<SBModuleSpec, InvalidRefError> {
+        SBModuleSpec::maybe_wrap(raw).ok_or(InvalidRefError)
+    }
+}
+
 impl Drop for SBModuleSpec {
     fn drop(&mut self) {
         unsafe { sys::DisposeSBModuleSpec(self.raw) };
@@ -131,4 +164,3 @@ impl Drop for SBModuleSpec {
 }
 
 unsafe impl Send for SBModuleSpec {}
-unsafe impl Sync for SBModuleSpec {}
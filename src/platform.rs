@@ -4,10 +4,20 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
-use super::error::SBError;
+use super::error::{InvalidRefError, SBError};
+use super::filespec::SBFileSpec;
+#[cfg(feature = "graphql")]
+use super::graphql::U64;
 use super::launchinfo::SBLaunchInfo;
 use super::lldb_pid_t;
-use std::ffi::CStr;
+use super::processinfo::ProcessInfo;
+use super::remoteurl::RemoteUrl;
+use super::strings;
+use std::convert::TryFrom;
+use std::ffi::CString;
+use std::fmt;
+#[cfg(target_os = "linux")]
+use std::fs;
 use sys;
 
 /// A platform that can represent the current host or a
@@ -33,7 +43,6 @@ use sys;
 /// a suitable platform will be found automatically.
 ///
 /// [`SBTarget`]: struct.SBTarget.html
-#[derive(Debug)]
 pub struct SBPlatform {
     /// The underlying raw `SBPlatformRef`.
     pub raw: sys::SBPlatformRef,
@@ -41,89 +50,76 @@ pub struct SBPlatform {
 
 impl SBPlatform {
     /// Construct a new `SBPlatform`.
-    pub fn wrap(raw: sys::SBPlatformRef) -> SBPlatform {
+    ///
+    /// # Safety
+    ///
+    /// `raw` must be a valid reference, which is disposed of when the
+    /// `SBPlatform` is dropped. Use [`maybe_wrap`] when it may be invalid.
+    ///
+    /// [`maybe_wrap`]: #method.maybe_wrap
+    pub unsafe fn wrap(raw: sys::SBPlatformRef) -> SBPlatform {
         SBPlatform { raw }
     }
 
     /// Construct a new `Some(SBPlatform)` or `None`.
+    ///
+    /// `raw` is disposed of if it is invalid.
     pub fn maybe_wrap(raw: sys::SBPlatformRef) -> Option<SBPlatform> {
-        if unsafe { sys::SBPlatformIsValid(raw) != 0 } {
-            Some(SBPlatform { raw })
+        let wrapped = SBPlatform { raw };
+        if wrapped.is_valid() {
+            Some(wrapped)
         } else {
             None
         }
     }
 
+    /// Create the platform plugin named `name`, such as
+    /// `remote-linux` or `remote-android`, if there is one.
+    pub fn new(name: &str) -> Option<SBPlatform> {
+        let name = CString::new(name).unwrap();
+        SBPlatform::maybe_wrap(unsafe { sys::CreateSBPlatform2(name.as_ptr()) })
+    }
+
     /// Check whether or not this is a valid `SBPlatform` value.
     pub fn is_valid(&self) -> bool {
         unsafe { sys::SBPlatformIsValid(self.raw) != 0 }
     }
 
     /// The working directory for this platform.
-    pub fn working_directory(&self) -> &str {
-        unsafe {
-            match CStr::from_ptr(sys::SBPlatformGetWorkingDirectory(self.raw)).to_str() {
-                Ok(s) => s,
-                _ => panic!("Invalid string?"),
-            }
-        }
+    pub fn working_directory(&self) -> Option<String> {
+        unsafe { strings::to_string(sys::SBPlatformGetWorkingDirectory(self.raw)) }
     }
 
     /// The name of the platform.
     ///
     /// When debugging on the host platform, this would be `"host"`.
-    pub fn name(&self) -> &str {
-        unsafe {
-            match CStr::from_ptr(sys::SBPlatformGetName(self.raw)).to_str() {
-                Ok(s) => s,
-                _ => panic!("Invalid string?"),
-            }
-        }
+    pub fn name(&self) -> String {
+        unsafe { strings::to_string_lossy(sys::SBPlatformGetName(self.raw)) }
     }
 
     /// The triple used to describe this platform.
     ///
     /// An example value might be `"x86_64-apple-macosx"`.
-    pub fn triple(&self) -> &str {
-        unsafe {
-            match CStr::from_ptr(sys::SBPlatformGetTriple(self.raw)).to_str() {
-                Ok(s) => s,
-                _ => panic!("Invalid string?"),
-            }
-        }
+    pub fn triple(&self) -> Option<String> {
+        unsafe { strings::to_string(sys::SBPlatformGetTriple(self.raw)) }
     }
 
     /// The hostname for this platform.
-    pub fn hostname(&self) -> &str {
-        unsafe {
-            match CStr::from_ptr(sys::SBPlatformGetHostname(self.raw)).to_str() {
-                Ok(s) => s,
-                _ => panic!("Invalid string?"),
-            }
-        }
+    pub fn hostname(&self) -> Option<String> {
+        unsafe { strings::to_string(sys::SBPlatformGetHostname(self.raw)) }
     }
 
     /// The build ID for the platforms' OS version.
-    pub fn os_build(&self) -> &str {
-        unsafe {
-            match CStr::from_ptr(sys::SBPlatformGetOSBuild(self.raw)).to_str() {
-                Ok(s) => s,
-                _ => panic!("Invalid string?"),
-            }
-        }
+    pub fn os_build(&self) -> Option<String> {
+        unsafe { strings::to_string(sys::SBPlatformGetOSBuild(self.raw)) }
     }
 
     /// The long form description of the platform's OS version.
     ///
     /// On Mac OS X, this might look like `"Darwin Kernel Version 15.5.0:
     /// Tue Apr 19 18:36:36 PDT 2016; root:xnu-3248.50.21~8/RELEASE_X86_64"`.
-    pub fn os_description(&self) -> &str {
-        unsafe {
-            match CStr::from_ptr(sys::SBPlatformGetOSDescription(self.raw)).to_str() {
-                Ok(s) => s,
-                _ => panic!("Invalid string?"),
-            }
-        }
+    pub fn os_description(&self) -> Option<String> {
+        unsafe { strings::to_string(sys::SBPlatformGetOSDescription(self.raw)) }
     }
 
     /// The major component of the platform's OS version.
@@ -147,9 +143,64 @@ impl SBPlatform {
         unsafe { sys::SBPlatformGetOSUpdateVersion(self.raw) }
     }
 
+    /// Connect to an `lldb-server platform` running on a remote
+    /// system at `url`.
+    pub fn connect_remote(&self, url: &RemoteUrl) -> Result<(), SBError> {
+        let url = CString::new(url.as_str()).unwrap();
+        let error = unsafe {
+            let options = sys::CreateSBPlatformConnectOptions(url.as_ptr());
+            let error = SBError::wrap(sys::SBPlatformConnectRemote(self.raw, options));
+            sys::DisposeSBPlatformConnectOptions(options);
+            error
+        };
+        if error.is_success() {
+            Ok(())
+        } else {
+            Err(error)
+        }
+    }
+
+    /// Disconnect from the remote system.
+    pub fn disconnect_remote(&self) {
+        unsafe { sys::SBPlatformDisconnectRemote(self.raw) }
+    }
+
+    /// Is the platform connected to a remote system?
+    pub fn is_connected(&self) -> bool {
+        unsafe { sys::SBPlatformIsConnected(self.raw) != 0 }
+    }
+
+    /// Copy the local file `src` to `dst` on the platform, keeping its
+    /// permissions.
+    pub fn put(&self, src: &SBFileSpec, dst: &SBFileSpec) -> Result<(), SBError> {
+        let error = unsafe { SBError::wrap(sys::SBPlatformPut(self.raw, src.raw, dst.raw)) };
+        if error.is_success() {
+            Ok(())
+        } else {
+            Err(error)
+        }
+    }
+
+    /// Install the local file or bundle `src` at `dst` on the
+    /// platform.
+    ///
+    /// Unlike [`put`], this uses the platform's own mechanism for
+    /// installing programs, such as installing an app bundle on an iOS
+    /// device.
+    ///
+    /// [`put`]: #method.put
+    pub fn install(&self, src: &SBFileSpec, dst: &SBFileSpec) -> Result<(), SBError> {
+        let error = unsafe { SBError::wrap(sys::SBPlatformInstall(self.raw, src.raw, dst.raw)) };
+        if error.is_success() {
+            Ok(())
+        } else {
+            Err(error)
+        }
+    }
+
     /// Launch a process. This is not for debugging that process.
     pub fn launch(&self, launch_info: &SBLaunchInfo) -> Result<(), SBError> {
-        let error = SBError::wrap(unsafe { sys::SBPlatformLaunch(self.raw, launch_info.raw) });
+        let error = unsafe { SBError::wrap(sys::SBPlatformLaunch(self.raw, launch_info.raw)) };
         if error.is_success() {
             Ok(())
         } else {
@@ -159,13 +210,77 @@ impl SBPlatform {
 
     /// Kill a process.
     pub fn kill(&self, pid: lldb_pid_t) -> Result<(), SBError> {
-        let error = SBError::wrap(unsafe { sys::SBPlatformKill(self.raw, pid) });
+        let error = unsafe { SBError::wrap(sys::SBPlatformKill(self.raw, pid)) };
         if error.is_success() {
             Ok(())
         } else {
             Err(error)
         }
     }
+
+    /// Information about the process `pid` on the platform, or `None`
+    /// if there is no such process.
+    ///
+    /// This looks up just the one process, so it is cheap enough to
+    /// call repeatedly to refresh the details of a process being
+    /// watched.
+    ///
+    /// For the host platform on Linux, this reads `/proc`. Otherwise,
+    /// including on remote platforms, it runs `ps` on the platform, so
+    /// the arguments are split on whitespace, as `ps` doesn't show
+    /// where arguments containing spaces begin and end, and the
+    /// executable isn't known.
+    pub fn process_info(&self, pid: lldb_pid_t) -> Option<ProcessInfo> {
+        #[cfg(target_os = "linux")]
+        {
+            if self.name() == "host" {
+                return host_process_info(pid);
+            }
+        }
+        let command = format!(
+            "ps -p {} -o pid= -o ppid= -o ruid= -o rgid= -o uid= -o gid= -o args=",
+            pid
+        );
+        let (status, output) = self.run_shell_command(&command).ok()?;
+        if status != 0 {
+            return None;
+        }
+        output
+            .lines()
+            .filter_map(ProcessInfo::from_ps_line)
+            .find(|info| info.pid == pid)
+    }
+
+    /// Run `command` in a shell on the platform, returning its exit
+    /// status and output.
+    fn run_shell_command(&self, command: &str) -> Result<(i32, String), SBError> {
+        let command = CString::new(command).unwrap();
+        unsafe {
+            let shell_command = sys::CreateSBPlatformShellCommand(command.as_ptr());
+            let error = SBError::wrap(sys::SBPlatformRun(self.raw, shell_command));
+            let result = (
+                sys::SBPlatformShellCommandGetStatus(shell_command),
+                strings::to_string_lossy(sys::SBPlatformShellCommandGetOutput(shell_command)),
+            );
+            sys::DisposeSBPlatformShellCommand(shell_command);
+            if error.is_success() {
+                Ok(result)
+            } else {
+                Err(error)
+            }
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn host_process_info(pid: lldb_pid_t) -> Option<ProcessInfo> {
+    let dir = format!("/proc/{}", pid);
+    let status = fs::read_to_string(format!("{}/status", dir)).ok()?;
+    let cmdline = fs::read(format!("{}/cmdline", dir)).unwrap_or_default();
+    let executable = fs::read_link(format!("{}/exe", dir))
+        .ok()
+        .map(|path| path.to_string_lossy().into_owned());
+    ProcessInfo::from_proc(pid, &status, &cmdline, executable)
 }
 
 impl Clone for SBPlatform {
@@ -176,6 +291,24 @@ impl Clone for SBPlatform {
     }
 }
 
+impl fmt::Debug for SBPlatform {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.debug_struct("SBPlatform")
+            .field("name", &self.name())
+            .field("triple", &self.triple())
+            .field("hostname", &self.hostname())
+            .finish()
+    }
+}
+
+impl TryFrom<sys::SBPlatformRef> for SBPlatform {
+    type Error = InvalidRefError;
+
+    fn try_from(raw: sys::SBPlatformRef) -> Result<SBPlatform, InvalidRefError> {
+        SBPlatform::maybe_wrap(raw).ok_or(InvalidRefError)
+    }
+}
+
 impl Drop for SBPlatform {
     fn drop(&mut self) {
         unsafe { sys::DisposeSBPlatform(self.raw) };
@@ -186,47 +319,55 @@ unsafe impl Send for SBPlatform {}
 unsafe impl Sync for SBPlatform {}
 
 #[cfg(feature = "graphql")]
-graphql_object!(SBPlatform: super::debugger::SBDebugger | &self | {
-    field is_valid() -> bool {
+#[graphql_object(context = super::debugger::SBDebugger)]
+impl SBPlatform {
+    #[graphql(name = "isValid")]
+    fn graphql_is_valid(&self) -> bool {
         self.is_valid()
     }
 
-    field working_directory() -> &str {
+    #[graphql(name = "workingDirectory")]
+    fn graphql_working_directory(&self) -> Option<String> {
         self.working_directory()
     }
 
-    field name() -> &str {
+    #[graphql(name = "name")]
+    fn graphql_name(&self) -> String {
         self.name()
     }
 
-    field triple() -> &str {
+    #[graphql(name = "triple")]
+    fn graphql_triple(&self) -> Option<String> {
         self.triple()
     }
 
-    field hostname() -> &str {
+    #[graphql(name = "hostname")]
+    fn graphql_hostname(&self) -> Option<String> {
         self.hostname()
     }
 
-    field os_build() -> &str {
+    #[graphql(name = "osBuild")]
+    fn graphql_os_build(&self) -> Option<String> {
         self.os_build()
     }
 
-    field os_description() -> &str {
+    #[graphql(name = "osDescription")]
+    fn graphql_os_description(&self) -> Option<String> {
         self.os_description()
     }
 
-    // TODO(bm) This should be u32
-    field os_major_version() -> i32 {
-        self.os_major_version() as i32
+    #[graphql(name = "osMajorVersion")]
+    fn graphql_os_major_version(&self) -> U64 {
+        self.os_major_version().into()
     }
 
-    // TODO(bm) This should be u32
-    field os_minor_version() -> i32 {
-        self.os_minor_version() as i32
+    #[graphql(name = "osMinorVersion")]
+    fn graphql_os_minor_version(&self) -> U64 {
+        self.os_minor_version().into()
     }
 
-    // TODO(bm) This should be u32
-    field os_update_version() -> i32 {
-        self.os_update_version() as i32
+    #[graphql(name = "osUpdateVersion")]
+    fn graphql_os_update_version(&self) -> U64 {
+        self.os_update_version().into()
     }
-});
+}
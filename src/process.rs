@@ -5,15 +5,44 @@
 // except according to those terms.
 
 use super::broadcaster::SBBroadcaster;
-use super::error::SBError;
+use super::connectionlost::ConnectionLost;
+use super::environment::Environment;
+use super::error::{InvalidRefError, SBError};
 use super::event::SBEvent;
+use super::expressionoptions::SBExpressionOptions;
+use super::frame::SBFrame;
+#[cfg(feature = "graphql")]
+use super::graphql::U64;
+use super::listener::SBListener;
+use super::memoryregioninfo::SBMemoryRegionInfo;
+use super::memoryregioninfolist::SBMemoryRegionInfoList;
+use super::memoryscan::MemoryScan;
 use super::processinfo::SBProcessInfo;
+#[cfg(feature = "lldb-15")]
+use super::processortrace::ProcessorTrace;
+use super::processstate::ProcessState;
 use super::queue::SBQueue;
+use super::savecoreoptions::SaveCoreOptions;
+use super::stophandle::StopHandle;
 use super::stream::SBStream;
+use super::strings;
+#[cfg(feature = "lldb-15")]
+use super::structureddata::SBStructuredData;
+use super::target::SBTarget;
 use super::thread::SBThread;
-use super::{lldb_pid_t, lldb_tid_t, StateType};
-use std::ffi::{CStr, CString};
-use std::fmt;
+use super::timeout::TimeoutError;
+use super::trace::traced;
+use super::{lldb_addr_t, lldb_pid_t, lldb_tid_t, ByteOrder, StateType, StopReason};
+use libc::{c_char, c_void};
+use std::convert::TryFrom;
+use std::error::Error;
+use std::ffi::CString;
+use std::fmt::{self, Write as FmtWrite};
+use std::fs::File;
+use std::hash::{Hash, Hasher};
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+use std::time::{Duration, Instant};
 use sys;
 
 /// The process associated with the target program.
@@ -118,15 +147,44 @@ pub struct SBProcess {
 }
 
 impl SBProcess {
+    /// Broadcast when the state of the process changes.
+    pub const BROADCAST_BIT_STATE_CHANGED: u32 = 1 << 0;
+    /// Broadcast when the process is interrupted.
+    pub const BROADCAST_BIT_INTERRUPT: u32 = 1 << 1;
+    /// Broadcast when the process has written to its standard output.
+    /// Use [`read_stdout`] to get the output.
+    ///
+    /// [`read_stdout`]: #method.read_stdout
+    pub const BROADCAST_BIT_STDOUT: u32 = 1 << 2;
+    /// Broadcast when the process has written to its standard error.
+    /// Use [`read_stderr`] to get the output.
+    ///
+    /// [`read_stderr`]: #method.read_stderr
+    pub const BROADCAST_BIT_STDERR: u32 = 1 << 3;
+    /// Broadcast when asynchronous profile data is available.
+    pub const BROADCAST_BIT_PROFILE_DATA: u32 = 1 << 4;
+    /// Broadcast when structured data is available from the process.
+    pub const BROADCAST_BIT_STRUCTURED_DATA: u32 = 1 << 5;
+
     /// Construct a new `SBProcess`.
-    pub fn wrap(raw: sys::SBProcessRef) -> SBProcess {
+    ///
+    /// # Safety
+    ///
+    /// `raw` must be a valid reference, which is disposed of when the
+    /// `SBProcess` is dropped. Use [`maybe_wrap`] when it may be invalid.
+    ///
+    /// [`maybe_wrap`]: #method.maybe_wrap
+    pub unsafe fn wrap(raw: sys::SBProcessRef) -> SBProcess {
         SBProcess { raw }
     }
 
     /// Construct a new `Some(SBProcess)` or `None`.
+    ///
+    /// `raw` is disposed of if it is invalid.
     pub fn maybe_wrap(raw: sys::SBProcessRef) -> Option<SBProcess> {
-        if unsafe { sys::SBProcessIsValid(raw) != 0 } {
-            Some(SBProcess { raw })
+        let wrapped = SBProcess { raw };
+        if wrapped.is_valid() {
+            Some(wrapped)
         } else {
             None
         }
@@ -139,12 +197,7 @@ impl SBProcess {
 
     #[allow(missing_docs)]
     pub fn broadcaster_class_name() -> &'static str {
-        unsafe {
-            match CStr::from_ptr(sys::SBProcessGetBroadcasterClassName()).to_str() {
-                Ok(s) => s,
-                _ => panic!("Invalid string?"),
-            }
-        }
+        unsafe { strings::to_static_str(sys::SBProcessGetBroadcasterClassName()) }
     }
 
     /// The current state of this process (running, stopped, exited, etc.).
@@ -152,22 +205,29 @@ impl SBProcess {
         unsafe { sys::SBProcessGetState(self.raw) }
     }
 
+    /// The current state of this process, with the exit status if it
+    /// has exited, in a form which suits `match`.
+    ///
+    /// See [`ProcessState`].
+    ///
+    /// [`ProcessState`]: enum.ProcessState.html
+    pub fn process_state(&self) -> ProcessState {
+        let state = self.state();
+        let exit_status = if state == StateType::Exited {
+            self.exit_status()
+        } else {
+            0
+        };
+        ProcessState::new(state, exit_status)
+    }
+
     /// Returns `true` if the process is currently alive.
     ///
     /// This corresponds to the process being in the `Attaching`,
     /// `Launching`, `Stopped`, `Running`, `Stepping`, `Crashed`
     /// or `Suspended` states.
     pub fn is_alive(&self) -> bool {
-        match self.state() {
-            StateType::Attaching
-            | StateType::Launching
-            | StateType::Stopped
-            | StateType::Running
-            | StateType::Stepping
-            | StateType::Crashed
-            | StateType::Suspended => true,
-            _ => false,
-        }
+        ProcessState::new(self.state(), 0).is_alive()
     }
 
     /// Returns `true` if the process is currently running.
@@ -175,10 +235,7 @@ impl SBProcess {
     /// This corresponds to the process being in the `Running`
     /// or `Stepping` states.
     pub fn is_running(&self) -> bool {
-        match self.state() {
-            StateType::Running | StateType::Stepping => true,
-            _ => false,
-        }
+        ProcessState::new(self.state(), 0).is_running()
     }
 
     /// Returns `true` if the process is currently stopped.
@@ -186,10 +243,7 @@ impl SBProcess {
     /// This corresponds to the process being in the `Stopped`, `Crashed`,
     /// or `Suspended` states.
     pub fn is_stopped(&self) -> bool {
-        match self.state() {
-            StateType::Stopped | StateType::Crashed | StateType::Suspended => true,
-            _ => false,
-        }
+        ProcessState::new(self.state(), 0).is_stopped()
     }
 
     /// The exit status of the process when the process state is
@@ -200,13 +254,16 @@ impl SBProcess {
 
     /// The exit description of the process when the process state
     /// is `Exited`.
-    pub fn exit_description(&self) -> &str {
-        unsafe {
-            match CStr::from_ptr(sys::SBProcessGetExitDescription(self.raw)).to_str() {
-                Ok(s) => s,
-                _ => panic!("Invalid string?"),
-            }
-        }
+    pub fn exit_description(&self) -> Option<String> {
+        unsafe { strings::to_string(sys::SBProcessGetExitDescription(self.raw)) }
+    }
+
+    /// Why the process exited, if it was because the connection to
+    /// its remote debug server was lost. See [`ConnectionLost`].
+    ///
+    /// [`ConnectionLost`]: struct.ConnectionLost.html
+    pub fn connection_lost(&self) -> Option<ConnectionLost> {
+        ConnectionLost::from_process(self)
     }
 
     /// Returns the process ID of the process.
@@ -221,15 +278,33 @@ impl SBProcess {
         unsafe { sys::SBProcessGetUniqueID(self.raw) }
     }
 
+    /// A counter which goes up each time the process stops, so that
+    /// anything learned at one stop can be told apart from a later
+    /// one. Stops for running expressions aren't counted.
+    pub fn stop_id(&self) -> u32 {
+        unsafe { sys::SBProcessGetStopID(self.raw, false as u8) }
+    }
+
+    /// A handle to the process which can only be used until it runs
+    /// again or exits.
+    pub fn stop_handle(&self) -> StopHandle<SBProcess> {
+        StopHandle::new(self.clone(), self.clone(), SBProcess::is_valid)
+    }
+
     /// Get the size, in bytes, of an address.
     pub fn address_byte_size(&self) -> u32 {
         unsafe { sys::SBProcessGetAddressByteSize(self.raw) }
     }
 
+    /// The byte order of the process.
+    pub fn byte_order(&self) -> ByteOrder {
+        unsafe { sys::SBProcessGetByteOrder(self.raw) }
+    }
+
     /// Kills the process and shuts down all threads that were spawned to
     /// track and monitor the process.
     pub fn destroy(&self) -> Result<(), SBError> {
-        let error = SBError::wrap(unsafe { sys::SBProcessDestroy(self.raw) });
+        let error = unsafe { SBError::wrap(sys::SBProcessDestroy(self.raw)) };
         if error.is_success() {
             Ok(())
         } else {
@@ -239,27 +314,265 @@ impl SBProcess {
 
     #[allow(missing_docs)]
     pub fn continue_execution(&self) -> Result<(), SBError> {
-        let error = SBError::wrap(unsafe { sys::SBProcessContinue(self.raw) });
-        if error.is_success() {
-            Ok(())
-        } else {
-            Err(error)
+        traced("SBProcess::continue_execution", || {
+            let error = unsafe { SBError::wrap(sys::SBProcessContinue(self.raw)) };
+            if error.is_success() {
+                Ok(())
+            } else {
+                Err(error)
+            }
+        })
+    }
+
+    /// Resume the process until it reaches `location`, using a
+    /// temporary breakpoint, or stops for some other reason.
+    ///
+    /// ```no_run
+    /// # use lldb::{ContinueToStop, SBProcess, TimeoutError};
+    /// # use std::time::Duration;
+    /// # fn run(process: &SBProcess) -> Result<(), TimeoutError> {
+    /// match process.continue_to("parse_config", Duration::from_secs(10))? {
+    ///     ContinueToStop::Reached(stop) => println!("At {:#x}", stop.pc),
+    ///     ContinueToStop::Elsewhere(stop) => println!("Stopped first: {:?}", stop.stop_reason),
+    ///     ContinueToStop::Ended(state) => println!("The process ended: {:?}", state),
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// The breakpoint is removed however the wait ends. When it
+    /// times out, the process is left running.
+    ///
+    /// The stop is waited for with a listener of its own, so this
+    /// works alongside the debugger's listener. In synchronous mode,
+    /// resuming the process already waits for it to stop, so the
+    /// timeout only applies in asynchronous mode.
+    pub fn continue_to<L>(
+        &self,
+        location: L,
+        timeout: Duration,
+    ) -> Result<ContinueToStop, TimeoutError>
+    where
+        L: Into<ContinueToLocation>,
+    {
+        let target = self.target();
+        let breakpoint = match location.into() {
+            ContinueToLocation::Address(address) => target.breakpoint_create_by_address(address),
+            ContinueToLocation::Function(name) => target.breakpoint_create_by_name(&name, None),
+            ContinueToLocation::Line { file, line } => {
+                target.breakpoint_create_by_location(&file, line)
+            }
+        };
+        if !breakpoint.is_valid() || breakpoint.num_locations() == 0 {
+            target.delete_breakpoint(breakpoint.id());
+            return Err(
+                SBError::from_message("the location to continue to could not be found").into(),
+            );
+        }
+        breakpoint.set_oneshot(true);
+        let id = breakpoint.id();
+
+        let listener = SBListener::new();
+        self.broadcaster()
+            .add_listener(&listener, SBProcess::BROADCAST_BIT_STATE_CHANGED);
+        let result = self
+            .continue_execution()
+            .map_err(TimeoutError::from)
+            .and_then(|()| self.wait_for_stop(&listener, timeout));
+        self.broadcaster()
+            .remove_listener(&listener, SBProcess::BROADCAST_BIT_STATE_CHANGED);
+        target.delete_breakpoint(id);
+
+        let state = result?;
+        if state != StateType::Stopped {
+            return Ok(ContinueToStop::Ended(state));
+        }
+        let reached = self.threads().find(|thread| {
+            thread.stop_reason() == StopReason::Breakpoint
+                && thread.stop_reason_data_count() > 0
+                && thread.stop_reason_data_at_index(0) == id as u64
+        });
+        match reached {
+            Some(thread) => {
+                self.set_selected_thread(&thread);
+                Ok(ContinueToStop::Reached(self.stop_snapshot()))
+            }
+            None => Ok(ContinueToStop::Elsewhere(self.stop_snapshot())),
+        }
+    }
+
+    /// Wait for a state changed event on `listener` which leaves the
+    /// process stopped or ended, returning that state.
+    fn wait_for_stop(
+        &self,
+        listener: &SBListener,
+        timeout: Duration,
+    ) -> Result<StateType, TimeoutError> {
+        let deadline = Instant::now() + timeout;
+        let mut event = SBEvent::new();
+        loop {
+            if !listener
+                .wait_for_event_until(Some(deadline), &mut event, || Ok::<_, TimeoutError>(()))?
+            {
+                return Err(TimeoutError::TimedOut);
+            }
+            if let Some(process_event) = SBProcess::event_as_process_event(&event) {
+                match process_event.state() {
+                    StateType::Stopped if !process_event.restarted() => {
+                        return Ok(StateType::Stopped)
+                    }
+                    state @ StateType::Exited
+                    | state @ StateType::Detached
+                    | state @ StateType::Crashed => return Ok(state),
+                    _ => {}
+                }
+            }
         }
     }
 
+    /// Wait until the process is in `state`, or has finished, for up
+    /// to `timeout`, returning the state it is in.
+    ///
+    /// The state is reached when the process is found in it, so a
+    /// stop which is immediately followed by the process running again,
+    /// such as at a breakpoint which continues automatically, doesn't
+    /// count, and neither do events left over from earlier changes.
+    /// The exit status of [`ProcessState::Exited`] isn't compared.
+    ///
+    /// If the process exits or is detached from first, that state is
+    /// returned rather than waiting for the timeout:
+    ///
+    /// ```no_run
+    /// # use lldb::{ProcessState, SBProcess};
+    /// # use std::time::Duration;
+    /// # fn wait(process: &SBProcess) {
+    /// process.continue_execution().unwrap();
+    /// match process.wait_for_state(ProcessState::Stopped, Duration::from_secs(10)) {
+    ///     Ok(ProcessState::Stopped) => println!("stopped"),
+    ///     Ok(state) => println!("{} instead", state),
+    ///     Err(error) => println!("{}", error),
+    /// }
+    /// # }
+    /// ```
+    ///
+    /// [`ProcessState::Exited`]: enum.ProcessState.html#variant.Exited
+    pub fn wait_for_state(
+        &self,
+        state: ProcessState,
+        timeout: Duration,
+    ) -> Result<ProcessState, TimeoutError> {
+        let wanted = state.state_type();
+        // Listen before looking at the state, so that a change between
+        // the two isn't missed.
+        let listener = SBListener::new();
+        self.broadcaster()
+            .add_listener(&listener, SBProcess::BROADCAST_BIT_STATE_CHANGED);
+        let deadline = Instant::now() + timeout;
+        let mut event = SBEvent::new();
+        let mut restarted = false;
+        let result = loop {
+            let current = self.process_state();
+            if current.is_finished() || (current.state_type() == wanted && !restarted) {
+                break Ok(current);
+            }
+            // The state is read from the process after each event,
+            // rather than from the event, as it may have changed again
+            // since. A stop which LLDB has already restarted from is
+            // passed over.
+            match listener.wait_for_event_until(Some(deadline), &mut event, || Ok(())) {
+                Ok(true) => {
+                    restarted = SBProcess::event_as_process_event(&event)
+                        .is_some_and(|process_event| process_event.restarted())
+                }
+                Ok(false) => break Err(TimeoutError::TimedOut),
+                Err(error) => break Err(error),
+            }
+        };
+        self.broadcaster()
+            .remove_listener(&listener, SBProcess::BROADCAST_BIT_STATE_CHANGED);
+        result
+    }
+
     #[allow(missing_docs)]
     pub fn stop(&self) -> Result<(), SBError> {
-        let error = SBError::wrap(unsafe { sys::SBProcessStop(self.raw) });
-        if error.is_success() {
-            Ok(())
-        } else {
-            Err(error)
-        }
+        traced("SBProcess::stop", || {
+            let error = unsafe { SBError::wrap(sys::SBProcessStop(self.raw)) };
+            if error.is_success() {
+                Ok(())
+            } else {
+                Err(error)
+            }
+        })
     }
 
     /// Same as calling `destroy`.
     pub fn kill(&self) -> Result<(), SBError> {
-        let error = SBError::wrap(unsafe { sys::SBProcessKill(self.raw) });
+        traced("SBProcess::kill", || {
+            let error = unsafe { SBError::wrap(sys::SBProcessKill(self.raw)) };
+            if error.is_success() {
+                Ok(())
+            } else {
+                Err(error)
+            }
+        })
+    }
+
+    #[allow(missing_docs)]
+    pub fn detach(&self) -> Result<(), SBError> {
+        traced("SBProcess::detach", || {
+            let error = unsafe { SBError::wrap(sys::SBProcessDetach(self.raw)) };
+            if error.is_success() {
+                Ok(())
+            } else {
+                Err(error)
+            }
+        })
+    }
+
+    /// Detach from the process, choosing whether it is left stopped
+    /// or resumes running.
+    ///
+    /// Leaving the process stopped lets another debugger attach to it
+    /// and find it exactly where this one left it. The process must
+    /// already be stopped: in asynchronous mode, [`stop`] returns
+    /// before the process has stopped, so wait for the stopped event
+    /// first, or use [`DebugSession::detach_keep_stopped`], which does
+    /// this for you. Detaching a running process with `keep_stopped`
+    /// returns an error rather than racing with it.
+    ///
+    /// Not every platform can leave a process stopped, in which case
+    /// LLDB returns an error and the process stays attached.
+    ///
+    /// [`stop`]: #method.stop
+    /// [`DebugSession::detach_keep_stopped`]: struct.DebugSession.html#method.detach_keep_stopped
+    pub fn detach_with(&self, keep_stopped: bool) -> Result<(), SBError> {
+        traced("SBProcess::detach_with", || {
+            if keep_stopped && !self.is_stopped() {
+                return Err(SBError::from_message(
+                    "the process must be stopped to detach and leave it stopped",
+                ));
+            }
+            let error =
+                unsafe { SBError::wrap(sys::SBProcessDetach2(self.raw, keep_stopped as u8)) };
+            if error.is_success() {
+                Ok(())
+            } else {
+                Err(error)
+            }
+        })
+    }
+
+    /// Ask the process to stop, without waiting for it to do so.
+    ///
+    /// This also interrupts an expression which is being evaluated
+    /// in the process.
+    pub fn send_async_interrupt(&self) {
+        unsafe { sys::SBProcessSendAsyncInterrupt(self.raw) };
+    }
+
+    /// Send the process a Unix signal.
+    pub fn signal(&self, signal: i32) -> Result<(), SBError> {
+        let error = unsafe { SBError::wrap(sys::SBProcessSignal(self.raw, signal)) };
         if error.is_success() {
             Ok(())
         } else {
@@ -268,28 +581,465 @@ impl SBProcess {
     }
 
     #[allow(missing_docs)]
-    pub fn detach(&self) -> Result<(), SBError> {
-        let error = SBError::wrap(unsafe { sys::SBProcessDetach(self.raw) });
+    pub fn broadcaster(&self) -> SBBroadcaster {
+        unsafe { SBBroadcaster::wrap(sys::SBProcessGetBroadcaster(self.raw)) }
+    }
+
+    /// Read everything that the process has written to its standard
+    /// output since the last call.
+    ///
+    /// This is only available when the process was launched with its
+    /// standard output redirected to LLDB, rather than to a file or a
+    /// terminal.
+    pub fn read_stdout(&self) -> String {
+        read_output(|buf, len| unsafe { sys::SBProcessGetSTDOUT(self.raw, buf, len) })
+    }
+
+    /// Read everything that the process has written to its standard
+    /// error since the last call.
+    ///
+    /// See [`read_stdout`] for when this is available.
+    ///
+    /// [`read_stdout`]: #method.read_stdout
+    pub fn read_stderr(&self) -> String {
+        read_output(|buf, len| unsafe { sys::SBProcessGetSTDERR(self.raw, buf, len) })
+    }
+
+    /// Read memory from the process into `buffer`, returning the
+    /// number of bytes which were read.
+    pub fn read_memory(&self, addr: lldb_addr_t, buffer: &mut [u8]) -> Result<usize, SBError> {
+        traced("SBProcess::read_memory", || {
+            let error = SBError::new();
+            let count = unsafe {
+                sys::SBProcessReadMemory(
+                    self.raw,
+                    addr,
+                    buffer.as_mut_ptr() as *mut c_void,
+                    buffer.len(),
+                    error.raw,
+                )
+            };
+            if error.is_success() {
+                Ok(count as usize)
+            } else {
+                Err(error)
+            }
+        })
+    }
+
+    /// Write `bytes` to the memory of the process at `addr`, returning
+    /// the number of bytes which were written.
+    pub fn write_memory(&self, addr: lldb_addr_t, bytes: &[u8]) -> Result<usize, SBError> {
+        traced("SBProcess::write_memory", || {
+            let error = SBError::new();
+            let count = unsafe {
+                sys::SBProcessWriteMemory(
+                    self.raw,
+                    addr,
+                    bytes.as_ptr() as *mut c_void,
+                    bytes.len(),
+                    error.raw,
+                )
+            };
+            if error.is_success() {
+                Ok(count as usize)
+            } else {
+                Err(error)
+            }
+        })
+    }
+
+    /// The region of memory containing `addr`, or the unmapped gap
+    /// around it.
+    pub fn memory_region_info(&self, addr: lldb_addr_t) -> Result<SBMemoryRegionInfo, SBError> {
+        let region = SBMemoryRegionInfo::new();
+        let error = unsafe {
+            SBError::wrap(sys::SBProcessGetMemoryRegionInfo(
+                self.raw, addr, region.raw,
+            ))
+        };
         if error.is_success() {
-            Ok(())
+            Ok(region)
         } else {
             Err(error)
         }
     }
 
-    /// Send the process a Unix signal.
-    pub fn signal(&self, signal: i32) -> Result<(), SBError> {
-        let error = SBError::wrap(unsafe { sys::SBProcessSignal(self.raw, signal) });
-        if error.is_success() {
-            Ok(())
+    /// All of the regions of the address space of the process,
+    /// including the unmapped gaps between them.
+    pub fn memory_regions(&self) -> SBMemoryRegionInfoList {
+        unsafe { SBMemoryRegionInfoList::wrap(sys::SBProcessGetMemoryRegions(self.raw)) }
+    }
+
+    /// Search the readable memory of the process for `pattern`,
+    /// returning an iterator over the addresses where it is found.
+    ///
+    /// Only the bits set in `mask` are compared, so a byte of the
+    /// mask which is 0 matches anything, as from [`parse_signature`].
+    /// Only regions for which `region_filter` returns true are
+    /// searched, such as only executable ones for code.
+    ///
+    /// LLDB's own search of memory isn't available, so the memory is
+    /// read and searched a chunk at a time as the iterator advances,
+    /// which lets a search of a large address space report progress,
+    /// be cancelled and stop at the first match. See [`MemoryScan`].
+    ///
+    /// Returns an error if `mask` isn't the same length as `pattern`.
+    ///
+    /// [`parse_signature`]: fn.parse_signature.html
+    /// [`MemoryScan`]: struct.MemoryScan.html
+    pub fn scan_for_pattern<'a, F>(
+        &'a self,
+        pattern: &[u8],
+        mask: Option<&[u8]>,
+        region_filter: F,
+    ) -> Result<MemoryScan<'a>, SBError>
+    where
+        F: FnMut(&SBMemoryRegionInfo) -> bool + 'a,
+    {
+        MemoryScan::new(self, pattern, mask, region_filter)
+    }
+
+    /// Overwrite the memory at `addr` with `bytes`, even when it is
+    /// read-only, such as to patch code.
+    ///
+    /// Most debug servers can write through the protection of a page,
+    /// so the write is tried first. If it doesn't take, the region is
+    /// made writable by calling `mprotect`, or `VirtualProtect` on
+    /// Windows, in the process, written to and then given back its
+    /// protection. The process must be stopped, and `bytes` must fit
+    /// in one region.
+    ///
+    /// The bytes are read back to check that they were written. LLDB
+    /// flushes its instruction cache for the addresses written, but
+    /// software breakpoints in the patched range keep the original
+    /// instructions that they replaced.
+    pub fn patch_memory(&self, addr: lldb_addr_t, bytes: &[u8]) -> Result<(), SBError> {
+        if bytes.is_empty() {
+            return Ok(());
+        }
+        if !self.is_stopped() {
+            return Err(SBError::from_message(
+                "the process must be stopped to patch its memory",
+            ));
+        }
+        let region = self.memory_region_info(addr)?;
+        if !region.is_mapped() {
+            return Err(SBError::from_message(&format!("{:#x} is not mapped", addr)));
+        }
+        let end = addr.saturating_add(bytes.len() as lldb_addr_t);
+        if end > region.region_end() {
+            return Err(SBError::from_message(&format!(
+                "{:#x}-{:#x} spans more than one memory region",
+                addr, end
+            )));
+        }
+        let first_error = match self.write_and_verify(addr, bytes) {
+            Ok(()) => return Ok(()),
+            Err(error) => error,
+        };
+        if region.is_writable() {
+            return Err(first_error);
+        }
+
+        let restore = self.make_writable(&region)?;
+        let written = self.write_and_verify(addr, bytes);
+        let restored = self.evaluate_int(&restore).and_then(|result| {
+            if result == 0 {
+                Ok(())
+            } else {
+                Err(SBError::from_message(
+                    "the protection of the memory could not be restored",
+                ))
+            }
+        });
+        written.and(restored)
+    }
+
+    fn write_and_verify(&self, addr: lldb_addr_t, bytes: &[u8]) -> Result<(), SBError> {
+        if self.write_memory(addr, bytes)? != bytes.len() {
+            return Err(SBError::from_message(&format!(
+                "the memory at {:#x} was not written",
+                addr
+            )));
+        }
+        let mut check = vec![0; bytes.len()];
+        if self.read_memory(addr, &mut check)? != bytes.len() || check != bytes {
+            return Err(SBError::from_message(&format!(
+                "the memory at {:#x} did not change",
+                addr
+            )));
+        }
+        Ok(())
+    }
+
+    /// Make `region` writable, returning the expression which restores
+    /// its protection and evaluates to 0 when it succeeds.
+    fn make_writable(&self, region: &SBMemoryRegionInfo) -> Result<String, SBError> {
+        let base = region.region_base();
+        let size = region.region_end() - base;
+        let windows = self
+            .target()
+            .triple()
+            .is_some_and(|triple| triple.contains("windows"));
+        if windows {
+            const PAGE_EXECUTE_READWRITE: u32 = 0x40;
+            let protect = |protection: &str| {
+                format!(
+                    "unsigned long old = 0; \
+                     (long long)(VirtualProtect((void *){:#x}, {:#x}, {}, &old) ? old : -1)",
+                    base, size, protection
+                )
+            };
+            let old = self.evaluate_int(&protect(&PAGE_EXECUTE_READWRITE.to_string()))?;
+            if old < 0 {
+                return Err(SBError::from_message(
+                    "VirtualProtect failed to make the memory writable",
+                ));
+            }
+            Ok(format!("{} < 0", protect(&old.to_string())))
         } else {
-            Err(error)
+            const PROT_READ: u32 = 1;
+            const PROT_WRITE: u32 = 2;
+            const PROT_EXEC: u32 = 4;
+            let mut protection = 0;
+            if region.is_readable() {
+                protection |= PROT_READ;
+            }
+            if region.is_executable() {
+                protection |= PROT_EXEC;
+            }
+            let mprotect = |protection: u32| {
+                format!(
+                    "(int)mprotect((void *){:#x}, {:#x}, {})",
+                    base, size, protection
+                )
+            };
+            if self.evaluate_int(&mprotect(protection | PROT_READ | PROT_WRITE))? != 0 {
+                return Err(SBError::from_message(
+                    "mprotect failed to make the memory writable",
+                ));
+            }
+            Ok(mprotect(protection))
         }
     }
 
-    #[allow(missing_docs)]
-    pub fn broadcaster(&self) -> SBBroadcaster {
-        SBBroadcaster::wrap(unsafe { sys::SBProcessGetBroadcaster(self.raw) })
+    /// Evaluate `expression` in the selected frame, returning its
+    /// value as an integer.
+    fn evaluate_int(&self, expression: &str) -> Result<i64, SBError> {
+        let options = SBExpressionOptions::new();
+        options.set_ignore_breakpoints(true);
+        options.set_unwind_on_error(true);
+        let value = self.target().evaluate_expression(expression, &options);
+        match value.error() {
+            Some(error) if error.is_failure() => Err(error),
+            _ => value.value_as_signed(),
+        }
+    }
+
+    /// The environment of the process, such as to check it against
+    /// the one it was launched with using [`Environment::diff`].
+    ///
+    /// This is the current environment of the process: the strings
+    /// that the C library's `environ` points to, including any changes
+    /// the process has made with `setenv` and `putenv` since it
+    /// started. It is only set up once the C library has been
+    /// initialized, so not when stopped at the entry point.
+    ///
+    /// [`Environment::diff`]: struct.Environment.html#method.diff
+    pub fn environment(&self) -> Result<Environment, SBError> {
+        let apple = self
+            .target()
+            .triple()
+            .is_some_and(|triple| triple.contains("apple"));
+        let environ = if apple {
+            self.evaluate_int("(long)*(char ***)_NSGetEnviron()")?
+        } else {
+            self.evaluate_int("(long)(char **)environ")?
+        } as lldb_addr_t;
+        if environ == 0 {
+            return Err(SBError::from_message(
+                "the process has no environment set up yet",
+            ));
+        }
+        let size = self.address_byte_size() as lldb_addr_t;
+        let mut entries = vec![];
+        for i in 0.. {
+            let entry = self
+                .walk_pointer_chain(environ + i * size, &[0])
+                .map_err(|_| {
+                    SBError::from_message("could not read the environment of the process")
+                })?;
+            if entry == 0 {
+                break;
+            }
+            entries.push(self.read_c_string(entry)?);
+        }
+        Ok(Environment::from_entries(entries))
+    }
+
+    /// Read the NUL-terminated string at `addr`, of up to 1 MiB.
+    fn read_c_string(&self, addr: lldb_addr_t) -> Result<String, SBError> {
+        const MAX_LEN: usize = 1 << 20;
+        let mut buffer = vec![0u8; 4096];
+        loop {
+            let error = SBError::new();
+            let count = unsafe {
+                sys::SBProcessReadCStringFromMemory(
+                    self.raw,
+                    addr,
+                    buffer.as_mut_ptr() as *mut c_void,
+                    buffer.len(),
+                    error.raw,
+                )
+            } as usize;
+            if error.is_failure() {
+                return Err(error);
+            }
+            // LLDB stops one byte short of the end of the buffer to
+            // leave room for the NUL, so a string which fills it may
+            // have been cut short.
+            if count + 1 < buffer.len() || buffer.len() >= MAX_LEN {
+                buffer.truncate(count);
+                return Ok(String::from_utf8_lossy(&buffer).into_owned());
+            }
+            let len = buffer.len() * 2;
+            buffer.resize(len, 0);
+        }
+    }
+
+    /// Read several ranges of memory, given as `(address, length)`
+    /// pairs, with as few calls into LLDB as possible.
+    ///
+    /// Ranges which overlap or are adjacent are read together. When
+    /// such a read fails, the ranges in it are retried one at a time,
+    /// so that an unreadable range doesn't cause its neighbours to
+    /// fail. The results are in the same order as `ranges`.
+    pub fn read_memory_ranges(
+        &self,
+        ranges: &[(lldb_addr_t, usize)],
+    ) -> Vec<Result<Vec<u8>, SBError>> {
+        let mut order: Vec<usize> = (0..ranges.len()).collect();
+        order.sort_by_key(|&i| ranges[i].0);
+
+        let mut results: Vec<Option<Result<Vec<u8>, SBError>>> =
+            (0..ranges.len()).map(|_| None).collect();
+        let mut scratch = Vec::new();
+        let mut start = 0;
+        while start < order.len() {
+            // Gather the ranges which overlap or touch the first one.
+            let span_start = ranges[order[start]].0;
+            let mut span_end = span_start + ranges[order[start]].1 as lldb_addr_t;
+            let mut end = start + 1;
+            while end < order.len() && ranges[order[end]].0 <= span_end {
+                let (addr, len) = ranges[order[end]];
+                span_end = span_end.max(addr + len as lldb_addr_t);
+                end += 1;
+            }
+
+            scratch.clear();
+            scratch.resize((span_end - span_start) as usize, 0);
+            let complete = match self.read_memory(span_start, &mut scratch) {
+                Ok(count) => count == scratch.len(),
+                Err(_) => false,
+            };
+            for &i in &order[start..end] {
+                let (addr, len) = ranges[i];
+                results[i] = Some(if complete {
+                    let offset = (addr - span_start) as usize;
+                    Ok(scratch[offset..offset + len].to_vec())
+                } else {
+                    let mut bytes = vec![0; len];
+                    self.read_memory(addr, &mut bytes).map(|count| {
+                        bytes.truncate(count);
+                        bytes
+                    })
+                });
+            }
+            start = end;
+        }
+        results.into_iter().map(Option::unwrap).collect()
+    }
+
+    /// Write `len` bytes of memory starting at `addr` to the file at
+    /// `path`, reading it in chunks so that large regions don't have
+    /// to fit in memory.
+    ///
+    /// Fails if any part of the region can't be read, in which case
+    /// the file holds the part before the failure.
+    pub fn dump_memory_to_file<P: AsRef<Path>>(
+        &self,
+        addr: lldb_addr_t,
+        len: usize,
+        path: P,
+    ) -> io::Result<()> {
+        let mut file = BufWriter::new(File::create(path)?);
+        let mut chunk = vec![0; DUMP_CHUNK_SIZE.min(len)];
+        let mut done = 0;
+        while done < len {
+            let chunk_len = chunk.len().min(len - done);
+            let chunk_addr = addr + done as lldb_addr_t;
+            let count = self
+                .read_memory(chunk_addr, &mut chunk[..chunk_len])
+                .map_err(|error| io::Error::other(error.to_string()))?;
+            file.write_all(&chunk[..count])?;
+            if count < chunk_len {
+                return Err(io::Error::other(format!(
+                    "memory read failed for {:#x}",
+                    chunk_addr + count as u64
+                )));
+            }
+            done += count;
+        }
+        file.flush()
+    }
+
+    /// Follow a chain of pointers, starting at `base`.
+    ///
+    /// For each offset, the pointer at the current address is read
+    /// and the offset is added to it to give the next address. The
+    /// final address is returned, so `walk_pointer_chain(base, &[0x10,
+    /// 0x8])` is `*(*base + 0x10) + 0x8`.
+    ///
+    /// Pointers are read with the address size and byte order of the
+    /// process. The error says which read in the chain failed.
+    pub fn walk_pointer_chain(
+        &self,
+        base: lldb_addr_t,
+        offsets: &[i64],
+    ) -> Result<lldb_addr_t, ChainError> {
+        let size = self.address_byte_size() as usize;
+        let byte_order = self.byte_order();
+        let mut buffer = [0; 8];
+        let buffer = &mut buffer[..size.min(8)];
+        let mut address = base;
+        for (hop, &offset) in offsets.iter().enumerate() {
+            match self.read_memory(address, buffer) {
+                Ok(count) if count == buffer.len() => {}
+                result => {
+                    return Err(ChainError {
+                        hop,
+                        address,
+                        error: result.err(),
+                    })
+                }
+            }
+            address = decode_pointer(buffer, byte_order).wrapping_add(offset as u64);
+        }
+        Ok(address)
+    }
+
+    /// Read `len` bytes of memory starting at `addr` and format them
+    /// like `hexdump -C`, with the address of each line, the bytes in
+    /// hex and the printable bytes as ASCII:
+    ///
+    /// ```text
+    /// 0000000100003f80  48 65 6c 6c 6f 2c 20 77  6f 72 6c 64 21 0a 00 00  |Hello, world!...|
+    /// ```
+    pub fn hexdump(&self, addr: lldb_addr_t, len: usize) -> Result<String, SBError> {
+        let mut bytes = vec![0; len];
+        let count = self.read_memory(addr, &mut bytes)?;
+        Ok(format_hexdump(addr, &bytes[..count]))
     }
 
     /// Get an iterator over the [threads] known to this process instance.
@@ -322,9 +1072,10 @@ impl SBProcess {
         SBThread::maybe_wrap(unsafe { sys::SBProcessGetThreadByIndexID(self.raw, thread_index_id) })
     }
 
-    /// Returns the currently selected thread.
-    pub fn selected_thread(&self) -> SBThread {
-        SBThread::wrap(unsafe { sys::SBProcessGetSelectedThread(self.raw) })
+    /// Returns the currently selected thread, if there is one. There
+    /// is none while the process is running.
+    pub fn selected_thread(&self) -> Option<SBThread> {
+        SBThread::maybe_wrap(unsafe { sys::SBProcessGetSelectedThread(self.raw) })
     }
 
     /// Set the selected thread.
@@ -354,7 +1105,7 @@ impl SBProcess {
     /// Save the state of the process in a core file (or mini dump on Windows).
     pub fn save_core(&self, file_name: &str) -> Result<(), SBError> {
         let f = CString::new(file_name).unwrap();
-        let error = SBError::wrap(unsafe { sys::SBProcessSaveCore(self.raw, f.as_ptr()) });
+        let error = unsafe { SBError::wrap(sys::SBProcessSaveCore(self.raw, f.as_ptr())) };
         if error.is_success() {
             Ok(())
         } else {
@@ -362,12 +1113,215 @@ impl SBProcess {
         }
     }
 
+    /// Save the state of the process in a core file, choosing which
+    /// memory is saved and the format of the file.
+    pub fn save_core_with_options(&self, options: &SaveCoreOptions) -> Result<(), SBError> {
+        let target = self.target();
+        let result = target
+            .debugger()
+            .command_interpreter()
+            .handle_command_for_target(&options.command(), &target, false);
+        if result.succeeded() {
+            Ok(())
+        } else {
+            Err(SBError::from_message(&result.error().unwrap_or_default()))
+        }
+    }
+
+    /// Start processor tracing of the whole process, such as with
+    /// Intel PT, as configured by `config`. See [`ProcessorTrace`].
+    ///
+    /// This needs LLDB 15 or later, and the `lldb-15` feature.
+    ///
+    /// [`ProcessorTrace`]: struct.ProcessorTrace.html
+    #[cfg(feature = "lldb-15")]
+    pub fn start_trace(&self, config: &SBStructuredData) -> Result<ProcessorTrace, SBError> {
+        ProcessorTrace::start(self, None, config)
+    }
+
+    /// The target that this process belongs to.
+    pub fn target(&self) -> SBTarget {
+        unsafe { SBTarget::wrap(sys::SBProcessGetTarget(self.raw)) }
+    }
+
     #[allow(missing_docs)]
     pub fn process_info(&self) -> SBProcessInfo {
-        SBProcessInfo::wrap(unsafe { sys::SBProcessGetProcessInfo(self.raw) })
+        unsafe { SBProcessInfo::wrap(sys::SBProcessGetProcessInfo(self.raw)) }
+    }
+
+    /// Capture a [`StopSnapshot`] describing where the process is
+    /// currently stopped.
+    ///
+    /// [`StopSnapshot`]: struct.StopSnapshot.html
+    pub fn stop_snapshot(&self) -> StopSnapshot {
+        let thread = self.selected_thread();
+        let frame = thread.as_ref().and_then(SBThread::selected_frame);
+        StopSnapshot {
+            process_id: self.process_id(),
+            state: self.state(),
+            thread_id: thread.as_ref().map_or(0, SBThread::thread_id),
+            stop_reason: thread
+                .as_ref()
+                .map_or(StopReason::Invalid, SBThread::stop_reason),
+            pc: frame.as_ref().map_or(!0, SBFrame::pc),
+            function_name: frame.as_ref().and_then(SBFrame::function_name),
+        }
+    }
+
+    fn description(&self) -> String {
+        let stream = SBStream::new();
+        unsafe { sys::SBProcessGetDescription(self.raw, stream.raw) };
+        stream.data()
     }
 }
 
+/// A description of where a process stopped.
+///
+/// Unlike the `SB` types, this holds plain data, so it remains
+/// valid after the process has resumed.
+///
+/// This is created with [`SBProcess::stop_snapshot`].
+///
+/// [`SBProcess::stop_snapshot`]: struct.SBProcess.html#method.stop_snapshot
+#[derive(Clone, Debug, PartialEq)]
+pub struct StopSnapshot {
+    /// The process ID of the stopped process.
+    pub process_id: lldb_pid_t,
+    /// The state of the process.
+    pub state: StateType,
+    /// The thread ID of the selected thread.
+    pub thread_id: lldb_tid_t,
+    /// Why the selected thread stopped.
+    pub stop_reason: StopReason,
+    /// The program counter of the selected frame.
+    pub pc: lldb_addr_t,
+    /// The name of the function for the selected frame, if known.
+    pub function_name: Option<String>,
+}
+
+/// Where [`SBProcess::continue_to`] runs to.
+///
+/// [`SBProcess::continue_to`]: struct.SBProcess.html#method.continue_to
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ContinueToLocation {
+    /// A load address.
+    Address(lldb_addr_t),
+    /// The start of a function.
+    Function(String),
+    /// A line of a source file.
+    Line {
+        /// The path, or the file name, of the source file.
+        file: String,
+        /// The line number.
+        line: u32,
+    },
+}
+
+impl From<lldb_addr_t> for ContinueToLocation {
+    fn from(address: lldb_addr_t) -> ContinueToLocation {
+        ContinueToLocation::Address(address)
+    }
+}
+
+impl<'a> From<&'a str> for ContinueToLocation {
+    fn from(function: &'a str) -> ContinueToLocation {
+        ContinueToLocation::Function(function.to_string())
+    }
+}
+
+/// How [`SBProcess::continue_to`] ended.
+///
+/// [`SBProcess::continue_to`]: struct.SBProcess.html#method.continue_to
+#[derive(Clone, Debug, PartialEq)]
+pub enum ContinueToStop {
+    /// The process stopped at the location. The thread which reached
+    /// it is selected.
+    Reached(StopSnapshot),
+    /// The process stopped for some other reason first, such as
+    /// another breakpoint or a signal.
+    Elsewhere(StopSnapshot),
+    /// The process ended, in this state, before reaching the
+    /// location.
+    Ended(StateType),
+}
+
+/// The error returned by [`SBProcess::walk_pointer_chain`].
+///
+/// [`SBProcess::walk_pointer_chain`]: struct.SBProcess.html#method.walk_pointer_chain
+#[derive(Debug)]
+pub struct ChainError {
+    /// The index of the offset whose pointer couldn't be read.
+    pub hop: usize,
+    /// The address of the pointer which couldn't be read.
+    pub address: lldb_addr_t,
+    /// The error from LLDB, unless the read just came up short.
+    pub error: Option<SBError>,
+}
+
+impl fmt::Display for ChainError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "could not read the pointer at {:#x} for offset {}",
+            self.address, self.hop
+        )?;
+        if let Some(ref error) = self.error {
+            write!(f, ": {}", error)?;
+        }
+        Ok(())
+    }
+}
+
+impl Error for ChainError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        self.error
+            .as_ref()
+            .map(|error| error as &(dyn Error + 'static))
+    }
+}
+
+/// Decode an unsigned integer, such as a pointer, stored in
+/// `byte_order`.
+pub(crate) fn decode_pointer(bytes: &[u8], byte_order: ByteOrder) -> u64 {
+    let fold = |value: u64, &b: &u8| value << 8 | u64::from(b);
+    if byte_order == ByteOrder::Big {
+        bytes.iter().fold(0, fold)
+    } else {
+        bytes.iter().rev().fold(0, fold)
+    }
+}
+
+/// How much memory `dump_memory_to_file` reads at a time.
+const DUMP_CHUNK_SIZE: usize = 1 << 20;
+
+fn format_hexdump(addr: lldb_addr_t, bytes: &[u8]) -> String {
+    let mut out = String::new();
+    for (line, chunk) in bytes.chunks(16).enumerate() {
+        let _ = write!(out, "{:016x} ", addr + line as u64 * 16);
+        for i in 0..16 {
+            if i == 8 {
+                out.push(' ');
+            }
+            match chunk.get(i) {
+                Some(b) => {
+                    let _ = write!(out, " {:02x}", b);
+                }
+                None => out.push_str("   "),
+            }
+        }
+        out.push_str("  |");
+        out.extend(chunk.iter().map(|&b| {
+            if b.is_ascii_graphic() || b == b' ' {
+                b as char
+            } else {
+                '.'
+            }
+        }));
+        out.push_str("|\n");
+    }
+    out
+}
+
 /// Iterate over the [threads] in a [process].
 ///
 /// [threads]: struct.SBThread.html
@@ -382,9 +1336,9 @@ impl<'d> Iterator for SBProcessThreadIter<'d> {
 
     fn next(&mut self) -> Option<SBThread> {
         if self.idx < unsafe { sys::SBProcessGetNumThreads(self.process.raw) as usize } {
-            let r = Some(SBThread::wrap(unsafe {
-                sys::SBProcessGetThreadAtIndex(self.process.raw, self.idx)
-            }));
+            let r = Some(unsafe {
+                SBThread::wrap(sys::SBProcessGetThreadAtIndex(self.process.raw, self.idx))
+            });
             self.idx += 1;
             r
         } else {
@@ -412,9 +1366,9 @@ impl<'d> Iterator for SBProcessQueueIter<'d> {
 
     fn next(&mut self) -> Option<SBQueue> {
         if self.idx < unsafe { sys::SBProcessGetNumQueues(self.process.raw) as usize } {
-            let r = Some(SBQueue::wrap(unsafe {
-                sys::SBProcessGetQueueAtIndex(self.process.raw, self.idx)
-            }));
+            let r = Some(unsafe {
+                SBQueue::wrap(sys::SBProcessGetQueueAtIndex(self.process.raw, self.idx))
+            });
             self.idx += 1;
             r
         } else {
@@ -438,9 +1392,36 @@ impl Clone for SBProcess {
 
 impl fmt::Debug for SBProcess {
     fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
-        let stream = SBStream::new();
-        unsafe { sys::SBProcessGetDescription(self.raw, stream.raw) };
-        write!(fmt, "SBProcess {{ {} }}", stream.data())
+        write!(fmt, "SBProcess {{ {} }}", self.description())
+    }
+}
+
+impl fmt::Display for SBProcess {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "{}", self.description())
+    }
+}
+
+/// Processes are compared by their unique ID, which is unique within a debug session.
+impl PartialEq for SBProcess {
+    fn eq(&self, other: &SBProcess) -> bool {
+        self.unique_id() == other.unique_id()
+    }
+}
+
+impl Eq for SBProcess {}
+
+impl Hash for SBProcess {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.unique_id().hash(state);
+    }
+}
+
+impl TryFrom<sys::SBProcessRef> for SBProcess {
+    type Error = InvalidRefError;
+
+    fn try_from(raw: sys::SBProcessRef) -> Result<SBProcess, InvalidRefError> {
+        SBProcess::maybe_wrap(raw).ok_or(InvalidRefError)
     }
 }
 
@@ -450,6 +1431,22 @@ impl Drop for SBProcess {
     }
 }
 
+fn read_output<F>(read: F) -> String
+where
+    F: Fn(*mut c_char, usize) -> u32,
+{
+    let mut output = Vec::new();
+    let mut buf = [0u8; 1024];
+    loop {
+        let count = read(buf.as_mut_ptr() as *mut c_char, buf.len()) as usize;
+        if count == 0 {
+            break;
+        }
+        output.extend_from_slice(&buf[..count]);
+    }
+    String::from_utf8_lossy(&output).into_owned()
+}
+
 unsafe impl Send for SBProcess {}
 unsafe impl Sync for SBProcess {}
 
@@ -464,12 +1461,38 @@ impl<'e> SBProcessEvent<'e> {
         SBProcessEvent { event }
     }
 
-    pub fn process_state(&self) -> StateType {
+    pub fn state(&self) -> StateType {
         unsafe { sys::SBProcessGetStateFromEvent(self.event.raw) }
     }
 
+    /// The state the process changed to, with the exit status if it
+    /// exited. See [`ProcessState`].
+    ///
+    /// [`ProcessState`]: enum.ProcessState.html
+    pub fn process_state(&self) -> ProcessState {
+        let state = self.state();
+        let exit_status = if state == StateType::Exited {
+            self.process().exit_status()
+        } else {
+            0
+        };
+        ProcessState::new(state, exit_status)
+    }
+
+    /// Why the process exited, if this is the event for it exiting
+    /// because the connection to its remote debug server was lost.
+    /// See [`ConnectionLost`].
+    ///
+    /// [`ConnectionLost`]: struct.ConnectionLost.html
+    pub fn connection_lost(&self) -> Option<ConnectionLost> {
+        if self.state() != StateType::Exited {
+            return None;
+        }
+        ConnectionLost::from_process(&self.process())
+    }
+
     pub fn process(&self) -> SBProcess {
-        SBProcess::wrap(unsafe { sys::SBProcessGetProcessFromEvent(self.event.raw) })
+        unsafe { SBProcess::wrap(sys::SBProcessGetProcessFromEvent(self.event.raw)) }
     }
 
     pub fn interrupted(&self) -> bool {
@@ -497,19 +1520,15 @@ pub struct SBProcessEventRestartedReasonIter<'d> {
 }
 
 impl<'d> Iterator for SBProcessEventRestartedReasonIter<'d> {
-    type Item = &'d str;
+    type Item = String;
 
-    fn next(&mut self) -> Option<&'d str> {
+    fn next(&mut self) -> Option<String> {
         let raw = self.event.event.raw;
         if self.idx < unsafe { sys::SBProcessGetNumRestartedReasonsFromEvent(raw) as usize } {
             let r = unsafe {
-                let s = CStr::from_ptr(sys::SBProcessGetRestartedReasonAtIndexFromEvent(
+                strings::to_string_lossy(sys::SBProcessGetRestartedReasonAtIndexFromEvent(
                     raw, self.idx,
-                ));
-                match s.to_str() {
-                    Ok(s) => s,
-                    _ => panic!("Invalid string?"),
-                }
+                ))
             };
             self.idx += 1;
             Some(r)
@@ -528,59 +1547,92 @@ impl<'d> Iterator for SBProcessEventRestartedReasonIter<'d> {
 impl<'d> ExactSizeIterator for SBProcessEventRestartedReasonIter<'d> {}
 
 #[cfg(feature = "graphql")]
-graphql_object!(SBProcess: super::debugger::SBDebugger | &self | {
-    field is_valid() -> bool {
+#[graphql_object(context = super::debugger::SBDebugger)]
+impl SBProcess {
+    #[graphql(name = "isValid")]
+    fn graphql_is_valid(&self) -> bool {
         self.is_valid()
     }
 
-    field is_alive() -> bool {
+    #[graphql(name = "isAlive")]
+    fn graphql_is_alive(&self) -> bool {
         self.is_alive()
     }
 
-    field is_running() -> bool {
+    #[graphql(name = "isRunning")]
+    fn graphql_is_running(&self) -> bool {
         self.is_running()
     }
 
-    field is_stopped() -> bool {
+    #[graphql(name = "isStopped")]
+    fn graphql_is_stopped(&self) -> bool {
         self.is_stopped()
     }
 
-    field exit_status() -> i32 {
+    #[graphql(name = "exitStatus")]
+    fn graphql_exit_status(&self) -> i32 {
         self.exit_status()
     }
 
-    field exit_description() -> &str {
+    #[graphql(name = "exitDescription")]
+    fn graphql_exit_description(&self) -> Option<String> {
         self.exit_description()
     }
 
-    // TODO(bm): This should be u64
-    field process_id() -> i32 {
-        self.process_id() as i32
+    #[graphql(name = "processId")]
+    fn graphql_process_id(&self) -> U64 {
+        self.process_id().into()
     }
 
-    // TODO(bm) This should be u32
-    field unique_id() -> i32 {
-        self.unique_id() as i32
+    #[graphql(name = "uniqueId")]
+    fn graphql_unique_id(&self) -> U64 {
+        self.unique_id().into()
     }
 
-    // TODO(bm) This should be u32
-    field address_byte_size() -> i32 {
-        self.address_byte_size() as i32
+    #[graphql(name = "addressByteSize")]
+    fn graphql_address_byte_size(&self) -> U64 {
+        self.address_byte_size().into()
     }
 
-    field threads() -> Vec<SBThread> {
+    #[graphql(name = "threads")]
+    fn graphql_threads(&self) -> Vec<SBThread> {
         self.threads().collect()
     }
 
-    field queues() -> Vec<SBQueue> {
+    #[graphql(name = "queues")]
+    fn graphql_queues(&self) -> Vec<SBQueue> {
         self.queues().collect()
     }
 
-    field selected_thread() -> SBThread {
+    #[graphql(name = "selectedThread")]
+    fn graphql_selected_thread(&self) -> Option<SBThread> {
         self.selected_thread()
     }
 
-    field process_info() -> SBProcessInfo {
+    #[graphql(name = "processInfo")]
+    fn graphql_process_info(&self) -> SBProcessInfo {
         self.process_info()
     }
-});
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{decode_pointer, format_hexdump};
+    use ByteOrder;
+
+    #[test]
+    fn pointers() {
+        let bytes = [0x10, 0x20, 0x30, 0x40];
+        assert_eq!(decode_pointer(&bytes, ByteOrder::Little), 0x4030_2010);
+        assert_eq!(decode_pointer(&bytes, ByteOrder::Big), 0x1020_3040);
+    }
+
+    #[test]
+    fn hexdump() {
+        assert_eq!(
+            format_hexdump(0x1000, b"Hello, world!\n\0\0\x7fA"),
+            "0000000000001000  48 65 6c 6c 6f 2c 20 77  6f 72 6c 64 21 0a 00 00  |Hello, world!...|\n\
+             0000000000001010  7f 41                                             |.A|\n"
+        );
+    }
+}
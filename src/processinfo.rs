@@ -5,12 +5,14 @@
 // except according to those terms.
 
 use super::filespec::SBFileSpec;
+#[cfg(feature = "graphql")]
+use super::graphql::U64;
 use super::lldb_pid_t;
-use std::ffi::CStr;
+use super::strings;
+use std::fmt;
 use sys;
 
 #[allow(missing_docs)]
-#[derive(Debug)]
 pub struct SBProcessInfo {
     /// The underlying raw `SBProcessInfoRef`.
     pub raw: sys::SBProcessInfoRef,
@@ -18,23 +20,24 @@ pub struct SBProcessInfo {
 
 impl SBProcessInfo {
     /// Construct a new `SBProcessInfo`.
-    pub fn wrap(raw: sys::SBProcessInfoRef) -> SBProcessInfo {
+    ///
+    /// # Safety
+    ///
+    /// `raw` must be an `SBProcessInfoRef` which isn't used elsewhere,
+    /// such as one from `SBProcessGetProcessInfo`, as it is disposed of
+    /// when the `SBProcessInfo` is dropped.
+    pub unsafe fn wrap(raw: sys::SBProcessInfoRef) -> SBProcessInfo {
         SBProcessInfo { raw }
     }
 
     #[allow(missing_docs)]
-    pub fn name(&self) -> &str {
-        unsafe {
-            match CStr::from_ptr(sys::SBProcessInfoGetName(self.raw)).to_str() {
-                Ok(s) => s,
-                _ => panic!("Invalid string?"),
-            }
-        }
+    pub fn name(&self) -> Option<String> {
+        unsafe { strings::to_string(sys::SBProcessInfoGetName(self.raw)) }
     }
 
     #[allow(missing_docs)]
     pub fn executable_file(&self) -> SBFileSpec {
-        SBFileSpec::wrap(unsafe { sys::SBProcessInfoGetExecutableFile(self.raw) })
+        unsafe { SBFileSpec::wrap(sys::SBProcessInfoGetExecutableFile(self.raw)) }
     }
 
     #[allow(missing_docs)]
@@ -88,6 +91,136 @@ impl SBProcessInfo {
     }
 }
 
+/// A process, from [`SBProcessInfo`] or [`SBPlatform::process_info`].
+///
+/// `lldb-sys` can only give an [`SBProcessInfo`] for a process which is
+/// being debugged, so this holds the same details for any process.
+///
+/// [`SBProcessInfo`]: struct.SBProcessInfo.html
+/// [`SBPlatform::process_info`]: struct.SBPlatform.html#method.process_info
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ProcessInfo {
+    /// The process ID.
+    pub pid: lldb_pid_t,
+    /// The ID of the parent process.
+    pub parent_pid: lldb_pid_t,
+    /// The real user ID.
+    pub user_id: u32,
+    /// The real group ID.
+    pub group_id: u32,
+    /// The effective user ID.
+    pub effective_user_id: u32,
+    /// The effective group ID.
+    pub effective_group_id: u32,
+    /// The name of the program.
+    pub name: String,
+    /// The path of the executable, if it is known.
+    pub executable: Option<String>,
+    /// The arguments, including the program, if they are known. They
+    /// aren't known from an [`SBProcessInfo`], and are split on
+    /// whitespace when read with `ps`.
+    ///
+    /// [`SBProcessInfo`]: struct.SBProcessInfo.html
+    pub arguments: Vec<String>,
+}
+
+impl ProcessInfo {
+    /// Build a `ProcessInfo` from the `status` and `cmdline` files of
+    /// the process in `/proc`.
+    #[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+    pub(crate) fn from_proc(
+        pid: lldb_pid_t,
+        status: &str,
+        cmdline: &[u8],
+        executable: Option<String>,
+    ) -> Option<ProcessInfo> {
+        let field = |name: &str| {
+            status.lines().find_map(|line| {
+                let (key, value) = line.split_once(':')?;
+                if key == name {
+                    Some(value.trim())
+                } else {
+                    None
+                }
+            })
+        };
+        // The real, effective, saved and filesystem IDs.
+        let ids = |name: &str| -> Option<Vec<u32>> {
+            field(name)?
+                .split_whitespace()
+                .map(|id| id.parse().ok())
+                .collect()
+        };
+        let user_ids = ids("Uid")?;
+        let group_ids = ids("Gid")?;
+        let arguments = cmdline
+            .split(|&b| b == 0)
+            .filter(|argument| !argument.is_empty())
+            .map(|argument| String::from_utf8_lossy(argument).into_owned())
+            .collect();
+        Some(ProcessInfo {
+            pid,
+            parent_pid: field("PPid")?.parse().ok()?,
+            user_id: *user_ids.first()?,
+            group_id: *group_ids.first()?,
+            effective_user_id: *user_ids.get(1)?,
+            effective_group_id: *group_ids.get(1)?,
+            name: field("Name")?.to_string(),
+            executable,
+            arguments,
+        })
+    }
+
+    /// Parse a line of
+    /// `ps -o pid= -o ppid= -o ruid= -o rgid= -o uid= -o gid= -o args=`.
+    pub(crate) fn from_ps_line(line: &str) -> Option<ProcessInfo> {
+        let mut fields = line.split_whitespace();
+        let pid = fields.next()?.parse().ok()?;
+        let parent_pid = fields.next()?.parse().ok()?;
+        let user_id = fields.next()?.parse().ok()?;
+        let group_id = fields.next()?.parse().ok()?;
+        let effective_user_id = fields.next()?.parse().ok()?;
+        let effective_group_id = fields.next()?.parse().ok()?;
+        let arguments: Vec<String> = fields.map(str::to_string).collect();
+        let name = arguments
+            .first()
+            .map(|program| program.rsplit('/').next().unwrap_or(program).to_string())
+            .unwrap_or_default();
+        Some(ProcessInfo {
+            pid,
+            parent_pid,
+            user_id,
+            group_id,
+            effective_user_id,
+            effective_group_id,
+            name,
+            executable: None,
+            arguments,
+        })
+    }
+}
+
+impl From<&SBProcessInfo> for ProcessInfo {
+    fn from(info: &SBProcessInfo) -> ProcessInfo {
+        let executable = info.executable_file();
+        ProcessInfo {
+            pid: info.process_id(),
+            parent_pid: info.parent_process_id(),
+            user_id: info.user_id(),
+            group_id: info.group_id(),
+            effective_user_id: info.effective_user_id(),
+            effective_group_id: info.effective_group_id(),
+            name: info.name().unwrap_or_default(),
+            executable: if executable.is_valid() {
+                Some(executable.path())
+            } else {
+                None
+            },
+            arguments: vec![],
+        }
+    }
+}
+
 impl Clone for SBProcessInfo {
     fn clone(&self) -> SBProcessInfo {
         SBProcessInfo {
@@ -96,6 +229,16 @@ impl Clone for SBProcessInfo {
     }
 }
 
+impl fmt::Debug for SBProcessInfo {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.debug_struct("SBProcessInfo")
+            .field("name", &self.name())
+            .field("process_id", &self.process_id())
+            .field("parent_process_id", &self.parent_process_id())
+            .finish()
+    }
+}
+
 impl Drop for SBProcessInfo {
     fn drop(&mut self) {
         unsafe { sys::DisposeSBProcessInfo(self.raw) };
@@ -106,58 +249,128 @@ unsafe impl Send for SBProcessInfo {}
 unsafe impl Sync for SBProcessInfo {}
 
 #[cfg(feature = "graphql")]
-graphql_object!(SBProcessInfo: super::debugger::SBDebugger | &self | {
-    field name() -> &str {
+#[graphql_object(context = super::debugger::SBDebugger)]
+impl SBProcessInfo {
+    #[graphql(name = "name")]
+    fn graphql_name(&self) -> Option<String> {
         self.name()
     }
 
-    field executable_file() -> SBFileSpec {
+    #[graphql(name = "executableFile")]
+    fn graphql_executable_file(&self) -> SBFileSpec {
         self.executable_file()
     }
 
-    // TODO(bm) This should be lldb_pid_t
-    field process_id() -> i32 {
-        self.process_id() as i32
+    #[graphql(name = "processId")]
+    fn graphql_process_id(&self) -> U64 {
+        self.process_id().into()
     }
 
-    // TODO(bm) This should be u32
-    field user_id() -> i32 {
-        self.user_id() as i32
+    #[graphql(name = "userId")]
+    fn graphql_user_id(&self) -> U64 {
+        self.user_id().into()
     }
 
-    // TODO(bm) This should be u32
-    field group_id() -> i32 {
-        self.group_id() as i32
+    #[graphql(name = "groupId")]
+    fn graphql_group_id(&self) -> U64 {
+        self.group_id().into()
     }
 
-    field user_id_is_valid() -> bool {
+    #[graphql(name = "userIdIsValid")]
+    fn graphql_user_id_is_valid(&self) -> bool {
         self.user_id_is_valid()
     }
 
-    field group_id_is_valid() -> bool {
+    #[graphql(name = "groupIdIsValid")]
+    fn graphql_group_id_is_valid(&self) -> bool {
         self.group_id_is_valid()
     }
 
-    // TODO(bm) This should be u32
-    field effective_user_id() -> i32 {
-        self.effective_user_id() as i32
+    #[graphql(name = "effectiveUserId")]
+    fn graphql_effective_user_id(&self) -> U64 {
+        self.effective_user_id().into()
     }
 
-    // TODO(bm) This should be u32
-    field effective_group_id() -> i32 {
-        self.effective_group_id() as i32
+    #[graphql(name = "effectiveGroupId")]
+    fn graphql_effective_group_id(&self) -> U64 {
+        self.effective_group_id().into()
     }
 
-    field effective_user_id_is_valid() -> bool {
+    #[graphql(name = "effectiveUserIdIsValid")]
+    fn graphql_effective_user_id_is_valid(&self) -> bool {
         self.effective_user_id_is_valid()
     }
 
-    field effective_group_id_is_valid() -> bool {
+    #[graphql(name = "effectiveGroupIdIsValid")]
+    fn graphql_effective_group_id_is_valid(&self) -> bool {
         self.effective_group_id_is_valid()
     }
 
-    // TODO(bm) This should be lldb_pid_t
-    field parent_process_id() -> i32 {
-        self.parent_process_id() as i32
+    #[graphql(name = "parentProcessId")]
+    fn graphql_parent_process_id(&self) -> U64 {
+        self.parent_process_id().into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ProcessInfo;
+
+    #[test]
+    fn reads_proc_files() {
+        let status = "Name:\tserver\nState:\tS (sleeping)\nPid:\t4242\nPPid:\t1\n\
+                      Uid:\t1000\t0\t1000\t1000\nGid:\t1000\t1000\t1000\t1000\n";
+        assert_eq!(
+            ProcessInfo::from_proc(
+                4242,
+                status,
+                b"/usr/bin/server\0--name\0my server\0",
+                Some("/usr/bin/server".to_string()),
+            ),
+            Some(ProcessInfo {
+                pid: 4242,
+                parent_pid: 1,
+                user_id: 1000,
+                group_id: 1000,
+                effective_user_id: 0,
+                effective_group_id: 1000,
+                name: "server".to_string(),
+                executable: Some("/usr/bin/server".to_string()),
+                arguments: vec![
+                    "/usr/bin/server".to_string(),
+                    "--name".to_string(),
+                    "my server".to_string(),
+                ],
+            })
+        );
+        assert_eq!(
+            ProcessInfo::from_proc(4242, "Name:\tserver\n", b"", None),
+            None
+        );
+    }
+
+    #[test]
+    fn parses_ps_lines() {
+        assert_eq!(
+            ProcessInfo::from_ps_line(
+                "  4242     1  1000  1000     0  1000 /usr/bin/server --port 8080"
+            ),
+            Some(ProcessInfo {
+                pid: 4242,
+                parent_pid: 1,
+                user_id: 1000,
+                group_id: 1000,
+                effective_user_id: 0,
+                effective_group_id: 1000,
+                name: "server".to_string(),
+                executable: None,
+                arguments: vec![
+                    "/usr/bin/server".to_string(),
+                    "--port".to_string(),
+                    "8080".to_string(),
+                ],
+            })
+        );
+        assert_eq!(ProcessInfo::from_ps_line("  PID  PPID"), None);
     }
-});
+}
@@ -0,0 +1,189 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use super::error::SBError;
+use super::filespec::SBFileSpec;
+use super::process::SBProcess;
+use super::strings;
+use super::structureddata::SBStructuredData;
+use super::StructuredDataType;
+use std::path::Path;
+
+/// Processor tracing, such as Intel PT, of a process or of one of its
+/// threads, started by [`SBProcess::start_trace`] or
+/// [`SBThread::start_trace`].
+///
+/// ```no_run
+/// # use lldb::{SBError, SBProcess, SBStructuredData};
+/// # fn trace(process: &SBProcess) -> Result<(), SBError> {
+/// let config = SBStructuredData::from_json_str(r#"{"iptTraceSize": 8192, "enableTsc": true}"#)?;
+/// let trace = process.start_trace(&config)?;
+/// process.continue_execution()?;
+/// // ... wait for the process to stop ...
+/// let description = trace.save("/tmp/trace", false)?;
+/// trace.stop()?;
+/// # Ok(())
+/// # }
+/// ```
+///
+/// `SBTrace` is newer than the version of LLDB that these bindings are
+/// built against, so this uses the `process trace` and `thread trace`
+/// commands instead, which need LLDB 15 or later, and the `lldb-15`
+/// feature.
+///
+/// The configuration is a dictionary with the same keys as
+/// `SBTrace::Start` takes for Intel PT:
+///
+/// * `iptTraceSize`: the size of the trace buffer of each thread or
+///   CPU, in bytes.
+/// * `enableTsc`: whether to record timestamps.
+/// * `psbPeriod`: how often to emit synchronization packets.
+/// * `perCpuTracing`: trace each CPU rather than each thread. Only for
+///   a process.
+/// * `processBufferSizeLimit`: the limit on the total size of the
+///   trace buffers of a process, in bytes. Only for a process.
+/// * `disableCgroupFiltering`: trace every process in the cgroup when
+///   tracing each CPU. Only for a process.
+///
+/// [`SBProcess::start_trace`]: struct.SBProcess.html#method.start_trace
+/// [`SBThread::start_trace`]: struct.SBThread.html#method.start_trace
+pub struct ProcessorTrace {
+    process: SBProcess,
+    thread: Option<u32>,
+}
+
+impl ProcessorTrace {
+    /// Start tracing `process`, or the thread with the index ID
+    /// `thread` if there is one.
+    pub(crate) fn start(
+        process: &SBProcess,
+        thread: Option<u32>,
+        config: &SBStructuredData,
+    ) -> Result<ProcessorTrace, SBError> {
+        let trace = ProcessorTrace {
+            process: process.clone(),
+            thread,
+        };
+        let mut command = trace.command("start");
+        for option in start_options(config)? {
+            command.push(' ');
+            command.push_str(&option);
+        }
+        trace.run(&command)?;
+        Ok(trace)
+    }
+
+    /// Stop tracing.
+    pub fn stop(&self) -> Result<(), SBError> {
+        self.run(&self.command("stop")).map(|_| ())
+    }
+
+    /// Save the trace of the process to `directory` as a trace bundle,
+    /// returning its description file, which can be loaded again with
+    /// `trace load`.
+    ///
+    /// A compact bundle leaves out the parts of the trace buffers
+    /// which belong to other processes when tracing each CPU.
+    ///
+    /// The trace of every traced thread of the process is saved, even
+    /// when this is the trace of one thread.
+    pub fn save(&self, directory: &str, compact: bool) -> Result<SBFileSpec, SBError> {
+        let mut command = "trace save".to_string();
+        if compact {
+            command.push_str(" --compact");
+        }
+        command.push(' ');
+        command.push_str(&strings::quote_argument(directory));
+        self.run(&command)?;
+        let description = Path::new(directory).join("trace.json");
+        Ok(SBFileSpec::from_path(&description.to_string_lossy(), false))
+    }
+
+    /// The `process trace` or `thread trace` command `action`.
+    fn command(&self, action: &str) -> String {
+        match self.thread {
+            Some(index_id) => format!("thread trace {} {}", action, index_id),
+            None => format!("process trace {}", action),
+        }
+    }
+
+    fn run(&self, command: &str) -> Result<String, SBError> {
+        self.process.target().run_command(command)
+    }
+}
+
+/// The options of the `trace start` commands for the `SBTrace::Start`
+/// configuration `config`.
+fn start_options(config: &SBStructuredData) -> Result<Vec<String>, SBError> {
+    if !config.is_valid() || config.data_type() == StructuredDataType::Null {
+        return Ok(vec![]);
+    }
+    let keys = config
+        .keys()
+        .ok_or_else(|| SBError::from_message("the trace configuration must be a dictionary"))?;
+    let mut options = vec![];
+    for key in keys.iter() {
+        let value = config.value_for_key(&key).unwrap();
+        let option = match start_option(&key) {
+            Some(StartOption::Flag(flag)) => match value.boolean_value() {
+                Some(true) => flag.to_string(),
+                Some(false) => continue,
+                None => return Err(SBError::from_message(&format!("{} must be a boolean", key))),
+            },
+            Some(StartOption::Integer(option)) => match value.integer_value() {
+                Some(n) => format!("{} {}", option, n),
+                None => {
+                    return Err(SBError::from_message(&format!(
+                        "{} must be an integer",
+                        key
+                    )))
+                }
+            },
+            None => {
+                return Err(SBError::from_message(&format!(
+                    "unknown trace configuration key {}",
+                    key
+                )))
+            }
+        };
+        options.push(option);
+    }
+    Ok(options)
+}
+
+#[derive(Debug, PartialEq)]
+enum StartOption {
+    Flag(&'static str),
+    Integer(&'static str),
+}
+
+/// The command option for the `SBTrace::Start` configuration key `key`.
+fn start_option(key: &str) -> Option<StartOption> {
+    match key {
+        "iptTraceSize" => Some(StartOption::Integer("--buffer-size")),
+        "enableTsc" => Some(StartOption::Flag("--tsc")),
+        "psbPeriod" => Some(StartOption::Integer("--psb-period")),
+        "perCpuTracing" => Some(StartOption::Flag("--per-cpu-tracing")),
+        "processBufferSizeLimit" => Some(StartOption::Integer("--total-size-limit")),
+        "disableCgroupFiltering" => Some(StartOption::Flag("--disable-cgroup-filtering")),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{start_option, StartOption};
+
+    #[test]
+    fn maps_configuration_keys_to_options() {
+        assert_eq!(
+            start_option("iptTraceSize"),
+            Some(StartOption::Integer("--buffer-size"))
+        );
+        assert_eq!(start_option("enableTsc"), Some(StartOption::Flag("--tsc")));
+        assert_eq!(start_option("iptTraceSizeInBytes"), None);
+    }
+}
@@ -0,0 +1,189 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use super::StateType;
+use std::fmt;
+
+/// The state of a process, with the exit status of a process which
+/// has exited.
+///
+/// This is [`StateType`] in a form which suits `match`, from
+/// [`SBProcess::process_state`] or [`SBProcessEvent::state`]:
+///
+/// ```no_run
+/// # use lldb::{ProcessState, SBProcess};
+/// # fn report(process: &SBProcess) {
+/// match process.process_state() {
+///     ProcessState::Stopped | ProcessState::Crashed => println!("stopped"),
+///     ProcessState::Running | ProcessState::Stepping => println!("running"),
+///     ProcessState::Exited { code } => println!("exited with status {}", code),
+///     state => println!("{}", state),
+/// }
+/// # }
+/// ```
+///
+/// [`StateType`]: enum.StateType.html
+/// [`SBProcess::process_state`]: struct.SBProcess.html#method.process_state
+/// [`SBProcessEvent::state`]: struct.SBProcessEvent.html#method.state
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ProcessState {
+    /// There is no process.
+    Invalid,
+    /// The process object is valid, but nothing is loaded.
+    Unloaded,
+    /// Connected to a remote debug server, but nothing has been
+    /// launched or attached to.
+    Connected,
+    /// Attaching to the process.
+    Attaching,
+    /// Launching the process.
+    Launching,
+    /// Stopped, and can be examined.
+    Stopped,
+    /// Running, and can't be examined.
+    Running,
+    /// Stepping, and can't be examined.
+    Stepping,
+    /// Crashed, and can be examined.
+    Crashed,
+    /// Detached from, and can't be examined.
+    Detached,
+    /// Exited, and can't be examined.
+    Exited {
+        /// The exit status.
+        code: i32,
+    },
+    /// Held by the debugger while other processes run, and can be
+    /// examined.
+    Suspended,
+}
+
+impl ProcessState {
+    /// The `ProcessState` for `state`, taking the exit status of an
+    /// exited process from `exit_status`.
+    pub fn new(state: StateType, exit_status: i32) -> ProcessState {
+        match state {
+            StateType::Invalid => ProcessState::Invalid,
+            StateType::Unloaded => ProcessState::Unloaded,
+            StateType::Connected => ProcessState::Connected,
+            StateType::Attaching => ProcessState::Attaching,
+            StateType::Launching => ProcessState::Launching,
+            StateType::Stopped => ProcessState::Stopped,
+            StateType::Running => ProcessState::Running,
+            StateType::Stepping => ProcessState::Stepping,
+            StateType::Crashed => ProcessState::Crashed,
+            StateType::Detached => ProcessState::Detached,
+            StateType::Exited => ProcessState::Exited { code: exit_status },
+            StateType::Suspended => ProcessState::Suspended,
+        }
+    }
+
+    /// The `StateType` for this state.
+    pub fn state_type(self) -> StateType {
+        match self {
+            ProcessState::Invalid => StateType::Invalid,
+            ProcessState::Unloaded => StateType::Unloaded,
+            ProcessState::Connected => StateType::Connected,
+            ProcessState::Attaching => StateType::Attaching,
+            ProcessState::Launching => StateType::Launching,
+            ProcessState::Stopped => StateType::Stopped,
+            ProcessState::Running => StateType::Running,
+            ProcessState::Stepping => StateType::Stepping,
+            ProcessState::Crashed => StateType::Crashed,
+            ProcessState::Detached => StateType::Detached,
+            ProcessState::Exited { .. } => StateType::Exited,
+            ProcessState::Suspended => StateType::Suspended,
+        }
+    }
+
+    /// Is the process alive? This is true from when it starts
+    /// launching or attaching until it exits or is detached from.
+    pub fn is_alive(self) -> bool {
+        matches!(
+            self,
+            ProcessState::Attaching
+                | ProcessState::Launching
+                | ProcessState::Stopped
+                | ProcessState::Running
+                | ProcessState::Stepping
+                | ProcessState::Crashed
+                | ProcessState::Suspended
+        )
+    }
+
+    /// Is the process running or stepping?
+    pub fn is_running(self) -> bool {
+        matches!(self, ProcessState::Running | ProcessState::Stepping)
+    }
+
+    /// Is the process stopped, so that it can be examined?
+    pub fn is_stopped(self) -> bool {
+        matches!(
+            self,
+            ProcessState::Stopped | ProcessState::Crashed | ProcessState::Suspended
+        )
+    }
+
+    /// Has the debugger finished with the process, because it exited
+    /// or was detached from? A process doesn't leave these states.
+    pub fn is_finished(self) -> bool {
+        matches!(self, ProcessState::Exited { .. } | ProcessState::Detached)
+    }
+
+    /// The exit status, if the process has exited.
+    pub fn exit_code(self) -> Option<i32> {
+        match self {
+            ProcessState::Exited { code } => Some(code),
+            _ => None,
+        }
+    }
+}
+
+impl From<ProcessState> for StateType {
+    fn from(state: ProcessState) -> StateType {
+        state.state_type()
+    }
+}
+
+impl fmt::Display for ProcessState {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ProcessState::Invalid => write!(f, "invalid"),
+            ProcessState::Unloaded => write!(f, "unloaded"),
+            ProcessState::Connected => write!(f, "connected"),
+            ProcessState::Attaching => write!(f, "attaching"),
+            ProcessState::Launching => write!(f, "launching"),
+            ProcessState::Stopped => write!(f, "stopped"),
+            ProcessState::Running => write!(f, "running"),
+            ProcessState::Stepping => write!(f, "stepping"),
+            ProcessState::Crashed => write!(f, "crashed"),
+            ProcessState::Detached => write!(f, "detached"),
+            ProcessState::Exited { code } => write!(f, "exited with status {}", code),
+            ProcessState::Suspended => write!(f, "suspended"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ProcessState;
+    use StateType;
+
+    #[test]
+    fn round_trips_state_types() {
+        let exited = ProcessState::new(StateType::Exited, 3);
+        assert_eq!(exited, ProcessState::Exited { code: 3 });
+        assert_eq!(exited.state_type(), StateType::Exited);
+        assert_eq!(exited.exit_code(), Some(3));
+        assert!(exited.is_finished());
+        assert!(!exited.is_alive());
+
+        let stopped = ProcessState::new(StateType::Stopped, 0);
+        assert_eq!(stopped.state_type(), StateType::Stopped);
+        assert!(stopped.is_alive() && stopped.is_stopped() && !stopped.is_running());
+        assert_eq!(stopped.exit_code(), None);
+    }
+}
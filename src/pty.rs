@@ -0,0 +1,160 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use std::ffi::CStr;
+use std::fs::File;
+use std::io;
+use std::os::unix::io::{AsRawFd, FromRawFd, RawFd};
+
+/// A pseudo-terminal for a process to be launched with, so that it
+/// sees a terminal as its standard input and output.
+///
+/// Programs behave differently without a terminal: output is buffered
+/// in blocks rather than lines, `isatty` checks fail, and anything
+/// which sets up the terminal with `termios` gives up. Launching with
+/// a pseudo-terminal, through [`SBLaunchInfo::set_pseudo_terminal`],
+/// makes them behave as they do when run from a shell. The process
+/// reads and writes the terminal, and the debugger reads what it
+/// writes from, and sends it input through, the [`master`] side:
+///
+/// ```no_run
+/// # use lldb::{PseudoTerminal, SBLaunchInfo, SBTarget};
+/// # use std::io::{BufRead, BufReader};
+/// # fn launch(target: &SBTarget) -> std::io::Result<()> {
+/// let pty = PseudoTerminal::open()?;
+/// let launch_info = SBLaunchInfo::new();
+/// launch_info.set_pseudo_terminal(&pty);
+/// target.launch(launch_info).unwrap();
+///
+/// for line in BufReader::new(pty.into_master()).lines() {
+///     println!("process: {}", line?);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+///
+/// Reading the master side gives an error rather than the end of the
+/// file once the process has exited and closed the terminal. For use
+/// with an async runtime, the master side can be made non-blocking
+/// with [`set_nonblocking`] and registered with the runtime by its
+/// file descriptor.
+///
+/// [`SBLaunchInfo::set_pseudo_terminal`]: struct.SBLaunchInfo.html#method.set_pseudo_terminal
+/// [`master`]: #method.master
+/// [`set_nonblocking`]: #method.set_nonblocking
+#[derive(Debug)]
+pub struct PseudoTerminal {
+    master: File,
+    slave_path: String,
+}
+
+impl PseudoTerminal {
+    /// Open a new pseudo-terminal.
+    pub fn open() -> io::Result<PseudoTerminal> {
+        unsafe {
+            let fd = libc::posix_openpt(libc::O_RDWR | libc::O_NOCTTY);
+            if fd < 0 {
+                return Err(io::Error::last_os_error());
+            }
+            // Owning the file descriptor closes it if anything below
+            // fails.
+            let master = File::from_raw_fd(fd);
+            // The process shouldn't inherit the master side, or the
+            // terminal would never be closed.
+            if libc::fcntl(fd, libc::F_SETFD, libc::FD_CLOEXEC) < 0
+                || libc::grantpt(fd) < 0
+                || libc::unlockpt(fd) < 0
+            {
+                return Err(io::Error::last_os_error());
+            }
+            let slave_path = slave_path(fd)?;
+            Ok(PseudoTerminal { master, slave_path })
+        }
+    }
+
+    /// The master side, which reads what the process writes to the
+    /// terminal, and sends it input.
+    pub fn master(&self) -> &File {
+        &self.master
+    }
+
+    /// The master side, for reading and writing it elsewhere, such as
+    /// on another thread.
+    pub fn into_master(self) -> File {
+        self.master
+    }
+
+    /// The path of the terminal device which the process opens, such
+    /// as `/dev/pts/4`.
+    pub fn slave_path(&self) -> &str {
+        &self.slave_path
+    }
+
+    /// Set the size of the terminal, in characters, as a terminal
+    /// emulator does when its window is resized.
+    pub fn set_window_size(&self, rows: u16, columns: u16) -> io::Result<()> {
+        let size = libc::winsize {
+            ws_row: rows,
+            ws_col: columns,
+            ws_xpixel: 0,
+            ws_ypixel: 0,
+        };
+        if unsafe { libc::ioctl(self.master.as_raw_fd(), libc::TIOCSWINSZ, &size) } < 0 {
+            Err(io::Error::last_os_error())
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Make reading and writing the master side return
+    /// `io::ErrorKind::WouldBlock` rather than waiting.
+    pub fn set_nonblocking(&self, nonblocking: bool) -> io::Result<()> {
+        let fd = self.master.as_raw_fd();
+        unsafe {
+            let flags = libc::fcntl(fd, libc::F_GETFL);
+            if flags < 0 {
+                return Err(io::Error::last_os_error());
+            }
+            let flags = if nonblocking {
+                flags | libc::O_NONBLOCK
+            } else {
+                flags & !libc::O_NONBLOCK
+            };
+            if libc::fcntl(fd, libc::F_SETFL, flags) < 0 {
+                return Err(io::Error::last_os_error());
+            }
+        }
+        Ok(())
+    }
+}
+
+impl AsRawFd for PseudoTerminal {
+    fn as_raw_fd(&self) -> RawFd {
+        self.master.as_raw_fd()
+    }
+}
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+unsafe fn slave_path(fd: RawFd) -> io::Result<String> {
+    let mut buffer: [libc::c_char; 128] = [0; 128];
+    let error = libc::ptsname_r(fd, buffer.as_mut_ptr(), buffer.len());
+    if error != 0 {
+        return Err(io::Error::from_raw_os_error(error));
+    }
+    Ok(CStr::from_ptr(buffer.as_ptr())
+        .to_string_lossy()
+        .into_owned())
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "android")))]
+unsafe fn slave_path(fd: RawFd) -> io::Result<String> {
+    // `ptsname` uses a static buffer, so it is only read from here.
+    let name = libc::ptsname(fd);
+    if name.is_null() {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(CStr::from_ptr(name).to_string_lossy().into_owned())
+}
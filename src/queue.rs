@@ -4,10 +4,15 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
+use super::error::InvalidRefError;
+#[cfg(feature = "graphql")]
+use super::graphql::U64;
 use super::process::SBProcess;
 use super::queueitem::SBQueueItem;
+use super::strings;
 use super::thread::SBThread;
-use std::ffi::CStr;
+use std::convert::TryFrom;
+use std::fmt;
 use sys;
 
 /// A `libdispatch` (aka Grand Central Dispatch) queue.
@@ -44,14 +49,24 @@ pub struct SBQueue {
 
 impl SBQueue {
     /// Construct a new `SBQueue`.
-    pub fn wrap(raw: sys::SBQueueRef) -> SBQueue {
+    ///
+    /// # Safety
+    ///
+    /// `raw` must be a valid reference, which is disposed of when the
+    /// `SBQueue` is dropped. Use [`maybe_wrap`] when it may be invalid.
+    ///
+    /// [`maybe_wrap`]: #method.maybe_wrap
+    pub unsafe fn wrap(raw: sys::SBQueueRef) -> SBQueue {
         SBQueue { raw }
     }
 
     /// Construct a new `Some(SBQueue)` or `None`.
+    ///
+    /// `raw` is disposed of if it is invalid.
     pub fn maybe_wrap(raw: sys::SBQueueRef) -> Option<SBQueue> {
-        if unsafe { sys::SBQueueIsValid(raw) != 0 } {
-            Some(SBQueue { raw })
+        let wrapped = SBQueue { raw };
+        if wrapped.is_valid() {
+            Some(wrapped)
         } else {
             None
         }
@@ -62,9 +77,9 @@ impl SBQueue {
         unsafe { sys::SBQueueIsValid(self.raw) != 0 }
     }
 
-    #[allow(missing_docs)]
-    pub fn process(&self) -> SBProcess {
-        SBProcess::wrap(unsafe { sys::SBQueueGetProcess(self.raw) })
+    /// The process this queue belongs to, if it still exists.
+    pub fn process(&self) -> Option<SBProcess> {
+        SBProcess::maybe_wrap(unsafe { sys::SBQueueGetProcess(self.raw) })
     }
 
     /// Returns a unique identifying number for this queue that will not
@@ -77,13 +92,8 @@ impl SBQueue {
     }
 
     /// The name of this queue.
-    pub fn name(&self) -> &str {
-        unsafe {
-            match CStr::from_ptr(sys::SBQueueGetName(self.raw)).to_str() {
-                Ok(s) => s,
-                _ => panic!("Invalid string?"),
-            }
-        }
+    pub fn name(&self) -> Option<String> {
+        unsafe { strings::to_string(sys::SBQueueGetName(self.raw)) }
     }
 
     /// Get an iterator over the [threads] associated with this queue.
@@ -128,6 +138,24 @@ impl Clone for SBQueue {
     }
 }
 
+impl fmt::Debug for SBQueue {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.debug_struct("SBQueue")
+            .field("queue_id", &self.queue_id())
+            .field("name", &self.name())
+            .field("kind", &self.kind())
+            .finish()
+    }
+}
+
+impl TryFrom<sys::SBQueueRef> for SBQueue {
+    type Error = InvalidRefError;
+
+    fn try_from(raw: sys::SBQueueRef) -> Result<SBQueue, InvalidRefError> {
+        SBQueue::maybe_wrap(raw).ok_or(InvalidRefError)
+    }
+}
+
 impl Drop for SBQueue {
     fn drop(&mut self) {
         unsafe { sys::DisposeSBQueue(self.raw) };
@@ -151,9 +179,12 @@ impl<'d> Iterator for SBQueueThreadIter<'d> {
 
     fn next(&mut self) -> Option<SBThread> {
         if self.idx < unsafe { sys::SBQueueGetNumThreads(self.queue.raw) as usize } {
-            let r = Some(SBThread::wrap(unsafe {
-                sys::SBQueueGetThreadAtIndex(self.queue.raw, self.idx as u32)
-            }));
+            let r = Some(unsafe {
+                SBThread::wrap(sys::SBQueueGetThreadAtIndex(
+                    self.queue.raw,
+                    self.idx as u32,
+                ))
+            });
             self.idx += 1;
             r
         } else {
@@ -183,9 +214,12 @@ impl<'d> Iterator for SBQueueQueueItemIter<'d> {
 
     fn next(&mut self) -> Option<SBQueueItem> {
         if self.idx < unsafe { sys::SBQueueGetNumPendingItems(self.queue.raw) as usize } {
-            let r = Some(SBQueueItem::wrap(unsafe {
-                sys::SBQueueGetPendingItemAtIndex(self.queue.raw, self.idx as u32)
-            }));
+            let r = Some(unsafe {
+                SBQueueItem::wrap(sys::SBQueueGetPendingItemAtIndex(
+                    self.queue.raw,
+                    self.idx as u32,
+                ))
+            });
             self.idx += 1;
             r
         } else {
@@ -202,30 +236,35 @@ impl<'d> Iterator for SBQueueQueueItemIter<'d> {
 impl<'d> ExactSizeIterator for SBQueueQueueItemIter<'d> {}
 
 #[cfg(feature = "graphql")]
-graphql_object!(SBQueue: super::debugger::SBDebugger | &self | {
-    field is_valid() -> bool {
+#[graphql_object(context = super::debugger::SBDebugger)]
+impl SBQueue {
+    #[graphql(name = "isValid")]
+    fn graphql_is_valid(&self) -> bool {
         self.is_valid()
     }
 
-    // TODO(bm) This should be u64
-    field queue_id() -> i32 {
-        self.queue_id() as i32
+    #[graphql(name = "queueId")]
+    fn graphql_queue_id(&self) -> U64 {
+        self.queue_id().into()
     }
 
-    field name() -> &str {
+    #[graphql(name = "name")]
+    fn graphql_name(&self) -> Option<String> {
         self.name()
     }
 
-    field threads() -> Vec<SBThread> {
+    #[graphql(name = "threads")]
+    fn graphql_threads(&self) -> Vec<SBThread> {
         self.threads().collect()
     }
 
-    field pending_items() -> Vec<SBQueueItem> {
+    #[graphql(name = "pendingItems")]
+    fn graphql_pending_items(&self) -> Vec<SBQueueItem> {
         self.pending_items().collect()
     }
 
-    // TODO(bm) This should be u32
-    field num_running_items() -> i32 {
-        self.num_running_items() as i32
+    #[graphql(name = "numRunningItems")]
+    fn graphql_num_running_items(&self) -> U64 {
+        self.num_running_items().into()
     }
-});
+}
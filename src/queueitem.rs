@@ -5,8 +5,11 @@
 // except according to those terms.
 
 use super::address::SBAddress;
+use super::error::InvalidRefError;
 use super::thread::SBThread;
+use std::convert::TryFrom;
 use std::ffi::CString;
+use std::fmt;
 use sys;
 
 /// A work item enqueued on a libdispatch aka Grand Central
@@ -24,14 +27,24 @@ pub struct SBQueueItem {
 
 impl SBQueueItem {
     /// Construct a new `SBQueueItem`.
-    pub fn wrap(raw: sys::SBQueueItemRef) -> SBQueueItem {
+    ///
+    /// # Safety
+    ///
+    /// `raw` must be a valid reference, which is disposed of when the
+    /// `SBQueueItem` is dropped. Use [`maybe_wrap`] when it may be invalid.
+    ///
+    /// [`maybe_wrap`]: #method.maybe_wrap
+    pub unsafe fn wrap(raw: sys::SBQueueItemRef) -> SBQueueItem {
         SBQueueItem { raw }
     }
 
     /// Construct a new `Some(SBQueueItem)` or `None`.
+    ///
+    /// `raw` is disposed of if it is invalid.
     pub fn maybe_wrap(raw: sys::SBQueueItemRef) -> Option<SBQueueItem> {
-        if unsafe { sys::SBQueueItemIsValid(raw) != 0 } {
-            Some(SBQueueItem { raw })
+        let wrapped = SBQueueItem { raw };
+        if wrapped.is_valid() {
+            Some(wrapped)
         } else {
             None
         }
@@ -80,6 +93,23 @@ impl Clone for SBQueueItem {
     }
 }
 
+impl fmt::Debug for SBQueueItem {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.debug_struct("SBQueueItem")
+            .field("kind", &self.kind())
+            .field("address", &self.address())
+            .finish()
+    }
+}
+
+impl TryFrom<sys::SBQueueItemRef> for SBQueueItem {
+    type Error = InvalidRefError;
+
+    fn try_from(raw: sys::SBQueueItemRef) -> Result<SBQueueItem, InvalidRefError> {
+        SBQueueItem::maybe_wrap(raw).ok_or(InvalidRefError)
+    }
+}
+
 impl Drop for SBQueueItem {
     fn drop(&mut self) {
         unsafe { sys::DisposeSBQueueItem(self.raw) };
@@ -90,8 +120,10 @@ unsafe impl Send for SBQueueItem {}
 unsafe impl Sync for SBQueueItem {}
 
 #[cfg(feature = "graphql")]
-graphql_object!(SBQueueItem: super::debugger::SBDebugger | &self | {
-    field is_valid() -> bool {
+#[graphql_object(context = super::debugger::SBDebugger)]
+impl SBQueueItem {
+    #[graphql(name = "isValid")]
+    fn graphql_is_valid(&self) -> bool {
         self.is_valid()
     }
-});
+}
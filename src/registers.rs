@@ -0,0 +1,191 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use super::frame::SBFrame;
+use super::lldb_addr_t;
+#[cfg(feature = "snapshots")]
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// The values of the registers of a frame, taken with
+/// [`SBFrame::register_snapshot`] or [`SBThread::register_snapshot`].
+///
+/// Compare two snapshots with [`diff`] to find out what an
+/// instruction changed:
+///
+/// ```no_run
+/// # use lldb::{SBError, SBThread};
+/// # fn step(thread: &SBThread) -> Result<(), SBError> {
+/// let before = thread.register_snapshot();
+/// thread.step_instruction(false)?;
+/// for change in before.diff(&thread.register_snapshot()) {
+///     println!("{}: {:?} -> {:?}", change.name, change.old, change.new);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+///
+/// [`SBFrame::register_snapshot`]: struct.SBFrame.html#method.register_snapshot
+/// [`SBThread::register_snapshot`]: struct.SBThread.html#method.register_snapshot
+/// [`diff`]: #method.diff
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "snapshots", derive(Serialize, Deserialize))]
+pub struct RegisterSnapshot {
+    /// The program counter of the frame.
+    pub pc: lldb_addr_t,
+    /// The registers, in the order that LLDB lists them.
+    pub registers: Vec<RegisterValue>,
+}
+
+/// The value of one register in a [`RegisterSnapshot`].
+///
+/// [`RegisterSnapshot`]: struct.RegisterSnapshot.html
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "snapshots", derive(Serialize, Deserialize))]
+pub struct RegisterValue {
+    /// The name of the register set, such as
+    /// `General Purpose Registers`.
+    pub set: String,
+    /// The name of the register, such as `rax`.
+    pub name: String,
+    /// The value, formatted by LLDB, if it could be read.
+    pub value: Option<String>,
+}
+
+/// A register whose value differs between two snapshots.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RegisterChange {
+    /// The name of the register.
+    pub name: String,
+    /// The value in the earlier snapshot, or `None` if it wasn't
+    /// present or couldn't be read.
+    pub old: Option<String>,
+    /// The value in the later snapshot, or `None` if it wasn't
+    /// present or couldn't be read.
+    pub new: Option<String>,
+}
+
+impl RegisterSnapshot {
+    /// Capture every register set of `frame`.
+    pub fn capture(frame: &SBFrame) -> RegisterSnapshot {
+        let mut registers = Vec::new();
+        for set in frame.registers().iter() {
+            let set_name = set.name().unwrap_or_default();
+            registers.extend(
+                (0..set.num_children())
+                    .filter_map(|idx| set.child_at_index(idx))
+                    .map(|register| RegisterValue {
+                        set: set_name.clone(),
+                        name: register.name().unwrap_or_default(),
+                        value: register.value(),
+                    }),
+            );
+        }
+        RegisterSnapshot {
+            pc: frame.pc(),
+            registers,
+        }
+    }
+
+    /// The value of the register `name`, if it was captured and could
+    /// be read.
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.registers
+            .iter()
+            .find(|register| register.name == name)
+            .and_then(|register| register.value.as_deref())
+    }
+
+    /// The registers in the first register set, which LLDB always
+    /// lists as the general purpose registers.
+    pub fn general_purpose<'a>(&'a self) -> impl Iterator<Item = &'a RegisterValue> + 'a {
+        let set = self.registers.first().map(|register| register.set.as_str());
+        self.registers
+            .iter()
+            .filter(move |register| Some(register.set.as_str()) == set)
+    }
+
+    /// The registers whose values differ in `other`, which is
+    /// usually a later snapshot of the same frame, in the order of
+    /// this snapshot followed by any registers only in `other`.
+    pub fn diff(&self, other: &RegisterSnapshot) -> Vec<RegisterChange> {
+        let others: HashMap<&str, &Option<String>> = other
+            .registers
+            .iter()
+            .map(|register| (register.name.as_str(), &register.value))
+            .collect();
+        let mut changes: Vec<RegisterChange> = self
+            .registers
+            .iter()
+            .filter_map(|register| {
+                let new = others.get(register.name.as_str()).and_then(|&v| v.clone());
+                if new == register.value {
+                    None
+                } else {
+                    Some(RegisterChange {
+                        name: register.name.clone(),
+                        old: register.value.clone(),
+                        new,
+                    })
+                }
+            })
+            .collect();
+        changes.extend(
+            other
+                .registers
+                .iter()
+                .filter(|register| register.value.is_some())
+                .filter(|register| self.registers.iter().all(|r| r.name != register.name))
+                .map(|register| RegisterChange {
+                    name: register.name.clone(),
+                    old: None,
+                    new: register.value.clone(),
+                }),
+        );
+        changes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{RegisterChange, RegisterSnapshot, RegisterValue};
+
+    fn snapshot(registers: &[(&str, Option<&str>)]) -> RegisterSnapshot {
+        RegisterSnapshot {
+            pc: 0,
+            registers: registers
+                .iter()
+                .map(|&(name, value)| RegisterValue {
+                    set: "General Purpose Registers".to_string(),
+                    name: name.to_string(),
+                    value: value.map(str::to_string),
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn diff() {
+        let before = snapshot(&[("rax", Some("0x1")), ("rbx", Some("0x2")), ("rcx", None)]);
+        let after = snapshot(&[
+            ("rax", Some("0x1")),
+            ("rbx", Some("0x3")),
+            ("rdx", Some("0x4")),
+        ]);
+        let change = |name: &str, old: Option<&str>, new: Option<&str>| RegisterChange {
+            name: name.to_string(),
+            old: old.map(str::to_string),
+            new: new.map(str::to_string),
+        };
+        assert_eq!(
+            before.diff(&after),
+            vec![
+                change("rbx", Some("0x2"), Some("0x3")),
+                change("rdx", None, Some("0x4")),
+            ]
+        );
+    }
+}
@@ -0,0 +1,81 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use std::fmt;
+
+/// The URL of a remote debug server, such as a `gdbserver`, the
+/// gdbstub in qemu or an OpenOCD probe.
+///
+/// ```
+/// # use lldb::RemoteUrl;
+/// assert_eq!(RemoteUrl::tcp("localhost", 1234).as_str(), "connect://localhost:1234");
+/// assert_eq!(RemoteUrl::unix("/tmp/gdb.sock").as_str(), "unix-connect:///tmp/gdb.sock");
+/// ```
+///
+/// Use [`SBTarget::connect_gdb_remote`] to connect to it.
+///
+/// [`SBTarget::connect_gdb_remote`]: struct.SBTarget.html#method.connect_gdb_remote
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RemoteUrl {
+    url: String,
+}
+
+impl RemoteUrl {
+    /// A server listening on a TCP `port` on `host`.
+    pub fn tcp(host: &str, port: u16) -> RemoteUrl {
+        let url = if host.contains(':') {
+            format!("connect://[{}]:{}", host, port)
+        } else {
+            format!("connect://{}:{}", host, port)
+        };
+        RemoteUrl { url }
+    }
+
+    /// A server listening on the Unix domain socket at `path`.
+    pub fn unix(path: &str) -> RemoteUrl {
+        RemoteUrl {
+            url: format!("unix-connect://{}", path),
+        }
+    }
+
+    /// A server on the serial port `device`, such as `/dev/ttyUSB0`,
+    /// running at `baud_rate`.
+    pub fn serial(device: &str, baud_rate: u32) -> RemoteUrl {
+        RemoteUrl {
+            url: format!("serial://{}?baud={}", device, baud_rate),
+        }
+    }
+
+    /// An `lldb-server platform` listening on `port` on the Android
+    /// device with the serial number `serial`, or on the only
+    /// connected device when `serial` is `None`.
+    ///
+    /// LLDB forwards the port over `adb` itself, so `adb` must be on
+    /// the `PATH`. Connect to it with the `remote-android` platform.
+    pub fn android(serial: Option<&str>, port: u16) -> RemoteUrl {
+        RemoteUrl {
+            url: format!("connect://{}:{}", serial.unwrap_or("localhost"), port),
+        }
+    }
+
+    /// A URL in any other form that LLDB understands.
+    pub fn raw(url: &str) -> RemoteUrl {
+        RemoteUrl {
+            url: url.to_string(),
+        }
+    }
+
+    /// The URL as LLDB expects it.
+    pub fn as_str(&self) -> &str {
+        &self.url
+    }
+}
+
+impl fmt::Display for RemoteUrl {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.url)
+    }
+}
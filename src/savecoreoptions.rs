@@ -0,0 +1,96 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use super::strings;
+
+/// How to write a core file with [`SBProcess::save_core_with_options`].
+///
+/// ```no_run
+/// # use lldb::{CoreStyle, SBError, SBProcess, SaveCoreOptions};
+/// # fn save(process: &SBProcess) -> Result<(), SBError> {
+/// let options = SaveCoreOptions::new("server.core")
+///     .style(CoreStyle::StackOnly)
+///     .plugin("elf");
+/// process.save_core_with_options(&options)
+/// # }
+/// ```
+///
+/// `SBSaveCoreOptions`, which also chooses the threads and memory
+/// regions to save, is newer than the version of LLDB that these
+/// bindings are built against. Until it is available, the style is
+/// the way to keep a core small: [`CoreStyle::StackOnly`] saves just
+/// the stacks of the threads.
+///
+/// [`SBProcess::save_core_with_options`]: struct.SBProcess.html#method.save_core_with_options
+/// [`CoreStyle::StackOnly`]: enum.CoreStyle.html#variant.StackOnly
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SaveCoreOptions {
+    output_file: String,
+    style: Option<CoreStyle>,
+    plugin: Option<String>,
+}
+
+/// Which memory a core file contains.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CoreStyle {
+    /// All of the memory of the process.
+    Full,
+    /// The memory which the process has modified, leaving out what
+    /// can be read from the executable and libraries.
+    ModifiedMemory,
+    /// Only the stacks of the threads.
+    StackOnly,
+}
+
+impl SaveCoreOptions {
+    /// Save a core to `output_file`, with the default style and
+    /// plugin for the platform.
+    pub fn new(output_file: &str) -> SaveCoreOptions {
+        SaveCoreOptions {
+            output_file: output_file.to_string(),
+            style: None,
+            plugin: None,
+        }
+    }
+
+    /// Choose which memory to save. Not every plugin supports every
+    /// style.
+    pub fn style(mut self, style: CoreStyle) -> Self {
+        self.style = Some(style);
+        self
+    }
+
+    /// Write the core with the plugin named `plugin`, such as `elf`,
+    /// `mach-o` or `minidump`.
+    pub fn plugin(mut self, plugin: &str) -> Self {
+        self.plugin = Some(plugin.to_string());
+        self
+    }
+
+    /// The file that the core is written to.
+    pub fn output_file(&self) -> &str {
+        &self.output_file
+    }
+
+    /// The `process save-core` command for these options.
+    pub(crate) fn command(&self) -> String {
+        let mut command = "process save-core".to_string();
+        if let Some(style) = self.style {
+            command.push_str(match style {
+                CoreStyle::Full => " --style full",
+                CoreStyle::ModifiedMemory => " --style modified-memory",
+                CoreStyle::StackOnly => " --style stack",
+            });
+        }
+        if let Some(plugin) = &self.plugin {
+            command.push_str(" --plugin-name ");
+            command.push_str(&strings::quote_argument(plugin));
+        }
+        command.push(' ');
+        command.push_str(&strings::quote_argument(&self.output_file));
+        command
+    }
+}
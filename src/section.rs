@@ -5,9 +5,14 @@
 // except according to those terms.
 
 use super::data::SBData;
+use super::error::InvalidRefError;
+#[cfg(feature = "graphql")]
+use super::graphql::U64;
 use super::stream::SBStream;
+use super::strings;
 use super::target::SBTarget;
-use std::ffi::{CStr, CString};
+use std::convert::TryFrom;
+use std::ffi::CString;
 use std::fmt;
 use sys;
 
@@ -19,14 +24,24 @@ pub struct SBSection {
 
 impl SBSection {
     /// Construct a new `SBSection`.
-    pub fn wrap(raw: sys::SBSectionRef) -> SBSection {
+    ///
+    /// # Safety
+    ///
+    /// `raw` must be a valid reference, which is disposed of when the
+    /// `SBSection` is dropped. Use [`maybe_wrap`] when it may be invalid.
+    ///
+    /// [`maybe_wrap`]: #method.maybe_wrap
+    pub unsafe fn wrap(raw: sys::SBSectionRef) -> SBSection {
         SBSection { raw }
     }
 
     /// Construct a new `Some(SBSection)` or `None`.
+    ///
+    /// `raw` is disposed of if it is invalid.
     pub fn maybe_wrap(raw: sys::SBSectionRef) -> Option<SBSection> {
-        if unsafe { sys::SBSectionIsValid(raw) != 0 } {
-            Some(SBSection { raw })
+        let wrapped = SBSection { raw };
+        if wrapped.is_valid() {
+            Some(wrapped)
         } else {
             None
         }
@@ -38,13 +53,8 @@ impl SBSection {
     }
 
     /// The section name.
-    pub fn name(&self) -> &str {
-        unsafe {
-            match CStr::from_ptr(sys::SBSectionGetName(self.raw)).to_str() {
-                Ok(s) => s,
-                _ => panic!("Invalid string?"),
-            }
-        }
+    pub fn name(&self) -> String {
+        unsafe { strings::to_string_lossy(sys::SBSectionGetName(self.raw)) }
     }
 
     /// The section parent, if there is one.
@@ -95,12 +105,12 @@ impl SBSection {
 
     #[allow(missing_docs)]
     pub fn section_data(&self) -> SBData {
-        SBData::wrap(unsafe { sys::SBSectionGetSectionData(self.raw) })
+        unsafe { SBData::wrap(sys::SBSectionGetSectionData(self.raw)) }
     }
 
     #[allow(missing_docs)]
     pub fn section_data_slice(&self, offset: u64, size: u64) -> SBData {
-        SBData::wrap(unsafe { sys::SBSectionGetSectionData2(self.raw, offset, size) })
+        unsafe { SBData::wrap(sys::SBSectionGetSectionData2(self.raw, offset, size)) }
     }
 
     #[allow(missing_docs)]
@@ -112,6 +122,12 @@ impl SBSection {
     pub fn target_byte_size(&self) -> u32 {
         unsafe { sys::SBSectionGetTargetByteSize(self.raw) }
     }
+
+    fn description(&self) -> String {
+        let stream = SBStream::new();
+        unsafe { sys::SBSectionGetDescription(self.raw, stream.raw) };
+        stream.data()
+    }
 }
 
 /// Iterate over the [subsections] in a [section].
@@ -128,9 +144,12 @@ impl<'d> Iterator for SBSectionSubSectionIter<'d> {
 
     fn next(&mut self) -> Option<SBSection> {
         if self.idx < unsafe { sys::SBSectionGetNumSubSections(self.section.raw) as usize } {
-            let r = Some(SBSection::wrap(unsafe {
-                sys::SBSectionGetSubSectionAtIndex(self.section.raw, self.idx)
-            }));
+            let r = Some(unsafe {
+                SBSection::wrap(sys::SBSectionGetSubSectionAtIndex(
+                    self.section.raw,
+                    self.idx,
+                ))
+            });
             self.idx += 1;
             r
         } else {
@@ -156,9 +175,21 @@ impl Clone for SBSection {
 
 impl fmt::Debug for SBSection {
     fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
-        let stream = SBStream::new();
-        unsafe { sys::SBSectionGetDescription(self.raw, stream.raw) };
-        write!(fmt, "SBSection {{ {} }}", stream.data())
+        write!(fmt, "SBSection {{ {} }}", self.description())
+    }
+}
+
+impl fmt::Display for SBSection {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "{}", self.description())
+    }
+}
+
+impl TryFrom<sys::SBSectionRef> for SBSection {
+    type Error = InvalidRefError;
+
+    fn try_from(raw: sys::SBSectionRef) -> Result<SBSection, InvalidRefError> {
+        SBSection::maybe_wrap(raw).ok_or(InvalidRefError)
     }
 }
 
@@ -172,36 +203,45 @@ unsafe impl Send for SBSection {}
 unsafe impl Sync for SBSection {}
 
 #[cfg(feature = "graphql")]
-graphql_object!(SBSection: super::debugger::SBDebugger | &self | {
-    field is_valid() -> bool {
+#[graphql_object(context = super::debugger::SBDebugger)]
+impl SBSection {
+    #[graphql(name = "isValid")]
+    fn graphql_is_valid(&self) -> bool {
         self.is_valid()
     }
 
-    field name() -> &str {
+    #[graphql(name = "name")]
+    fn graphql_name(&self) -> String {
         self.name()
     }
 
-    field subsections() -> Vec<SBSection> {
+    #[graphql(name = "subsections")]
+    fn graphql_subsections(&self) -> Vec<SBSection> {
         self.subsections().collect()
     }
 
-    field file_address() -> i32 {
-        self.file_address() as i32
+    #[graphql(name = "fileAddress")]
+    fn graphql_file_address(&self) -> U64 {
+        self.file_address().into()
     }
 
-    field byte_size() -> i32 {
-        self.byte_size() as i32
+    #[graphql(name = "byteSize")]
+    fn graphql_byte_size(&self) -> U64 {
+        self.byte_size().into()
     }
 
-    field file_offset() -> i32 {
-        self.file_offset() as i32
+    #[graphql(name = "fileOffset")]
+    fn graphql_file_offset(&self) -> U64 {
+        self.file_offset().into()
     }
 
-    field file_byte_size() -> i32 {
-        self.file_byte_size() as i32
+    #[graphql(name = "fileByteSize")]
+    fn graphql_file_byte_size(&self) -> U64 {
+        self.file_byte_size().into()
     }
 
-    field target_byte_size() -> i32 {
-        self.target_byte_size() as i32
+    #[graphql(name = "targetByteSize")]
+    fn graphql_target_byte_size(&self) -> U64 {
+        self.target_byte_size().into()
     }
-});
+}
@@ -0,0 +1,573 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use super::attachinfo::SBAttachInfo;
+use super::breakpoint::SBBreakpoint;
+use super::connectionlost::ConnectionLost;
+use super::debugger::SBDebugger;
+use super::error::SBError;
+use super::event::SBEvent;
+use super::launchinfo::SBLaunchInfo;
+use super::listener::SBListener;
+use super::process::{SBProcess, StopSnapshot};
+use super::target::SBTarget;
+use super::thread::StepKind;
+use super::{lldb_addr_t, lldb_pid_t, RunMode, StateType};
+use std::error::Error;
+use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+/// A debugger, a target and its process, driven by an event thread.
+///
+/// This takes care of the parts that most programs using LLDB need:
+/// putting the debugger in asynchronous mode, listening for process
+/// events on a thread of its own, reading the output of the process
+/// and waiting for the process to stop after resuming it.
+///
+/// Callbacks registered with [`on_stop`], [`on_stdout`], [`on_stderr`],
+/// [`on_exit`] and [`add_stop_hook`] are called from the event thread. They must not
+/// register further callbacks.
+///
+/// ```no_run
+/// # use lldb::{DebugSession, SBLaunchInfo, SessionError, StepKind};
+/// # use std::time::Duration;
+/// # fn run() -> Result<(), SessionError> {
+/// let mut session = DebugSession::new("/bin/ls")?;
+/// session.on_stdout(|text| print!("{}", text));
+/// session.on_exit(|status| println!("Exited with status {}", status));
+/// session.break_at_function("main");
+///
+/// let stop = session.launch(SBLaunchInfo::new(), Duration::from_secs(10))?;
+/// println!("Stopped in {:?}", stop.function_name);
+/// let stop = session.step(StepKind::Over, Duration::from_secs(5))?;
+/// println!("Stepped to {:#x}", stop.pc);
+/// session.continue_until_stop(Duration::from_secs(10))?;
+/// # Ok(())
+/// # }
+/// ```
+///
+/// When the session is dropped, a process that it launched is killed,
+/// a process that it attached to is detached from and the debugger is
+/// destroyed.
+///
+/// [`on_stop`]: #method.on_stop
+/// [`on_stdout`]: #method.on_stdout
+/// [`on_stderr`]: #method.on_stderr
+/// [`on_exit`]: #method.on_exit
+/// [`add_stop_hook`]: #method.add_stop_hook
+pub struct DebugSession {
+    debugger: SBDebugger,
+    target: SBTarget,
+    listener: SBListener,
+    process: Option<SBProcess>,
+    attached: bool,
+    callbacks: Arc<Mutex<Callbacks>>,
+    transitions: Receiver<Transition>,
+    running: Arc<AtomicBool>,
+    event_thread: Option<JoinHandle<()>>,
+}
+
+type Callback<T> = Box<dyn FnMut(&T) + Send>;
+
+#[derive(Default)]
+struct Callbacks {
+    on_stop: Vec<Callback<StopSnapshot>>,
+    on_stdout: Vec<Callback<str>>,
+    on_stderr: Vec<Callback<str>>,
+    on_exit: Vec<Callback<i32>>,
+    on_connection_lost: Vec<Callback<ConnectionLost>>,
+    stop_hooks: Vec<(u32, Callback<SBProcess>)>,
+    next_stop_hook: u32,
+}
+
+/// A change in the state of the process, as seen by the event thread.
+enum Transition {
+    Stopped(StopSnapshot),
+    Exited { status: i32, description: String },
+    ConnectionLost(ConnectionLost),
+    Detached,
+}
+
+impl DebugSession {
+    /// Create a session for debugging `executable`.
+    ///
+    /// This initializes LLDB and creates a new debugger for the
+    /// session.
+    pub fn new(executable: &str) -> Result<DebugSession, SBError> {
+        SBDebugger::initialize();
+        let debugger = SBDebugger::create(false);
+        let target = debugger.create_target(executable, None, None, false)?;
+        Ok(DebugSession::with_target(debugger, target))
+    }
+
+    /// Create a session for an existing debugger and target.
+    ///
+    /// The debugger is put into asynchronous mode, and is destroyed
+    /// when the session is dropped.
+    pub fn with_target(debugger: SBDebugger, target: SBTarget) -> DebugSession {
+        debugger.set_async(true);
+        let listener = SBListener::new();
+        let callbacks = Arc::new(Mutex::new(Callbacks::default()));
+        let running = Arc::new(AtomicBool::new(true));
+        let (sender, transitions) = mpsc::channel();
+        let event_thread = {
+            let listener = listener.clone();
+            let callbacks = Arc::clone(&callbacks);
+            let running = Arc::clone(&running);
+            thread::spawn(move || handle_events(&listener, &callbacks, &running, &sender))
+        };
+        DebugSession {
+            debugger,
+            target,
+            listener,
+            process: None,
+            attached: false,
+            callbacks,
+            transitions,
+            running,
+            event_thread: Some(event_thread),
+        }
+    }
+
+    /// The debugger for this session.
+    pub fn debugger(&self) -> &SBDebugger {
+        &self.debugger
+    }
+
+    /// The target being debugged.
+    pub fn target(&self) -> &SBTarget {
+        &self.target
+    }
+
+    /// The process being debugged, once one has been launched or
+    /// attached to.
+    pub fn process(&self) -> Option<&SBProcess> {
+        self.process.as_ref()
+    }
+
+    /// Call `callback` whenever the process stops.
+    pub fn on_stop<F>(&self, callback: F)
+    where
+        F: FnMut(&StopSnapshot) + Send + 'static,
+    {
+        self.callbacks
+            .lock()
+            .unwrap()
+            .on_stop
+            .push(Box::new(callback));
+    }
+
+    /// Call `callback` with the process whenever it stops, until the
+    /// hook is removed with [`remove_stop_hook`], returning the ID of
+    /// the hook.
+    ///
+    /// This is the Rust equivalent of [`SBTarget::add_stop_hook`],
+    /// for actions such as logging the length of a queue at every
+    /// stop. Hooks are called before the [`on_stop`] callbacks.
+    ///
+    /// [`remove_stop_hook`]: #method.remove_stop_hook
+    /// [`SBTarget::add_stop_hook`]: struct.SBTarget.html#method.add_stop_hook
+    /// [`on_stop`]: #method.on_stop
+    pub fn add_stop_hook<F>(&self, callback: F) -> u32
+    where
+        F: FnMut(&SBProcess) + Send + 'static,
+    {
+        let mut callbacks = self.callbacks.lock().unwrap();
+        callbacks.next_stop_hook += 1;
+        let id = callbacks.next_stop_hook;
+        callbacks.stop_hooks.push((id, Box::new(callback)));
+        id
+    }
+
+    /// Remove the stop hook with the ID `id`, returning whether there
+    /// was one.
+    pub fn remove_stop_hook(&self, id: u32) -> bool {
+        let mut callbacks = self.callbacks.lock().unwrap();
+        let len = callbacks.stop_hooks.len();
+        callbacks.stop_hooks.retain(|&(hook, _)| hook != id);
+        callbacks.stop_hooks.len() != len
+    }
+
+    /// Call `callback` with the output that the process writes to its
+    /// standard output.
+    pub fn on_stdout<F>(&self, callback: F)
+    where
+        F: FnMut(&str) + Send + 'static,
+    {
+        self.callbacks
+            .lock()
+            .unwrap()
+            .on_stdout
+            .push(Box::new(callback));
+    }
+
+    /// Call `callback` with the output that the process writes to its
+    /// standard error.
+    pub fn on_stderr<F>(&self, callback: F)
+    where
+        F: FnMut(&str) + Send + 'static,
+    {
+        self.callbacks
+            .lock()
+            .unwrap()
+            .on_stderr
+            .push(Box::new(callback));
+    }
+
+    /// Call `callback` with the exit status when the process exits.
+    pub fn on_exit<F>(&self, mut callback: F)
+    where
+        F: FnMut(i32) + Send + 'static,
+    {
+        self.callbacks
+            .lock()
+            .unwrap()
+            .on_exit
+            .push(Box::new(move |status: &i32| callback(*status)));
+    }
+
+    /// Call `callback` when the connection to the remote debug server
+    /// of the process is lost, rather than leaving the session
+    /// waiting for a stop which won't come. The process is treated as
+    /// gone, and the callbacks set with [`on_exit`] aren't called.
+    ///
+    /// A tool can create a new session and attach to the process
+    /// again from here, if the debug server can be restarted.
+    ///
+    /// [`on_exit`]: #method.on_exit
+    pub fn on_connection_lost<F>(&self, callback: F)
+    where
+        F: FnMut(&ConnectionLost) + Send + 'static,
+    {
+        self.callbacks
+            .lock()
+            .unwrap()
+            .on_connection_lost
+            .push(Box::new(callback));
+    }
+
+    /// Set a breakpoint on a function.
+    pub fn break_at_function(&self, name: &str) -> SBBreakpoint {
+        self.target.breakpoint_create_by_name(name, None)
+    }
+
+    /// Set a breakpoint on a line of a source file.
+    pub fn break_at_line(&self, file: &str, line: u32) -> SBBreakpoint {
+        self.target.breakpoint_create_by_location(file, line)
+    }
+
+    /// Set a breakpoint at a load address.
+    pub fn break_at_address(&self, address: lldb_addr_t) -> SBBreakpoint {
+        self.target.breakpoint_create_by_address(address)
+    }
+
+    /// The breakpoints that have been set.
+    pub fn breakpoints(&self) -> Vec<SBBreakpoint> {
+        self.target.breakpoints().collect()
+    }
+
+    /// Delete a breakpoint.
+    pub fn delete_breakpoint(&self, breakpoint: &SBBreakpoint) {
+        self.target.delete_breakpoint(breakpoint.id());
+    }
+
+    /// Launch the process and wait for it to stop, either at the
+    /// entry point or at a breakpoint.
+    ///
+    /// The listener on `launch_info` is replaced with the session's.
+    pub fn launch(
+        &mut self,
+        launch_info: SBLaunchInfo,
+        timeout: Duration,
+    ) -> Result<StopSnapshot, SessionError> {
+        self.start_launch(launch_info)?;
+        self.wait_for_stop(timeout)
+    }
+
+    /// Launch the process without waiting for it to stop.
+    ///
+    /// This is for programs which react to the [`on_stop`] and
+    /// [`on_exit`] callbacks rather than waiting.
+    ///
+    /// [`on_stop`]: #method.on_stop
+    /// [`on_exit`]: #method.on_exit
+    pub fn start_launch(&mut self, launch_info: SBLaunchInfo) -> Result<(), SessionError> {
+        self.drain_transitions();
+        launch_info.set_listener(&self.listener);
+        let process = self.target.launch(launch_info)?;
+        self.process = Some(process);
+        self.attached = false;
+        Ok(())
+    }
+
+    /// Attach to the process with the ID `pid` and wait for it to
+    /// stop.
+    pub fn attach(
+        &mut self,
+        pid: lldb_pid_t,
+        timeout: Duration,
+    ) -> Result<StopSnapshot, SessionError> {
+        self.start_attach(pid)?;
+        self.wait_for_stop(timeout)
+    }
+
+    /// Attach to the process with the ID `pid` without waiting for
+    /// it to stop. See [`start_launch`].
+    ///
+    /// [`start_launch`]: #method.start_launch
+    pub fn start_attach(&mut self, pid: lldb_pid_t) -> Result<(), SessionError> {
+        self.drain_transitions();
+        let attach_info = SBAttachInfo::new_with_pid(pid);
+        attach_info.set_listener(&self.listener);
+        let process = self.target.attach(attach_info)?;
+        self.process = Some(process);
+        self.attached = true;
+        Ok(())
+    }
+
+    /// Resume the process and wait for it to stop again.
+    pub fn continue_until_stop(&self, timeout: Duration) -> Result<StopSnapshot, SessionError> {
+        let process = self.process.as_ref().ok_or(SessionError::NoProcess)?;
+        self.drain_transitions();
+        process.continue_execution()?;
+        self.wait_for_stop(timeout)
+    }
+
+    /// Step the selected thread and wait for the step to finish.
+    pub fn step(&self, kind: StepKind, timeout: Duration) -> Result<StopSnapshot, SessionError> {
+        let process = self.process.as_ref().ok_or(SessionError::NoProcess)?;
+        self.drain_transitions();
+        let thread = process
+            .selected_thread()
+            .ok_or_else(|| SBError::from_message("there is no selected thread"))?;
+        thread.step(kind, RunMode::OnlyDuringStepping)?;
+        self.wait_for_stop(timeout)
+    }
+
+    /// Interrupt the running process and wait for it to stop.
+    pub fn interrupt(&self, timeout: Duration) -> Result<StopSnapshot, SessionError> {
+        let process = self.process.as_ref().ok_or(SessionError::NoProcess)?;
+        self.drain_transitions();
+        process.stop()?;
+        self.wait_for_stop(timeout)
+    }
+
+    /// Kill the process.
+    pub fn kill(&mut self) -> Result<(), SessionError> {
+        let process = self.process.take().ok_or(SessionError::NoProcess)?;
+        process.kill()?;
+        Ok(())
+    }
+
+    /// Detach from the process, leaving it running.
+    pub fn detach(&mut self) -> Result<(), SessionError> {
+        let process = self.process.take().ok_or(SessionError::NoProcess)?;
+        process.detach()?;
+        Ok(())
+    }
+
+    /// Detach from the process, leaving it stopped so that another
+    /// debugger can take it over.
+    ///
+    /// A running process is interrupted first, waiting up to
+    /// `timeout` for it to stop.
+    pub fn detach_keep_stopped(&mut self, timeout: Duration) -> Result<(), SessionError> {
+        let process = self.process.as_ref().ok_or(SessionError::NoProcess)?;
+        if !process.is_stopped() {
+            self.interrupt(timeout)?;
+        }
+        if let Some(process) = self.process.as_ref() {
+            process.detach_with(true)?;
+        }
+        self.process = None;
+        Ok(())
+    }
+
+    /// Throw away transitions which happened before the process was
+    /// last resumed, so that they aren't mistaken for the next stop.
+    fn drain_transitions(&self) {
+        while self.transitions.try_recv().is_ok() {}
+    }
+
+    fn wait_for_stop(&self, timeout: Duration) -> Result<StopSnapshot, SessionError> {
+        match self.transitions.recv_timeout(timeout) {
+            Ok(Transition::Stopped(snapshot)) => Ok(snapshot),
+            Ok(Transition::Exited {
+                status,
+                description,
+            }) => Err(SessionError::Exited {
+                status,
+                description,
+            }),
+            Ok(Transition::ConnectionLost(lost)) => Err(SessionError::ConnectionLost(lost)),
+            Ok(Transition::Detached) => Err(SessionError::Detached),
+            Err(RecvTimeoutError::Timeout) => Err(SessionError::TimedOut),
+            Err(RecvTimeoutError::Disconnected) => Err(SessionError::EventThreadStopped),
+        }
+    }
+}
+
+impl Drop for DebugSession {
+    fn drop(&mut self) {
+        if let Some(process) = self.process.take() {
+            if process.is_alive() {
+                // Errors can't be reported from here, and there is
+                // nothing more to do about them anyway.
+                let _ = if self.attached {
+                    process.detach()
+                } else {
+                    process.kill()
+                };
+            }
+        }
+        self.running.store(false, Ordering::SeqCst);
+        if let Some(event_thread) = self.event_thread.take() {
+            let _ = event_thread.join();
+        }
+        SBDebugger::destroy(&self.debugger);
+    }
+}
+
+/// The body of the event thread.
+fn handle_events(
+    listener: &SBListener,
+    callbacks: &Mutex<Callbacks>,
+    running: &AtomicBool,
+    transitions: &Sender<Transition>,
+) {
+    let mut event = SBEvent::new();
+    while running.load(Ordering::SeqCst) {
+        if !listener.wait_for_event(1, &mut event) {
+            continue;
+        }
+        let process_event = match SBProcess::event_as_process_event(&event) {
+            Some(process_event) => process_event,
+            None => continue,
+        };
+        let process = process_event.process();
+        let event_type = event.event_type();
+        let mut callbacks = callbacks.lock().unwrap();
+
+        if event_type & SBProcess::BROADCAST_BIT_STDOUT != 0 {
+            let output = process.read_stdout();
+            if !output.is_empty() {
+                callbacks.on_stdout.iter_mut().for_each(|f| f(&output));
+            }
+        }
+        if event_type & SBProcess::BROADCAST_BIT_STDERR != 0 {
+            let output = process.read_stderr();
+            if !output.is_empty() {
+                callbacks.on_stderr.iter_mut().for_each(|f| f(&output));
+            }
+        }
+        if event_type & SBProcess::BROADCAST_BIT_STATE_CHANGED == 0 || process_event.restarted() {
+            continue;
+        }
+
+        let transition = match process_event.state() {
+            StateType::Stopped | StateType::Crashed | StateType::Suspended => {
+                callbacks
+                    .stop_hooks
+                    .iter_mut()
+                    .for_each(|(_, f)| f(&process));
+                let snapshot = process.stop_snapshot();
+                callbacks.on_stop.iter_mut().for_each(|f| f(&snapshot));
+                Transition::Stopped(snapshot)
+            }
+            StateType::Exited => match process_event.connection_lost() {
+                Some(lost) => {
+                    callbacks
+                        .on_connection_lost
+                        .iter_mut()
+                        .for_each(|f| f(&lost));
+                    Transition::ConnectionLost(lost)
+                }
+                None => {
+                    let status = process.exit_status();
+                    callbacks.on_exit.iter_mut().for_each(|f| f(&status));
+                    Transition::Exited {
+                        status,
+                        description: process.exit_description().unwrap_or_default(),
+                    }
+                }
+            },
+            StateType::Detached => Transition::Detached,
+            _ => continue,
+        };
+        // The session may be in the middle of being dropped.
+        let _ = transitions.send(transition);
+    }
+}
+
+/// The error returned when a [`DebugSession`] can't do what was asked.
+///
+/// [`DebugSession`]: struct.DebugSession.html
+#[derive(Debug)]
+pub enum SessionError {
+    /// A call to LLDB failed.
+    Lldb(SBError),
+    /// No process has been launched or attached to.
+    NoProcess,
+    /// The process exited rather than stopping.
+    Exited {
+        /// The exit status of the process.
+        status: i32,
+        /// The exit description of the process.
+        description: String,
+    },
+    /// The connection to the remote debug server of the process was
+    /// lost.
+    ConnectionLost(ConnectionLost),
+    /// The process was detached from rather than stopping.
+    Detached,
+    /// The process did not stop before the timeout expired.
+    TimedOut,
+    /// The event thread has stopped, because a callback panicked, so
+    /// the process can no longer be waited for.
+    EventThreadStopped,
+}
+
+impl fmt::Display for SessionError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            SessionError::Lldb(ref error) => write!(fmt, "{}", error),
+            SessionError::NoProcess => write!(fmt, "there is no process"),
+            SessionError::Exited {
+                status,
+                ref description,
+            } => {
+                write!(fmt, "process exited with status {}", status)?;
+                if !description.is_empty() {
+                    write!(fmt, " ({})", description)?;
+                }
+                Ok(())
+            }
+            SessionError::ConnectionLost(ref lost) => write!(fmt, "{}", lost),
+            SessionError::Detached => write!(fmt, "process was detached"),
+            SessionError::TimedOut => write!(fmt, "timed out waiting for process to stop"),
+            SessionError::EventThreadStopped => write!(fmt, "the event thread has stopped"),
+        }
+    }
+}
+
+impl Error for SessionError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match *self {
+            SessionError::Lldb(ref error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<SBError> for SessionError {
+    fn from(error: SBError) -> SessionError {
+        SessionError::Lldb(error)
+    }
+}
@@ -0,0 +1,600 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Plain, owned copies of debugger state.
+//!
+//! The wrapper types in this crate are handles to live LLDB objects,
+//! so they can't outlive the debugger or be sent elsewhere. The
+//! snapshots in this module copy the interesting parts of that state
+//! into ordinary Rust values which implement `Serialize` and
+//! `Deserialize`, so they can be persisted, compared or sent over
+//! the wire:
+//!
+//! ```no_run
+//! # use lldb::SBProcess;
+//! # use lldb::snapshots::ProcessSnapshot;
+//! # fn save(process: &SBProcess) {
+//! let snapshot = ProcessSnapshot::capture(process);
+//! for thread in &snapshot.threads {
+//!     println!("{} {:?}", thread.thread_id, thread.frames.first());
+//! }
+//! # }
+//! ```
+//!
+//! [`snapshot_process`] attaches to a running process just long
+//! enough to capture a `ProcessSnapshot`, for finding out what a stuck
+//! process is doing without disturbing it for long.
+//!
+//! Snapshots of processes, frames and values record the
+//! [stop ID] of the process they were captured at, so that a cache of
+//! them can check with `is_stale` whether the process has run since.
+//!
+//! Enumerations from LLDB, such as the process state, are stored
+//! using their `Debug` representation.
+//!
+//! This requires the `snapshots` feature.
+//!
+//! [`snapshot_process`]: fn.snapshot_process.html
+//! [stop ID]: ../struct.SBProcess.html#method.stop_id
+
+use super::attachinfo::SBAttachInfo;
+use super::breakpoint::SBBreakpoint;
+use super::breakpointlocation::SBBreakpointLocation;
+use super::debugger::SBDebugger;
+use super::error::SBError;
+use super::frame::SBFrame;
+use super::module::SBModule;
+use super::process::SBProcess;
+use super::target::SBTarget;
+use super::thread::SBThread;
+use super::value::SBValue;
+use super::{lldb_pid_t, StateType};
+use serde::{Deserialize, Serialize};
+use std::fmt::Write;
+use std::path::Path;
+
+/// A snapshot of an [`SBProcess`] and its threads.
+///
+/// [`SBProcess`]: ../struct.SBProcess.html
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ProcessSnapshot {
+    /// The process ID.
+    pub process_id: u64,
+    /// The state of the process, such as `Stopped`.
+    pub state: String,
+    /// The exit status, if the process has exited.
+    pub exit_status: Option<i32>,
+    /// The threads in the process.
+    pub threads: Vec<ThreadSnapshot>,
+    /// Global variables, when captured by [`snapshot_process`].
+    ///
+    /// [`snapshot_process`]: fn.snapshot_process.html
+    #[serde(default)]
+    pub globals: Vec<ValueSnapshot>,
+    /// The stop ID of the process when this was captured.
+    #[serde(default)]
+    pub stop_id: Option<u32>,
+}
+
+impl ProcessSnapshot {
+    /// Capture the current state of a process, including the stack
+    /// of every thread.
+    pub fn capture(process: &SBProcess) -> ProcessSnapshot {
+        let state = process.state();
+        ProcessSnapshot {
+            process_id: process.process_id(),
+            state: format!("{:?}", state),
+            exit_status: if state == StateType::Exited {
+                Some(process.exit_status())
+            } else {
+                None
+            },
+            threads: process
+                .threads()
+                .map(|t| ThreadSnapshot::capture(&t))
+                .collect(),
+            globals: vec![],
+            stop_id: Some(process.stop_id()),
+        }
+    }
+
+    /// Has `process`, the process this was captured from, run or
+    /// exited since?
+    pub fn is_stale(&self, process: &SBProcess) -> bool {
+        is_stale(self.stop_id, process)
+    }
+
+    /// Render the snapshot as text in the style of an Apple crash
+    /// report, with the stack of each thread.
+    ///
+    /// See [`CrashReport::to_report_text`] for a report which also
+    /// includes the registers and the loaded modules.
+    ///
+    /// [`CrashReport::to_report_text`]: ../triage/struct.CrashReport.html#method.to_report_text
+    pub fn to_report_text(&self) -> String {
+        let mut text = String::new();
+        let _ = writeln!(text, "{:<23}{}", "Process ID:", self.process_id);
+        let _ = writeln!(text, "{:<23}{}", "State:", self.state);
+        if let Some(status) = self.exit_status {
+            let _ = writeln!(text, "{:<23}{}", "Exit Status:", status);
+        }
+        write_threads(&mut text, &self.threads, None, &[]);
+        text
+    }
+}
+
+/// What [`snapshot_process`] captures besides the stack of each
+/// thread.
+///
+/// [`snapshot_process`]: fn.snapshot_process.html
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SnapshotOptions {
+    /// The names of global variables to capture.
+    pub globals: Vec<String>,
+    /// Whether to capture the arguments and local variables of every
+    /// frame.
+    pub locals: bool,
+    /// How many levels of children to capture for each variable.
+    pub max_depth: u32,
+}
+
+/// Attach to the process with the ID `pid`, capture a snapshot of it
+/// and detach again, leaving it running.
+///
+/// The process is stopped only while the snapshot is taken. A new
+/// debugger is created for the attach, so this doesn't interfere
+/// with any other debugging going on in this program.
+///
+/// ```no_run
+/// # use lldb::snapshots::{snapshot_process, SnapshotOptions};
+/// let options = SnapshotOptions {
+///     globals: vec!["g_request_count".to_string()],
+///     ..SnapshotOptions::default()
+/// };
+/// let snapshot = snapshot_process(1234, &options).unwrap();
+/// for thread in &snapshot.threads {
+///     println!("{:?}", thread.frames.first().and_then(|f| f.function_name.as_ref()));
+/// }
+/// ```
+pub fn snapshot_process(
+    pid: lldb_pid_t,
+    options: &SnapshotOptions,
+) -> Result<ProcessSnapshot, SBError> {
+    SBDebugger::initialize();
+    let debugger = DestroyOnDrop(SBDebugger::create(false));
+    let debugger = &debugger.0;
+    // Wait for the attach to stop the process.
+    debugger.set_async(false);
+    let target = debugger.create_target("", None, None, false)?;
+    let process = target.attach(SBAttachInfo::new_with_pid(pid))?;
+
+    let mut snapshot = ProcessSnapshot::capture(&process);
+    if options.locals {
+        for (thread, thread_snapshot) in process.threads().zip(&mut snapshot.threads) {
+            for (frame, frame_snapshot) in thread.frames().zip(&mut thread_snapshot.frames) {
+                frame_snapshot.variables = frame
+                    .arguments()
+                    .iter()
+                    .chain(frame.locals().iter())
+                    .map(|value| ValueSnapshot::capture(&value, options.max_depth))
+                    .collect();
+            }
+        }
+    }
+    snapshot.globals = options
+        .globals
+        .iter()
+        .filter_map(|name| target.find_first_global_variable(name))
+        .map(|value| ValueSnapshot::capture(&value, options.max_depth))
+        .collect();
+
+    process.detach()?;
+    Ok(snapshot)
+}
+
+/// Destroys the debugger created by [`snapshot_process`] on the way
+/// out, whether or not the snapshot was taken.
+///
+/// [`snapshot_process`]: fn.snapshot_process.html
+struct DestroyOnDrop(SBDebugger);
+
+impl Drop for DestroyOnDrop {
+    fn drop(&mut self) {
+        SBDebugger::destroy(&self.0);
+    }
+}
+
+/// A snapshot of an [`SBThread`] and its stack.
+///
+/// [`SBThread`]: ../struct.SBThread.html
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ThreadSnapshot {
+    /// The thread ID.
+    pub thread_id: u64,
+    /// The index ID, as shown by the `thread list` command.
+    pub index_id: u32,
+    /// The name of the thread, if it has one.
+    pub name: Option<String>,
+    /// The name of the queue that the thread is running, if any.
+    pub queue_name: Option<String>,
+    /// The reason that the thread stopped, such as `Breakpoint`.
+    pub stop_reason: String,
+    /// The stack frames, starting with the innermost frame.
+    pub frames: Vec<FrameSnapshot>,
+}
+
+impl ThreadSnapshot {
+    /// Capture the current state of a thread, including its stack.
+    pub fn capture(thread: &SBThread) -> ThreadSnapshot {
+        ThreadSnapshot {
+            thread_id: thread.thread_id(),
+            index_id: thread.index_id(),
+            name: thread.name(),
+            queue_name: thread.queue_name(),
+            stop_reason: format!("{:?}", thread.stop_reason()),
+            frames: thread
+                .frames()
+                .map(|f| FrameSnapshot::capture(&f))
+                .collect(),
+        }
+    }
+}
+
+/// A snapshot of an [`SBFrame`].
+///
+/// The variables in the frame are not included, as capturing them
+/// can be expensive. Use [`ValueSnapshot::capture`] for the variables
+/// of interest.
+///
+/// [`SBFrame`]: ../struct.SBFrame.html
+/// [`ValueSnapshot::capture`]: struct.ValueSnapshot.html#method.capture
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct FrameSnapshot {
+    /// The index of the frame in the stack.
+    pub index: u32,
+    /// The program counter.
+    pub pc: u64,
+    /// The name of the function, if known.
+    pub function_name: Option<String>,
+    /// The path of the module containing the code, if known.
+    pub module: Option<String>,
+    /// The path of the source file, if there is line information.
+    pub file: Option<String>,
+    /// The source line, if there is line information.
+    pub line: Option<u32>,
+    /// The source column, if there is column information.
+    pub column: Option<u32>,
+    /// The arguments and local variables, when captured by
+    /// [`snapshot_process`].
+    ///
+    /// [`snapshot_process`]: fn.snapshot_process.html
+    #[serde(default)]
+    pub variables: Vec<ValueSnapshot>,
+    /// The offset of the program counter from the start of the
+    /// symbol containing it, if known.
+    #[serde(default)]
+    pub symbol_offset: Option<u64>,
+    /// The stop ID of the process when this was captured.
+    #[serde(default)]
+    pub stop_id: Option<u32>,
+}
+
+impl FrameSnapshot {
+    /// Capture the location of a frame.
+    pub fn capture(frame: &SBFrame) -> FrameSnapshot {
+        let module = frame.module();
+        let line_entry = frame.line_entry();
+        let symbol = frame.symbol();
+        let symbol_offset = if symbol.is_valid() {
+            symbol.start_address().map(|start| {
+                frame
+                    .pc_address()
+                    .file_address()
+                    .wrapping_sub(start.file_address())
+            })
+        } else {
+            None
+        };
+        FrameSnapshot {
+            index: frame.frame_id(),
+            pc: frame.pc(),
+            function_name: frame.function_name(),
+            module: if module.is_valid() {
+                Some(module.filespec().path())
+            } else {
+                None
+            },
+            file: line_entry.as_ref().map(|l| l.filespec().path()),
+            line: line_entry.as_ref().map(|l| l.line()).filter(|&l| l != 0),
+            column: line_entry.as_ref().map(|l| l.column()).filter(|&c| c != 0),
+            variables: vec![],
+            symbol_offset,
+            stop_id: Some(frame.thread().process().stop_id()),
+        }
+    }
+
+    /// Has `process`, the process this was captured from, run or
+    /// exited since?
+    pub fn is_stale(&self, process: &SBProcess) -> bool {
+        is_stale(self.stop_id, process)
+    }
+}
+
+/// A snapshot of an [`SBValue`] and, optionally, its children.
+///
+/// [`SBValue`]: ../struct.SBValue.html
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ValueSnapshot {
+    /// The name of the value, if it has one.
+    pub name: Option<String>,
+    /// The name of the type of the value.
+    pub type_name: Option<String>,
+    /// The value formatted as a string, if it has a value.
+    pub value: Option<String>,
+    /// A summary of the value, if there is one.
+    pub summary: Option<String>,
+    /// The children of the value, such as the fields of a struct.
+    pub children: Vec<ValueSnapshot>,
+    /// The stop ID of the process when this was captured.
+    #[serde(default)]
+    pub stop_id: Option<u32>,
+}
+
+impl ValueSnapshot {
+    /// Capture a value, along with its children down to `max_depth`
+    /// levels. A `max_depth` of 0 only captures the value itself.
+    pub fn capture(value: &SBValue, max_depth: u32) -> ValueSnapshot {
+        let stop_id = value.process().map(|process| process.stop_id());
+        ValueSnapshot::capture_at(value, max_depth, stop_id)
+    }
+
+    fn capture_at(value: &SBValue, max_depth: u32, stop_id: Option<u32>) -> ValueSnapshot {
+        let children = if max_depth > 0 {
+            (0..value.num_children())
+                .filter_map(|idx| value.child_at_index(idx))
+                .map(|child| ValueSnapshot::capture_at(&child, max_depth - 1, stop_id))
+                .collect()
+        } else {
+            vec![]
+        };
+        ValueSnapshot {
+            name: value.name(),
+            type_name: value.display_type_name(),
+            value: value.value(),
+            summary: value.summary(),
+            children,
+            stop_id,
+        }
+    }
+
+    /// Has `process`, the process this was captured from, run or
+    /// exited since? Values captured without a process, such as the
+    /// globals of a target, are never stale.
+    pub fn is_stale(&self, process: &SBProcess) -> bool {
+        self.stop_id.is_some() && is_stale(self.stop_id, process)
+    }
+}
+
+/// A snapshot of an [`SBModule`].
+///
+/// [`SBModule`]: ../struct.SBModule.html
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ModuleSnapshot {
+    /// The path of the module on the host.
+    pub path: String,
+    /// The path of the module on the platform being debugged.
+    pub platform_path: String,
+    /// The UUID of the module, if it has one.
+    pub uuid: Option<String>,
+    /// The triple of the module, such as `x86_64-unknown-linux-gnu`.
+    #[serde(default)]
+    pub triple: Option<String>,
+    /// The lowest address that the module is loaded at, when captured
+    /// by [`capture_loaded`].
+    ///
+    /// [`capture_loaded`]: #method.capture_loaded
+    #[serde(default)]
+    pub load_address: Option<u64>,
+    /// The address just past the end of the module, when captured by
+    /// [`capture_loaded`].
+    ///
+    /// [`capture_loaded`]: #method.capture_loaded
+    #[serde(default)]
+    pub load_end_address: Option<u64>,
+}
+
+impl ModuleSnapshot {
+    /// Capture the identity of a module.
+    pub fn capture(module: &SBModule) -> ModuleSnapshot {
+        ModuleSnapshot {
+            path: module.filespec().path(),
+            platform_path: module.platform_filespec().path(),
+            uuid: module.uuid_string(),
+            triple: module.triple(),
+            load_address: None,
+            load_end_address: None,
+        }
+    }
+
+    /// Capture the identity of a module and where it is loaded in the
+    /// process of `target`.
+    pub fn capture_loaded(module: &SBModule, target: &SBTarget) -> ModuleSnapshot {
+        let mut snapshot = ModuleSnapshot::capture(module);
+        for section in module.sections() {
+            let start = section.load_address(target);
+            if start == LLDB_INVALID_ADDRESS || section.byte_size() == 0 {
+                continue;
+            }
+            let end = start + section.byte_size();
+            snapshot.load_address = Some(snapshot.load_address.map_or(start, |a| a.min(start)));
+            snapshot.load_end_address = Some(snapshot.load_end_address.map_or(end, |a| a.max(end)));
+        }
+        snapshot
+    }
+}
+
+/// A snapshot of an [`SBBreakpoint`] and its locations.
+///
+/// [`SBBreakpoint`]: ../struct.SBBreakpoint.html
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BreakpointSnapshot {
+    /// The breakpoint ID.
+    pub id: i32,
+    /// Whether the breakpoint is enabled.
+    pub enabled: bool,
+    /// Whether the breakpoint is deleted after it is hit.
+    pub oneshot: bool,
+    /// The number of times the breakpoint has been hit.
+    pub hit_count: u32,
+    /// The number of hits to ignore before stopping.
+    pub ignore_count: u32,
+    /// The locations the breakpoint has resolved to.
+    pub locations: Vec<BreakpointLocationSnapshot>,
+}
+
+impl BreakpointSnapshot {
+    /// Capture the state of a breakpoint and its locations.
+    pub fn capture(breakpoint: &SBBreakpoint) -> BreakpointSnapshot {
+        BreakpointSnapshot {
+            id: breakpoint.id(),
+            enabled: breakpoint.is_enabled(),
+            oneshot: breakpoint.is_oneshot(),
+            hit_count: breakpoint.hit_count(),
+            ignore_count: breakpoint.ignore_count(),
+            locations: breakpoint
+                .locations()
+                .map(|l| BreakpointLocationSnapshot::capture(&l))
+                .collect(),
+        }
+    }
+}
+
+/// A snapshot of an [`SBBreakpointLocation`].
+///
+/// [`SBBreakpointLocation`]: ../struct.SBBreakpointLocation.html
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BreakpointLocationSnapshot {
+    /// The location ID.
+    pub id: i32,
+    /// The load address of the location.
+    pub load_address: u64,
+    /// Whether the location is enabled.
+    pub enabled: bool,
+    /// Whether the location has been resolved to an address.
+    pub resolved: bool,
+    /// The number of times the location has been hit.
+    pub hit_count: u32,
+}
+
+impl BreakpointLocationSnapshot {
+    /// Capture the state of a breakpoint location.
+    pub fn capture(location: &SBBreakpointLocation) -> BreakpointLocationSnapshot {
+        BreakpointLocationSnapshot {
+            id: location.id(),
+            load_address: location.load_address(),
+            enabled: location.is_enabled(),
+            resolved: location.is_resolved(),
+            hit_count: location.hit_count(),
+        }
+    }
+}
+
+const LLDB_INVALID_ADDRESS: u64 = !0;
+
+/// Has `process` run or exited since the stop `stop_id`? A snapshot
+/// which doesn't know its stop is taken to be stale.
+fn is_stale(stop_id: Option<u32>, process: &SBProcess) -> bool {
+    !process.is_valid() || !process.is_stopped() || stop_id != Some(process.stop_id())
+}
+
+/// The file name of `path`, for the short names of modules and source
+/// files in report text.
+fn file_name(path: &str) -> &str {
+    Path::new(path)
+        .file_name()
+        .and_then(|name| name.to_str())
+        .unwrap_or(path)
+}
+
+/// Write the stack of each thread, marking the one at `crashed`, in
+/// the style of an Apple crash report.
+pub(crate) fn write_threads(
+    text: &mut String,
+    threads: &[ThreadSnapshot],
+    crashed: Option<usize>,
+    modules: &[ModuleSnapshot],
+) {
+    for (idx, thread) in threads.iter().enumerate() {
+        let _ = write!(text, "\nThread {}", thread.index_id);
+        if crashed == Some(idx) {
+            text.push_str(" Crashed");
+        }
+        text.push(':');
+        if let Some(name) = &thread.name {
+            let _ = write!(text, ": {}", name);
+        }
+        if let Some(queue) = &thread.queue_name {
+            let _ = write!(text, " Dispatch queue: {}", queue);
+        }
+        text.push('\n');
+        for frame in &thread.frames {
+            let module = frame.module.as_deref();
+            let _ = write!(
+                text,
+                "{:<4}{:<30}\t0x{:016x} ",
+                frame.index,
+                module.map_or("???", file_name),
+                frame.pc
+            );
+            let base = modules
+                .iter()
+                .find(|m| Some(m.path.as_str()) == module)
+                .and_then(|m| m.load_address);
+            match (&frame.function_name, frame.symbol_offset, base) {
+                (Some(name), Some(offset), _) => {
+                    let _ = write!(text, "{} + {}", name, offset);
+                }
+                (Some(name), None, _) => text.push_str(name),
+                (None, _, Some(base)) => {
+                    let _ = write!(text, "0x{:x} + {}", base, frame.pc.wrapping_sub(base));
+                }
+                (None, _, None) => text.push_str("???"),
+            }
+            if let (Some(file), Some(line)) = (&frame.file, frame.line) {
+                let _ = write!(text, " ({}:{})", file_name(file), line);
+            }
+            text.push('\n');
+        }
+    }
+}
+
+/// Write the list of loaded modules in the style of the binary images
+/// of an Apple crash report.
+#[cfg(feature = "triage")]
+pub(crate) fn write_binary_images(text: &mut String, modules: &[ModuleSnapshot]) {
+    text.push_str("\nBinary Images:\n");
+    for module in modules {
+        let arch = module
+            .triple
+            .as_deref()
+            .and_then(|triple| triple.split('-').next())
+            .unwrap_or("???");
+        let _ = writeln!(
+            text,
+            "{:>18} - {:>18} {} {} <{}> {}",
+            module
+                .load_address
+                .map_or("???".to_string(), |a| format!("{:#x}", a)),
+            module
+                .load_end_address
+                .map_or("???".to_string(), |a| format!("{:#x}", a - 1)),
+            file_name(&module.path),
+            arch,
+            module.uuid.as_deref().unwrap_or(""),
+            module.path
+        );
+    }
+}
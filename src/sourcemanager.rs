@@ -0,0 +1,117 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use super::filespec::SBFileSpec;
+use super::stream::SBStream;
+use std::ffi::CString;
+use sys;
+
+/// Displays source code, applying the source path mappings from
+/// the `target.source-map` setting.
+///
+/// A source manager can be obtained from a [debugger] or a [target]:
+///
+/// ```no_run
+/// # use lldb::{SBFileSpec, SBTarget};
+/// # fn show(target: &SBTarget, file: &SBFileSpec) {
+/// let source_manager = target.source_manager();
+/// print!("{}", source_manager.source_lines(file, 42, 3, 3, "->"));
+/// # }
+/// ```
+///
+/// [debugger]: struct.SBDebugger.html#method.source_manager
+/// [target]: struct.SBTarget.html#method.source_manager
+#[derive(Debug)]
+pub struct SBSourceManager {
+    /// The underlying raw `SBSourceManagerRef`.
+    pub raw: sys::SBSourceManagerRef,
+}
+
+impl SBSourceManager {
+    /// Construct a new `SBSourceManager`.
+    ///
+    /// # Safety
+    ///
+    /// `raw` must be an `SBSourceManagerRef` which isn't used elsewhere,
+    /// such as one from `SBDebuggerGetSourceManager`, as it is disposed
+    /// of when the `SBSourceManager` is dropped.
+    pub unsafe fn wrap(raw: sys::SBSourceManagerRef) -> SBSourceManager {
+        SBSourceManager { raw }
+    }
+
+    /// Write the source lines around `line` in `file` to `stream`,
+    /// with line numbers.
+    ///
+    /// `context_before` and `context_after` give the number of lines
+    /// to show before and after `line`. `line` itself is marked with
+    /// `current_line_marker`, which may be empty.
+    ///
+    /// Returns the number of bytes written.
+    pub fn display_source_lines(
+        &self,
+        file: &SBFileSpec,
+        line: u32,
+        context_before: u32,
+        context_after: u32,
+        current_line_marker: &str,
+        stream: &SBStream,
+    ) -> usize {
+        let current_line_marker = CString::new(current_line_marker).unwrap();
+        unsafe {
+            sys::SBSourceManagerDisplaySourceLinesWithLineNumbers(
+                self.raw,
+                file.raw,
+                line,
+                context_before,
+                context_after,
+                current_line_marker.as_ptr(),
+                stream.raw,
+            ) as usize
+        }
+    }
+
+    /// Get the source lines around `line` in `file`, with line numbers.
+    ///
+    /// See [`display_source_lines`] for details.
+    ///
+    /// [`display_source_lines`]: #method.display_source_lines
+    pub fn source_lines(
+        &self,
+        file: &SBFileSpec,
+        line: u32,
+        context_before: u32,
+        context_after: u32,
+        current_line_marker: &str,
+    ) -> String {
+        let stream = SBStream::new();
+        self.display_source_lines(
+            file,
+            line,
+            context_before,
+            context_after,
+            current_line_marker,
+            &stream,
+        );
+        stream.data()
+    }
+}
+
+impl Clone for SBSourceManager {
+    fn clone(&self) -> SBSourceManager {
+        SBSourceManager {
+            raw: unsafe { sys::CloneSBSourceManager(self.raw) },
+        }
+    }
+}
+
+impl Drop for SBSourceManager {
+    fn drop(&mut self) {
+        unsafe { sys::DisposeSBSourceManager(self.raw) };
+    }
+}
+
+unsafe impl Send for SBSourceManager {}
+unsafe impl Sync for SBSourceManager {}
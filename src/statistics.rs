@@ -0,0 +1,136 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use super::error::SBError;
+use super::structureddata::SBStructuredData;
+use serde::{Deserialize, Serialize};
+use serde_json;
+
+/// Statistics about a debug session, as reported by
+/// [`SBTarget::session_statistics`].
+///
+/// This is a typed view of the data reported by the `statistics dump`
+/// command. Fields which aren't reported by the version of LLDB in
+/// use are left at their default values. All times are in seconds.
+///
+/// This requires the `json` feature.
+///
+/// [`SBTarget::session_statistics`]: struct.SBTarget.html#method.session_statistics
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SessionStatistics {
+    /// The number of modules loaded.
+    pub total_module_count: u64,
+    /// The number of modules which have debug info.
+    pub total_module_count_has_debug_info: u64,
+    /// The time spent parsing symbol tables.
+    pub total_symbol_table_parse_time: f64,
+    /// The time spent indexing symbol tables.
+    pub total_symbol_table_index_time: f64,
+    /// The time spent parsing debug info.
+    pub total_debug_info_parse_time: f64,
+    /// The time spent indexing debug info.
+    pub total_debug_info_index_time: f64,
+    /// The total size of the debug info, in bytes.
+    pub total_debug_info_byte_size: u64,
+    /// Per-module statistics.
+    pub modules: Vec<ModuleStatistics>,
+    /// Per-target statistics.
+    pub targets: Vec<TargetStatistics>,
+}
+
+impl SessionStatistics {
+    /// Parse statistics from the structured data reported by LLDB.
+    ///
+    /// Older versions of LLDB report the statistics for a single target
+    /// at the top level, rather than in a list of targets. These are
+    /// parsed into a single entry in `targets`.
+    pub fn from_structured_data(data: &SBStructuredData) -> Result<SessionStatistics, SBError> {
+        let value = data.to_json_value()?;
+        let mut statistics: SessionStatistics =
+            serde_json::from_value(value.clone()).map_err(json_error)?;
+        if value.get("targets").is_none() {
+            statistics.targets = vec![serde_json::from_value(value).map_err(json_error)?];
+        }
+        Ok(statistics)
+    }
+}
+
+/// Statistics about a module in a [`SessionStatistics`].
+///
+/// [`SessionStatistics`]: struct.SessionStatistics.html
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ModuleStatistics {
+    /// The path to the module.
+    pub path: String,
+    /// The UUID of the module.
+    pub uuid: String,
+    /// The target triple of the module.
+    pub triple: String,
+    /// The time spent parsing the symbol table.
+    pub symbol_table_parse_time: f64,
+    /// The time spent indexing the symbol table.
+    pub symbol_table_index_time: f64,
+    /// The time spent parsing debug info.
+    pub debug_info_parse_time: f64,
+    /// The time spent indexing debug info.
+    pub debug_info_index_time: f64,
+    /// The size of the debug info, in bytes.
+    pub debug_info_byte_size: u64,
+}
+
+/// Statistics about a target in a [`SessionStatistics`].
+///
+/// [`SessionStatistics`]: struct.SessionStatistics.html
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct TargetStatistics {
+    /// The time spent creating the target.
+    pub target_create_time: f64,
+    /// The time spent launching or attaching to the process.
+    pub launch_or_attach_time: Option<f64>,
+    /// The time until the process first stopped.
+    pub first_stop_time: Option<f64>,
+    /// The number of times the process has stopped.
+    pub stop_count: u64,
+    /// The time spent resolving breakpoints.
+    pub total_breakpoint_resolve_time: f64,
+    /// The number of expressions evaluated.
+    pub expression_evaluation: SuccessFailureCount,
+    /// The number of `frame variable` commands run.
+    pub frame_variable: SuccessFailureCount,
+    /// Per-breakpoint statistics.
+    pub breakpoints: Vec<BreakpointStatistics>,
+}
+
+/// The number of times an operation succeeded and failed.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SuccessFailureCount {
+    /// The number of successes.
+    pub successes: u64,
+    /// The number of failures.
+    pub failures: u64,
+}
+
+/// Statistics about a breakpoint in a [`TargetStatistics`].
+///
+/// [`TargetStatistics`]: struct.TargetStatistics.html
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct BreakpointStatistics {
+    /// The breakpoint ID.
+    pub id: u32,
+    /// The number of locations the breakpoint resolved to.
+    pub num_locations: u32,
+    /// The time spent resolving the breakpoint.
+    pub resolve_time: f64,
+}
+
+fn json_error(e: serde_json::Error) -> SBError {
+    SBError::from_message(&e.to_string())
+}
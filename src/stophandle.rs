@@ -0,0 +1,138 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use super::process::SBProcess;
+use std::error::Error;
+use std::fmt;
+
+/// A process, thread or frame which is only used while the process
+/// stays stopped at the stop it was taken at.
+///
+/// Threads and frames describe the process as it was when it stopped.
+/// Once it has run again, an `SBFrame` may still be valid as far as
+/// LLDB can tell, but describe a different function, or one which has
+/// returned, so reading from it silently gives the wrong answer.
+/// [`get`] checks that the process is the same one, and hasn't run
+/// since, before giving access:
+///
+/// ```no_run
+/// # use lldb::{SBThread, StaleHandle};
+/// # fn show(thread: &SBThread) -> Result<(), StaleHandle> {
+/// let frame = thread.selected_frame().unwrap().stop_handle();
+/// thread.process().continue_execution().unwrap();
+/// // ...
+/// match frame.get() {
+///     Ok(frame) => println!("{:?}", frame.function_name()),
+///     Err(StaleHandle::Resumed { .. }) => println!("the frame is gone"),
+///     Err(error) => return Err(error),
+/// }
+/// # Ok(())
+/// # }
+/// ```
+///
+/// Running an expression in the process doesn't count as running
+/// again, as the stack is put back afterwards.
+///
+/// [`get`]: #method.get
+#[derive(Clone, Debug)]
+pub struct StopHandle<T> {
+    handle: T,
+    process: SBProcess,
+    unique_id: u32,
+    stop_id: u32,
+    is_valid: fn(&T) -> bool,
+}
+
+impl<T> StopHandle<T> {
+    pub(crate) fn new(handle: T, process: SBProcess, is_valid: fn(&T) -> bool) -> StopHandle<T> {
+        StopHandle {
+            unique_id: process.unique_id(),
+            stop_id: process.stop_id(),
+            handle,
+            process,
+            is_valid,
+        }
+    }
+
+    /// The handle, if the process is still at the same stop and the
+    /// handle is still valid.
+    pub fn get(&self) -> Result<&T, StaleHandle> {
+        self.check()?;
+        Ok(&self.handle)
+    }
+
+    /// Check that the handle may still be used.
+    pub fn check(&self) -> Result<(), StaleHandle> {
+        if !self.process.is_valid()
+            || !self.process.is_alive()
+            || self.process.unique_id() != self.unique_id
+        {
+            return Err(StaleHandle::ProcessGone);
+        }
+        let stop_id = self.process.stop_id();
+        if stop_id != self.stop_id || !self.process.is_stopped() {
+            return Err(StaleHandle::Resumed {
+                taken_at: self.stop_id,
+                now: stop_id,
+            });
+        }
+        if !(self.is_valid)(&self.handle) {
+            return Err(StaleHandle::Invalid);
+        }
+        Ok(())
+    }
+
+    /// Is the handle out of date? See [`check`].
+    ///
+    /// [`check`]: #method.check
+    pub fn is_stale(&self) -> bool {
+        self.check().is_err()
+    }
+
+    /// The stop ID of the process when the handle was taken.
+    pub fn stop_id(&self) -> u32 {
+        self.stop_id
+    }
+
+    /// The handle, without checking whether it may still be used.
+    pub fn into_inner(self) -> T {
+        self.handle
+    }
+}
+
+/// Why a [`StopHandle`] can no longer be used.
+///
+/// [`StopHandle`]: struct.StopHandle.html
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StaleHandle {
+    /// The process has exited, or been replaced by another.
+    ProcessGone,
+    /// The process has run since the handle was taken.
+    Resumed {
+        /// The stop ID when the handle was taken.
+        taken_at: u32,
+        /// The stop ID now.
+        now: u32,
+    },
+    /// LLDB no longer knows about the thread or frame.
+    Invalid,
+}
+
+impl fmt::Display for StaleHandle {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            StaleHandle::ProcessGone => write!(f, "the process has exited"),
+            StaleHandle::Resumed { taken_at, now } => write!(
+                f,
+                "the process has run since stop {}, and is now at stop {}",
+                taken_at, now
+            ),
+            StaleHandle::Invalid => write!(f, "the handle is no longer valid"),
+        }
+    }
+}
+
+impl Error for StaleHandle {}
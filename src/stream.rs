@@ -4,7 +4,12 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
-use std::ffi::CStr;
+use super::error::InvalidRefError;
+use super::strings;
+use std::convert::TryFrom;
+use std::ffi::{CStr, CString};
+#[cfg(unix)]
+use std::os::unix::io::IntoRawFd;
 use sys;
 
 /// A destination for streaming data output. By default, this is
@@ -18,18 +23,28 @@ pub struct SBStream {
 impl SBStream {
     /// Construct a new `SBStream`.
     pub fn new() -> SBStream {
-        SBStream::wrap(unsafe { sys::CreateSBStream() })
+        unsafe { SBStream::wrap(sys::CreateSBStream()) }
     }
 
     /// Construct a new `SBStream`.
-    pub fn wrap(raw: sys::SBStreamRef) -> SBStream {
+    ///
+    /// # Safety
+    ///
+    /// `raw` must be a valid reference, which is disposed of when the
+    /// `SBStream` is dropped. Use [`maybe_wrap`] when it may be invalid.
+    ///
+    /// [`maybe_wrap`]: #method.maybe_wrap
+    pub unsafe fn wrap(raw: sys::SBStreamRef) -> SBStream {
         SBStream { raw }
     }
 
     /// Construct a new `Some(SBStream)` or `None`.
+    ///
+    /// `raw` is disposed of if it is invalid.
     pub fn maybe_wrap(raw: sys::SBStreamRef) -> Option<SBStream> {
-        if unsafe { sys::SBStreamIsValid(raw) != 0 } {
-            Some(SBStream { raw })
+        let wrapped = SBStream { raw };
+        if wrapped.is_valid() {
+            Some(wrapped)
         } else {
             None
         }
@@ -48,13 +63,53 @@ impl SBStream {
         unsafe { sys::SBStreamClear(self.raw) }
     }
 
+    /// Redirect this stream to the file at `path`.
+    ///
+    /// If `append` is `true`, output is appended to the file rather
+    /// than replacing its contents. The file is closed when the stream
+    /// is cleared or dropped.
+    pub fn redirect_to_file(&self, path: &str, append: bool) {
+        let path = CString::new(path).unwrap();
+        unsafe { sys::SBStreamRedirectToFile(self.raw, path.as_ptr(), append as u8) }
+    }
+
+    /// Redirect this stream to a file descriptor, such as one
+    /// owned by a `std::fs::File`.
+    ///
+    /// The stream takes ownership of `fd` and closes it when the
+    /// stream is cleared or dropped.
+    #[cfg(unix)]
+    pub fn redirect_to_fd<F: IntoRawFd>(&self, fd: F) {
+        unsafe { sys::SBStreamRedirectToFileDescriptor(self.raw, fd.into_raw_fd(), 1) }
+    }
+
+    /// Write some text to this stream.
+    pub fn print(&self, text: &str) {
+        let text = CString::new(text).unwrap();
+        unsafe { sys::SBStreamPrintf(self.raw, b"%s\0".as_ptr() as *const _, text.as_ptr()) };
+    }
+
     /// If this stream is not redirected to a file, this retrieves the
     /// locally cached data.
-    pub fn data(&self) -> &str {
+    pub fn data(&self) -> String {
+        unsafe { strings::to_string_lossy(sys::SBStreamGetData(self.raw)) }
+    }
+
+    /// If this stream is not redirected to a file, this retrieves the
+    /// locally cached data as bytes.
+    ///
+    /// Unlike [`data`], this does not require the data to be valid
+    /// UTF-8, which isn't guaranteed for descriptions that include
+    /// memory or string contents from the inferior.
+    ///
+    /// [`data`]: #method.data
+    pub fn bytes(&self) -> Vec<u8> {
         unsafe {
-            match CStr::from_ptr(sys::SBStreamGetData(self.raw)).to_str() {
-                Ok(s) => s,
-                _ => panic!("Invalid string?"),
+            let data = sys::SBStreamGetData(self.raw);
+            if data.is_null() {
+                vec![]
+            } else {
+                CStr::from_ptr(data).to_bytes().to_vec()
             }
         }
     }
@@ -77,6 +132,14 @@ impl Default for SBStream {
     }
 }
 
+impl TryFrom<sys::SBStreamRef> for SBStream {
+    type Error = InvalidRefError;
+
+    fn try_from(raw: sys::SBStreamRef) -> Result<SBStream, InvalidRefError> {
+        SBStream::maybe_wrap(raw).ok_or(InvalidRefError)
+    }
+}
+
 impl Drop for SBStream {
     fn drop(&mut self) {
         unsafe { sys::DisposeSBStream(self.raw) };
@@ -84,4 +147,3 @@ impl Drop for SBStream {
 }
 
 unsafe impl Send for SBStream {}
-unsafe impl Sync for SBStream {}
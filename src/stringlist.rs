@@ -4,11 +4,28 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
-use std::ffi::{CStr, CString};
+use super::error::InvalidRefError;
+use super::strings;
+use std::convert::TryFrom;
+use std::ffi::CString;
+use std::fmt;
+use std::iter::FromIterator;
 use sys;
 
 /// A list of strings.
-#[derive(Debug)]
+///
+/// This converts to and from the standard collections, so that APIs
+/// taking or returning lists of strings, such as the commands of a
+/// breakpoint, can be used with `Vec`s:
+///
+/// ```no_run
+/// # use lldb::{SBBreakpoint, SBStringList};
+/// # fn show(breakpoint: &SBBreakpoint) {
+/// let commands: SBStringList = vec!["bt".to_string(), "continue".to_string()].into();
+/// breakpoint.set_command_line_commands(&commands);
+/// let commands: Vec<String> = breakpoint.command_line_commands().into();
+/// # }
+/// ```
 pub struct SBStringList {
     /// The underlying raw `SBStringListRef`.
     pub raw: sys::SBStringListRef,
@@ -17,17 +34,27 @@ pub struct SBStringList {
 impl SBStringList {
     /// Construct a new `SBStringList`.
     pub fn new() -> SBStringList {
-        SBStringList::wrap(unsafe { sys::CreateSBStringList() })
+        unsafe { SBStringList::wrap(sys::CreateSBStringList()) }
     }
     /// Construct a new `SBStringList`.
-    pub fn wrap(raw: sys::SBStringListRef) -> SBStringList {
+    ///
+    /// # Safety
+    ///
+    /// `raw` must be a valid reference, which is disposed of when the
+    /// `SBStringList` is dropped. Use [`maybe_wrap`] when it may be invalid.
+    ///
+    /// [`maybe_wrap`]: #method.maybe_wrap
+    pub unsafe fn wrap(raw: sys::SBStringListRef) -> SBStringList {
         SBStringList { raw }
     }
 
     /// Construct a new `Some(SBStringList)` or `None`.
+    ///
+    /// `raw` is disposed of if it is invalid.
     pub fn maybe_wrap(raw: sys::SBStringListRef) -> Option<SBStringList> {
-        if unsafe { sys::SBStringListIsValid(raw) != 0 } {
-            Some(SBStringList { raw })
+        let wrapped = SBStringList { raw };
+        if wrapped.is_valid() {
+            Some(wrapped)
         } else {
             None
         }
@@ -38,9 +65,33 @@ impl SBStringList {
         unsafe { sys::SBStringListIsValid(self.raw) != 0 }
     }
 
+    /// The number of strings in this list.
+    pub fn len(&self) -> usize {
+        unsafe { sys::SBStringListGetSize(self.raw) as usize }
+    }
+
     /// Is this string list empty?
     pub fn is_empty(&self) -> bool {
-        unsafe { sys::SBStringListGetSize(self.raw) == 0 }
+        self.len() == 0
+    }
+
+    /// The string at index `idx`, if there is one.
+    ///
+    /// The string is copied out of the list, so this can't be done
+    /// with `Index`.
+    pub fn get(&self, idx: usize) -> Option<String> {
+        if idx < self.len() {
+            Some(unsafe {
+                strings::to_string_lossy(sys::SBStringListGetStringAtIndex(self.raw, idx))
+            })
+        } else {
+            None
+        }
+    }
+
+    /// Copy the strings in this list into a `Vec`.
+    pub fn to_vec(&self) -> Vec<String> {
+        self.iter().collect()
     }
 
     /// Clear this string list.
@@ -60,7 +111,7 @@ impl SBStringList {
     }
 
     /// Iterate over this string list.
-    pub fn iter(&self) -> SBStringListIter {
+    pub fn iter(&self) -> SBStringListIter<'_> {
         SBStringListIter {
             string_list: self,
             idx: 0,
@@ -82,6 +133,69 @@ impl Default for SBStringList {
     }
 }
 
+impl fmt::Debug for SBStringList {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<S: AsRef<str>> Extend<S> for SBStringList {
+    fn extend<I: IntoIterator<Item = S>>(&mut self, iter: I) {
+        for string in iter {
+            self.append_string(string.as_ref());
+        }
+    }
+}
+
+impl<S: AsRef<str>> FromIterator<S> for SBStringList {
+    fn from_iter<I: IntoIterator<Item = S>>(iter: I) -> SBStringList {
+        let mut list = SBStringList::new();
+        list.extend(iter);
+        list
+    }
+}
+
+impl From<Vec<String>> for SBStringList {
+    fn from(strings: Vec<String>) -> SBStringList {
+        strings.into_iter().collect()
+    }
+}
+
+impl<'a> From<&'a [&'a str]> for SBStringList {
+    fn from(strings: &'a [&'a str]) -> SBStringList {
+        strings.iter().collect()
+    }
+}
+
+impl From<SBStringList> for Vec<String> {
+    fn from(list: SBStringList) -> Vec<String> {
+        list.to_vec()
+    }
+}
+
+impl<'a> From<&'a SBStringList> for Vec<String> {
+    fn from(list: &'a SBStringList) -> Vec<String> {
+        list.to_vec()
+    }
+}
+
+impl<'d> IntoIterator for &'d SBStringList {
+    type Item = String;
+    type IntoIter = SBStringListIter<'d>;
+
+    fn into_iter(self) -> SBStringListIter<'d> {
+        self.iter()
+    }
+}
+
+impl TryFrom<sys::SBStringListRef> for SBStringList {
+    type Error = InvalidRefError;
+
+    fn try_from(raw: sys::SBStringListRef) -> Result<SBStringList, InvalidRefError> {
+        SBStringList::maybe_wrap(raw).ok_or(InvalidRefError)
+    }
+}
+
 impl Drop for SBStringList {
     fn drop(&mut self) {
         unsafe { sys::DisposeSBStringList(self.raw) };
@@ -89,7 +203,6 @@ impl Drop for SBStringList {
 }
 
 unsafe impl Send for SBStringList {}
-unsafe impl Sync for SBStringList {}
 
 /// An iterator over an `SBStringList`.
 pub struct SBStringListIter<'d> {
@@ -98,30 +211,16 @@ pub struct SBStringListIter<'d> {
 }
 
 impl<'d> Iterator for SBStringListIter<'d> {
-    type Item = &'d str;
-
-    fn next(&mut self) -> Option<&'d str> {
-        if self.idx < unsafe { sys::SBStringListGetSize(self.string_list.raw) as usize } {
-            let r = unsafe {
-                match CStr::from_ptr(sys::SBStringListGetStringAtIndex(
-                    self.string_list.raw,
-                    self.idx,
-                ))
-                .to_str()
-                {
-                    Ok(s) => s,
-                    _ => panic!("Invalid string?"),
-                }
-            };
-            self.idx += 1;
-            Some(r)
-        } else {
-            None
-        }
+    type Item = String;
+
+    fn next(&mut self) -> Option<String> {
+        let string = self.string_list.get(self.idx)?;
+        self.idx += 1;
+        Some(string)
     }
 
     fn size_hint(&self) -> (usize, Option<usize>) {
-        let sz = unsafe { sys::SBStringListGetSize(self.string_list.raw) } as usize;
+        let sz = self.string_list.len();
         (sz - self.idx, Some(sz))
     }
 }
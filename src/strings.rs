@@ -0,0 +1,241 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use std::ffi::{CStr, CString};
+use std::os::raw::c_char;
+use std::str;
+
+/// Strings shorter than this are converted to C strings on the stack.
+const STACK_BUFFER_SIZE: usize = 128;
+
+/// A string which can be passed to LLDB.
+///
+/// LLDB takes NUL-terminated C strings, so a Rust string has to be
+/// copied before it can be passed in. The methods which are likely to
+/// be called in hot loops, such as [`SBFrame::find_variable`],
+/// [`SBValue::child_member_with_name`] and
+/// [`SBCommandInterpreter::handle_command`], accept any `AsCStr` so
+/// that this copy can be avoided or made cheap:
+///
+/// * A `CStr` or `CString` is passed to LLDB without being copied.
+///   Convert a name once and reuse it when looking it up repeatedly.
+/// * A `str` or `String` shorter than 128 bytes is copied into a
+///   buffer on the stack, without allocating.
+/// * Longer strings are copied into a new `CString`.
+///
+/// ```no_run
+/// # use lldb::SBFrame;
+/// # use std::ffi::CString;
+/// # fn lookup(frames: &[SBFrame]) {
+/// let name = CString::new("self").unwrap();
+/// for frame in frames {
+///     println!("{:?}", frame.find_variable(&*name));
+/// }
+/// # }
+/// ```
+///
+/// # Panics
+///
+/// Converting a `str` or `String` panics if it contains a NUL byte.
+///
+/// [`SBFrame::find_variable`]: struct.SBFrame.html#method.find_variable
+/// [`SBValue::child_member_with_name`]: struct.SBValue.html#method.child_member_with_name
+/// [`SBCommandInterpreter::handle_command`]: struct.SBCommandInterpreter.html#method.handle_command
+pub trait AsCStr {
+    /// Call `f` with this string as a `CStr`.
+    fn with_cstr<R, F: FnOnce(&CStr) -> R>(&self, f: F) -> R;
+}
+
+impl AsCStr for CStr {
+    fn with_cstr<R, F: FnOnce(&CStr) -> R>(&self, f: F) -> R {
+        f(self)
+    }
+}
+
+impl AsCStr for CString {
+    fn with_cstr<R, F: FnOnce(&CStr) -> R>(&self, f: F) -> R {
+        f(self)
+    }
+}
+
+impl AsCStr for str {
+    fn with_cstr<R, F: FnOnce(&CStr) -> R>(&self, f: F) -> R {
+        let bytes = self.as_bytes();
+        if bytes.len() < STACK_BUFFER_SIZE {
+            let mut buffer = [0u8; STACK_BUFFER_SIZE];
+            buffer[..bytes.len()].copy_from_slice(bytes);
+            f(CStr::from_bytes_with_nul(&buffer[..=bytes.len()])
+                .expect("strings passed to LLDB must not contain NUL bytes"))
+        } else {
+            f(&CString::new(bytes).expect("strings passed to LLDB must not contain NUL bytes"))
+        }
+    }
+}
+
+impl AsCStr for String {
+    fn with_cstr<R, F: FnOnce(&CStr) -> R>(&self, f: F) -> R {
+        self.as_str().with_cstr(f)
+    }
+}
+
+/// Copy a C string returned by LLDB into a `String`.
+///
+/// LLDB returns a null pointer when there is no string, such as
+/// when the object is invalid, which gives `None`. Invalid UTF-8 is
+/// replaced rather than treated as an error.
+pub(crate) unsafe fn to_string(ptr: *const c_char) -> Option<String> {
+    if ptr.is_null() {
+        None
+    } else {
+        Some(CStr::from_ptr(ptr).to_string_lossy().into_owned())
+    }
+}
+
+/// Copy a C string returned by LLDB into a `String`, using an
+/// empty string for a null pointer.
+pub(crate) unsafe fn to_string_lossy(ptr: *const c_char) -> String {
+    to_string(ptr).unwrap_or_default()
+}
+
+/// Quote `arg` so that LLDB's command parser reads it as a single
+/// argument.
+pub(crate) fn quote_argument(arg: &str) -> String {
+    let mut quoted = String::with_capacity(arg.len() + 2);
+    quoted.push('"');
+    for c in arg.chars() {
+        if c == '"' || c == '\\' || c == '`' {
+            quoted.push('\\');
+        }
+        quoted.push(c);
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// Quote `s` as a Python string literal, for code run with the
+/// `script` command.
+#[cfg(feature = "lldb-13")]
+pub(crate) fn python_string(s: &str) -> String {
+    let mut quoted = String::with_capacity(s.len() + 2);
+    quoted.push('"');
+    for c in s.chars() {
+        match c {
+            '"' | '\\' => {
+                quoted.push('\\');
+                quoted.push(c);
+            }
+            c if (c as u32) < 0x20 || c == '\x7f' => {
+                quoted.push_str(&format!("\\x{:02x}", c as u32));
+            }
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// Borrow a C string returned by LLDB which lives as long as the
+/// program, such as the name of a broadcaster class.
+///
+/// A null pointer gives an empty string, and a string which isn't
+/// UTF-8 is cut short at the first invalid byte, as it can't be
+/// replaced without copying it.
+pub(crate) unsafe fn to_static_str(ptr: *const c_char) -> &'static str {
+    if ptr.is_null() {
+        return "";
+    }
+    let bytes = CStr::from_ptr(ptr).to_bytes();
+    match str::from_utf8(bytes) {
+        Ok(s) => s,
+        Err(e) => str::from_utf8(&bytes[..e.valid_up_to()]).unwrap(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(feature = "lldb-13")]
+    use super::python_string;
+    use super::{quote_argument, to_static_str, to_string, to_string_lossy, AsCStr};
+    use std::ptr;
+    use sys;
+    use {
+        SBCommandInterpreter, SBError, SBFileSpec, SBFrame, SBProcess, SBStringList, SBSymbol,
+        SBTarget, SBThread, SBValue,
+    };
+
+    #[test]
+    fn null_pointers() {
+        unsafe {
+            assert_eq!(to_string(ptr::null()), None);
+            assert_eq!(to_string_lossy(ptr::null()), "");
+            assert_eq!(to_static_str(ptr::null()), "");
+        }
+    }
+
+    #[test]
+    fn invalid_utf8() {
+        let bytes = b"a\xffb\0";
+        let s = unsafe { to_string_lossy(bytes.as_ptr() as *const _) };
+        assert_eq!(s, "a\u{fffd}b");
+        static BYTES: &[u8] = b"a\xffb\0";
+        assert_eq!(unsafe { to_static_str(BYTES.as_ptr() as *const _) }, "a");
+    }
+
+    #[test]
+    fn short_and_long_strings() {
+        let long = "x".repeat(200);
+        assert_eq!("main".with_cstr(|s| s.to_bytes().len()), 4);
+        assert_eq!(long.with_cstr(|s| s.to_bytes().len()), 200);
+    }
+
+    #[test]
+    fn empty_objects() {
+        assert_eq!(SBFileSpec::new().filename(), None);
+        assert_eq!(SBError::new().error_string(), "");
+        assert_eq!(SBStringList::new().iter().count(), 0);
+        assert_eq!(SBStringList::new().get(0), None);
+    }
+
+    #[test]
+    fn invalid_objects() {
+        unsafe {
+            assert_eq!(
+                SBProcess::wrap(sys::CreateSBProcess()).exit_description(),
+                None
+            );
+            assert_eq!(SBTarget::wrap(sys::CreateSBTarget()).triple(), None);
+            assert_eq!(SBThread::wrap(sys::CreateSBThread()).name(), None);
+            assert_eq!(SBFrame::wrap(sys::CreateSBFrame()).function_name(), None);
+            assert_eq!(SBValue::wrap(sys::CreateSBValue()).name(), None);
+            assert_eq!(SBValue::wrap(sys::CreateSBValue()).summary(), None);
+            assert_eq!(SBSymbol::wrap(sys::CreateSBSymbol()).name(), None);
+        }
+    }
+
+    #[test]
+    fn broadcaster_class_names() {
+        assert!(!SBCommandInterpreter::broadcaster_class_name().is_empty());
+        assert!(!SBProcess::broadcaster_class_name().is_empty());
+        assert!(!SBTarget::broadcaster_class_name().is_empty());
+        assert!(!SBThread::broadcaster_class_name().is_empty());
+    }
+
+    #[test]
+    fn quoting() {
+        assert_eq!(quote_argument("bt 3"), "\"bt 3\"");
+        assert_eq!(quote_argument(r#"p "a\b" `x`"#), r#""p \"a\\b\" \`x\`""#);
+    }
+
+    #[test]
+    #[cfg(feature = "lldb-13")]
+    fn python_strings() {
+        assert_eq!(python_string("a.B"), "\"a.B\"");
+        assert_eq!(
+            python_string("{\"k\": \"a\\\\b\"}\n"),
+            r#""{\"k\": \"a\\\\b\"}\x0a""#
+        );
+    }
+}
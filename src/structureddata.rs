@@ -4,29 +4,83 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
-use super::error::SBError;
+use super::error::{InvalidRefError, SBError};
 use super::stream::SBStream;
+use super::stringlist::SBStringList;
+use super::StructuredDataType;
+#[cfg(feature = "json")]
+use serde_json;
+use std::convert::TryFrom;
 use std::ffi::CString;
 use std::fmt;
 use std::ptr;
 use sys;
 
-/// The value of a variable, register or expression.
+/// Structured data, such as a dictionary, array or scalar value,
+/// in a JSON-like format.
+///
+/// This is used by a number of APIs to pass around extended
+/// information, such as the details of the available platforms.
+///
+/// The kind of data held can be found with [`data_type`]. The
+/// children of an array or dictionary can be accessed with
+/// [`item_at_index`] and [`value_for_key`] respectively.
+///
+/// Structured data can be converted to and from JSON text with
+/// [`to_json_string`] and [`from_json_str`]. With the `json` feature
+/// enabled, it can also be converted to and from a `serde_json::Value`
+/// with `to_json_value` and `from_json_value`.
+///
+/// [`data_type`]: #method.data_type
+/// [`item_at_index`]: #method.item_at_index
+/// [`value_for_key`]: #method.value_for_key
+/// [`to_json_string`]: #method.to_json_string
+/// [`from_json_str`]: #method.from_json_str
 pub struct SBStructuredData {
     /// The underlying raw `SBStructuredDataRef`.
     pub raw: sys::SBStructuredDataRef,
 }
 
 impl SBStructuredData {
+    /// Construct a new, empty `SBStructuredData`.
+    pub fn new() -> SBStructuredData {
+        unsafe { SBStructuredData::wrap(sys::CreateSBStructuredData()) }
+    }
+
+    /// Construct a new `SBStructuredData` from JSON text.
+    pub fn from_json_str(json: &str) -> Result<SBStructuredData, SBError> {
+        let mut data = SBStructuredData::new();
+        let stream = SBStream::new();
+        stream.print(json);
+        data.set_from_json(&stream)?;
+        Ok(data)
+    }
+
+    /// Construct a new `SBStructuredData` from a JSON value.
+    #[cfg(feature = "json")]
+    pub fn from_json_value(json: &serde_json::Value) -> Result<SBStructuredData, SBError> {
+        SBStructuredData::from_json_str(&json.to_string())
+    }
+
     /// Construct a new `SBStructuredData`.
-    pub fn wrap(raw: sys::SBStructuredDataRef) -> SBStructuredData {
+    ///
+    /// # Safety
+    ///
+    /// `raw` must be a valid reference, which is disposed of when the
+    /// `SBStructuredData` is dropped. Use [`maybe_wrap`] when it may be invalid.
+    ///
+    /// [`maybe_wrap`]: #method.maybe_wrap
+    pub unsafe fn wrap(raw: sys::SBStructuredDataRef) -> SBStructuredData {
         SBStructuredData { raw }
     }
 
     /// Construct a new `Some(SBStructuredData)` or `None`.
+    ///
+    /// `raw` is disposed of if it is invalid.
     pub fn maybe_wrap(raw: sys::SBStructuredDataRef) -> Option<SBStructuredData> {
-        if unsafe { sys::SBStructuredDataIsValid(raw) != 0 } {
-            Some(SBStructuredData { raw })
+        let wrapped = SBStructuredData { raw };
+        if wrapped.is_valid() {
+            Some(wrapped)
         } else {
             None
         }
@@ -38,13 +92,13 @@ impl SBStructuredData {
     }
 
     #[allow(missing_docs)]
-    pub fn clear(&self) {
+    pub fn clear(&mut self) {
         unsafe { sys::SBStructuredDataClear(self.raw) };
     }
 
     #[allow(missing_docs)]
-    pub fn set_from_json(&self, stream: &SBStream) -> Result<(), SBError> {
-        let e = SBError::wrap(unsafe { sys::SBStructuredDataSetFromJSON(self.raw, stream.raw) });
+    pub fn set_from_json(&mut self, stream: &SBStream) -> Result<(), SBError> {
+        let e = unsafe { SBError::wrap(sys::SBStructuredDataSetFromJSON(self.raw, stream.raw)) };
         if e.is_success() {
             Ok(())
         } else {
@@ -55,7 +109,7 @@ impl SBStructuredData {
     #[allow(missing_docs)]
     pub fn get_as_json(&self) -> Result<SBStream, SBError> {
         let stream = SBStream::new();
-        let e = SBError::wrap(unsafe { sys::SBStructuredDataGetAsJSON(self.raw, stream.raw) });
+        let e = unsafe { SBError::wrap(sys::SBStructuredDataGetAsJSON(self.raw, stream.raw)) };
         if e.is_success() {
             Ok(stream)
         } else {
@@ -63,11 +117,33 @@ impl SBStructuredData {
         }
     }
 
+    /// Convert this data structure into JSON text.
+    pub fn to_json_string(&self) -> Result<String, SBError> {
+        self.get_as_json().map(|stream| stream.data().to_owned())
+    }
+
+    /// Convert this data structure into a JSON value.
+    #[cfg(feature = "json")]
+    pub fn to_json_value(&self) -> Result<serde_json::Value, SBError> {
+        let json = self.to_json_string()?;
+        serde_json::from_str(&json).map_err(|e| SBError::from_message(&e.to_string()))
+    }
+
     /// Return the type of data in this data structure.
-    pub fn data_type(&self) -> sys::StructuredDataType {
+    pub fn data_type(&self) -> StructuredDataType {
         unsafe { sys::SBStructuredDataGetType(self.raw) }
     }
 
+    /// Return the keys of this data structure if it is a dictionary type.
+    pub fn keys(&self) -> Option<SBStringList> {
+        let keys = SBStringList::new();
+        if unsafe { sys::SBStructuredDataGetKeys(self.raw, keys.raw) } {
+            Some(keys)
+        } else {
+            None
+        }
+    }
+
     /// Return the size (number of elements) in this data structure
     /// if it is an array or dictionary type. For other types,
     /// `0` will be returned.
@@ -92,7 +168,7 @@ impl SBStructuredData {
 
     /// Return the integer value if this data structure is an integer type.
     pub fn integer_value(&self) -> Option<u64> {
-        if self.data_type() == sys::StructuredDataType::Integer {
+        if self.data_type() == StructuredDataType::Integer {
             Some(unsafe { sys::SBStructuredDataGetIntegerValue(self.raw, 0) })
         } else {
             None
@@ -102,7 +178,7 @@ impl SBStructuredData {
     /// Return the floating point value if this data structure is a floating
     /// type.
     pub fn float_value(&self) -> Option<f64> {
-        if self.data_type() == sys::StructuredDataType::Float {
+        if self.data_type() == StructuredDataType::Float {
             Some(unsafe { sys::SBStructuredDataGetFloatValue(self.raw, 0.0) })
         } else {
             None
@@ -111,7 +187,7 @@ impl SBStructuredData {
 
     /// Return the boolean value if this data structure is a boolean type.
     pub fn boolean_value(&self) -> Option<bool> {
-        if self.data_type() == sys::StructuredDataType::Boolean {
+        if self.data_type() == StructuredDataType::Boolean {
             Some(unsafe { sys::SBStructuredDataGetBooleanValue(self.raw, false as u8) != 0 })
         } else {
             None
@@ -120,7 +196,7 @@ impl SBStructuredData {
 
     /// Provides the string value if this data structure is a string type.
     pub fn string_value(&self) -> Option<String> {
-        if self.data_type() == sys::StructuredDataType::String {
+        if self.data_type() == StructuredDataType::String {
             unsafe {
                 let sz = sys::SBStructuredDataGetStringValue(self.raw, ptr::null_mut(), 0);
                 let mut buf: Vec<u8> = Vec::with_capacity(sz);
@@ -142,6 +218,12 @@ impl Clone for SBStructuredData {
     }
 }
 
+impl Default for SBStructuredData {
+    fn default() -> SBStructuredData {
+        SBStructuredData::new()
+    }
+}
+
 impl fmt::Debug for SBStructuredData {
     fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
         let stream = SBStream::new();
@@ -150,6 +232,14 @@ impl fmt::Debug for SBStructuredData {
     }
 }
 
+impl TryFrom<sys::SBStructuredDataRef> for SBStructuredData {
+    type Error = InvalidRefError;
+
+    fn try_from(raw: sys::SBStructuredDataRef) -> Result<SBStructuredData, InvalidRefError> {
+        SBStructuredData::maybe_wrap(raw).ok_or(InvalidRefError)
+    }
+}
+
 impl Drop for SBStructuredData {
     fn drop(&mut self) {
         unsafe { sys::DisposeSBStructuredData(self.raw) };
@@ -160,8 +250,10 @@ unsafe impl Send for SBStructuredData {}
 unsafe impl Sync for SBStructuredData {}
 
 #[cfg(feature = "graphql")]
-graphql_object!(SBStructuredData: super::debugger::SBDebugger | &self | {
-    field is_valid() -> bool {
+#[graphql_object(context = super::debugger::SBDebugger)]
+impl SBStructuredData {
+    #[graphql(name = "isValid")]
+    fn graphql_is_valid(&self) -> bool {
         self.is_valid()
     }
-});
+}
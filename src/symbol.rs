@@ -5,11 +5,16 @@
 // except according to those terms.
 
 use super::address::SBAddress;
+use super::error::InvalidRefError;
+#[cfg(feature = "graphql")]
+use super::graphql::U64;
 use super::instructionlist::SBInstructionList;
 use super::stream::SBStream;
+use super::strings;
 use super::target::SBTarget;
 use super::{DisassemblyFlavor, SymbolType};
-use std::ffi::{CStr, CString};
+use std::convert::TryFrom;
+use std::ffi::CString;
 use std::fmt;
 use std::ptr;
 use sys;
@@ -22,14 +27,24 @@ pub struct SBSymbol {
 
 impl SBSymbol {
     /// Construct a new `SBSymbol`.
-    pub fn wrap(raw: sys::SBSymbolRef) -> SBSymbol {
+    ///
+    /// # Safety
+    ///
+    /// `raw` must be a valid reference, which is disposed of when the
+    /// `SBSymbol` is dropped. Use [`maybe_wrap`] when it may be invalid.
+    ///
+    /// [`maybe_wrap`]: #method.maybe_wrap
+    pub unsafe fn wrap(raw: sys::SBSymbolRef) -> SBSymbol {
         SBSymbol { raw }
     }
 
     /// Construct a new `Some(SBSymbol)` or `None`.
+    ///
+    /// `raw` is disposed of if it is invalid.
     pub fn maybe_wrap(raw: sys::SBSymbolRef) -> Option<SBSymbol> {
-        if unsafe { sys::SBSymbolIsValid(raw) != 0 } {
-            Some(SBSymbol { raw })
+        let wrapped = SBSymbol { raw };
+        if wrapped.is_valid() {
+            Some(wrapped)
         } else {
             None
         }
@@ -41,33 +56,18 @@ impl SBSymbol {
     }
 
     /// The name of this function.
-    pub fn name(&self) -> &str {
-        unsafe {
-            match CStr::from_ptr(sys::SBSymbolGetName(self.raw)).to_str() {
-                Ok(s) => s,
-                _ => panic!("Invalid string?"),
-            }
-        }
+    pub fn name(&self) -> Option<String> {
+        unsafe { strings::to_string(sys::SBSymbolGetName(self.raw)) }
     }
 
     /// The display name for the function, as it should be seen in a UI.
-    pub fn display_name(&self) -> &str {
-        unsafe {
-            match CStr::from_ptr(sys::SBSymbolGetDisplayName(self.raw)).to_str() {
-                Ok(s) => s,
-                _ => panic!("Invalid string?"),
-            }
-        }
+    pub fn display_name(&self) -> Option<String> {
+        unsafe { strings::to_string(sys::SBSymbolGetDisplayName(self.raw)) }
     }
 
     /// The mangled (linkage) name for this function.
-    pub fn mangled_name(&self) -> &str {
-        unsafe {
-            match CStr::from_ptr(sys::SBSymbolGetMangledName(self.raw)).to_str() {
-                Ok(s) => s,
-                _ => panic!("Invalid string?"),
-            }
-        }
+    pub fn mangled_name(&self) -> Option<String> {
+        unsafe { strings::to_string(sys::SBSymbolGetMangledName(self.raw)) }
     }
 
     ///
@@ -81,13 +81,13 @@ impl SBSymbol {
             DisassemblyFlavor::Default => None,
             DisassemblyFlavor::Intel => CString::new("intel").ok(),
         };
-        SBInstructionList::wrap(unsafe {
-            sys::SBSymbolGetInstructions2(
+        unsafe {
+            SBInstructionList::wrap(sys::SBSymbolGetInstructions2(
                 self.raw,
                 target.raw,
                 flavor.map_or(ptr::null(), |s| s.as_ptr()),
-            )
-        })
+            ))
+        }
     }
 
     /// Get the address that this symbol refers to, if present.
@@ -127,6 +127,12 @@ impl SBSymbol {
     pub fn is_synthetic(&self) -> bool {
         unsafe { sys::SBSymbolIsSynthetic(self.raw) != 0 }
     }
+
+    fn description(&self) -> String {
+        let stream = SBStream::new();
+        unsafe { sys::SBSymbolGetDescription(self.raw, stream.raw) };
+        stream.data()
+    }
 }
 
 impl Clone for SBSymbol {
@@ -139,9 +145,21 @@ impl Clone for SBSymbol {
 
 impl fmt::Debug for SBSymbol {
     fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
-        let stream = SBStream::new();
-        unsafe { sys::SBSymbolGetDescription(self.raw, stream.raw) };
-        write!(fmt, "SBSymbol {{ {} }}", stream.data())
+        write!(fmt, "SBSymbol {{ {} }}", self.description())
+    }
+}
+
+impl fmt::Display for SBSymbol {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "{}", self.description())
+    }
+}
+
+impl TryFrom<sys::SBSymbolRef> for SBSymbol {
+    type Error = InvalidRefError;
+
+    fn try_from(raw: sys::SBSymbolRef) -> Result<SBSymbol, InvalidRefError> {
+        SBSymbol::maybe_wrap(raw).ok_or(InvalidRefError)
     }
 }
 
@@ -155,41 +173,50 @@ unsafe impl Send for SBSymbol {}
 unsafe impl Sync for SBSymbol {}
 
 #[cfg(feature = "graphql")]
-graphql_object!(SBSymbol: super::debugger::SBDebugger | &self | {
-    field is_valid() -> bool {
+#[graphql_object(context = super::debugger::SBDebugger)]
+impl SBSymbol {
+    #[graphql(name = "isValid")]
+    fn graphql_is_valid(&self) -> bool {
         self.is_valid()
     }
 
-    field name() -> &str {
+    #[graphql(name = "name")]
+    fn graphql_name(&self) -> Option<String> {
         self.name()
     }
 
-    field display_name() -> &str {
+    #[graphql(name = "displayName")]
+    fn graphql_display_name(&self) -> Option<String> {
         self.display_name()
     }
 
-    field mangled_name() -> &str {
+    #[graphql(name = "mangledName")]
+    fn graphql_mangled_name(&self) -> Option<String> {
         self.mangled_name()
     }
 
-    field start_address() -> Option<SBAddress> {
+    #[graphql(name = "startAddress")]
+    fn graphql_start_address(&self) -> Option<SBAddress> {
         self.start_address()
     }
 
-    field end_address() -> Option<SBAddress> {
+    #[graphql(name = "endAddress")]
+    fn graphql_end_address(&self) -> Option<SBAddress> {
         self.end_address()
     }
 
-    // TODO(bm) This should be a u32
-    field prologue_byte_size() -> i32 {
-        self.prologue_byte_size() as i32
+    #[graphql(name = "prologueByteSize")]
+    fn graphql_prologue_byte_size(&self) -> U64 {
+        self.prologue_byte_size().into()
     }
 
-    field is_external() -> bool {
+    #[graphql(name = "isExternal")]
+    fn graphql_is_external(&self) -> bool {
         self.is_external()
     }
 
-    field is_synthetic() -> bool {
+    #[graphql(name = "isSynthetic")]
+    fn graphql_is_synthetic(&self) -> bool {
         self.is_synthetic()
     }
-});
+}
@@ -7,11 +7,13 @@
 use super::address::SBAddress;
 use super::block::SBBlock;
 use super::compileunit::SBCompileUnit;
+use super::error::InvalidRefError;
 use super::function::SBFunction;
 use super::lineentry::SBLineEntry;
 use super::module::SBModule;
 use super::stream::SBStream;
 use super::symbol::SBSymbol;
+use std::convert::TryFrom;
 use std::fmt;
 use sys;
 
@@ -23,14 +25,24 @@ pub struct SBSymbolContext {
 
 impl SBSymbolContext {
     /// Construct a new `SBSymbolContext`.
-    pub fn wrap(raw: sys::SBSymbolContextRef) -> SBSymbolContext {
+    ///
+    /// # Safety
+    ///
+    /// `raw` must be a valid reference, which is disposed of when the
+    /// `SBSymbolContext` is dropped. Use [`maybe_wrap`] when it may be invalid.
+    ///
+    /// [`maybe_wrap`]: #method.maybe_wrap
+    pub unsafe fn wrap(raw: sys::SBSymbolContextRef) -> SBSymbolContext {
         SBSymbolContext { raw }
     }
 
     /// Construct a new `Some(SBSymbolContext)` or `None`.
+    ///
+    /// `raw` is disposed of if it is invalid.
     pub fn maybe_wrap(raw: sys::SBSymbolContextRef) -> Option<SBSymbolContext> {
-        if unsafe { sys::SBSymbolContextIsValid(raw) != 0 } {
-            Some(SBSymbolContext { raw })
+        let wrapped = SBSymbolContext { raw };
+        if wrapped.is_valid() {
+            Some(wrapped)
         } else {
             None
         }
@@ -43,22 +55,22 @@ impl SBSymbolContext {
 
     #[allow(missing_docs)]
     pub fn module(&self) -> SBModule {
-        SBModule::wrap(unsafe { sys::SBSymbolContextGetModule(self.raw) })
+        unsafe { SBModule::wrap(sys::SBSymbolContextGetModule(self.raw)) }
     }
 
     #[allow(missing_docs)]
     pub fn compile_unit(&self) -> SBCompileUnit {
-        SBCompileUnit::wrap(unsafe { sys::SBSymbolContextGetCompileUnit(self.raw) })
+        unsafe { SBCompileUnit::wrap(sys::SBSymbolContextGetCompileUnit(self.raw)) }
     }
 
     #[allow(missing_docs)]
     pub fn function(&self) -> SBFunction {
-        SBFunction::wrap(unsafe { sys::SBSymbolContextGetFunction(self.raw) })
+        unsafe { SBFunction::wrap(sys::SBSymbolContextGetFunction(self.raw)) }
     }
 
     #[allow(missing_docs)]
     pub fn block(&self) -> SBBlock {
-        SBBlock::wrap(unsafe { sys::SBSymbolContextGetBlock(self.raw) })
+        unsafe { SBBlock::wrap(sys::SBSymbolContextGetBlock(self.raw)) }
     }
 
     #[allow(missing_docs)]
@@ -68,7 +80,7 @@ impl SBSymbolContext {
 
     #[allow(missing_docs)]
     pub fn symbol(&self) -> SBSymbol {
-        SBSymbol::wrap(unsafe { sys::SBSymbolContextGetSymbol(self.raw) })
+        unsafe { SBSymbol::wrap(sys::SBSymbolContextGetSymbol(self.raw)) }
     }
 
     #[allow(missing_docs)]
@@ -77,13 +89,19 @@ impl SBSymbolContext {
         curr_frame_pc: &SBAddress,
         parent_frame_addr: &SBAddress,
     ) -> SBSymbolContext {
-        SBSymbolContext::wrap(unsafe {
-            sys::SBSymbolContextGetParentOfInlinedScope(
+        unsafe {
+            SBSymbolContext::wrap(sys::SBSymbolContextGetParentOfInlinedScope(
                 self.raw,
                 curr_frame_pc.raw,
                 parent_frame_addr.raw,
-            )
-        })
+            ))
+        }
+    }
+
+    fn description(&self) -> String {
+        let stream = SBStream::new();
+        unsafe { sys::SBSymbolContextGetDescription(self.raw, stream.raw) };
+        stream.data()
     }
 }
 
@@ -97,9 +115,21 @@ impl Clone for SBSymbolContext {
 
 impl fmt::Debug for SBSymbolContext {
     fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
-        let stream = SBStream::new();
-        unsafe { sys::SBSymbolContextGetDescription(self.raw, stream.raw) };
-        write!(fmt, "SBSymbolContext {{ {} }}", stream.data())
+        write!(fmt, "SBSymbolContext {{ {} }}", self.description())
+    }
+}
+
+impl fmt::Display for SBSymbolContext {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "{}", self.description())
+    }
+}
+
+impl TryFrom<sys::SBSymbolContextRef> for SBSymbolContext {
+    type Error = InvalidRefError;
+
+    fn try_from(raw: sys::SBSymbolContextRef) -> Result<SBSymbolContext, InvalidRefError> {
+        SBSymbolContext::maybe_wrap(raw).ok_or(InvalidRefError)
     }
 }
 
@@ -113,32 +143,40 @@ unsafe impl Send for SBSymbolContext {}
 unsafe impl Sync for SBSymbolContext {}
 
 #[cfg(feature = "graphql")]
-graphql_object!(SBSymbolContext: super::debugger::SBDebugger | &self | {
-    field is_valid() -> bool {
+#[graphql_object(context = super::debugger::SBDebugger)]
+impl SBSymbolContext {
+    #[graphql(name = "isValid")]
+    fn graphql_is_valid(&self) -> bool {
         self.is_valid()
     }
 
-    field module() -> SBModule {
+    #[graphql(name = "module")]
+    fn graphql_module(&self) -> SBModule {
         self.module()
     }
 
-    field compile_unit() -> SBCompileUnit {
+    #[graphql(name = "compileUnit")]
+    fn graphql_compile_unit(&self) -> SBCompileUnit {
         self.compile_unit()
     }
 
-    field function() -> SBFunction {
+    #[graphql(name = "function")]
+    fn graphql_function(&self) -> SBFunction {
         self.function()
     }
 
-    field block() -> SBBlock {
+    #[graphql(name = "block")]
+    fn graphql_block(&self) -> SBBlock {
         self.block()
     }
 
-    field line_entry() -> Option<SBLineEntry> {
+    #[graphql(name = "lineEntry")]
+    fn graphql_line_entry(&self) -> Option<SBLineEntry> {
         self.line_entry()
     }
 
-    field symbol() -> SBSymbol {
+    #[graphql(name = "symbol")]
+    fn graphql_symbol(&self) -> SBSymbol {
         self.symbol()
     }
-});
+}
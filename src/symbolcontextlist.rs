@@ -4,13 +4,16 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
+use super::error::InvalidRefError;
+use super::stream::SBStream;
 use super::symbolcontext::SBSymbolContext;
+use std::convert::TryFrom;
+use std::fmt;
 use sys;
 
 /// A list of [symbol contexts].
 ///
 /// [symbol contexts]: struct.SBSymbolContext.html
-#[derive(Debug)]
 pub struct SBSymbolContextList {
     /// The underlying raw `SBSymbolContextListRef`.
     pub raw: sys::SBSymbolContextListRef,
@@ -18,14 +21,24 @@ pub struct SBSymbolContextList {
 
 impl SBSymbolContextList {
     /// Construct a new `SBSymbolContextList`.
-    pub fn wrap(raw: sys::SBSymbolContextListRef) -> SBSymbolContextList {
+    ///
+    /// # Safety
+    ///
+    /// `raw` must be a valid reference, which is disposed of when the
+    /// `SBSymbolContextList` is dropped. Use [`maybe_wrap`] when it may be invalid.
+    ///
+    /// [`maybe_wrap`]: #method.maybe_wrap
+    pub unsafe fn wrap(raw: sys::SBSymbolContextListRef) -> SBSymbolContextList {
         SBSymbolContextList { raw }
     }
 
     /// Construct a new `Some(SBSymbolContextList)` or `None`.
+    ///
+    /// `raw` is disposed of if it is invalid.
     pub fn maybe_wrap(raw: sys::SBSymbolContextListRef) -> Option<SBSymbolContextList> {
-        if unsafe { sys::SBSymbolContextListIsValid(raw) != 0 } {
-            Some(SBSymbolContextList { raw })
+        let wrapped = SBSymbolContextList { raw };
+        if wrapped.is_valid() {
+            Some(wrapped)
         } else {
             None
         }
@@ -80,6 +93,22 @@ impl Clone for SBSymbolContextList {
     }
 }
 
+impl fmt::Debug for SBSymbolContextList {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        let stream = SBStream::new();
+        unsafe { sys::SBSymbolContextListGetDescription(self.raw, stream.raw) };
+        write!(fmt, "SBSymbolContextList {{ {} }}", stream.data())
+    }
+}
+
+impl TryFrom<sys::SBSymbolContextListRef> for SBSymbolContextList {
+    type Error = InvalidRefError;
+
+    fn try_from(raw: sys::SBSymbolContextListRef) -> Result<SBSymbolContextList, InvalidRefError> {
+        SBSymbolContextList::maybe_wrap(raw).ok_or(InvalidRefError)
+    }
+}
+
 impl Drop for SBSymbolContextList {
     fn drop(&mut self) {
         unsafe { sys::DisposeSBSymbolContextList(self.raw) };
@@ -87,7 +116,6 @@ impl Drop for SBSymbolContextList {
 }
 
 unsafe impl Send for SBSymbolContextList {}
-unsafe impl Sync for SBSymbolContextList {}
 
 /// An iterator over the [contexts] in an [`SBSymbolContextList`].
 ///
@@ -103,9 +131,12 @@ impl<'d> Iterator for SBSymbolContextListIter<'d> {
 
     fn next(&mut self) -> Option<SBSymbolContext> {
         if self.idx < unsafe { sys::SBSymbolContextListGetSize(self.context_list.raw) as usize } {
-            let r = SBSymbolContext::wrap(unsafe {
-                sys::SBSymbolContextListGetContextAtIndex(self.context_list.raw, self.idx as u32)
-            });
+            let r = unsafe {
+                SBSymbolContext::wrap(sys::SBSymbolContextListGetContextAtIndex(
+                    self.context_list.raw,
+                    self.idx as u32,
+                ))
+            };
             self.idx += 1;
             Some(r)
         } else {
@@ -0,0 +1,474 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Turning addresses into function names and source locations without
+//! a process.
+//!
+//! Stack traces logged by a program, such as from a crash handler,
+//! are often only a list of addresses. [`symbolicate`] resolves them
+//! with a target created from the executable and its debug
+//! information. When the program was loaded at a different address
+//! than it was linked at, each module is first placed at the slide
+//! that was logged with the trace:
+//!
+//! ```no_run
+//! # use lldb::{SBDebugger, SBError};
+//! # use lldb::symbolication;
+//! # fn show(debugger: &SBDebugger) -> Result<(), SBError> {
+//! let target = debugger.create_target("server", None, None, false)?;
+//! let module = target.modules().next().unwrap();
+//! target.set_module_load_address(&module, 0x5555_5555_4000)?;
+//! for address in symbolication::symbolicate(&target, &[0x5555_5555_5189, 0x5555_5555_5230]) {
+//!     print!("{}", address);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Crash logs usually list the binary images that were loaded, with
+//! their UUIDs and load addresses. [`apply_slides`] finds the modules
+//! of the target with those UUIDs and slides each of them to where it
+//! was loaded, which saves doing it by hand:
+//!
+//! ```no_run
+//! # use lldb::{SBError, SBTarget};
+//! # use lldb::symbolication;
+//! # fn show(target: &SBTarget, report: &str) -> Result<(), SBError> {
+//! let images = symbolication::parse_binary_images(report);
+//! for image in symbolication::apply_slides(target, &images)? {
+//!     if image.module.is_none() {
+//!         eprintln!("no symbols for {:?}", image.image.path);
+//!     }
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Addresses are looked up as load addresses. When nothing has been
+//! given a load address, they are looked up as file addresses, the
+//! addresses in the object files.
+//!
+//! Return addresses taken from a stack point at the instruction after
+//! the call, which can belong to the next line or even the next
+//! function. Subtract one from them to find the call itself.
+//!
+//! [`symbolicate`]: fn.symbolicate.html
+//! [`apply_slides`]: fn.apply_slides.html
+
+use super::address::SBAddress;
+use super::block::SBBlock;
+use super::error::SBError;
+use super::filespec::SBFileSpec;
+use super::lineentry::SBLineEntry;
+use super::module::SBModule;
+use super::target::SBTarget;
+use std::fmt;
+
+/// An address, with where it is in its module and the functions it is
+/// in.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SymbolizedAddress {
+    /// The address that was looked up.
+    pub address: u64,
+    /// The path of the module containing the address, if one does.
+    pub module: Option<String>,
+    /// The offset of the address from the start of its module.
+    pub module_offset: Option<u64>,
+    /// The functions containing the address, starting with the
+    /// innermost one. All but the last were inlined into the one
+    /// following them. This is empty when no symbol contains the
+    /// address.
+    pub frames: Vec<SymbolizedFrame>,
+}
+
+/// A function containing a [`SymbolizedAddress`], and where in it the
+/// address is.
+///
+/// [`SymbolizedAddress`]: struct.SymbolizedAddress.html
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SymbolizedFrame {
+    /// The name of the function.
+    pub function: String,
+    /// The offset of the address from the start of the function, for
+    /// the outermost function.
+    pub function_offset: Option<u64>,
+    /// The source file, if there is debug information for it.
+    pub file: Option<String>,
+    /// The line in `file`.
+    pub line: Option<u32>,
+    /// The column in `line`.
+    pub column: Option<u32>,
+    /// Was this function inlined into the next one?
+    pub inlined: bool,
+}
+
+impl SymbolizedAddress {
+    /// The innermost function containing the address.
+    pub fn function(&self) -> Option<&str> {
+        self.frames.first().map(|frame| frame.function.as_str())
+    }
+}
+
+/// Look up each of `addresses` in `target`, in order.
+///
+/// Addresses which aren't in any module are returned with only their
+/// address.
+pub fn symbolicate(target: &SBTarget, addresses: &[u64]) -> Vec<SymbolizedAddress> {
+    addresses
+        .iter()
+        .map(|&address| symbolicate_address(target, address))
+        .collect()
+}
+
+fn symbolicate_address(target: &SBTarget, address: u64) -> SymbolizedAddress {
+    let mut symbolized = SymbolizedAddress {
+        address,
+        ..SymbolizedAddress::default()
+    };
+    let resolved = target.resolve_load_address(address);
+    let module = match resolved.module() {
+        Some(module) => module,
+        None => return symbolized,
+    };
+    symbolized.module = Some(module.filespec().path());
+    symbolized.module_offset = module
+        .object_file_header_address()
+        .map(|header| resolved.file_address().wrapping_sub(header.file_address()));
+
+    // Each inlined block is called from the location recorded on it,
+    // so the location of the address belongs to the innermost function
+    // and each call site to the function around it.
+    let mut location = location_of(resolved.line_entry());
+    let mut block = resolved.block().and_then(|b| b.containing_inlined_block());
+    while let Some(inlined) = block {
+        let (file, line, column) = location;
+        symbolized.frames.push(SymbolizedFrame {
+            function: inlined.inlined_name().unwrap_or_default(),
+            function_offset: None,
+            file,
+            line,
+            column,
+            inlined: true,
+        });
+        location = call_site_of(&inlined);
+        block = inlined
+            .parent()
+            .and_then(|parent| parent.containing_inlined_block());
+    }
+
+    let (function, start) = match (resolved.function(), resolved.symbol()) {
+        (Some(function), _) => (function.display_name(), Some(function.start_address())),
+        (None, Some(symbol)) => (symbol.display_name(), symbol.start_address()),
+        (None, None) => (None, None),
+    };
+    if let Some(function) = function {
+        let (file, line, column) = location;
+        symbolized.frames.push(SymbolizedFrame {
+            function,
+            function_offset: start.map(|start| offset_from(&resolved, &start)),
+            file,
+            line,
+            column,
+            inlined: false,
+        });
+    }
+    symbolized
+}
+
+/// A module listed in a crash log as loaded into the process.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct BinaryImage {
+    /// The UUID of the module, if it was logged.
+    pub uuid: Option<String>,
+    /// The address that the start of the module was loaded at.
+    pub load_address: u64,
+    /// The path of the module in the process, if it was logged.
+    pub path: Option<String>,
+}
+
+impl BinaryImage {
+    /// Parse a line from the binary images of an Apple crash report,
+    /// such as:
+    ///
+    /// ```text
+    /// 0x100ab0000 - 0x100ab7fff MyApp arm64  <1b2c3d4e5f60718293a4b5c6d7e8f901> /private/var/MyApp
+    /// ```
+    pub fn parse_apple(line: &str) -> Option<BinaryImage> {
+        let mut tokens = line.split_whitespace();
+        let load_address = parse_hex(tokens.next()?)?;
+        if tokens.next()? != "-" {
+            return None;
+        }
+        parse_hex(tokens.next()?)?;
+        let rest: Vec<&str> = tokens.collect();
+        let uuid = rest
+            .iter()
+            .position(|token| token.starts_with('<') && token.ends_with('>'));
+        let path = uuid
+            .map(|i| rest[i + 1..].join(" "))
+            .filter(|path| !path.is_empty());
+        Some(BinaryImage {
+            uuid: uuid.map(|i| normalize_uuid(rest[i])),
+            load_address,
+            path,
+        })
+    }
+}
+
+/// Parse the binary images listed in a crash report, skipping lines
+/// which aren't images.
+///
+/// See [`BinaryImage::parse_apple`] for the format.
+///
+/// [`BinaryImage::parse_apple`]: struct.BinaryImage.html#method.parse_apple
+pub fn parse_binary_images(report: &str) -> Vec<BinaryImage> {
+    report
+        .lines()
+        .filter_map(BinaryImage::parse_apple)
+        .collect()
+}
+
+/// A [`BinaryImage`] with the module of the target that it was matched
+/// to.
+///
+/// [`BinaryImage`]: struct.BinaryImage.html
+#[derive(Clone, Debug)]
+pub struct ImageMatch {
+    /// The image from the crash log.
+    pub image: BinaryImage,
+    /// The module with the same UUID, or the same file name when the
+    /// image has no UUID, if the target has one.
+    pub module: Option<SBModule>,
+    /// How far the module was moved from its address in the object
+    /// file, for [`SBTarget::set_module_load_address`].
+    ///
+    /// [`SBTarget::set_module_load_address`]: ../struct.SBTarget.html#method.set_module_load_address
+    pub slide: Option<i64>,
+}
+
+/// Match each of `images` to a module of `target` and work out how far
+/// it was slid.
+///
+/// UUIDs are compared without case or dashes, so that both the Apple
+/// and the LLDB forms match.
+pub fn match_images(target: &SBTarget, images: &[BinaryImage]) -> Vec<ImageMatch> {
+    let modules: Vec<(SBModule, Option<String>, Option<String>)> = target
+        .modules()
+        .map(|module| {
+            let uuid = module.uuid_string().map(|uuid| normalize_uuid(&uuid));
+            let name = module.filespec().filename();
+            (module, uuid, name)
+        })
+        .collect();
+    images
+        .iter()
+        .map(|image| {
+            let module = modules.iter().find(|(_, uuid, name)| match &image.uuid {
+                Some(image_uuid) => uuid.as_ref() == Some(image_uuid),
+                None => {
+                    let image_name = image.path.as_deref().and_then(|p| p.rsplit('/').next());
+                    image_name.is_some() && name.as_deref() == image_name
+                }
+            });
+            let module = module.map(|(module, _, _)| module.clone());
+            let slide = module
+                .as_ref()
+                .and_then(|module| module.object_file_header_address())
+                .map(|header| image.load_address.wrapping_sub(header.file_address()) as i64);
+            ImageMatch {
+                image: image.clone(),
+                module,
+                slide,
+            }
+        })
+        .collect()
+}
+
+/// Match `images` to the modules of `target` and load each module
+/// that was found where the crash log says it was.
+///
+/// Modules without a match are left where they are. Modules which
+/// aren't in the target must be added to it first, such as with
+/// [`SBTarget::add_module_spec`], to be matched.
+///
+/// [`SBTarget::add_module_spec`]: ../struct.SBTarget.html#method.add_module_spec
+pub fn apply_slides(target: &SBTarget, images: &[BinaryImage]) -> Result<Vec<ImageMatch>, SBError> {
+    let matches = match_images(target, images);
+    for image in &matches {
+        if let (Some(module), Some(slide)) = (&image.module, image.slide) {
+            target.set_module_load_address(module, slide)?;
+        }
+    }
+    Ok(matches)
+}
+
+fn parse_hex(text: &str) -> Option<u64> {
+    let digits = text
+        .strip_prefix("0x")
+        .or_else(|| text.strip_prefix("0X"))?;
+    u64::from_str_radix(digits, 16).ok()
+}
+
+/// Put a UUID into one form, in upper case without dashes or the
+/// angle brackets of Apple crash reports.
+fn normalize_uuid(uuid: &str) -> String {
+    uuid.chars()
+        .filter(|c| c.is_ascii_hexdigit())
+        .map(|c| c.to_ascii_uppercase())
+        .collect()
+}
+
+type Location = (Option<String>, Option<u32>, Option<u32>);
+
+fn location_of(line_entry: Option<SBLineEntry>) -> Location {
+    match line_entry {
+        Some(entry) => (
+            path_of(&entry.filespec()),
+            Some(entry.line()).filter(|&line| line > 0),
+            Some(entry.column()).filter(|&column| column > 0),
+        ),
+        None => (None, None, None),
+    }
+}
+
+fn call_site_of(block: &SBBlock) -> Location {
+    (
+        block.inlined_call_site_file().and_then(|f| path_of(&f)),
+        block.inlined_call_site_line(),
+        block.inlined_call_site_column(),
+    )
+}
+
+fn path_of(filespec: &SBFileSpec) -> Option<String> {
+    if filespec.is_valid() && filespec.filename().is_some() {
+        Some(filespec.path())
+    } else {
+        None
+    }
+}
+
+fn offset_from(address: &SBAddress, start: &SBAddress) -> u64 {
+    address.file_address().wrapping_sub(start.file_address())
+}
+
+impl fmt::Display for SymbolizedAddress {
+    /// Formats the address on one line per function, such as:
+    ///
+    /// ```text
+    /// 0x555555555189 server+0x1189 parse_header at src/parse.c:52:9 [inlined]
+    ///                              handle_request+0x39 at src/server.c:120:5
+    /// ```
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut prefix = format!("{:#x}", self.address);
+        if let Some(module) = &self.module {
+            let name = module.rsplit('/').next().unwrap_or(module);
+            prefix.push(' ');
+            prefix.push_str(name);
+            if let Some(offset) = self.module_offset {
+                prefix.push_str(&format!("+{:#x}", offset));
+            }
+        }
+        if self.frames.is_empty() {
+            return writeln!(f, "{}", prefix);
+        }
+        for (i, frame) in self.frames.iter().enumerate() {
+            if i == 0 {
+                write!(f, "{} ", prefix)?;
+            } else {
+                write!(f, "{:width$} ", "", width = prefix.len())?;
+            }
+            write!(f, "{}", frame.function)?;
+            if let Some(offset) = frame.function_offset {
+                write!(f, "+{:#x}", offset)?;
+            }
+            if let Some(file) = &frame.file {
+                write!(f, " at {}", file)?;
+                if let Some(line) = frame.line {
+                    write!(f, ":{}", line)?;
+                    if let Some(column) = frame.column {
+                        write!(f, ":{}", column)?;
+                    }
+                }
+            }
+            if frame.inlined {
+                write!(f, " [inlined]")?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        normalize_uuid, parse_binary_images, BinaryImage, SymbolizedAddress, SymbolizedFrame,
+    };
+
+    #[test]
+    fn binary_images() {
+        let report = "Binary Images:\n\
+            0x100ab0000 - 0x100ab7fff MyApp arm64  <1b2c3d4e5f60718293a4b5c6d7e8f901> /private/var/My App\n\
+            0x1a0000000 - 0x1a0001fff libfoo.dylib arm64\n\
+            EOF\n";
+        assert_eq!(
+            parse_binary_images(report),
+            vec![
+                BinaryImage {
+                    uuid: Some("1B2C3D4E5F60718293A4B5C6D7E8F901".to_string()),
+                    load_address: 0x100ab0000,
+                    path: Some("/private/var/My App".to_string()),
+                },
+                BinaryImage {
+                    uuid: None,
+                    load_address: 0x1a0000000,
+                    path: None,
+                },
+            ]
+        );
+        assert_eq!(
+            normalize_uuid("1b2c3d4e-5f60-7182-93a4-b5c6d7e8f901"),
+            "1B2C3D4E5F60718293A4B5C6D7E8F901"
+        );
+    }
+
+    #[test]
+    fn display() {
+        let address = SymbolizedAddress {
+            address: 0x1189,
+            module: Some("/srv/server".to_string()),
+            module_offset: Some(0x189),
+            frames: vec![
+                SymbolizedFrame {
+                    function: "parse".to_string(),
+                    file: Some("parse.c".to_string()),
+                    line: Some(52),
+                    column: Some(9),
+                    inlined: true,
+                    ..SymbolizedFrame::default()
+                },
+                SymbolizedFrame {
+                    function: "handle".to_string(),
+                    function_offset: Some(0x39),
+                    file: Some("server.c".to_string()),
+                    line: Some(120),
+                    ..SymbolizedFrame::default()
+                },
+            ],
+        };
+        assert_eq!(address.function(), Some("parse"));
+        assert_eq!(
+            address.to_string(),
+            "0x1189 server+0x189 parse at parse.c:52:9 [inlined]\n\
+             \x20                   handle+0x39 at server.c:120\n"
+        );
+        let unknown = SymbolizedAddress {
+            address: 0x10,
+            ..SymbolizedAddress::default()
+        };
+        assert_eq!(unknown.to_string(), "0x10\n");
+    }
+}
@@ -0,0 +1,243 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use super::cancel::CancelToken;
+use super::debugger::SBDebugger;
+use super::error::SBError;
+use super::filespec::SBFileSpec;
+use super::module::SBModule;
+use super::target::SBTarget;
+use super::FunctionNameType;
+use std::sync::mpsc;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// A name looked up in each module to make LLDB build its index of
+/// the module's debug information. It isn't expected to be found.
+const INDEX_PROBE: &str = "__lldb_rs_preload_symbols__";
+
+type ProgressCallback<'a> = Box<dyn FnMut(&PreloadProgress) + 'a>;
+
+/// Loading the symbols of every module of a target up front, created
+/// by [`SBTarget::preload_symbols`].
+///
+/// LLDB otherwise reads symbol tables and indexes debug information
+/// the first time they're needed, which is often when the first
+/// breakpoint is hit after attaching, and can take seconds for a large
+/// program. This does that work straight away, for several modules at
+/// once:
+///
+/// ```no_run
+/// # use lldb::SBTarget;
+/// # fn preload(target: &SBTarget) {
+/// let modules = target
+///     .preload_symbols(4)
+///     .on_progress(|progress| eprint!("\r{}/{} modules", progress.loaded, progress.total))
+///     .run()
+///     .unwrap();
+/// # }
+/// ```
+///
+/// While it runs, it also sets `target.preload-symbols`, and with the
+/// `lldb-14` feature turns off `symbols.load-on-demand` where LLDB has
+/// that setting, so that modules loaded meanwhile, such as by `dlopen`,
+/// are loaded in full as well. Both are put back as they were when it
+/// finishes.
+///
+/// [`SBTarget::preload_symbols`]: struct.SBTarget.html#method.preload_symbols
+pub struct SymbolPreload<'a> {
+    target: &'a SBTarget,
+    parallelism: usize,
+    progress: Option<ProgressCallback<'a>>,
+    token: Option<CancelToken>,
+}
+
+/// A module whose symbols have been loaded by a [`SymbolPreload`].
+///
+/// [`SymbolPreload`]: struct.SymbolPreload.html
+#[derive(Clone, Debug)]
+pub struct PreloadedModule {
+    /// The module.
+    pub module: SBModule,
+    /// The file its debug information came from, if any was found.
+    pub symbol_file: Option<SBFileSpec>,
+    /// The number of symbols in its symbol table.
+    pub num_symbols: usize,
+    /// The number of compile units in its debug information.
+    pub num_compile_units: u32,
+    /// How long it took to load.
+    pub duration: Duration,
+}
+
+/// How far a [`SymbolPreload`] has got, reported after each module.
+///
+/// [`SymbolPreload`]: struct.SymbolPreload.html
+#[derive(Debug)]
+pub struct PreloadProgress<'p> {
+    /// The module which has just been loaded.
+    pub module: &'p PreloadedModule,
+    /// The number of modules loaded so far.
+    pub loaded: usize,
+    /// The number of modules being loaded.
+    pub total: usize,
+}
+
+impl<'p> PreloadProgress<'p> {
+    /// The fraction of the modules which have been loaded, from 0 to
+    /// 1.
+    pub fn fraction(&self) -> f64 {
+        if self.total == 0 {
+            1.0
+        } else {
+            self.loaded as f64 / self.total as f64
+        }
+    }
+}
+
+impl<'a> SymbolPreload<'a> {
+    pub(crate) fn new(target: &'a SBTarget, parallelism: usize) -> SymbolPreload<'a> {
+        SymbolPreload {
+            target,
+            parallelism,
+            progress: None,
+            token: None,
+        }
+    }
+
+    /// Call `callback` as each module is loaded. It is called on the
+    /// thread which called [`run`].
+    ///
+    /// [`run`]: #method.run
+    pub fn on_progress<F>(mut self, callback: F) -> Self
+    where
+        F: FnMut(&PreloadProgress) + 'a,
+    {
+        self.progress = Some(Box::new(callback));
+        self
+    }
+
+    /// Stop starting on more modules when `token` is cancelled. The
+    /// modules already being loaded are finished.
+    pub fn cancel_token(mut self, token: &CancelToken) -> Self {
+        self.token = Some(token.clone());
+        self
+    }
+
+    /// Load the symbols, returning the modules in the order they
+    /// finished loading.
+    ///
+    /// This fails only if `target.preload-symbols` can't be set.
+    pub fn run(mut self) -> Result<Vec<PreloadedModule>, SBError> {
+        let mut settings = RestoreSettings {
+            debugger: self.target.debugger(),
+            previous: vec![],
+        };
+        settings.set("target.preload-symbols", "true")?;
+        // LLDB only has this setting when it was built with it, so
+        // carry on without it.
+        #[cfg(feature = "lldb-14")]
+        let _ = settings.set("symbols.load-on-demand", "false");
+
+        let modules: Vec<SBModule> = self.target.modules().collect();
+        let total = modules.len();
+        let workers = match self.parallelism {
+            0 => thread::available_parallelism().map_or(1, |n| n.get()),
+            n => n,
+        }
+        .min(total);
+
+        let queue = Arc::new(Mutex::new(modules.into_iter()));
+        let (sender, receiver) = mpsc::channel();
+        let handles: Vec<_> = (0..workers)
+            .map(|_| {
+                let queue = Arc::clone(&queue);
+                let sender = sender.clone();
+                let token = self.token.clone();
+                thread::spawn(move || loop {
+                    if token.as_ref().is_some_and(CancelToken::is_cancelled) {
+                        break;
+                    }
+                    let module = match queue.lock().unwrap().next() {
+                        Some(module) => module,
+                        None => break,
+                    };
+                    if sender.send(preload(module)).is_err() {
+                        break;
+                    }
+                })
+            })
+            .collect();
+        drop(sender);
+
+        let mut loaded = vec![];
+        for module in receiver {
+            loaded.push(module);
+            if let Some(ref mut progress) = self.progress {
+                progress(&PreloadProgress {
+                    module: loaded.last().unwrap(),
+                    loaded: loaded.len(),
+                    total,
+                });
+            }
+        }
+        for handle in handles {
+            let _ = handle.join();
+        }
+        Ok(loaded)
+    }
+}
+
+/// Settings changed for the length of a [`SymbolPreload`], which are
+/// put back as they were when this is dropped.
+///
+/// [`SymbolPreload`]: struct.SymbolPreload.html
+struct RestoreSettings {
+    debugger: SBDebugger,
+    previous: Vec<(&'static str, String)>,
+}
+
+impl RestoreSettings {
+    fn set(&mut self, name: &'static str, value: &str) -> Result<(), SBError> {
+        let previous = self
+            .debugger
+            .setting(name)
+            .ok_or_else(|| SBError::from_message(&format!("there is no setting {}", name)))?
+            .join(" ");
+        self.debugger.set_setting(name, value)?;
+        self.previous.push((name, previous));
+        Ok(())
+    }
+}
+
+impl Drop for RestoreSettings {
+    fn drop(&mut self) {
+        for (name, value) in self.previous.iter().rev() {
+            // Errors can't be reported from here, and the setting was
+            // changed successfully moments ago.
+            let _ = self.debugger.set_setting(name, value);
+        }
+    }
+}
+
+/// Make LLDB read the symbol table of `module` and index its debug
+/// information.
+fn preload(module: SBModule) -> PreloadedModule {
+    let start = Instant::now();
+    let num_symbols = module.symbols().len();
+    // Listing the compile units makes LLDB locate and parse the symbol
+    // file, and looking up a name builds its index.
+    let num_compile_units = module.num_compile_units();
+    module.find_functions(INDEX_PROBE, FunctionNameType::Auto as u32);
+    let symbol_file = module.symbol_filespec();
+    PreloadedModule {
+        module,
+        symbol_file,
+        num_symbols,
+        num_compile_units,
+        duration: start.elapsed(),
+    }
+}
@@ -4,28 +4,59 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
+use super::address::SBAddress;
 use super::attachinfo::SBAttachInfo;
 use super::breakpoint::SBBreakpoint;
+use super::breakpointbuilder::BreakpointBuilder;
 use super::broadcaster::SBBroadcaster;
+use super::cancel::{CancelError, CancelToken};
+use super::data::SBData;
 use super::debugger::SBDebugger;
-use super::error::SBError;
+use super::error::{InvalidRefError, SBError};
 use super::event::SBEvent;
 use super::expressionoptions::SBExpressionOptions;
 use super::filespec::SBFileSpec;
+use super::filespeclist::SBFileSpecList;
+use super::instructionlist::SBInstructionList;
 use super::launchinfo::SBLaunchInfo;
+use super::listener::SBListener;
+#[cfg(feature = "lldb-17")]
+use super::lldb_pid_t;
 use super::module::SBModule;
 use super::modulespec::SBModuleSpec;
 use super::platform::SBPlatform;
-use super::process::SBProcess;
+use super::process::{SBProcess, StopSnapshot};
+use super::remoteurl::RemoteUrl;
+use super::section::SBSection;
+use super::sourcemanager::SBSourceManager;
+#[cfg(feature = "json")]
+use super::statistics::SessionStatistics;
 use super::stream::SBStream;
+use super::strings;
+use super::structureddata::SBStructuredData;
 use super::symbolcontextlist::SBSymbolContextList;
+use super::symbolpreload::SymbolPreload;
+use super::timeout::{with_timeout_or_interrupt, TimeoutError};
+use super::trace::traced;
+use super::types::SBType;
 use super::value::SBValue;
 use super::watchpoint::SBWatchpoint;
-use super::{lldb_addr_t, DescriptionLevel, MatchType, SymbolType};
-use std::ffi::{CStr, CString};
+use super::{
+    lldb_addr_t, BasicType, ByteOrder, DescriptionLevel, MatchType, StateType, SymbolType,
+};
+use std::convert::TryFrom;
+use std::error::Error;
+use std::ffi::CString;
 use std::fmt;
+use std::ptr;
+use std::time::{Duration, Instant};
 use sys;
 
+/// Printed before the result of launching or attaching to a scripted
+/// process, to find it among anything printed by the class itself.
+#[cfg(feature = "lldb-13")]
+const SCRIPTED_PROCESS_MARKER: &str = "lldb.rs scripted process result: ";
+
 /// The target program running under the debugger.
 ///
 /// # Process Management
@@ -88,15 +119,39 @@ pub struct SBTarget {
 }
 
 impl SBTarget {
+    /// Broadcast when a breakpoint is added, removed or changed.
+    pub const BROADCAST_BIT_BREAKPOINT_CHANGED: u32 = 1 << 0;
+    /// Broadcast when modules are loaded into the process. Use
+    /// [`SBTargetEvent::modules`] to find out which.
+    ///
+    /// [`SBTargetEvent::modules`]: struct.SBTargetEvent.html#method.modules
+    pub const BROADCAST_BIT_MODULES_LOADED: u32 = 1 << 1;
+    /// Broadcast when modules are unloaded from the process.
+    pub const BROADCAST_BIT_MODULES_UNLOADED: u32 = 1 << 2;
+    /// Broadcast when a watchpoint is added, removed or changed.
+    pub const BROADCAST_BIT_WATCHPOINT_CHANGED: u32 = 1 << 3;
+    /// Broadcast when symbols are loaded for modules.
+    pub const BROADCAST_BIT_SYMBOLS_LOADED: u32 = 1 << 4;
+
     /// Construct a new `SBTarget`.
-    pub fn wrap(raw: sys::SBTargetRef) -> SBTarget {
+    ///
+    /// # Safety
+    ///
+    /// `raw` must be a valid reference, which is disposed of when the
+    /// `SBTarget` is dropped. Use [`maybe_wrap`] when it may be invalid.
+    ///
+    /// [`maybe_wrap`]: #method.maybe_wrap
+    pub unsafe fn wrap(raw: sys::SBTargetRef) -> SBTarget {
         SBTarget { raw }
     }
 
     /// Construct a new `Some(SBTarget)` or `None`.
+    ///
+    /// `raw` is disposed of if it is invalid.
     pub fn maybe_wrap(raw: sys::SBTargetRef) -> Option<SBTarget> {
-        if unsafe { sys::SBTargetIsValid(raw) != 0 } {
-            Some(SBTarget { raw })
+        let wrapped = SBTarget { raw };
+        if wrapped.is_valid() {
+            Some(wrapped)
         } else {
             None
         }
@@ -109,12 +164,7 @@ impl SBTarget {
 
     #[allow(missing_docs)]
     pub fn broadcaster_class_name() -> &'static str {
-        unsafe {
-            match CStr::from_ptr(sys::SBTargetGetBroadcasterClassName()).to_str() {
-                Ok(s) => s,
-                _ => panic!("Invalid string?"),
-            }
-        }
+        unsafe { strings::to_static_str(sys::SBTargetGetBroadcasterClassName()) }
     }
 
     /// Get the [`SBPlatform`] associated with this target.
@@ -130,55 +180,444 @@ impl SBTarget {
         }
     }
 
-    /// Get the [`SBProcess`] associated with this target.
+    /// Get the [`SBProcess`] associated with this target, if it has
+    /// been launched, attached to or loaded from a core file.
     ///
     /// [`SBProcess`]: struct.SBProcess.html
-    pub fn process(&self) -> SBProcess {
-        unsafe {
-            SBProcess {
-                raw: sys::SBTargetGetProcess(self.raw),
-            }
-        }
+    pub fn process(&self) -> Option<SBProcess> {
+        SBProcess::maybe_wrap(unsafe { sys::SBTargetGetProcess(self.raw) })
     }
 
     /// Launch a target for debugging.
     pub fn launch(&self, launch_info: SBLaunchInfo) -> Result<SBProcess, SBError> {
-        let error: SBError = SBError::new();
-        let process =
-            SBProcess::wrap(unsafe { sys::SBTargetLaunch2(self.raw, launch_info.raw, error.raw) });
-        if error.is_success() {
-            Ok(process)
-        } else {
-            Err(error)
+        traced("SBTarget::launch", || {
+            #[cfg(feature = "lldb-13")]
+            {
+                if let Some(class_name) = launch_info.scripted_process_class_name() {
+                    return self.start_scripted_process(
+                        "Launch",
+                        "lldb.SBLaunchInfo(None)",
+                        &class_name,
+                        launch_info.scripted_process_dictionary(),
+                    );
+                }
+            }
+            let error: SBError = SBError::new();
+            let process = unsafe {
+                SBProcess::wrap(sys::SBTargetLaunch2(self.raw, launch_info.raw, error.raw))
+            };
+            if error.is_success() {
+                Ok(process)
+            } else {
+                Err(error)
+            }
+        })
+    }
+
+    /// Launch a target for debugging and wait for the process to stop.
+    ///
+    /// In asynchronous mode, [`launch`] returns while the process is
+    /// still starting up, and the caller must then wait for the process
+    /// to stop, taking care to skip over any events for stops from which
+    /// the process has automatically restarted. This takes care of that.
+    ///
+    /// Events are read from the listener set on `launch_info`, or from
+    /// the debugger's listener if none has been set. Only events from
+    /// the new process are consumed.
+    ///
+    /// The process will stop at the entry point if
+    /// [`SBLaunchInfo::set_stop_at_entry`] was used. Otherwise, it
+    /// will run until it hits a breakpoint, crashes or exits.
+    ///
+    /// ```no_run
+    /// # use lldb::{LaunchError, SBLaunchInfo, SBTarget};
+    /// # use std::time::Duration;
+    /// # fn launch(target: &SBTarget) -> Result<(), LaunchError> {
+    /// let launch_info = SBLaunchInfo::builder().stop_at_entry(true).build();
+    /// let (process, stop) = target.launch_and_wait(&launch_info, Duration::from_secs(10))?;
+    /// println!("Stopped at {:#x}", stop.pc);
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`launch`]: #method.launch
+    /// [`SBLaunchInfo::set_stop_at_entry`]: struct.SBLaunchInfo.html#method.set_stop_at_entry
+    pub fn launch_and_wait(
+        &self,
+        launch_info: &SBLaunchInfo,
+        timeout: Duration,
+    ) -> Result<(SBProcess, StopSnapshot), LaunchError> {
+        let debugger = self.debugger();
+        let listener = launch_info
+            .listener()
+            .unwrap_or_else(|| debugger.listener());
+        let process = self
+            .launch(launch_info.clone())
+            .map_err(LaunchError::Launch)?;
+
+        if debugger.async() {
+            let broadcaster = process.broadcaster();
+            let deadline = Instant::now() + timeout;
+            let mut event = SBEvent::new();
+            loop {
+                let now = Instant::now();
+                if now >= deadline {
+                    return Err(LaunchError::TimedOut);
+                }
+                let remaining = deadline - now;
+                let seconds = remaining.as_secs() + u64::from(remaining.subsec_nanos() > 0);
+                if !listener.wait_for_event_for_broadcaster(
+                    seconds as u32,
+                    &broadcaster,
+                    &mut event,
+                ) {
+                    continue;
+                }
+                let process_event = match SBProcess::event_as_process_event(&event) {
+                    Some(process_event) => process_event,
+                    None => continue,
+                };
+                if process_event.restarted() {
+                    continue;
+                }
+                match process_event.state() {
+                    StateType::Stopped
+                    | StateType::Crashed
+                    | StateType::Suspended
+                    | StateType::Exited
+                    | StateType::Detached => break,
+                    _ => continue,
+                }
+            }
+        }
+
+        match process.state() {
+            StateType::Stopped | StateType::Crashed | StateType::Suspended => {
+                let snapshot = process.stop_snapshot();
+                Ok((process, snapshot))
+            }
+            StateType::Exited => Err(LaunchError::Exited {
+                status: process.exit_status(),
+                description: process.exit_description().unwrap_or_default(),
+            }),
+            state => Err(LaunchError::UnexpectedState(state)),
         }
     }
 
-    #[allow(missing_docs)]
-    pub fn load_core(&self, core_file: &str) -> Result<SBProcess, SBError> {
-        let error: SBError = SBError::new();
-        let core_file = CString::new(core_file).unwrap();
-        let process = SBProcess::wrap(unsafe {
-            sys::SBTargetLoadCore(self.raw, core_file.as_ptr(), error.raw)
-        });
-        if error.is_success() {
-            Ok(process)
-        } else {
-            Err(error)
+    /// Launch a scripted process: a process whose threads, registers
+    /// and memory are provided by the Python class `class_name`, such
+    /// as one backed by an emulator or a saved snapshot, rather than by
+    /// a real program.
+    ///
+    /// The class must subclass `lldb.plugins.scripted_process.ScriptedProcess`
+    /// and have been imported into the debugger. It is given `args`,
+    /// which is usually a dictionary, as its `SBStructuredData`
+    /// argument, with the types of its values kept:
+    ///
+    /// ```no_run
+    /// # use lldb::{SBError, SBStructuredData, SBTarget};
+    /// # fn load(target: &SBTarget) -> Result<(), SBError> {
+    /// target
+    ///     .debugger()
+    ///     .command_interpreter()
+    ///     .handle_command("command script import snapshot_process.py", false);
+    /// let args = SBStructuredData::from_json_str(r#"{"snapshot": "crash.snap", "threads": [1, 2]}"#)?;
+    /// let process = target.launch_scripted_process("snapshot_process.SnapshotProcess", &args)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// This is the same as [`launch`] with an [`SBLaunchInfo`] which
+    /// has the class and arguments set. This version of LLDB's API has
+    /// no way to give a class on the underlying `SBLaunchInfo`, so the
+    /// process is launched from Python with the `script` command,
+    /// which needs LLDB 13 or later, and the `lldb-13` feature.
+    ///
+    /// [`launch`]: #method.launch
+    /// [`SBLaunchInfo`]: struct.SBLaunchInfo.html
+    #[cfg(feature = "lldb-13")]
+    pub fn launch_scripted_process(
+        &self,
+        class_name: &str,
+        args: &SBStructuredData,
+    ) -> Result<SBProcess, SBError> {
+        let launch_info = SBLaunchInfo::new();
+        launch_info.set_scripted_process_class_name(class_name);
+        launch_info.set_scripted_process_dictionary(args);
+        self.launch(launch_info)
+    }
+
+    /// Attach to the process `pid` as a scripted process, whose
+    /// threads, registers and memory are provided by the Python class
+    /// `class_name`, such as one which adds threads of a green thread
+    /// runtime to a real process.
+    ///
+    /// `class_name` and `args` are as for [`launch_scripted_process`].
+    ///
+    /// This is the same as [`attach`] with an [`SBAttachInfo`] which
+    /// has the process ID, class and arguments set. This version of
+    /// LLDB's API has no way to give a class on the underlying
+    /// `SBAttachInfo`, so this attaches from Python with the `script`
+    /// command. Scripted processes can only be attached to with LLDB 17
+    /// or later, so this needs the `lldb-17` feature.
+    ///
+    /// [`launch_scripted_process`]: #method.launch_scripted_process
+    /// [`attach`]: #method.attach
+    /// [`SBAttachInfo`]: struct.SBAttachInfo.html
+    #[cfg(feature = "lldb-17")]
+    pub fn attach_scripted_process(
+        &self,
+        pid: lldb_pid_t,
+        class_name: &str,
+        args: &SBStructuredData,
+    ) -> Result<SBProcess, SBError> {
+        let attach_info = SBAttachInfo::new_with_pid(pid);
+        attach_info.set_scripted_process_class_name(class_name);
+        attach_info.set_scripted_process_dictionary(args);
+        self.attach(attach_info)
+    }
+
+    /// Launch or attach to a scripted process by calling `SBTarget`'s
+    /// `method` from Python with `info`, an `SBLaunchInfo` or
+    /// `SBAttachInfo` expression.
+    #[cfg(feature = "lldb-13")]
+    fn start_scripted_process(
+        &self,
+        method: &str,
+        info: &str,
+        class_name: &str,
+        args: Option<SBStructuredData>,
+    ) -> Result<SBProcess, SBError> {
+        let args = match args {
+            Some(ref args) if args.is_valid() => args.to_json_string()?,
+            _ => "{}".to_string(),
+        };
+        let debugger = self.debugger();
+        let index = unsafe { sys::SBDebuggerGetIndexOfTarget(debugger.raw, self.raw) };
+        let code = format!(
+            "info = {info}\n\
+             info.SetProcessPluginName(\"ScriptedProcess\")\n\
+             info.SetScriptedProcessClassName({class_name})\n\
+             args = lldb.SBStructuredData()\n\
+             args.SetFromJSON({args})\n\
+             info.SetScriptedProcessDictionary(args)\n\
+             error = lldb.SBError()\n\
+             lldb.debugger.GetTargetAtIndex({index}).{method}(info, error)\n\
+             print({marker} + (\"ok\" if error.Success() else \"error: %s\" % error.GetCString()))\n",
+            info = info,
+            class_name = strings::python_string(class_name),
+            args = strings::python_string(&args),
+            index = index,
+            method = method,
+            marker = strings::python_string(SCRIPTED_PROCESS_MARKER),
+        );
+        // Run the code with its own globals, so that those of the
+        // user's Python session are left alone.
+        let command = format!(
+            "script exec({}, {{\"lldb\": lldb}})",
+            strings::python_string(&code)
+        );
+        let output = self.run_command(&command)?;
+        // The class may print too, so look for the result after the
+        // marker rather than taking the last line.
+        let result = output
+            .rfind(SCRIPTED_PROCESS_MARKER)
+            .map(|start| output[start + SCRIPTED_PROCESS_MARKER.len()..].trim());
+        match result {
+            Some("ok") => self
+                .process()
+                .ok_or_else(|| SBError::from_message("the scripted process was not created")),
+            Some(result) if result.starts_with("error: ") => {
+                Err(SBError::from_message(&result["error: ".len()..]))
+            }
+            _ => Err(SBError::from_message(&format!(
+                "unexpected output from the scripted process {}: {}",
+                method.to_lowercase(),
+                output.trim()
+            ))),
         }
     }
 
+    /// Load a core file as the process of this target.
+    ///
+    /// As well as ELF and Mach-O core files, this accepts minidumps
+    /// from Windows and Breakpad. The exception which caused a
+    /// minidump to be written is reported as the stop reason of the
+    /// thread that raised it.
+    pub fn load_core(&self, core_file: &str) -> Result<SBProcess, SBError> {
+        traced("SBTarget::load_core", || {
+            let error: SBError = SBError::new();
+            let core_file = CString::new(core_file).unwrap();
+            let process = unsafe {
+                SBProcess::wrap(sys::SBTargetLoadCore(
+                    self.raw,
+                    core_file.as_ptr(),
+                    error.raw,
+                ))
+            };
+            if error.is_success() {
+                Ok(process)
+            } else {
+                Err(error)
+            }
+        })
+    }
+
+    /// Connect to a remote debug server which speaks the GDB remote
+    /// protocol, such as `gdbserver`, the gdbstub in qemu or an
+    /// OpenOCD probe.
+    ///
+    /// `plugin` is the name of the process plugin to use, which is
+    /// `gdb-remote` when it is `None`. Events for the process are
+    /// delivered to the debugger's listener.
+    ///
+    /// ```no_run
+    /// # use lldb::{RemoteUrl, SBDebugger, SBError};
+    /// # fn connect(debugger: &SBDebugger) -> Result<(), SBError> {
+    /// let target = debugger.create_target("kernel.elf", None, None, false)?;
+    /// let process = target.connect_gdb_remote(&RemoteUrl::tcp("localhost", 1234), None)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn connect_gdb_remote(
+        &self,
+        url: &RemoteUrl,
+        plugin: Option<&str>,
+    ) -> Result<SBProcess, SBError> {
+        traced("SBTarget::connect_gdb_remote", || {
+            let error: SBError = SBError::new();
+            let url = CString::new(url.as_str()).unwrap();
+            let plugin = CString::new(plugin.unwrap_or("gdb-remote")).unwrap();
+            // A listener without a name is invalid, so LLDB falls back to
+            // the debugger's listener.
+            let listener = SBListener::new();
+            let process = unsafe {
+                SBProcess::wrap(sys::SBTargetConnectRemote(
+                    self.raw,
+                    listener.raw,
+                    url.as_ptr(),
+                    plugin.as_ptr(),
+                    error.raw,
+                ))
+            };
+            if error.is_success() {
+                Ok(process)
+            } else {
+                Err(error)
+            }
+        })
+    }
+
     #[allow(missing_docs)]
     pub fn attach(&self, attach_info: SBAttachInfo) -> Result<SBProcess, SBError> {
-        let error: SBError = SBError::new();
-        let process =
-            SBProcess::wrap(unsafe { sys::SBTargetAttach(self.raw, attach_info.raw, error.raw) });
-        if error.is_success() {
-            Ok(process)
-        } else {
-            Err(error)
+        traced("SBTarget::attach", || {
+            #[cfg(feature = "lldb-17")]
+            {
+                if let Some(class_name) = attach_info.scripted_process_class_name() {
+                    let info = format!("lldb.SBAttachInfo({})", attach_info.process_id());
+                    return self.start_scripted_process(
+                        "Attach",
+                        &info,
+                        &class_name,
+                        attach_info.scripted_process_dictionary(),
+                    );
+                }
+            }
+            let error: SBError = SBError::new();
+            let process = unsafe {
+                SBProcess::wrap(sys::SBTargetAttach(self.raw, attach_info.raw, error.raw))
+            };
+            if error.is_success() {
+                Ok(process)
+            } else {
+                Err(error)
+            }
+        })
+    }
+
+    /// Attach to a process, giving up on the attach if it hasn't
+    /// finished after `timeout`.
+    ///
+    /// The process of the target is interrupted if there is one by
+    /// then. LLDB has no way to interrupt an attach before that, so
+    /// the attach may carry on in the background.
+    pub fn attach_with_timeout(
+        &self,
+        attach_info: SBAttachInfo,
+        timeout: Duration,
+    ) -> Result<SBProcess, TimeoutError> {
+        let target = self.clone();
+        self.run_with_timeout(timeout, move || target.attach(attach_info))
+    }
+
+    /// Connect to a remote debug server, giving up if the connection
+    /// hasn't been made after `timeout`. See [`connect_gdb_remote`].
+    ///
+    /// There is no process to interrupt until the connection has been
+    /// made, so a connection attempt which times out carries on in
+    /// the background until LLDB gives up on it.
+    ///
+    /// [`connect_gdb_remote`]: #method.connect_gdb_remote
+    pub fn connect_gdb_remote_with_timeout(
+        &self,
+        url: &RemoteUrl,
+        plugin: Option<&str>,
+        timeout: Duration,
+    ) -> Result<SBProcess, TimeoutError> {
+        let target = self.clone();
+        let url = url.clone();
+        let plugin = plugin.map(String::from);
+        self.run_with_timeout(timeout, move || {
+            target.connect_gdb_remote(&url, plugin.as_deref())
+        })
+    }
+
+    /// Launch a target for debugging, interrupting the launch if
+    /// `token` is cancelled.
+    pub fn launch_cancellable(
+        &self,
+        launch_info: SBLaunchInfo,
+        token: &CancelToken,
+    ) -> Result<SBProcess, CancelError> {
+        token.run(|| self.launch(launch_info), self.interrupter())
+    }
+
+    /// Attach to a process, interrupting the attach if `token` is
+    /// cancelled.
+    ///
+    /// As with [`attach_with_timeout`], the attach can only be
+    /// interrupted once the process of the target exists.
+    ///
+    /// [`attach_with_timeout`]: #method.attach_with_timeout
+    pub fn attach_cancellable(
+        &self,
+        attach_info: SBAttachInfo,
+        token: &CancelToken,
+    ) -> Result<SBProcess, CancelError> {
+        token.run(|| self.attach(attach_info), self.interrupter())
+    }
+
+    /// A function which interrupts the process of this target, if it
+    /// has one yet.
+    fn interrupter(&self) -> impl Fn() + Send + 'static {
+        let target = self.clone();
+        move || {
+            if let Some(process) = target.process() {
+                process.send_async_interrupt();
+            }
         }
     }
 
+    /// Run `op`, interrupting the process of this target if it hasn't
+    /// finished after `timeout`.
+    fn run_with_timeout<F>(&self, timeout: Duration, op: F) -> Result<SBProcess, TimeoutError>
+    where
+        F: FnOnce() -> Result<SBProcess, SBError> + Send + 'static,
+    {
+        let result = with_timeout_or_interrupt(timeout, op, self.interrupter())?;
+        Ok(result?)
+    }
+
     /// Get a filespec for the executable.
     pub fn executable(&self) -> Option<SBFileSpec> {
         SBFileSpec::maybe_wrap(unsafe { sys::SBTargetGetExecutable(self.raw) })
@@ -246,6 +685,78 @@ impl SBTarget {
         unsafe { sys::SBTargetDeleteAllBreakpoints(self.raw) };
     }
 
+    /// Start building a breakpoint, to choose its location and
+    /// settings in one chain.
+    pub fn breakpoint_builder(&self) -> BreakpointBuilder {
+        BreakpointBuilder::new(self)
+    }
+
+    /// Create a breakpoint on a function or symbol, optionally
+    /// restricted to the module named `module_name`.
+    pub fn breakpoint_create_by_name(
+        &self,
+        symbol_name: &str,
+        module_name: Option<&str>,
+    ) -> SBBreakpoint {
+        let symbol_name = CString::new(symbol_name).unwrap();
+        let module_name = module_name.map(|m| CString::new(m).unwrap());
+        unsafe {
+            SBBreakpoint::wrap(sys::SBTargetBreakpointCreateByName(
+                self.raw,
+                symbol_name.as_ptr(),
+                module_name.as_ref().map_or(ptr::null(), |m| m.as_ptr()),
+            ))
+        }
+    }
+
+    /// Create a breakpoint on a line of a source file.
+    pub fn breakpoint_create_by_location(&self, file: &str, line: u32) -> SBBreakpoint {
+        let file = CString::new(file).unwrap();
+        unsafe {
+            SBBreakpoint::wrap(sys::SBTargetBreakpointCreateByLocation(
+                self.raw,
+                file.as_ptr(),
+                line,
+            ))
+        }
+    }
+
+    /// Create a breakpoint on a line of a source file, restricted to
+    /// the modules in `modules`.
+    pub fn breakpoint_create_by_location_in_modules(
+        &self,
+        file: &str,
+        line: u32,
+        modules: &SBFileSpecList,
+    ) -> SBBreakpoint {
+        let file = SBFileSpec::from_path(file, false);
+        unsafe {
+            SBBreakpoint::wrap(sys::SBTargetBreakpointCreateByLocation4(
+                self.raw,
+                file.raw,
+                line,
+                0,
+                modules.raw,
+            ))
+        }
+    }
+
+    /// Create a breakpoint at a load address.
+    pub fn breakpoint_create_by_address(&self, address: lldb_addr_t) -> SBBreakpoint {
+        unsafe { SBBreakpoint::wrap(sys::SBTargetBreakpointCreateByAddress(self.raw, address)) }
+    }
+
+    /// Create a breakpoint at a section offset address, which moves
+    /// with the module when it is loaded.
+    pub fn breakpoint_create_by_sbaddress(&self, address: &SBAddress) -> SBBreakpoint {
+        unsafe {
+            SBBreakpoint::wrap(sys::SBTargetBreakpointCreateBySBAddress(
+                self.raw,
+                address.raw,
+            ))
+        }
+    }
+
     #[allow(missing_docs)]
     pub fn breakpoints(&self) -> SBTargetBreakpointIter {
         SBTargetBreakpointIter {
@@ -292,7 +803,7 @@ impl SBTarget {
             sys::SBTargetWatchAddress(self.raw, addr, size, read as u8, write as u8, error.raw)
         };
         if error.is_success() {
-            Ok(SBWatchpoint::wrap(watchpoint))
+            Ok(unsafe { SBWatchpoint::wrap(watchpoint) })
         } else {
             Err(error)
         }
@@ -308,15 +819,19 @@ impl SBTarget {
 
     #[allow(missing_docs)]
     pub fn broadcaster(&self) -> SBBroadcaster {
-        SBBroadcaster::wrap(unsafe { sys::SBTargetGetBroadcaster(self.raw) })
+        unsafe { SBBroadcaster::wrap(sys::SBTargetGetBroadcaster(self.raw)) }
     }
 
     #[allow(missing_docs)]
     pub fn find_functions(&self, name: &str, name_type_mask: u32) -> SBSymbolContextList {
         let name = CString::new(name).unwrap();
-        SBSymbolContextList::wrap(unsafe {
-            sys::SBTargetFindFunctions(self.raw, name.as_ptr(), name_type_mask)
-        })
+        unsafe {
+            SBSymbolContextList::wrap(sys::SBTargetFindFunctions(
+                self.raw,
+                name.as_ptr(),
+                name_type_mask,
+            ))
+        }
     }
 
     #[allow(missing_docs)]
@@ -327,24 +842,326 @@ impl SBTarget {
         matchtype: MatchType,
     ) -> SBSymbolContextList {
         let name = CString::new(name).unwrap();
-        SBSymbolContextList::wrap(unsafe {
-            sys::SBTargetFindGlobalFunctions(self.raw, name.as_ptr(), max_matches, matchtype)
+        unsafe {
+            SBSymbolContextList::wrap(sys::SBTargetFindGlobalFunctions(
+                self.raw,
+                name.as_ptr(),
+                max_matches,
+                matchtype,
+            ))
+        }
+    }
+
+    /// Resolve a load address in the process into a section offset
+    /// address.
+    pub fn resolve_load_address(&self, vm_addr: lldb_addr_t) -> SBAddress {
+        unsafe { SBAddress::wrap(sys::SBTargetResolveLoadAddress(self.raw, vm_addr)) }
+    }
+
+    /// Tell the target that `section` is loaded at `load_address`,
+    /// rather than at its address in the object file.
+    ///
+    /// This is needed when nothing reports where the sections of a
+    /// module are, such as for firmware debugged through a JTAG probe's
+    /// GDB stub, where an ELF image is often linked at one address and
+    /// copied to another, or its data relocated, at startup:
+    ///
+    /// ```no_run
+    /// # use lldb::{SBError, SBTarget};
+    /// # fn relocate(target: &SBTarget) -> Result<(), SBError> {
+    /// let module = target.modules().next().unwrap();
+    /// for (name, address) in &[(".text", 0x0800_0000), (".data", 0x2000_0000)] {
+    ///     if let Some(section) = module.find_section(name) {
+    ///         target.set_section_load_address(&section, *address)?;
+    ///     }
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn set_section_load_address(
+        &self,
+        section: &SBSection,
+        load_address: lldb_addr_t,
+    ) -> Result<(), SBError> {
+        let error = unsafe {
+            SBError::wrap(sys::SBTargetSetSectionLoadAddress(
+                self.raw,
+                section.raw,
+                load_address,
+            ))
+        };
+        if error.is_success() {
+            Ok(())
+        } else {
+            Err(error)
+        }
+    }
+
+    /// Forget the load address of `section`, so that its addresses
+    /// can no longer be resolved in the process.
+    pub fn clear_section_load_address(&self, section: &SBSection) -> Result<(), SBError> {
+        let error =
+            unsafe { SBError::wrap(sys::SBTargetClearSectionLoadAddress(self.raw, section.raw)) };
+        if error.is_success() {
+            Ok(())
+        } else {
+            Err(error)
+        }
+    }
+
+    /// Load every section of `module` at its address in the object
+    /// file plus `slide`.
+    pub fn set_module_load_address(&self, module: &SBModule, slide: i64) -> Result<(), SBError> {
+        let error = unsafe {
+            SBError::wrap(sys::SBTargetSetModuleLoadAddress(
+                self.raw, module.raw, slide,
+            ))
+        };
+        if error.is_success() {
+            Ok(())
+        } else {
+            Err(error)
+        }
+    }
+
+    /// Forget the load addresses of all of the sections of `module`.
+    pub fn clear_module_load_address(&self, module: &SBModule) -> Result<(), SBError> {
+        let error =
+            unsafe { SBError::wrap(sys::SBTargetClearModuleLoadAddress(self.raw, module.raw)) };
+        if error.is_success() {
+            Ok(())
+        } else {
+            Err(error)
+        }
+    }
+
+    /// Read and disassemble `count` instructions from the process,
+    /// starting at `base_addr`.
+    pub fn read_instructions(&self, base_addr: &SBAddress, count: u32) -> SBInstructionList {
+        unsafe {
+            SBInstructionList::wrap(sys::SBTargetReadInstructions(
+                self.raw,
+                base_addr.raw,
+                count,
+            ))
+        }
+    }
+
+    /// Load the debug information of every module now, rather than
+    /// when it is first needed, returning each module with the file
+    /// its debug information came from, if any was found.
+    ///
+    /// With the symbol download settings of [`SBDebugger`], such as
+    /// [`set_debuginfod_urls`], this downloads all of the missing
+    /// debug information up front.
+    ///
+    /// [`SBDebugger`]: struct.SBDebugger.html
+    /// [`set_debuginfod_urls`]: struct.SBDebugger.html#method.set_debuginfod_urls
+    pub fn prefetch_symbols_for_modules(&self) -> Vec<(SBModule, Option<SBFileSpec>)> {
+        self.modules()
+            .map(|module| {
+                // Listing the compile units makes LLDB locate and
+                // parse the symbol file.
+                module.num_compile_units();
+                let symbol_file = module.symbol_filespec();
+                (module, symbol_file)
+            })
+            .collect()
+    }
+
+    /// Load the symbol tables and index the debug information of
+    /// every module now, on up to `parallelism` threads at once, so
+    /// that the first breakpoint or lookup doesn't have to wait for
+    /// it. A `parallelism` of 0 uses a thread per CPU.
+    ///
+    /// See [`SymbolPreload`] for reporting progress.
+    ///
+    /// [`SymbolPreload`]: struct.SymbolPreload.html
+    pub fn preload_symbols(&self, parallelism: usize) -> SymbolPreload<'_> {
+        SymbolPreload::new(self, parallelism)
+    }
+
+    /// Find the first global or static variable named `name`.
+    pub fn find_first_global_variable(&self, name: &str) -> Option<SBValue> {
+        let name = CString::new(name).unwrap();
+        SBValue::maybe_wrap(unsafe {
+            sys::SBTargetFindFirstGlobalVariable(self.raw, name.as_ptr())
         })
     }
 
+    /// Add a stop hook which runs `commands` each time the process
+    /// stops, returning the ID of the hook.
+    ///
+    /// This is the `target stop-hook add` command, so the hook stays
+    /// in place across continues and its output goes wherever the
+    /// debugger's command output goes. For a hook written in Rust,
+    /// see [`DebugSession::add_stop_hook`].
+    ///
+    /// ```no_run
+    /// # use lldb::{SBError, SBTarget};
+    /// # fn hook(target: &SBTarget) -> Result<(), SBError> {
+    /// let id = target.add_stop_hook(&["expr queue.len", "bt 3"])?;
+    /// // ...
+    /// target.delete_stop_hook(id)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`DebugSession::add_stop_hook`]: struct.DebugSession.html#method.add_stop_hook
+    pub fn add_stop_hook(&self, commands: &[&str]) -> Result<u32, SBError> {
+        let mut command = "target stop-hook add".to_string();
+        for c in commands {
+            command.push_str(" -o ");
+            command.push_str(&strings::quote_argument(c));
+        }
+        let output = self.run_command(&command)?;
+        // The output is `Stop hook #1 added.`
+        output
+            .split('#')
+            .nth(1)
+            .and_then(|rest| {
+                let digits: String = rest.chars().take_while(char::is_ascii_digit).collect();
+                digits.parse().ok()
+            })
+            .ok_or_else(|| SBError::from_message(&format!("unexpected output: {}", output.trim())))
+    }
+
+    /// Delete the stop hook with the ID `id`.
+    pub fn delete_stop_hook(&self, id: u32) -> Result<(), SBError> {
+        self.run_command(&format!("target stop-hook delete {}", id))
+            .map(|_| ())
+    }
+
+    /// Enable or disable the stop hook with the ID `id`.
+    pub fn set_stop_hook_enabled(&self, id: u32, enabled: bool) -> Result<(), SBError> {
+        let command = if enabled { "enable" } else { "disable" };
+        self.run_command(&format!("target stop-hook {} {}", command, id))
+            .map(|_| ())
+    }
+
+    /// The IDs of the stop hooks of this target.
+    pub fn stop_hook_ids(&self) -> Vec<u32> {
+        self.run_command("target stop-hook list")
+            .unwrap_or_default()
+            .lines()
+            .filter_map(|line| line.trim().strip_prefix("Hook: "))
+            .filter_map(|id| id.trim().parse().ok())
+            .collect()
+    }
+
+    /// Run an LLDB command against this target, returning its output.
+    pub(crate) fn run_command(&self, command: &str) -> Result<String, SBError> {
+        let result = self
+            .debugger()
+            .command_interpreter()
+            .handle_command_for_target(command, self, false);
+        if result.succeeded() {
+            Ok(result.output().unwrap_or_default())
+        } else {
+            Err(SBError::from_message(&result.error().unwrap_or_default()))
+        }
+    }
+
     #[allow(missing_docs)]
     pub fn find_symbols(&self, name: &str, symbol_type: SymbolType) -> SBSymbolContextList {
         let name = CString::new(name).unwrap();
-        SBSymbolContextList::wrap(unsafe {
-            sys::SBTargetFindSymbols(self.raw, name.as_ptr(), symbol_type)
+        unsafe {
+            SBSymbolContextList::wrap(sys::SBTargetFindSymbols(
+                self.raw,
+                name.as_ptr(),
+                symbol_type,
+            ))
+        }
+    }
+
+    /// Demangle `mangled` with LLDB's own demangler.
+    ///
+    /// See [`SBDebugger::demangle`].
+    ///
+    /// [`SBDebugger::demangle`]: struct.SBDebugger.html#method.demangle
+    pub fn demangle(&self, mangled: &str) -> Option<String> {
+        self.debugger().demangle(mangled)
+    }
+
+    /// The triple of the target, such as `x86_64-unknown-linux-gnu`.
+    pub fn triple(&self) -> Option<String> {
+        unsafe { strings::to_string(sys::SBTargetGetTriple(self.raw)) }
+    }
+
+    /// The byte order of the target.
+    pub fn byte_order(&self) -> ByteOrder {
+        unsafe { sys::SBTargetGetByteOrder(self.raw) }
+    }
+
+    /// The size of an address, in bytes, for the target.
+    pub fn address_byte_size(&self) -> u32 {
+        unsafe { sys::SBTargetGetAddressByteSize(self.raw) }
+    }
+
+    /// The source manager for this target, which uses the target's
+    /// source path mappings.
+    pub fn source_manager(&self) -> SBSourceManager {
+        unsafe { SBSourceManager::wrap(sys::SBTargetGetSourceManager(self.raw)) }
+    }
+
+    /// Statistics about this debug session, such as the time spent
+    /// loading symbols and resolving breakpoints, in the same format as
+    /// the `statistics dump` command.
+    pub fn statistics(&self) -> SBStructuredData {
+        unsafe { SBStructuredData::wrap(sys::SBTargetGetStatistics(self.raw)) }
+    }
+
+    /// Statistics about this debug session, parsed from [`statistics`].
+    ///
+    /// This requires the `json` feature.
+    ///
+    /// [`statistics`]: #method.statistics
+    #[cfg(feature = "json")]
+    pub fn session_statistics(&self) -> Result<SessionStatistics, SBError> {
+        SessionStatistics::from_structured_data(&self.statistics())
+    }
+
+    /// Find the first type with the given name.
+    pub fn find_first_type(&self, type_name: &str) -> Option<SBType> {
+        let type_name = CString::new(type_name).unwrap();
+        SBType::maybe_wrap(unsafe { sys::SBTargetFindFirstType(self.raw, type_name.as_ptr()) })
+    }
+
+    /// Get the type for one of the basic types, such as `int`.
+    pub fn basic_type(&self, basic_type: BasicType) -> Option<SBType> {
+        SBType::maybe_wrap(unsafe { sys::SBTargetGetBasicType(self.raw, basic_type) })
+    }
+
+    /// Create a value of the given type from the contents of an `SBData`.
+    ///
+    /// The data should be laid out using the [`byte_order`] and
+    /// [`address_byte_size`] of this target.
+    ///
+    /// [`byte_order`]: #method.byte_order
+    /// [`address_byte_size`]: #method.address_byte_size
+    pub fn create_value_from_data(
+        &self,
+        name: &str,
+        data: &SBData,
+        value_type: &SBType,
+    ) -> Option<SBValue> {
+        let name = CString::new(name).unwrap();
+        SBValue::maybe_wrap(unsafe {
+            sys::SBTargetCreateValueFromData(self.raw, name.as_ptr(), data.raw, value_type.raw)
         })
     }
 
     /// Evaluate an expression.
     pub fn evaluate_expression(&self, expression: &str, options: &SBExpressionOptions) -> SBValue {
-        let expression = CString::new(expression).unwrap();
-        SBValue::wrap(unsafe {
-            sys::SBTargetEvaluateExpression(self.raw, expression.as_ptr(), options.raw)
+        traced("SBTarget::evaluate_expression", || {
+            let expression = CString::new(expression).unwrap();
+            unsafe {
+                SBValue::wrap(sys::SBTargetEvaluateExpression(
+                    self.raw,
+                    expression.as_ptr(),
+                    options.raw,
+                ))
+            }
         })
     }
 
@@ -356,6 +1173,12 @@ impl SBTarget {
             None
         }
     }
+
+    fn description(&self) -> String {
+        let stream = SBStream::new();
+        unsafe { sys::SBTargetGetDescription(self.raw, stream.raw, DescriptionLevel::Brief) };
+        stream.data()
+    }
 }
 
 impl Clone for SBTarget {
@@ -368,9 +1191,21 @@ impl Clone for SBTarget {
 
 impl fmt::Debug for SBTarget {
     fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
-        let stream = SBStream::new();
-        unsafe { sys::SBTargetGetDescription(self.raw, stream.raw, DescriptionLevel::Brief) };
-        write!(fmt, "SBTarget {{ {} }}", stream.data())
+        write!(fmt, "SBTarget {{ {} }}", self.description())
+    }
+}
+
+impl fmt::Display for SBTarget {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "{}", self.description())
+    }
+}
+
+impl TryFrom<sys::SBTargetRef> for SBTarget {
+    type Error = InvalidRefError;
+
+    fn try_from(raw: sys::SBTargetRef) -> Result<SBTarget, InvalidRefError> {
+        SBTarget::maybe_wrap(raw).ok_or(InvalidRefError)
     }
 }
 
@@ -383,6 +1218,53 @@ impl Drop for SBTarget {
 unsafe impl Send for SBTarget {}
 unsafe impl Sync for SBTarget {}
 
+/// The reasons that [`SBTarget::launch_and_wait`] can fail.
+///
+/// [`SBTarget::launch_and_wait`]: struct.SBTarget.html#method.launch_and_wait
+#[derive(Debug)]
+pub enum LaunchError {
+    /// The process could not be launched.
+    Launch(SBError),
+    /// The process exited before it stopped.
+    Exited {
+        /// The exit status of the process.
+        status: i32,
+        /// The exit description of the process.
+        description: String,
+    },
+    /// The process ended up in a state other than stopped or exited,
+    /// such as being detached.
+    UnexpectedState(StateType),
+    /// The process did not stop before the timeout expired.
+    TimedOut,
+}
+
+impl fmt::Display for LaunchError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            LaunchError::Launch(ref error) => {
+                write!(fmt, "failed to launch: {}", error.error_string())
+            }
+            LaunchError::Exited {
+                status,
+                ref description,
+            } => {
+                write!(fmt, "process exited with status {}", status)?;
+                if !description.is_empty() {
+                    write!(fmt, " ({})", description)?;
+                }
+                Ok(())
+            }
+            LaunchError::UnexpectedState(state) => {
+                write!(fmt, "process is in unexpected state {:?}", state)
+            }
+            LaunchError::TimedOut => write!(fmt, "timed out waiting for process to stop"),
+        }
+    }
+}
+
+impl Error for LaunchError {}
+
 /// Iterate over the [breakpoints] in a [target].
 ///
 /// [breakpoints]: struct.SBBreakpoint.html
@@ -397,9 +1279,12 @@ impl<'d> Iterator for SBTargetBreakpointIter<'d> {
 
     fn next(&mut self) -> Option<SBBreakpoint> {
         if self.idx < unsafe { sys::SBTargetGetNumBreakpoints(self.target.raw) as usize } {
-            let r = Some(SBBreakpoint::wrap(unsafe {
-                sys::SBTargetGetBreakpointAtIndex(self.target.raw, self.idx as u32)
-            }));
+            let r = Some(unsafe {
+                SBBreakpoint::wrap(sys::SBTargetGetBreakpointAtIndex(
+                    self.target.raw,
+                    self.idx as u32,
+                ))
+            });
             self.idx += 1;
             r
         } else {
@@ -429,9 +1314,12 @@ impl<'d> Iterator for SBTargetWatchpointIter<'d> {
 
     fn next(&mut self) -> Option<SBWatchpoint> {
         if self.idx < unsafe { sys::SBTargetGetNumWatchpoints(self.target.raw) as usize } {
-            let r = Some(SBWatchpoint::wrap(unsafe {
-                sys::SBTargetGetWatchpointAtIndex(self.target.raw, self.idx as u32)
-            }));
+            let r = Some(unsafe {
+                SBWatchpoint::wrap(sys::SBTargetGetWatchpointAtIndex(
+                    self.target.raw,
+                    self.idx as u32,
+                ))
+            });
             self.idx += 1;
             r
         } else {
@@ -459,7 +1347,7 @@ impl<'e> SBTargetEvent<'e> {
     }
 
     pub fn target(&self) -> SBTarget {
-        SBTarget::wrap(unsafe { sys::SBTargetGetTargetFromEvent(self.event.raw) })
+        unsafe { SBTarget::wrap(sys::SBTargetGetTargetFromEvent(self.event.raw)) }
     }
 
     pub fn modules(&self) -> SBTargetEventModuleIter {
@@ -485,9 +1373,12 @@ impl<'d> Iterator for SBTargetEventModuleIter<'d> {
     fn next(&mut self) -> Option<SBModule> {
         if self.idx < unsafe { sys::SBTargetGetNumModulesFromEvent(self.event.event.raw) as usize }
         {
-            let r = Some(SBModule::wrap(unsafe {
-                sys::SBTargetGetModuleAtIndexFromEvent(self.idx as u32, self.event.event.raw)
-            }));
+            let r = Some(unsafe {
+                SBModule::wrap(sys::SBTargetGetModuleAtIndexFromEvent(
+                    self.idx as u32,
+                    self.event.event.raw,
+                ))
+            });
             self.idx += 1;
             r
         } else {
@@ -517,9 +1408,9 @@ impl<'d> Iterator for SBTargetModuleIter<'d> {
 
     fn next(&mut self) -> Option<SBModule> {
         if self.idx < unsafe { sys::SBTargetGetNumModules(self.target.raw) } {
-            let r = Some(SBModule::wrap(unsafe {
-                sys::SBTargetGetModuleAtIndex(self.target.raw, self.idx)
-            }));
+            let r = Some(unsafe {
+                SBModule::wrap(sys::SBTargetGetModuleAtIndex(self.target.raw, self.idx))
+            });
             self.idx += 1;
             r
         } else {
@@ -536,32 +1427,45 @@ impl<'d> Iterator for SBTargetModuleIter<'d> {
 impl<'d> ExactSizeIterator for SBTargetModuleIter<'d> {}
 
 #[cfg(feature = "graphql")]
-graphql_object!(SBTarget: SBDebugger | &self | {
-    field is_valid() -> bool {
+#[graphql_object(context = SBDebugger)]
+impl SBTarget {
+    #[graphql(name = "isValid")]
+    fn graphql_is_valid(&self) -> bool {
         self.is_valid()
     }
 
-    field platform() -> SBPlatform {
+    #[graphql(name = "platform")]
+    fn graphql_platform(&self) -> SBPlatform {
         self.platform()
     }
 
-    field process() -> SBProcess {
+    #[graphql(name = "process")]
+    fn graphql_process(&self) -> Option<SBProcess> {
         self.process()
     }
 
-    field executable() -> Option<SBFileSpec> {
+    #[graphql(name = "executable")]
+    fn graphql_executable(&self) -> Option<SBFileSpec> {
         self.executable()
     }
 
-    field debugger() -> SBDebugger {
+    #[graphql(name = "modules")]
+    fn graphql_modules(&self) -> Vec<SBModule> {
+        self.modules().collect()
+    }
+
+    #[graphql(name = "debugger")]
+    fn graphql_debugger(&self) -> SBDebugger {
         self.debugger()
     }
 
-    field breakpoints() -> Vec<SBBreakpoint> {
+    #[graphql(name = "breakpoints")]
+    fn graphql_breakpoints(&self) -> Vec<SBBreakpoint> {
         self.breakpoints().collect()
     }
 
-    field watchpoints() -> Vec<SBWatchpoint> {
+    #[graphql(name = "watchpoints")]
+    fn graphql_watchpoints(&self) -> Vec<SBWatchpoint> {
         self.watchpoints().collect()
     }
-});
+}
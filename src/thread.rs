@@ -4,16 +4,31 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
-use super::error::SBError;
+use super::error::{InvalidRefError, SBError};
 use super::event::SBEvent;
-use super::frame::SBFrame;
+use super::frame::{FrameInfo, SBFrame};
+#[cfg(feature = "graphql")]
+use super::graphql::U64;
 use super::process::SBProcess;
+#[cfg(feature = "lldb-15")]
+use super::processortrace::ProcessorTrace;
 use super::queue::SBQueue;
+use super::registers::RegisterSnapshot;
+use super::stophandle::StopHandle;
 use super::stream::SBStream;
+use super::strings;
+#[cfg(feature = "lldb-15")]
+use super::structureddata::SBStructuredData;
+use super::trace::traced;
+#[cfg(feature = "lldb-16")]
+use super::tracecursor::TraceCursor;
 use super::value::SBValue;
-use super::{lldb_tid_t, StopReason};
-use std::ffi::CStr;
+use super::{lldb_tid_t, RunMode, StopReason};
+use libc::c_char;
+use std::convert::TryFrom;
 use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::ptr;
 use sys;
 
 /// A thread of execution.
@@ -56,9 +71,34 @@ use sys;
 ///
 /// # Events
 ///
-/// ...
+/// Threads broadcast events when their stack changes, when they are
+/// suspended or resumed, and when they or their selected frame are
+/// selected. Threads don't have broadcasters of their own in this
+/// version of LLDB, so listen for the events of every thread by their
+/// [broadcaster class], and decode them with [`ThreadEvent::from_event`]:
+///
+/// ```no_run
+/// # use lldb::{SBDebugger, SBEvent, SBListener, SBThread, ThreadEvent};
+/// # fn watch(debugger: &SBDebugger, listener: &SBListener) {
+/// listener.start_listening_for_event_class(
+///     debugger,
+///     SBThread::broadcaster_class_name(),
+///     SBThread::BROADCAST_BIT_STACK_CHANGED | SBThread::BROADCAST_BIT_SELECTED_FRAME_CHANGED,
+/// );
+/// let mut event = SBEvent::new();
+/// while listener.wait_for_event(1, &mut event) {
+///     match ThreadEvent::from_event(&event) {
+///         Some(ThreadEvent::StackChanged(thread)) => println!("refresh {}", thread.index_id()),
+///         Some(ThreadEvent::SelectedFrameChanged { frame, .. }) => println!("select {:?}", frame),
+///         _ => {}
+///     }
+/// }
+/// # }
+/// ```
 ///
 /// [stack frames]: struct.SBFrame.html
+/// [broadcaster class]: #method.broadcaster_class_name
+/// [`ThreadEvent::from_event`]: enum.ThreadEvent.html#method.from_event
 /// [`frames`]: #method.frames
 /// [`selected_frame`]: #method.selected_frame
 /// [`set_selected_frame`]: #method.set_selected_frame
@@ -68,15 +108,38 @@ pub struct SBThread {
 }
 
 impl SBThread {
+    /// Broadcast when the stack of the thread changes, such as when a
+    /// frame is added by stepping in or the stack is unwound again.
+    pub const BROADCAST_BIT_STACK_CHANGED: u32 = 1 << 0;
+    /// Broadcast when the thread is suspended, so that it won't run
+    /// when the process resumes.
+    pub const BROADCAST_BIT_THREAD_SUSPENDED: u32 = 1 << 1;
+    /// Broadcast when the thread is allowed to run again.
+    pub const BROADCAST_BIT_THREAD_RESUMED: u32 = 1 << 2;
+    /// Broadcast when another frame of the thread is selected.
+    pub const BROADCAST_BIT_SELECTED_FRAME_CHANGED: u32 = 1 << 3;
+    /// Broadcast when the thread becomes the selected thread.
+    pub const BROADCAST_BIT_THREAD_SELECTED: u32 = 1 << 4;
+
     /// Construct a new `SBThread`.
-    pub fn wrap(raw: sys::SBThreadRef) -> SBThread {
+    ///
+    /// # Safety
+    ///
+    /// `raw` must be a valid reference, which is disposed of when the
+    /// `SBThread` is dropped. Use [`maybe_wrap`] when it may be invalid.
+    ///
+    /// [`maybe_wrap`]: #method.maybe_wrap
+    pub unsafe fn wrap(raw: sys::SBThreadRef) -> SBThread {
         SBThread { raw }
     }
 
     /// Construct a new `Some(SBThread)` or `None`.
+    ///
+    /// `raw` is disposed of if it is invalid.
     pub fn maybe_wrap(raw: sys::SBThreadRef) -> Option<SBThread> {
-        if unsafe { sys::SBThreadIsValid(raw) != 0 } {
-            Some(SBThread { raw })
+        let wrapped = SBThread { raw };
+        if wrapped.is_valid() {
+            Some(wrapped)
         } else {
             None
         }
@@ -89,12 +152,7 @@ impl SBThread {
 
     #[allow(missing_docs)]
     pub fn broadcaster_class_name() -> &'static str {
-        unsafe {
-            match CStr::from_ptr(sys::SBThreadGetBroadcasterClassName()).to_str() {
-                Ok(s) => s,
-                _ => panic!("Invalid string?"),
-            }
-        }
+        unsafe { strings::to_static_str(sys::SBThreadGetBroadcasterClassName()) }
     }
 
     /// Get the stop reason for this thread.
@@ -102,6 +160,39 @@ impl SBThread {
         unsafe { sys::SBThreadGetStopReason(self.raw) }
     }
 
+    /// The number of data items for the current stop reason.
+    ///
+    /// See [`stop_reason_data_at_index`] for what the data means.
+    ///
+    /// [`stop_reason_data_at_index`]: #method.stop_reason_data_at_index
+    pub fn stop_reason_data_count(&self) -> u32 {
+        unsafe { sys::SBThreadGetStopReasonDataCount(self.raw) }
+    }
+
+    /// Get a data item for the current stop reason.
+    ///
+    /// The meaning depends on the stop reason. For a breakpoint, the
+    /// data is pairs of breakpoint IDs and location IDs. For a
+    /// watchpoint, it is the watchpoint ID. For a signal, it is the
+    /// signal number. For an exception, it is the exception data.
+    pub fn stop_reason_data_at_index(&self, idx: u32) -> u64 {
+        unsafe { sys::SBThreadGetStopReasonDataAtIndex(self.raw, idx) }
+    }
+
+    /// A description of why the thread stopped, such as
+    /// `EXC_BAD_ACCESS (code=1, address=0x0)`.
+    pub fn stop_description(&self) -> Option<String> {
+        let len = unsafe { sys::SBThreadGetStopDescription(self.raw, ptr::null_mut(), 0) };
+        if len == 0 {
+            return None;
+        }
+        let mut buf: Vec<c_char> = vec![0; len as usize + 1];
+        unsafe {
+            sys::SBThreadGetStopDescription(self.raw, buf.as_mut_ptr(), buf.len());
+            strings::to_string(buf.as_ptr())
+        }
+    }
+
     /// The return value from the last stop if we just stopped due
     /// to stepping out of a function
     pub fn stop_return_value(&self) -> Option<SBValue> {
@@ -132,14 +223,34 @@ impl SBThread {
         unsafe { sys::SBThreadGetIndexID(self.raw) }
     }
 
+    /// Start processor tracing of this thread, such as with Intel PT,
+    /// as configured by `config`. See [`ProcessorTrace`].
+    ///
+    /// This needs LLDB 15 or later, and the `lldb-15` feature.
+    ///
+    /// [`ProcessorTrace`]: struct.ProcessorTrace.html
+    #[cfg(feature = "lldb-15")]
+    pub fn start_trace(&self, config: &SBStructuredData) -> Result<ProcessorTrace, SBError> {
+        ProcessorTrace::start(&self.process(), Some(self.index_id()), config)
+    }
+
+    /// Iterate over the processor trace of this thread, from a trace
+    /// started with [`start_trace`] or loaded with
+    /// [`SBDebugger::load_trace`]. See [`TraceCursor`].
+    ///
+    /// This needs LLDB 16 or later, and the `lldb-16` feature.
+    ///
+    /// [`start_trace`]: #method.start_trace
+    /// [`SBDebugger::load_trace`]: struct.SBDebugger.html#method.load_trace
+    /// [`TraceCursor`]: struct.TraceCursor.html
+    #[cfg(feature = "lldb-16")]
+    pub fn trace_cursor(&self) -> TraceCursor {
+        TraceCursor::new(self)
+    }
+
     /// The name associated with the thread, if any.
-    pub fn name(&self) -> &str {
-        unsafe {
-            match CStr::from_ptr(sys::SBThreadGetName(self.raw)).to_str() {
-                Ok(s) => s,
-                _ => panic!("Invalid string?"),
-            }
-        }
+    pub fn name(&self) -> Option<String> {
+        unsafe { strings::to_string(sys::SBThreadGetName(self.raw)) }
     }
 
     /// Return the queue associated with this thread, if any.
@@ -156,13 +267,8 @@ impl SBThread {
     ///
     /// For example, this would report a libdispatch (Grand Central Dispatch)
     /// queue name.
-    pub fn queue_name(&self) -> &str {
-        unsafe {
-            match CStr::from_ptr(sys::SBThreadGetQueueName(self.raw)).to_str() {
-                Ok(s) => s,
-                _ => panic!("Invalid string?"),
-            }
-        }
+    pub fn queue_name(&self) -> Option<String> {
+        unsafe { strings::to_string(sys::SBThreadGetQueueName(self.raw)) }
     }
 
     /// Return the `dispatch_queue_id` for this thread, if any.
@@ -204,6 +310,77 @@ impl SBThread {
         unsafe { sys::SBThreadIsSuspended(self.raw) != 0 }
     }
 
+    /// Step in the way described by `kind`.
+    ///
+    /// `stop_other_threads` controls whether the other threads in the
+    /// process run while stepping. It is ignored when stepping out or
+    /// by instruction.
+    pub fn step(&self, kind: StepKind, stop_other_threads: RunMode) -> Result<(), SBError> {
+        match kind {
+            StepKind::Over => self.step_over(stop_other_threads),
+            StepKind::Into => {
+                self.step_into(stop_other_threads);
+                Ok(())
+            }
+            StepKind::Out => self.step_out(),
+            StepKind::Instruction => self.step_instruction(false),
+            StepKind::InstructionOver => self.step_instruction(true),
+        }
+    }
+
+    /// Step over the current source line.
+    ///
+    /// `stop_other_threads` controls whether the other threads in the
+    /// process run while stepping.
+    pub fn step_over(&self, stop_other_threads: RunMode) -> Result<(), SBError> {
+        traced("SBThread::step_over", || {
+            let error = SBError::new();
+            unsafe { sys::SBThreadStepOver(self.raw, stop_other_threads, error.raw) };
+            if error.is_success() {
+                Ok(())
+            } else {
+                Err(error)
+            }
+        })
+    }
+
+    /// Step into the function called on the current source line.
+    ///
+    /// `stop_other_threads` controls whether the other threads in the
+    /// process run while stepping.
+    pub fn step_into(&self, stop_other_threads: RunMode) {
+        traced("SBThread::step_into", || {
+            unsafe { sys::SBThreadStepInto(self.raw, stop_other_threads) };
+        })
+    }
+
+    /// Step out of the current function.
+    pub fn step_out(&self) -> Result<(), SBError> {
+        traced("SBThread::step_out", || {
+            let error = SBError::new();
+            unsafe { sys::SBThreadStepOut(self.raw, error.raw) };
+            if error.is_success() {
+                Ok(())
+            } else {
+                Err(error)
+            }
+        })
+    }
+
+    /// Step a single instruction. If `step_over` is `true`, calls
+    /// are stepped over rather than into.
+    pub fn step_instruction(&self, step_over: bool) -> Result<(), SBError> {
+        traced("SBThread::step_instruction", || {
+            let error = SBError::new();
+            unsafe { sys::SBThreadStepInstruction(self.raw, step_over as u8, error.raw) };
+            if error.is_success() {
+                Ok(())
+            } else {
+                Err(error)
+            }
+        })
+    }
+
     /// Is this thread stopped?
     pub fn is_stopped(&self) -> bool {
         unsafe { sys::SBThreadIsStopped(self.raw) != 0 }
@@ -219,9 +396,39 @@ impl SBThread {
         }
     }
 
-    /// Get the currently selected frame for this thread.
-    pub fn selected_frame(&self) -> SBFrame {
-        SBFrame::wrap(unsafe { sys::SBThreadGetSelectedFrame(self.raw) })
+    /// Gather the location of every frame in the stack.
+    ///
+    /// This is faster than calling the accessors on each frame from
+    /// [`frames`], as it makes fewer calls into LLDB. See
+    /// [`SBFrame::info`].
+    ///
+    /// [`frames`]: #method.frames
+    /// [`SBFrame::info`]: struct.SBFrame.html#method.info
+    pub fn collect_frames(&self) -> Vec<FrameInfo> {
+        let count = unsafe { sys::SBThreadGetNumFrames(self.raw) };
+        (0..count)
+            .filter_map(|idx| {
+                SBFrame::maybe_wrap(unsafe { sys::SBThreadGetFrameAtIndex(self.raw, idx) })
+            })
+            .map(|frame| frame.info())
+            .collect()
+    }
+
+    /// The number of frames in the stack of this thread.
+    pub fn num_frames(&self) -> u32 {
+        unsafe { sys::SBThreadGetNumFrames(self.raw) }
+    }
+
+    /// Capture the values of the registers of the innermost frame.
+    pub fn register_snapshot(&self) -> RegisterSnapshot {
+        let frame = unsafe { SBFrame::wrap(sys::SBThreadGetFrameAtIndex(self.raw, 0)) };
+        RegisterSnapshot::capture(&frame)
+    }
+
+    /// Get the currently selected frame for this thread, if it is
+    /// stopped.
+    pub fn selected_frame(&self) -> Option<SBFrame> {
+        SBFrame::maybe_wrap(unsafe { sys::SBThreadGetSelectedFrame(self.raw) })
     }
 
     /// Set the currently selected frame for this thread. This takes a frame index.
@@ -231,7 +438,13 @@ impl SBThread {
 
     /// Get the process in which this thread is running.
     pub fn process(&self) -> SBProcess {
-        SBProcess::wrap(unsafe { sys::SBThreadGetProcess(self.raw) })
+        unsafe { SBProcess::wrap(sys::SBThreadGetProcess(self.raw)) }
+    }
+
+    /// A handle to the thread which can only be used until the process
+    /// runs again or exits.
+    pub fn stop_handle(&self) -> StopHandle<SBThread> {
+        StopHandle::new(self.clone(), self.process(), SBThread::is_valid)
     }
 
     /// If the given event is a thread event, return it as an
@@ -243,6 +456,30 @@ impl SBThread {
             None
         }
     }
+
+    fn description(&self) -> String {
+        let stream = SBStream::new();
+        unsafe { sys::SBThreadGetDescription(self.raw, stream.raw) };
+        stream.data()
+    }
+}
+
+/// How [`SBThread::step`] should step.
+///
+/// [`SBThread::step`]: struct.SBThread.html#method.step
+#[cfg_attr(feature = "graphql", derive(GraphQLEnum))]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StepKind {
+    /// Step over the current source line.
+    Over,
+    /// Step into the function called on the current source line.
+    Into,
+    /// Step out of the current function.
+    Out,
+    /// Step a single instruction, into any call.
+    Instruction,
+    /// Step a single instruction, over any call.
+    InstructionOver,
 }
 
 /// Iterate over the [frames] in a [thread].
@@ -259,9 +496,12 @@ impl<'d> Iterator for SBThreadFrameIter<'d> {
 
     fn next(&mut self) -> Option<SBFrame> {
         if self.idx < unsafe { sys::SBThreadGetNumFrames(self.thread.raw) as usize } {
-            let r = Some(SBFrame::wrap(unsafe {
-                sys::SBThreadGetFrameAtIndex(self.thread.raw, self.idx as u32)
-            }));
+            let r = Some(unsafe {
+                SBFrame::wrap(sys::SBThreadGetFrameAtIndex(
+                    self.thread.raw,
+                    self.idx as u32,
+                ))
+            });
             self.idx += 1;
             r
         } else {
@@ -287,9 +527,36 @@ impl Clone for SBThread {
 
 impl fmt::Debug for SBThread {
     fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
-        let stream = SBStream::new();
-        unsafe { sys::SBThreadGetDescription(self.raw, stream.raw) };
-        write!(fmt, "SBThread {{ {} }}", stream.data())
+        write!(fmt, "SBThread {{ {} }}", self.description())
+    }
+}
+
+impl fmt::Display for SBThread {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "{}", self.description())
+    }
+}
+
+/// Threads are compared by their thread ID.
+impl PartialEq for SBThread {
+    fn eq(&self, other: &SBThread) -> bool {
+        self.thread_id() == other.thread_id()
+    }
+}
+
+impl Eq for SBThread {}
+
+impl Hash for SBThread {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.thread_id().hash(state);
+    }
+}
+
+impl TryFrom<sys::SBThreadRef> for SBThread {
+    type Error = InvalidRefError;
+
+    fn try_from(raw: sys::SBThreadRef) -> Result<SBThread, InvalidRefError> {
+        SBThread::maybe_wrap(raw).ok_or(InvalidRefError)
     }
 }
 
@@ -315,40 +582,128 @@ impl<'e> SBThreadEvent<'e> {
 
     /// Get the thread from this thread event.
     pub fn thread(&self) -> SBThread {
-        SBThread::wrap(unsafe { sys::SBThreadGetThreadFromEvent(self.event.raw) })
+        unsafe { SBThread::wrap(sys::SBThreadGetThreadFromEvent(self.event.raw)) }
     }
 
     /// Get the frame from this thread event.
     pub fn frame(&self) -> Option<SBFrame> {
         SBFrame::maybe_wrap(unsafe { sys::SBThreadGetStackFrameFromEvent(self.event.raw) })
     }
+
+    /// Decode what happened to the thread.
+    pub fn decode(&self) -> Option<ThreadEvent> {
+        let event_type = self.event.event_type();
+        let thread = self.thread();
+        if event_type & SBThread::BROADCAST_BIT_STACK_CHANGED != 0 {
+            Some(ThreadEvent::StackChanged(thread))
+        } else if event_type & SBThread::BROADCAST_BIT_THREAD_SUSPENDED != 0 {
+            Some(ThreadEvent::Suspended(thread))
+        } else if event_type & SBThread::BROADCAST_BIT_THREAD_RESUMED != 0 {
+            Some(ThreadEvent::Resumed(thread))
+        } else if event_type & SBThread::BROADCAST_BIT_SELECTED_FRAME_CHANGED != 0 {
+            Some(ThreadEvent::SelectedFrameChanged {
+                frame: self.frame(),
+                thread,
+            })
+        } else if event_type & SBThread::BROADCAST_BIT_THREAD_SELECTED != 0 {
+            Some(ThreadEvent::Selected(thread))
+        } else {
+            None
+        }
+    }
+}
+
+/// What a [thread event] says happened.
+///
+/// [thread event]: struct.SBThreadEvent.html
+#[derive(Clone, Debug)]
+pub enum ThreadEvent {
+    /// The stack of the thread changed, so any frames shown for it
+    /// should be fetched again.
+    StackChanged(SBThread),
+    /// The thread was suspended.
+    Suspended(SBThread),
+    /// The thread was allowed to run again.
+    Resumed(SBThread),
+    /// Another frame of the thread was selected.
+    SelectedFrameChanged {
+        /// The thread.
+        thread: SBThread,
+        /// The frame which is now selected, if the event has it.
+        frame: Option<SBFrame>,
+    },
+    /// The thread became the selected thread.
+    Selected(SBThread),
+}
+
+impl ThreadEvent {
+    /// Decode `event`, if it is a thread event.
+    pub fn from_event(event: &SBEvent) -> Option<ThreadEvent> {
+        SBThread::event_as_thread_event(event)?.decode()
+    }
+
+    /// The thread which the event is about.
+    pub fn thread(&self) -> &SBThread {
+        match self {
+            ThreadEvent::StackChanged(thread)
+            | ThreadEvent::Suspended(thread)
+            | ThreadEvent::Resumed(thread)
+            | ThreadEvent::SelectedFrameChanged { thread, .. }
+            | ThreadEvent::Selected(thread) => thread,
+        }
+    }
 }
 
 #[cfg(feature = "graphql")]
-graphql_object!(SBThread: super::debugger::SBDebugger | &self | {
-    field is_valid() -> bool {
+#[graphql_object(context = super::debugger::SBDebugger)]
+impl SBThread {
+    #[graphql(name = "isValid")]
+    fn graphql_is_valid(&self) -> bool {
         self.is_valid()
     }
 
-    // TODO(bm): This should be u64
-    field thread_id() -> i32 {
-        self.thread_id() as i32
+    #[graphql(name = "threadId")]
+    fn graphql_thread_id(&self) -> U64 {
+        self.thread_id().into()
     }
 
-    // TODO(bm) This should be u32
-    field index_id() -> i32 {
-        self.index_id() as i32
+    #[graphql(name = "indexId")]
+    fn graphql_index_id(&self) -> U64 {
+        self.index_id().into()
     }
 
-    field frames() -> Vec<SBFrame> {
+    #[graphql(name = "name")]
+    fn graphql_name(&self) -> Option<String> {
+        self.name()
+    }
+
+    #[graphql(name = "queueName")]
+    fn graphql_queue_name(&self) -> Option<String> {
+        self.queue_name()
+    }
+
+    #[graphql(name = "isStopped")]
+    fn graphql_is_stopped(&self) -> bool {
+        self.is_stopped()
+    }
+
+    #[graphql(name = "isSuspended")]
+    fn graphql_is_suspended(&self) -> bool {
+        self.is_suspended()
+    }
+
+    #[graphql(name = "frames")]
+    fn graphql_frames(&self) -> Vec<SBFrame> {
         self.frames().collect()
     }
 
-    field selected_frame() -> SBFrame {
+    #[graphql(name = "selectedFrame")]
+    fn graphql_selected_frame(&self) -> Option<SBFrame> {
         self.selected_frame()
     }
 
-    field process() -> SBProcess {
+    #[graphql(name = "process")]
+    fn graphql_process(&self) -> SBProcess {
         self.process()
     }
-});
+}
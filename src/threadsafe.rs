@@ -0,0 +1,68 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use std::fmt;
+use std::ops::Deref;
+
+/// Share a value that isn't `Sync` between threads anyway.
+///
+/// The lists, streams, options and other plain values from LLDB aren't
+/// protected by any locks, so they aren't `Sync`. The safe way to share
+/// one between threads is to put it in a `Mutex`. When the value will
+/// only ever be read, or access is already serialized in some other
+/// way, this can be used to skip the `Mutex` instead.
+///
+/// ```no_run
+/// # use lldb::{assert_thread_safe, SBStringList};
+/// # use std::sync::Arc;
+/// # use std::thread;
+/// let list = SBStringList::new();
+/// list.append_string("main");
+/// // Nothing modifies `list` after this point.
+/// let list = Arc::new(unsafe { assert_thread_safe(list) });
+/// let reader = Arc::clone(&list);
+/// thread::spawn(move || println!("{:?}", reader.iter().collect::<Vec<_>>()));
+/// ```
+///
+/// # Safety
+///
+/// The caller must ensure that the value isn't modified while it is
+/// shared, whether through its own methods or by passing it to other
+/// LLDB calls, such as passing an `SBStream` to `GetDescription`.
+pub unsafe fn assert_thread_safe<T: Send>(value: T) -> AssertThreadSafe<T> {
+    AssertThreadSafe { value }
+}
+
+/// A value that has been asserted to be safe to share between
+/// threads. See [`assert_thread_safe`].
+///
+/// [`assert_thread_safe`]: fn.assert_thread_safe.html
+pub struct AssertThreadSafe<T> {
+    value: T,
+}
+
+impl<T> AssertThreadSafe<T> {
+    /// Get back the wrapped value.
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+}
+
+impl<T> Deref for AssertThreadSafe<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.value
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for AssertThreadSafe<T> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        self.value.fmt(fmt)
+    }
+}
+
+unsafe impl<T: Send> Sync for AssertThreadSafe<T> {}
@@ -0,0 +1,136 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use super::error::SBError;
+use std::error::Error;
+use std::fmt;
+use std::panic;
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::thread;
+use std::time::Duration;
+
+/// Run `op` on a thread of its own, giving up on it after `timeout`.
+///
+/// Calls into LLDB which talk to a remote debug server or run code in
+/// the process can block for as long as the other side doesn't
+/// answer. This keeps such a call from blocking the caller forever.
+///
+/// LLDB calls can't be cancelled from outside, so when the timeout
+/// expires, `op` keeps running in the background until it returns.
+/// Use [`with_timeout_or_interrupt`] to interrupt it as well.
+///
+/// ```no_run
+/// # use lldb::{with_timeout, SBTarget};
+/// # use std::time::Duration;
+/// # fn modules(target: SBTarget) {
+/// let count = with_timeout(Duration::from_secs(5), move || target.modules().count());
+/// # }
+/// ```
+///
+/// [`with_timeout_or_interrupt`]: fn.with_timeout_or_interrupt.html
+pub fn with_timeout<T, F>(timeout: Duration, op: F) -> Result<T, TimedOut>
+where
+    F: FnOnce() -> T + Send + 'static,
+    T: Send + 'static,
+{
+    with_timeout_or_interrupt(timeout, op, || {})
+}
+
+/// Run `op` on a thread of its own, calling `interrupt` and giving up
+/// on it after `timeout`.
+///
+/// `interrupt` should use one of LLDB's interrupt APIs, such as
+/// [`SBProcess::send_async_interrupt`], to make `op` return early.
+/// Those need something to interrupt: a process can't be interrupted
+/// before it exists, such as while it is being attached to or
+/// connected to, so `op` then runs on in the background as it does
+/// with [`with_timeout`].
+///
+/// If `op` panics, the panic is passed on to the caller rather than
+/// being reported as a timeout.
+///
+/// [`with_timeout`]: fn.with_timeout.html
+/// [`SBProcess::send_async_interrupt`]: struct.SBProcess.html#method.send_async_interrupt
+pub fn with_timeout_or_interrupt<T, F, I>(
+    timeout: Duration,
+    op: F,
+    interrupt: I,
+) -> Result<T, TimedOut>
+where
+    F: FnOnce() -> T + Send + 'static,
+    T: Send + 'static,
+    I: FnOnce(),
+{
+    let (sender, receiver) = mpsc::channel();
+    let handle = thread::spawn(move || {
+        // The caller may have stopped waiting.
+        let _ = sender.send(op());
+    });
+    match receiver.recv_timeout(timeout) {
+        Ok(result) => Ok(result),
+        Err(RecvTimeoutError::Timeout) => {
+            interrupt();
+            Err(TimedOut)
+        }
+        // The sender was dropped without sending, so `op` panicked.
+        Err(RecvTimeoutError::Disconnected) => match handle.join() {
+            Err(panic) => panic::resume_unwind(panic),
+            Ok(()) => unreachable!("the operation finished without a result"),
+        },
+    }
+}
+
+/// The error returned when an operation didn't finish in time.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TimedOut;
+
+impl fmt::Display for TimedOut {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "the operation timed out")
+    }
+}
+
+impl Error for TimedOut {}
+
+/// The error returned by the variants of LLDB calls which take a
+/// timeout.
+#[derive(Debug)]
+pub enum TimeoutError {
+    /// The call failed.
+    Lldb(SBError),
+    /// The call didn't finish in time.
+    TimedOut,
+}
+
+impl fmt::Display for TimeoutError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            TimeoutError::Lldb(ref error) => fmt::Display::fmt(error, f),
+            TimeoutError::TimedOut => fmt::Display::fmt(&TimedOut, f),
+        }
+    }
+}
+
+impl Error for TimeoutError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match *self {
+            TimeoutError::Lldb(ref error) => Some(error),
+            TimeoutError::TimedOut => None,
+        }
+    }
+}
+
+impl From<SBError> for TimeoutError {
+    fn from(error: SBError) -> TimeoutError {
+        TimeoutError::Lldb(error)
+    }
+}
+
+impl From<TimedOut> for TimeoutError {
+    fn from(_: TimedOut) -> TimeoutError {
+        TimeoutError::TimedOut
+    }
+}
@@ -0,0 +1,86 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Spans around calls into LLDB, for the `trace-ffi` feature.
+
+use super::commandreturnobject::SBCommandReturnObject;
+use super::error::SBError;
+use super::value::SBValue;
+
+/// Run `call`, which calls into LLDB, inside a span recording `name`,
+/// how long the call took and whether it failed.
+///
+/// Without the `trace-ffi` feature, this just runs `call`.
+#[cfg(feature = "trace-ffi")]
+pub fn traced<T: Traced, F: FnOnce() -> T>(name: &'static str, call: F) -> T {
+    use std::time::Instant;
+    use tracing::field::Empty;
+
+    let span = tracing::debug_span!(
+        target: "lldb::ffi",
+        "lldb",
+        call = name,
+        duration_us = Empty,
+        error = Empty,
+    );
+    let _entered = span.enter();
+    let start = Instant::now();
+    let result = call();
+    span.record("duration_us", start.elapsed().as_micros() as u64);
+    if let Some(error) = result.failure() {
+        span.record("error", error.as_str());
+    }
+    result
+}
+
+#[cfg(not(feature = "trace-ffi"))]
+#[inline(always)]
+pub fn traced<T: Traced, F: FnOnce() -> T>(_: &'static str, call: F) -> T {
+    call()
+}
+
+/// The result of a call into LLDB, which may report a failure.
+pub trait Traced {
+    /// The error message, if the call failed.
+    #[cfg_attr(not(feature = "trace-ffi"), allow(dead_code))]
+    fn failure(&self) -> Option<String> {
+        None
+    }
+}
+
+impl Traced for () {}
+
+impl Traced for bool {}
+
+impl<T: Traced> Traced for &T {
+    fn failure(&self) -> Option<String> {
+        (**self).failure()
+    }
+}
+
+impl<T> Traced for Result<T, SBError> {
+    fn failure(&self) -> Option<String> {
+        self.as_ref().err().map(SBError::error_string)
+    }
+}
+
+impl Traced for SBValue {
+    fn failure(&self) -> Option<String> {
+        self.error()
+            .filter(SBError::is_failure)
+            .map(|error| error.error_string())
+    }
+}
+
+impl Traced for SBCommandReturnObject {
+    fn failure(&self) -> Option<String> {
+        if self.succeeded() {
+            None
+        } else {
+            Some(self.error().unwrap_or_default())
+        }
+    }
+}
@@ -0,0 +1,215 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use super::error::SBError;
+use super::lldb_addr_t;
+use super::structureddata::SBStructuredData;
+use super::thread::SBThread;
+use std::collections::VecDeque;
+
+/// The number of items read from LLDB at a time.
+const PAGE_SIZE: usize = 1024;
+
+/// The items of the processor trace of a thread, from the first to
+/// the last, created by [`SBThread::trace_cursor`].
+///
+/// This works both for a live process which is being traced, and for
+/// a trace loaded from a bundle with [`SBDebugger::load_trace`]:
+///
+/// ```no_run
+/// # use lldb::{SBDebugger, SBError, TraceItemKind};
+/// # fn dump(debugger: &SBDebugger) -> Result<(), SBError> {
+/// let target = debugger.load_trace("/tmp/trace/trace.json")?;
+/// let process = target.process().unwrap();
+/// for thread in process.threads() {
+///     for item in thread.trace_cursor() {
+///         let item = item?;
+///         if let TraceItemKind::Instruction { load_address, .. } = item.kind {
+///             println!("{:?} {:#x}", item.timestamp_ns, load_address);
+///         }
+///     }
+/// }
+/// # Ok(())
+/// # }
+/// ```
+///
+/// `SBTraceCursor` is newer than the version of LLDB that these
+/// bindings are built against, so this reads the items a page at a
+/// time from the JSON output of `thread trace dump instructions`,
+/// which needs LLDB 16 or later, and the `lldb-16` feature.
+///
+/// Iteration stops after the first error from LLDB.
+///
+/// [`SBThread::trace_cursor`]: struct.SBThread.html#method.trace_cursor
+/// [`SBDebugger::load_trace`]: struct.SBDebugger.html#method.load_trace
+pub struct TraceCursor {
+    thread: SBThread,
+    skip: usize,
+    items: VecDeque<TraceItem>,
+    done: bool,
+}
+
+/// An item in the processor trace of a thread, from a [`TraceCursor`].
+///
+/// [`TraceCursor`]: struct.TraceCursor.html
+#[derive(Clone, Debug, PartialEq)]
+pub struct TraceItem {
+    /// The ID of the item, which orders the items of the thread.
+    pub id: u64,
+    /// When the item happened, in nanoseconds, if the trace has
+    /// timestamps.
+    pub timestamp_ns: Option<f64>,
+    /// What the item is.
+    pub kind: TraceItemKind,
+}
+
+/// What a [`TraceItem`] is.
+///
+/// [`TraceItem`]: struct.TraceItem.html
+#[derive(Clone, Debug, PartialEq)]
+pub enum TraceItemKind {
+    /// An instruction which was executed.
+    Instruction {
+        /// The address of the instruction.
+        load_address: lldb_addr_t,
+        /// The mnemonic of the instruction, such as `mov`.
+        mnemonic: Option<String>,
+        /// The name of the function containing the instruction.
+        symbol: Option<String>,
+        /// The name of the module containing the instruction.
+        module: Option<String>,
+    },
+    /// A gap in the trace, such as where the trace buffer overflowed.
+    Error(String),
+    /// An event, such as the thread being paused or moving to another
+    /// CPU.
+    Event {
+        /// The name of the event, such as `paused` or `CPU core
+        /// changed`.
+        name: String,
+        /// The CPU which the thread moved to, for CPU change events.
+        cpu_id: Option<u64>,
+    },
+}
+
+impl TraceCursor {
+    pub(crate) fn new(thread: &SBThread) -> TraceCursor {
+        TraceCursor {
+            thread: thread.clone(),
+            skip: 0,
+            items: VecDeque::new(),
+            done: false,
+        }
+    }
+
+    /// Read the next page of items from LLDB. Nothing is added when any
+    /// item of the page can't be parsed.
+    fn read_page(&mut self) -> Result<(), SBError> {
+        let command = format!(
+            "thread trace dump instructions {} --forwards --json --show-timestamps \
+             --show-events --skip {} --count {}",
+            self.thread.index_id(),
+            self.skip,
+            PAGE_SIZE
+        );
+        let output = self.thread.process().target().run_command(&command)?;
+        let page = match output.find('[') {
+            Some(start) => SBStructuredData::from_json_str(&output[start..])?,
+            // There is nothing left to dump.
+            None => SBStructuredData::new(),
+        };
+        let items = (0..page.size())
+            .map(|index| parse_item(&page.item_at_index(index).unwrap()))
+            .collect::<Result<Vec<_>, _>>()?;
+        self.items.extend(items);
+        self.skip += page.size();
+        if page.size() < PAGE_SIZE {
+            self.done = true;
+        }
+        Ok(())
+    }
+}
+
+impl Iterator for TraceCursor {
+    type Item = Result<TraceItem, SBError>;
+
+    fn next(&mut self) -> Option<Result<TraceItem, SBError>> {
+        if self.items.is_empty() && !self.done {
+            if let Err(error) = self.read_page() {
+                self.done = true;
+                return Some(Err(error));
+            }
+        }
+        self.items.pop_front().map(Ok)
+    }
+}
+
+/// Parse an item of the JSON output of `thread trace dump
+/// instructions`.
+fn parse_item(item: &SBStructuredData) -> Result<TraceItem, SBError> {
+    let string = |key: &str| {
+        item.value_for_key(key)
+            .and_then(|value| value.string_value())
+    };
+    let id = item
+        .value_for_key("id")
+        .and_then(|id| id.integer_value())
+        .ok_or_else(|| SBError::from_message("trace item has no id"))?;
+    // LLDB formats the timestamp as a string, with a fraction of a
+    // nanosecond.
+    let timestamp_ns = item.value_for_key("timestamp_ns").and_then(|timestamp| {
+        timestamp
+            .string_value()
+            .and_then(|timestamp| timestamp.parse().ok())
+            .or_else(|| timestamp.float_value())
+    });
+    let kind = if let Some(error) = string("error") {
+        TraceItemKind::Error(error)
+    } else if let Some(name) = string("event") {
+        TraceItemKind::Event {
+            name,
+            cpu_id: item
+                .value_for_key("cpuId")
+                .and_then(|id| id.integer_value()),
+        }
+    } else {
+        let load_address = string("loadAddress")
+            .and_then(|address| parse_address(&address))
+            .ok_or_else(|| SBError::from_message("trace item has no load address"))?;
+        TraceItemKind::Instruction {
+            load_address,
+            mnemonic: string("mnemonic"),
+            symbol: string("symbol"),
+            module: string("module"),
+        }
+    };
+    Ok(TraceItem {
+        id,
+        timestamp_ns,
+        kind,
+    })
+}
+
+/// Parse an address such as `0x401126`.
+fn parse_address(address: &str) -> Option<lldb_addr_t> {
+    let address = address.trim();
+    let digits = address
+        .strip_prefix("0x")
+        .or_else(|| address.strip_prefix("0X"))?;
+    lldb_addr_t::from_str_radix(digits, 16).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse_address;
+
+    #[test]
+    fn parses_addresses() {
+        assert_eq!(parse_address("0x401126"), Some(0x401126));
+        assert_eq!(parse_address(" 0XFF "), Some(0xff));
+        assert_eq!(parse_address("401126"), None);
+    }
+}
@@ -0,0 +1,240 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use super::addressrange::{AddressRange, AddressRangeList};
+use super::error::SBError;
+use super::registers::RegisterSnapshot;
+use super::thread::SBThread;
+use super::{lldb_addr_t, StateType, StopReason};
+use std::thread;
+use std::time::Duration;
+
+/// Trace the instructions executed by a thread by single-stepping it.
+///
+/// This is slow, as every instruction is a round trip through LLDB,
+/// but it works on any target which LLDB can step, without hardware
+/// tracing support such as Intel PT.
+///
+/// The debugger must be in synchronous mode, so that each step has
+/// finished before the next one is taken.
+///
+/// ```no_run
+/// # use lldb::{SBError, SBThread, Tracer};
+/// # fn trace(thread: &SBThread) -> Result<(), SBError> {
+/// let end = Tracer::new()
+///     .max_steps(10_000)
+///     .until_function_exit()
+///     .trace(thread, |step| {
+///         println!("{:#x}  {}", step.pc, step.disassembly.as_deref().unwrap_or("?"));
+///     })?;
+/// println!("Stopped tracing: {:?}", end);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct Tracer {
+    stop_addresses: Vec<lldb_addr_t>,
+    stop_ranges: AddressRangeList,
+    within: Option<AddressRangeList>,
+    max_steps: Option<usize>,
+    until_function_exit: bool,
+    step_over_calls: bool,
+    registers: bool,
+    throttle: Option<Duration>,
+}
+
+/// One instruction executed by a traced thread.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TraceStep {
+    /// The number of steps taken before this one.
+    pub index: usize,
+    /// The address of the instruction.
+    pub pc: lldb_addr_t,
+    /// The instruction, such as `mov rax, qword ptr [rbp - 0x8]`, if
+    /// it could be disassembled.
+    pub disassembly: Option<String>,
+    /// The name of the function containing the instruction, if known.
+    pub function_name: Option<String>,
+    /// The registers before the instruction executed, if
+    /// [`Tracer::registers`] was set.
+    ///
+    /// [`Tracer::registers`]: struct.Tracer.html#method.registers
+    pub registers: Option<RegisterSnapshot>,
+}
+
+/// Why a [`Tracer`] stopped tracing.
+///
+/// [`Tracer`]: struct.Tracer.html
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TraceEnd {
+    /// The thread reached one of the addresses given to
+    /// [`Tracer::stop_at`], or one in a range given to
+    /// [`Tracer::stop_in`].
+    ///
+    /// [`Tracer::stop_at`]: struct.Tracer.html#method.stop_at
+    /// [`Tracer::stop_in`]: struct.Tracer.html#method.stop_in
+    Address(lldb_addr_t),
+    /// The thread reached an address outside of the ranges given to
+    /// [`Tracer::stay_within`], before executing the instruction
+    /// there.
+    ///
+    /// [`Tracer::stay_within`]: struct.Tracer.html#method.stay_within
+    LeftRanges(lldb_addr_t),
+    /// The number of steps given to [`Tracer::max_steps`] were taken.
+    ///
+    /// [`Tracer::max_steps`]: struct.Tracer.html#method.max_steps
+    Count,
+    /// The thread returned from the function it was in when tracing
+    /// started.
+    FunctionExit,
+    /// The thread stopped for some other reason, such as a breakpoint
+    /// or a signal.
+    Stopped(StopReason),
+    /// The process is no longer stopped, such as because it exited.
+    ProcessState(StateType),
+}
+
+impl Tracer {
+    /// Create a tracer which traces until the thread stops for some
+    /// other reason.
+    pub fn new() -> Tracer {
+        Tracer::default()
+    }
+
+    /// Stop when the thread reaches `address`, before executing the
+    /// instruction there.
+    pub fn stop_at(mut self, address: lldb_addr_t) -> Self {
+        self.stop_addresses.push(address);
+        self
+    }
+
+    /// Stop when the thread reaches any address in `range`, such as
+    /// the code of a function, reporting it as
+    /// [`TraceEnd::Address`].
+    ///
+    /// [`TraceEnd::Address`]: enum.TraceEnd.html#variant.Address
+    pub fn stop_in(mut self, range: AddressRange) -> Self {
+        self.stop_ranges.append(range);
+        self
+    }
+
+    /// Stop when the thread reaches an address outside of `ranges`,
+    /// such as to trace only the code of one function, given by
+    /// [`SBFunction::ranges`], without stepping over calls.
+    ///
+    /// [`SBFunction::ranges`]: struct.SBFunction.html#method.ranges
+    pub fn stay_within(mut self, ranges: AddressRangeList) -> Self {
+        self.within = Some(ranges);
+        self
+    }
+
+    /// Stop after `count` steps.
+    pub fn max_steps(mut self, count: usize) -> Self {
+        self.max_steps = Some(count);
+        self
+    }
+
+    /// Stop when the thread returns from the function that it's in
+    /// when tracing starts.
+    pub fn until_function_exit(mut self) -> Self {
+        self.until_function_exit = true;
+        self
+    }
+
+    /// Step over calls rather than into them, so that only the
+    /// instructions of the current function are traced.
+    pub fn step_over_calls(mut self, step_over: bool) -> Self {
+        self.step_over_calls = step_over;
+        self
+    }
+
+    /// Capture the registers before each instruction.
+    pub fn registers(mut self, registers: bool) -> Self {
+        self.registers = registers;
+        self
+    }
+
+    /// Wait for `delay` after each step, to limit how much of the
+    /// machine the tracing uses.
+    pub fn throttle(mut self, delay: Duration) -> Self {
+        self.throttle = Some(delay);
+        self
+    }
+
+    /// Trace `thread`, calling `callback` with each instruction before
+    /// it is executed.
+    pub fn trace<F>(&self, thread: &SBThread, mut callback: F) -> Result<TraceEnd, SBError>
+    where
+        F: FnMut(&TraceStep),
+    {
+        let process = thread.process();
+        let target = process.target();
+        let start_depth = thread.num_frames();
+        let mut index = 0;
+        loop {
+            let state = process.state();
+            if state != StateType::Stopped {
+                return Ok(TraceEnd::ProcessState(state));
+            }
+            if index > 0 {
+                match thread.stop_reason() {
+                    StopReason::Trace | StopReason::PlanComplete | StopReason::None => {}
+                    reason => return Ok(TraceEnd::Stopped(reason)),
+                }
+            }
+            if self.until_function_exit && thread.num_frames() < start_depth {
+                return Ok(TraceEnd::FunctionExit);
+            }
+            let frame = match thread.selected_frame() {
+                Some(frame) => frame,
+                None => return Ok(TraceEnd::Stopped(thread.stop_reason())),
+            };
+            let pc = frame.pc();
+            if self.stop_addresses.contains(&pc) || self.stop_ranges.contains(pc) {
+                return Ok(TraceEnd::Address(pc));
+            }
+            if let Some(within) = &self.within {
+                if !within.contains(pc) {
+                    return Ok(TraceEnd::LeftRanges(pc));
+                }
+            }
+            if self.max_steps == Some(index) {
+                return Ok(TraceEnd::Count);
+            }
+
+            let disassembly = target
+                .read_instructions(&target.resolve_load_address(pc), 1)
+                .iter()
+                .next()
+                .map(|instruction| {
+                    let mnemonic = instruction.mnemonic(&target);
+                    let operands = instruction.operands(&target);
+                    if operands.is_empty() {
+                        mnemonic
+                    } else {
+                        format!("{} {}", mnemonic, operands)
+                    }
+                });
+            callback(&TraceStep {
+                index,
+                pc,
+                disassembly,
+                function_name: frame.function_name(),
+                registers: if self.registers {
+                    Some(frame.register_snapshot())
+                } else {
+                    None
+                },
+            });
+
+            thread.step_instruction(self.step_over_calls)?;
+            index += 1;
+            if let Some(delay) = self.throttle {
+                thread::sleep(delay);
+            }
+        }
+    }
+}
@@ -0,0 +1,409 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Crash triage for core files and minidumps.
+//!
+//! [`CrashReport::load`] opens a core file with its executable and
+//! symbols, finds the thread which crashed and gathers everything
+//! needed to understand the crash into a [`CrashReport`], which can
+//! be serialized and stored by a crash ingestion service:
+//!
+//! ```no_run
+//! # use lldb::SBDebugger;
+//! # use lldb::triage::CrashReport;
+//! SBDebugger::initialize();
+//! let debugger = SBDebugger::create(false);
+//! let report = CrashReport::load(&debugger, "server", &["server.debug"], "core.1234").unwrap();
+//! if let Some(crash) = &report.crash {
+//!     println!("{}", crash.description.as_deref().unwrap_or("crashed"));
+//! }
+//! ```
+//!
+//! Minidumps from Windows and Breakpad are loaded by LLDB's minidump
+//! plugin. [`MinidumpInfo`] pulls out the parts of a minidump which
+//! have no equivalent in an ELF core: the exception record, and the
+//! versions of the modules in the module list.
+//!
+//! This requires the `triage` feature.
+//!
+//! [`CrashReport::load`]: struct.CrashReport.html#method.load
+//! [`CrashReport`]: struct.CrashReport.html
+//! [`MinidumpInfo`]: struct.MinidumpInfo.html
+
+use super::debugger::SBDebugger;
+use super::error::SBError;
+use super::module::SBModule;
+use super::process::SBProcess;
+use super::registers::RegisterSnapshot;
+use super::snapshots::{write_binary_images, write_threads, ModuleSnapshot, ThreadSnapshot};
+use super::thread::SBThread;
+use super::StopReason;
+use serde::{Deserialize, Serialize};
+use std::fmt::Write;
+
+/// Everything known about a crashed process.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct CrashReport {
+    /// The path of the executable.
+    pub executable: String,
+    /// The process ID of the crashed process.
+    pub process_id: u64,
+    /// Why the process crashed, if a crashing thread was found.
+    pub crash: Option<CrashInfo>,
+    /// The registers of the crashing thread, in its innermost frame.
+    pub registers: Option<RegisterSnapshot>,
+    /// Every thread in the process, with its stack.
+    pub threads: Vec<ThreadSnapshot>,
+    /// The modules loaded in the process.
+    pub modules: Vec<ModuleSnapshot>,
+}
+
+/// Why a thread crashed.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CrashInfo {
+    /// The ID of the crashing thread.
+    pub thread_id: u64,
+    /// The index of the crashing thread in [`CrashReport::threads`].
+    ///
+    /// [`CrashReport::threads`]: struct.CrashReport.html#structfield.threads
+    pub thread_index: usize,
+    /// The reason the thread stopped, such as `Signal` or `Exception`.
+    pub stop_reason: String,
+    /// The signal number, when the thread stopped because of a
+    /// signal.
+    pub signal: Option<u64>,
+    /// LLDB's description of the stop, such as
+    /// `signal SIGSEGV: invalid address (fault address: 0x0)`.
+    pub description: Option<String>,
+}
+
+impl CrashReport {
+    /// Load the core file at `core_path` for `executable`, add the
+    /// `symbol_files` to its modules and build a report of the crash.
+    ///
+    /// A new target is created in `debugger` for the core file.
+    pub fn load(
+        debugger: &SBDebugger,
+        executable: &str,
+        symbol_files: &[&str],
+        core_path: &str,
+    ) -> Result<CrashReport, SBError> {
+        let target = debugger.create_target(executable, None, None, true)?;
+        let process = target.load_core(core_path)?;
+        let interpreter = debugger.command_interpreter();
+        for symbol_file in symbol_files {
+            let result = interpreter
+                .handle_command(&format!("target symbols add \"{}\"", symbol_file), false);
+            if !result.succeeded() {
+                return Err(SBError::from_message(&result.error().unwrap_or_default()));
+            }
+        }
+        Ok(CrashReport::capture(&process))
+    }
+
+    /// Build a report for a process which has already been loaded
+    /// from a core file, or which has stopped after crashing.
+    pub fn capture(process: &SBProcess) -> CrashReport {
+        let threads: Vec<SBThread> = process.threads().collect();
+        let crashed = threads.iter().position(is_crash).or_else(|| {
+            let selected = process.selected_thread()?;
+            threads
+                .iter()
+                .position(|t| t.thread_id() == selected.thread_id())
+        });
+        let crash = crashed.map(|index| {
+            let thread = &threads[index];
+            let stop_reason = thread.stop_reason();
+            CrashInfo {
+                thread_id: thread.thread_id(),
+                thread_index: index,
+                stop_reason: format!("{:?}", stop_reason),
+                signal: if stop_reason == StopReason::Signal {
+                    Some(thread.stop_reason_data_at_index(0))
+                } else {
+                    None
+                },
+                description: thread.stop_description(),
+            }
+        });
+        let registers = crashed
+            .and_then(|index| threads[index].frames().next())
+            .map(|frame| frame.register_snapshot());
+        let target = process.target();
+        CrashReport {
+            executable: target
+                .executable()
+                .map(|file| file.path())
+                .unwrap_or_default(),
+            process_id: process.process_id(),
+            crash,
+            registers,
+            threads: threads.iter().map(ThreadSnapshot::capture).collect(),
+            modules: target
+                .modules()
+                .map(|m| ModuleSnapshot::capture_loaded(&m, &target))
+                .collect(),
+        }
+    }
+
+    /// Render the report as text in the style of an Apple crash
+    /// report: the crash, the stack of each thread, the registers of
+    /// the crashing thread and the loaded modules.
+    ///
+    /// ```no_run
+    /// # use lldb::SBProcess;
+    /// # use lldb::triage::CrashReport;
+    /// # fn report(process: &SBProcess) {
+    /// let report = CrashReport::capture(process);
+    /// std::fs::write("crash.txt", report.to_report_text()).unwrap();
+    /// # }
+    /// ```
+    pub fn to_report_text(&self) -> String {
+        let mut text = String::new();
+        let name = self.executable.rsplit('/').next().unwrap_or("");
+        let _ = writeln!(text, "{:<23}{} [{}]", "Process:", name, self.process_id);
+        let _ = writeln!(text, "{:<23}{}", "Path:", self.executable);
+        if let Some(crash) = &self.crash {
+            let _ = writeln!(
+                text,
+                "{:<23}{}",
+                "Exception Type:",
+                match crash.signal {
+                    Some(signal) => format!("{} ({})", crash.stop_reason, signal_name(signal)),
+                    None => crash.stop_reason.clone(),
+                }
+            );
+            if let Some(description) = &crash.description {
+                let _ = writeln!(text, "{:<23}{}", "Exception Codes:", description);
+            }
+            if let Some(thread) = self.threads.get(crash.thread_index) {
+                let _ = writeln!(text, "{:<23}{}", "Crashed Thread:", thread.index_id);
+            }
+        }
+
+        let crashed = self.crash.as_ref().map(|crash| crash.thread_index);
+        write_threads(&mut text, &self.threads, crashed, &self.modules);
+
+        if let Some(registers) = &self.registers {
+            let general_purpose: Vec<_> = registers.general_purpose().collect();
+            let index_id = crashed
+                .and_then(|idx| self.threads.get(idx))
+                .map_or(0, |thread| thread.index_id);
+            let _ = writeln!(text, "\nThread {} crashed with Thread State:", index_id);
+            for row in general_purpose.chunks(4) {
+                for register in row {
+                    let _ = write!(
+                        text,
+                        "{:>8}: {:<20}",
+                        register.name,
+                        register.value.as_deref().unwrap_or("???")
+                    );
+                }
+                text.truncate(text.trim_end().len());
+                text.push('\n');
+            }
+        }
+
+        write_binary_images(&mut text, &self.modules);
+        text
+    }
+}
+
+/// The name of the signal numbered `signal` on Linux and macOS, where
+/// they agree.
+fn signal_name(signal: u64) -> String {
+    match signal {
+        4 => "SIGILL".to_string(),
+        5 => "SIGTRAP".to_string(),
+        6 => "SIGABRT".to_string(),
+        8 => "SIGFPE".to_string(),
+        9 => "SIGKILL".to_string(),
+        11 => "SIGSEGV".to_string(),
+        _ => format!("signal {}", signal),
+    }
+}
+
+/// The contents of a minidump which are specific to minidumps.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MinidumpInfo {
+    /// The exception which caused the dump to be written, if any.
+    pub exception: Option<ExceptionRecord>,
+    /// The modules from the module list, with their versions.
+    pub modules: Vec<MinidumpModule>,
+    /// The register context of every thread.
+    pub threads: Vec<ThreadContext>,
+}
+
+/// The exception stream of a minidump.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExceptionRecord {
+    /// The ID of the thread which raised the exception.
+    pub thread_id: u64,
+    /// The exception code, such as `0xc0000005` for an access
+    /// violation on Windows, or the signal number for dumps written
+    /// by Breakpad on Linux.
+    pub code: u64,
+    /// LLDB's description of the exception, which includes the
+    /// faulting address when it is known.
+    pub description: Option<String>,
+}
+
+/// A module from the module list of a minidump.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MinidumpModule {
+    /// The path of the module on the machine which wrote the dump.
+    pub path: String,
+    /// The UUID of the module, which is built from the PDB signature
+    /// and age for Windows modules.
+    pub uuid: Option<String>,
+    /// The target triple of the module.
+    pub triple: Option<String>,
+    /// The version from the module's version resource, if any.
+    pub version: Vec<u32>,
+}
+
+/// The registers of a thread when the dump was written.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ThreadContext {
+    /// The thread ID.
+    pub thread_id: u64,
+    /// The registers of the thread, in its innermost frame.
+    pub registers: Option<RegisterSnapshot>,
+}
+
+impl MinidumpInfo {
+    /// Gather the minidump-specific parts of a process loaded from a
+    /// minidump with [`SBTarget::load_core`].
+    ///
+    /// [`SBTarget::load_core`]: ../struct.SBTarget.html#method.load_core
+    pub fn capture(process: &SBProcess) -> MinidumpInfo {
+        let threads: Vec<SBThread> = process.threads().collect();
+        MinidumpInfo {
+            exception: threads
+                .iter()
+                .find(|t| is_crash(t))
+                .map(|thread| ExceptionRecord {
+                    thread_id: thread.thread_id(),
+                    code: thread.stop_reason_data_at_index(0),
+                    description: thread.stop_description(),
+                }),
+            modules: process
+                .target()
+                .modules()
+                .map(|module| MinidumpModule::capture(&module))
+                .collect(),
+            threads: threads
+                .iter()
+                .map(|thread| ThreadContext {
+                    thread_id: thread.thread_id(),
+                    registers: thread
+                        .frames()
+                        .next()
+                        .map(|frame| frame.register_snapshot()),
+                })
+                .collect(),
+        }
+    }
+}
+
+impl MinidumpModule {
+    /// Capture the identity and version of a module.
+    pub fn capture(module: &SBModule) -> MinidumpModule {
+        MinidumpModule {
+            path: module.platform_filespec().path(),
+            uuid: module.uuid_string(),
+            triple: module.triple(),
+            version: module.version(),
+        }
+    }
+}
+
+fn is_crash(thread: &SBThread) -> bool {
+    matches!(
+        thread.stop_reason(),
+        StopReason::Signal | StopReason::Exception | StopReason::Instrumentation
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CrashInfo, CrashReport, ModuleSnapshot, ThreadSnapshot};
+    use registers::{RegisterSnapshot, RegisterValue};
+    use snapshots::FrameSnapshot;
+
+    #[test]
+    fn report_text() {
+        let report = CrashReport {
+            executable: "/usr/bin/server".to_string(),
+            process_id: 1234,
+            crash: Some(CrashInfo {
+                thread_id: 1234,
+                thread_index: 0,
+                stop_reason: "Signal".to_string(),
+                signal: Some(11),
+                description: None,
+            }),
+            registers: Some(RegisterSnapshot {
+                pc: 0x5555_5555_5139,
+                registers: vec![
+                    RegisterValue {
+                        set: "General Purpose Registers".to_string(),
+                        name: "rip".to_string(),
+                        value: Some("0x0000555555555139".to_string()),
+                    },
+                    RegisterValue {
+                        set: "Floating Point Registers".to_string(),
+                        name: "fcw".to_string(),
+                        value: Some("0x037f".to_string()),
+                    },
+                ],
+            }),
+            threads: vec![ThreadSnapshot {
+                thread_id: 1234,
+                index_id: 1,
+                name: None,
+                queue_name: None,
+                stop_reason: "Signal".to_string(),
+                frames: vec![FrameSnapshot {
+                    index: 0,
+                    pc: 0x5555_5555_5139,
+                    function_name: Some("main".to_string()),
+                    module: Some("/usr/bin/server".to_string()),
+                    file: Some("/src/main.c".to_string()),
+                    line: Some(5),
+                    column: None,
+                    variables: vec![],
+                    symbol_offset: Some(16),
+                    stop_id: None,
+                }],
+            }],
+            modules: vec![ModuleSnapshot {
+                path: "/usr/bin/server".to_string(),
+                platform_path: "/usr/bin/server".to_string(),
+                uuid: Some("0123ABCD".to_string()),
+                triple: Some("x86_64-unknown-linux-gnu".to_string()),
+                load_address: Some(0x5555_5555_4000),
+                load_end_address: Some(0x5555_5555_6000),
+            }],
+        };
+        assert_eq!(
+            report.to_report_text(),
+            "Process:               server [1234]\n\
+             Path:                  /usr/bin/server\n\
+             Exception Type:        Signal (SIGSEGV)\n\
+             Crashed Thread:        1\n\
+             \n\
+             Thread 1 Crashed:\n\
+             0   server                        \t0x0000555555555139 main + 16 (main.c:5)\n\
+             \n\
+             Thread 1 crashed with Thread State:\n     \
+             rip: 0x0000555555555139\n\
+             \n\
+             Binary Images:\n    \
+             0x555555554000 -     0x555555555fff server x86_64 <0123ABCD> /usr/bin/server\n"
+        );
+    }
+}
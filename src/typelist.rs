@@ -5,6 +5,7 @@
 // except according to those terms.
 
 use super::types::SBType;
+use std::fmt;
 use sys;
 
 /// A list of [types].
@@ -17,7 +18,13 @@ pub struct SBTypeList {
 
 impl SBTypeList {
     /// Construct a new `SBTypeList`.
-    pub fn wrap(raw: sys::SBTypeListRef) -> SBTypeList {
+    ///
+    /// # Safety
+    ///
+    /// `raw` must be an `SBTypeListRef` which isn't used elsewhere, such
+    /// as one from `SBModuleFindTypes` or `SBCompileUnitGetTypes`, as it
+    /// is disposed of when the `SBTypeList` is dropped.
+    pub unsafe fn wrap(raw: sys::SBTypeListRef) -> SBTypeList {
         SBTypeList { raw }
     }
 
@@ -26,6 +33,11 @@ impl SBTypeList {
         unsafe { sys::SBTypeListAppend(self.raw, t.raw) };
     }
 
+    /// The number of types in this list.
+    pub fn len(&self) -> usize {
+        unsafe { sys::SBTypeListGetSize(self.raw) as usize }
+    }
+
     /// Is this type list empty?
     pub fn is_empty(&self) -> bool {
         unsafe { sys::SBTypeListGetSize(self.raw) == 0 }
@@ -48,6 +60,12 @@ impl Clone for SBTypeList {
     }
 }
 
+impl fmt::Debug for SBTypeList {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.debug_list().entries(self.iter()).finish()
+    }
+}
+
 impl Drop for SBTypeList {
     fn drop(&mut self) {
         unsafe { sys::DisposeSBTypeList(self.raw) };
@@ -55,7 +73,6 @@ impl Drop for SBTypeList {
 }
 
 unsafe impl Send for SBTypeList {}
-unsafe impl Sync for SBTypeList {}
 
 /// An iterator over the [types] in an [`SBTypeList`].
 ///
@@ -71,9 +88,12 @@ impl<'d> Iterator for SBTypeListIter<'d> {
 
     fn next(&mut self) -> Option<SBType> {
         if self.idx < unsafe { sys::SBTypeListGetSize(self.type_list.raw) as usize } {
-            let r = SBType::wrap(unsafe {
-                sys::SBTypeListGetTypeAtIndex(self.type_list.raw, self.idx as u32)
-            });
+            let r = unsafe {
+                SBType::wrap(sys::SBTypeListGetTypeAtIndex(
+                    self.type_list.raw,
+                    self.idx as u32,
+                ))
+            };
             self.idx += 1;
             Some(r)
         } else {
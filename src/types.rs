@@ -4,9 +4,12 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
+use super::error::InvalidRefError;
 use super::stream::SBStream;
+use super::strings;
+use super::typelist::SBTypeList;
 use super::{BasicType, DescriptionLevel};
-use std::ffi::CStr;
+use std::convert::TryFrom;
 use std::fmt;
 use sys;
 
@@ -18,14 +21,24 @@ pub struct SBType {
 
 impl SBType {
     /// Construct a new `SBType`.
-    pub fn wrap(raw: sys::SBTypeRef) -> SBType {
+    ///
+    /// # Safety
+    ///
+    /// `raw` must be a valid reference, which is disposed of when the
+    /// `SBType` is dropped. Use [`maybe_wrap`] when it may be invalid.
+    ///
+    /// [`maybe_wrap`]: #method.maybe_wrap
+    pub unsafe fn wrap(raw: sys::SBTypeRef) -> SBType {
         SBType { raw }
     }
 
     /// Construct a new `Some(SBType)` or `None`.
+    ///
+    /// `raw` is disposed of if it is invalid.
     pub fn maybe_wrap(raw: sys::SBTypeRef) -> Option<SBType> {
-        if unsafe { sys::SBTypeIsValid(raw) != 0 } {
-            Some(SBType { raw })
+        let wrapped = SBType { raw };
+        if wrapped.is_valid() {
+            Some(wrapped)
         } else {
             None
         }
@@ -116,29 +129,35 @@ impl SBType {
         SBType::maybe_wrap(unsafe { sys::SBTypeGetCanonicalType(self.raw) })
     }
 
+    /// The return type, if this is a function type.
+    pub fn function_return_type(&self) -> Option<SBType> {
+        SBType::maybe_wrap(unsafe { sys::SBTypeGetFunctionReturnType(self.raw) })
+    }
+
+    /// The types of the parameters, if this is a function type.
+    pub fn function_argument_types(&self) -> SBTypeList {
+        unsafe { SBTypeList::wrap(sys::SBTypeGetFunctionArgumentTypes(self.raw)) }
+    }
+
     #[allow(missing_docs)]
     pub fn basic_type(&self) -> BasicType {
         unsafe { sys::SBTypeGetBasicType(self.raw) }
     }
 
     #[allow(missing_docs)]
-    pub fn name(&self) -> &str {
-        unsafe {
-            match CStr::from_ptr(sys::SBTypeGetName(self.raw)).to_str() {
-                Ok(s) => s,
-                _ => panic!("Invalid string?"),
-            }
-        }
+    pub fn name(&self) -> Option<String> {
+        unsafe { strings::to_string(sys::SBTypeGetName(self.raw)) }
     }
 
     #[allow(missing_docs)]
-    pub fn display_type_name(&self) -> &str {
-        unsafe {
-            match CStr::from_ptr(sys::SBTypeGetDisplayTypeName(self.raw)).to_str() {
-                Ok(s) => s,
-                _ => panic!("Invalid string?"),
-            }
-        }
+    pub fn display_type_name(&self) -> Option<String> {
+        unsafe { strings::to_string(sys::SBTypeGetDisplayTypeName(self.raw)) }
+    }
+
+    fn description(&self) -> String {
+        let stream = SBStream::new();
+        unsafe { sys::SBTypeGetDescription(self.raw, stream.raw, DescriptionLevel::Brief) };
+        stream.data()
     }
 }
 
@@ -152,9 +171,21 @@ impl Clone for SBType {
 
 impl fmt::Debug for SBType {
     fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
-        let stream = SBStream::new();
-        unsafe { sys::SBTypeGetDescription(self.raw, stream.raw, DescriptionLevel::Brief) };
-        write!(fmt, "SBType {{ {} }}", stream.data())
+        write!(fmt, "SBType {{ {} }}", self.description())
+    }
+}
+
+impl fmt::Display for SBType {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "{}", self.description())
+    }
+}
+
+impl TryFrom<sys::SBTypeRef> for SBType {
+    type Error = InvalidRefError;
+
+    fn try_from(raw: sys::SBTypeRef) -> Result<SBType, InvalidRefError> {
+        SBType::maybe_wrap(raw).ok_or(InvalidRefError)
     }
 }
 
@@ -168,78 +199,97 @@ unsafe impl Send for SBType {}
 unsafe impl Sync for SBType {}
 
 #[cfg(feature = "graphql")]
-graphql_object!(SBType: super::debugger::SBDebugger | &self | {
-    field is_valid() -> bool {
+#[graphql_object(context = super::debugger::SBDebugger)]
+impl SBType {
+    #[graphql(name = "isValid")]
+    fn graphql_is_valid(&self) -> bool {
         self.is_valid()
     }
 
-    field is_pointer_type() -> bool {
+    #[graphql(name = "isPointerType")]
+    fn graphql_is_pointer_type(&self) -> bool {
         self.is_pointer_type()
     }
 
-    field is_reference_type() -> bool {
+    #[graphql(name = "isReferenceType")]
+    fn graphql_is_reference_type(&self) -> bool {
         self.is_reference_type()
     }
 
-    field is_function_type() -> bool {
+    #[graphql(name = "isFunctionType")]
+    fn graphql_is_function_type(&self) -> bool {
         self.is_function_type()
     }
 
-    field is_polymorphic_class() -> bool {
+    #[graphql(name = "isPolymorphicClass")]
+    fn graphql_is_polymorphic_class(&self) -> bool {
         self.is_polymorphic_class()
     }
 
-    field is_array_type() -> bool {
+    #[graphql(name = "isArrayType")]
+    fn graphql_is_array_type(&self) -> bool {
         self.is_array_type()
     }
 
-    field is_vector_type() -> bool {
+    #[graphql(name = "isVectorType")]
+    fn graphql_is_vector_type(&self) -> bool {
         self.is_vector_type()
     }
 
-    field is_typedef_type() -> bool {
+    #[graphql(name = "isTypedefType")]
+    fn graphql_is_typedef_type(&self) -> bool {
         self.is_typedef_type()
     }
 
-    field pointer_type() -> Option<SBType> {
+    #[graphql(name = "pointerType")]
+    fn graphql_pointer_type(&self) -> Option<SBType> {
         self.pointer_type()
     }
 
-    field pointee_type() -> Option<SBType> {
+    #[graphql(name = "pointeeType")]
+    fn graphql_pointee_type(&self) -> Option<SBType> {
         self.pointee_type()
     }
 
-    field reference_type() -> Option<SBType> {
+    #[graphql(name = "referenceType")]
+    fn graphql_reference_type(&self) -> Option<SBType> {
         self.reference_type()
     }
 
-    field typedefed_type() -> Option<SBType> {
+    #[graphql(name = "typedefedType")]
+    fn graphql_typedefed_type(&self) -> Option<SBType> {
         self.typedefed_type()
     }
 
-    field dereferenced_type() -> Option<SBType> {
+    #[graphql(name = "dereferencedType")]
+    fn graphql_dereferenced_type(&self) -> Option<SBType> {
         self.dereferenced_type()
     }
 
-    field unqualified_type() -> Option<SBType> {
+    #[graphql(name = "unqualifiedType")]
+    fn graphql_unqualified_type(&self) -> Option<SBType> {
         self.unqualified_type()
     }
 
-    field array_element_type() -> Option<SBType> {
+    #[graphql(name = "arrayElementType")]
+    fn graphql_array_element_type(&self) -> Option<SBType> {
         self.array_element_type()
     }
 
-    field vector_element_type() -> Option<SBType> {
+    #[graphql(name = "vectorElementType")]
+    fn graphql_vector_element_type(&self) -> Option<SBType> {
         self.vector_element_type()
     }
 
-    field canonical_type() -> Option<SBType> {
+    #[graphql(name = "canonicalType")]
+    fn graphql_canonical_type(&self) -> Option<SBType> {
         self.canonical_type()
     }
 
     // TODO(bm) bind `basic_type`.
 
-    field name() -> &str {
+    #[graphql(name = "name")]
+    fn graphql_name(&self) -> Option<String> {
         self.name()
     }
-});
+}
@@ -6,16 +6,21 @@
 
 use super::address::SBAddress;
 use super::data::SBData;
-use super::error::SBError;
-use super::frame::SBFrame;
+use super::error::{InvalidRefError, SBError};
+use super::expressionoptions::SBExpressionOptions;
+use super::frame::{SBFrame, NO_RESULT};
+#[cfg(feature = "graphql")]
+use super::graphql::U64;
 use super::process::SBProcess;
 use super::stream::SBStream;
+use super::strings::{self, AsCStr};
 use super::target::SBTarget;
 use super::thread::SBThread;
 use super::watchpoint::SBWatchpoint;
-use super::{lldb_addr_t, lldb_user_id_t, Format};
-use std::ffi::CStr;
+use super::{lldb_addr_t, lldb_user_id_t, Format, LanguageType};
+use std::convert::TryFrom;
 use std::fmt;
+use std::hash::{Hash, Hasher};
 use sys;
 
 /// The value of a variable, register or expression.
@@ -26,14 +31,24 @@ pub struct SBValue {
 
 impl SBValue {
     /// Construct a new `SBValue`.
-    pub fn wrap(raw: sys::SBValueRef) -> SBValue {
+    ///
+    /// # Safety
+    ///
+    /// `raw` must be a valid reference, which is disposed of when the
+    /// `SBValue` is dropped. Use [`maybe_wrap`] when it may be invalid.
+    ///
+    /// [`maybe_wrap`]: #method.maybe_wrap
+    pub unsafe fn wrap(raw: sys::SBValueRef) -> SBValue {
         SBValue { raw }
     }
 
     /// Construct a new `Some(SBValue)` or `None`.
+    ///
+    /// `raw` is disposed of if it is invalid.
     pub fn maybe_wrap(raw: sys::SBValueRef) -> Option<SBValue> {
-        if unsafe { sys::SBValueIsValid(raw) != 0 } {
-            Some(SBValue { raw })
+        let wrapped = SBValue { raw };
+        if wrapped.is_valid() {
+            Some(wrapped)
         } else {
             None
         }
@@ -45,7 +60,7 @@ impl SBValue {
     }
 
     #[allow(missing_docs)]
-    pub fn clear(&self) {
+    pub fn clear(&mut self) {
         unsafe { sys::SBValueClear(self.raw) };
     }
 
@@ -60,32 +75,55 @@ impl SBValue {
     }
 
     #[allow(missing_docs)]
-    pub fn name(&self) -> &str {
-        unsafe {
-            match CStr::from_ptr(sys::SBValueGetName(self.raw)).to_str() {
-                Ok(s) => s,
-                _ => panic!("Invalid string?"),
-            }
-        }
+    pub fn name(&self) -> Option<String> {
+        unsafe { strings::to_string(sys::SBValueGetName(self.raw)) }
     }
 
     #[allow(missing_docs)]
-    pub fn type_name(&self) -> &str {
-        unsafe {
-            match CStr::from_ptr(sys::SBValueGetTypeName(self.raw)).to_str() {
-                Ok(s) => s,
-                _ => panic!("Invalid string?"),
-            }
+    pub fn type_name(&self) -> Option<String> {
+        unsafe { strings::to_string(sys::SBValueGetTypeName(self.raw)) }
+    }
+
+    /// The name of the type as it should be shown to the user, such
+    /// as `std::string` rather than the full name of the template
+    /// instantiation given by [`type_name`].
+    ///
+    /// [`type_name`]: #method.type_name
+    pub fn display_type_name(&self) -> Option<String> {
+        unsafe { strings::to_string(sys::SBValueGetDisplayTypeName(self.raw)) }
+    }
+
+    /// The expression which evaluates to this value from its frame,
+    /// such as `request->headers[2].name`.
+    ///
+    /// The path identifies the value across stops, so it can be saved
+    /// for a watch window and evaluated again later. Values which
+    /// weren't found through a variable, such as the results of
+    /// expressions, have no path.
+    ///
+    /// Children of pointers are written with `->`. The form which
+    /// dereferences pointers explicitly, `(*request).headers`, isn't
+    /// available through this version of LLDB.
+    pub fn expression_path(&self) -> Option<String> {
+        let stream = SBStream::new();
+        if unsafe { sys::SBValueGetExpressionPath(self.raw, stream.raw) } != 0 {
+            Some(stream.data()).filter(|path| !path.is_empty())
+        } else {
+            None
         }
     }
 
-    #[allow(missing_docs)]
-    pub fn display_type_name(&self) -> &str {
-        unsafe {
-            match CStr::from_ptr(sys::SBValueGetDisplayTypeName(self.raw)).to_str() {
-                Ok(s) => s,
-                _ => panic!("Invalid string?"),
-            }
+    /// The expression path of this value, like [`expression_path`],
+    /// with members inherited from C++ base classes qualified by the
+    /// name of the base class, as in `widget->Base::name`.
+    ///
+    /// [`expression_path`]: #method.expression_path
+    pub fn qualified_expression_path(&self) -> Option<String> {
+        let stream = SBStream::new();
+        if unsafe { sys::SBValueGetExpressionPath2(self.raw, stream.raw, 1) } != 0 {
+            Some(stream.data()).filter(|path| !path.is_empty())
+        } else {
+            None
         }
     }
 
@@ -110,20 +148,69 @@ impl SBValue {
     }
 
     #[allow(missing_docs)]
-    pub fn value(&self) -> &str {
-        unsafe {
-            match CStr::from_ptr(sys::SBValueGetValue(self.raw)).to_str() {
-                Ok(s) => s,
-                _ => panic!("Invalid string?"),
-            }
+    pub fn value(&self) -> Option<String> {
+        unsafe { strings::to_string(sys::SBValueGetValue(self.raw)) }
+    }
+
+    /// The value as an unsigned integer, such as the address held by a
+    /// pointer.
+    pub fn value_as_unsigned(&self) -> Result<u64, SBError> {
+        let error = SBError::new();
+        let value = unsafe { sys::SBValueGetValueAsUnsigned(self.raw, error.raw, 0) };
+        if error.is_success() {
+            Ok(value)
+        } else {
+            Err(error)
+        }
+    }
+
+    /// The value as a signed integer.
+    pub fn value_as_signed(&self) -> Result<i64, SBError> {
+        let error = SBError::new();
+        let value = unsafe { sys::SBValueGetValueAsSigned(self.raw, error.raw, 0) };
+        if error.is_success() {
+            Ok(value)
+        } else {
+            Err(error)
         }
     }
 
+    /// A summary of the value, such as the contents of a string, if
+    /// there is one.
+    pub fn summary(&self) -> Option<String> {
+        unsafe { strings::to_string(sys::SBValueGetSummary(self.raw)) }
+    }
+
+    /// The number of children of this value, such as the fields of a
+    /// struct or the elements of an array.
+    pub fn num_children(&self) -> u32 {
+        unsafe { sys::SBValueGetNumChildren(self.raw) }
+    }
+
+    /// Get the child at the given index.
+    pub fn child_at_index(&self, idx: u32) -> Option<SBValue> {
+        SBValue::maybe_wrap(unsafe { sys::SBValueGetChildAtIndex(self.raw, idx) })
+    }
+
     #[allow(missing_docs)]
     pub fn dereference(&self) -> Option<SBValue> {
         SBValue::maybe_wrap(unsafe { sys::SBValueDereference(self.raw) })
     }
 
+    /// Find a member of this struct, class or union by name.
+    ///
+    /// See [`AsCStr`] for avoiding the conversion of `name` on each
+    /// call.
+    ///
+    /// [`AsCStr`]: trait.AsCStr.html
+    pub fn child_member_with_name<S: AsCStr + ?Sized>(&self, name: &S) -> Option<SBValue> {
+        name.with_cstr(|name| {
+            SBValue::maybe_wrap(unsafe {
+                sys::SBValueGetChildMemberWithName(self.raw, name.as_ptr())
+            })
+        })
+    }
+
     #[allow(missing_docs)]
     pub fn address_of(&self) -> Option<SBValue> {
         SBValue::maybe_wrap(unsafe { sys::SBValueAddressOf(self.raw) })
@@ -136,22 +223,107 @@ impl SBValue {
 
     #[allow(missing_docs)]
     pub fn target(&self) -> SBTarget {
-        SBTarget::wrap(unsafe { sys::SBValueGetTarget(self.raw) })
+        unsafe { SBTarget::wrap(sys::SBValueGetTarget(self.raw)) }
     }
 
-    #[allow(missing_docs)]
-    pub fn process(&self) -> SBProcess {
-        SBProcess::wrap(unsafe { sys::SBValueGetProcess(self.raw) })
+    /// The process this value was read from, if any.
+    pub fn process(&self) -> Option<SBProcess> {
+        SBProcess::maybe_wrap(unsafe { sys::SBValueGetProcess(self.raw) })
     }
 
-    #[allow(missing_docs)]
-    pub fn thread(&self) -> SBThread {
-        SBThread::wrap(unsafe { sys::SBValueGetThread(self.raw) })
+    /// The thread this value was read from, if it was read from a
+    /// thread, such as a local variable or a register.
+    pub fn thread(&self) -> Option<SBThread> {
+        SBThread::maybe_wrap(unsafe { sys::SBValueGetThread(self.raw) })
     }
 
-    #[allow(missing_docs)]
-    pub fn frame(&self) -> SBFrame {
-        SBFrame::wrap(unsafe { sys::SBValueGetFrame(self.raw) })
+    /// The frame this value was read from, if it was read from a
+    /// frame, such as a local variable or a register.
+    pub fn frame(&self) -> Option<SBFrame> {
+        SBFrame::maybe_wrap(unsafe { sys::SBValueGetFrame(self.raw) })
+    }
+
+    /// Keep a copy of this value in a persistent variable, which
+    /// lasts for the rest of the debugging session, even once the
+    /// process has run on.
+    ///
+    /// The copy is named like the results of expressions, such as
+    /// `$3`, and the name, from [`name`], can be used in later
+    /// expressions:
+    ///
+    /// ```no_run
+    /// # use lldb::{SBExpressionOptions, SBFrame};
+    /// # fn analyse(frame: &SBFrame) {
+    /// let options = SBExpressionOptions::new();
+    /// let request = frame.find_variable("request").unwrap().persist().unwrap();
+    /// let name = request.name().unwrap();
+    /// // Later, perhaps at another stop:
+    /// let length = frame.evaluate_expression(&format!("{}.length", name), &options);
+    /// # }
+    /// ```
+    ///
+    /// [`name`]: #method.name
+    pub fn persist(&self) -> Option<SBValue> {
+        SBValue::maybe_wrap(unsafe { sys::SBValuePersist(self.raw) })
+    }
+
+    /// Keep a copy of this value in a persistent variable called
+    /// `name`, such as `$request`, as [`persist`] does, and return it.
+    ///
+    /// The `$` is added if `name` doesn't start with one, and the rest
+    /// must be an identifier: letters, digits and underscores, not
+    /// starting with a digit. This fails if there is already a
+    /// persistent variable called `name`, as LLDB doesn't allow them
+    /// to be redefined.
+    ///
+    /// The variable is declared with `auto`, so the declaration is
+    /// always evaluated as C++, whatever the language of the frame.
+    ///
+    /// [`persist`]: #method.persist
+    pub fn persist_as(&self, name: &str) -> Result<SBValue, SBError> {
+        let identifier = name.strip_prefix('$').unwrap_or(name);
+        let is_identifier = identifier
+            .chars()
+            .next()
+            .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+            && identifier
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_');
+        if !is_identifier {
+            return Err(SBError::from_message(&format!(
+                "{:?} is not a valid persistent variable name",
+                name
+            )));
+        }
+
+        let persisted = self
+            .persist()
+            .ok_or_else(|| SBError::from_message("the value could not be persisted"))?;
+        let persisted_name = persisted
+            .name()
+            .ok_or_else(|| SBError::from_message("the persisted value has no name"))?;
+        let name = format!("${}", identifier);
+
+        let options = SBExpressionOptions::new();
+        options.set_suppress_persistent_result(true);
+        // `auto` is C++, so don't use the language of the frame.
+        options.set_language(LanguageType::C_plus_plus_11);
+        let evaluate = |expression: &str| match persisted.frame() {
+            Some(frame) => frame.evaluate_expression(expression, &options),
+            None => persisted.target().evaluate_expression(expression, &options),
+        };
+        // A declaration has no result, which LLDB reports as an error
+        // with its own code.
+        let declaration = evaluate(&format!("auto {} = {};", name, persisted_name));
+        match declaration.error() {
+            Some(error) if error.is_failure() && error.error() != NO_RESULT => return Err(error),
+            _ => {}
+        }
+        let value = evaluate(&name);
+        match value.error() {
+            Some(error) if error.is_failure() => Err(error),
+            _ => Ok(value),
+        }
     }
 
     /// Find and watch a variable.
@@ -172,7 +344,7 @@ impl SBValue {
             )
         };
         if error.is_success() {
-            Ok(SBWatchpoint::wrap(wp))
+            Ok(unsafe { SBWatchpoint::wrap(wp) })
         } else {
             Err(error)
         }
@@ -196,7 +368,7 @@ impl SBValue {
             )
         };
         if error.is_success() {
-            Ok(SBWatchpoint::wrap(wp))
+            Ok(unsafe { SBWatchpoint::wrap(wp) })
         } else {
             Err(error)
         }
@@ -257,6 +429,12 @@ impl SBValue {
     pub fn address(&self) -> Option<SBAddress> {
         SBAddress::maybe_wrap(unsafe { sys::SBValueGetAddress(self.raw) })
     }
+
+    fn description(&self) -> String {
+        let stream = SBStream::new();
+        unsafe { sys::SBValueGetDescription(self.raw, stream.raw) };
+        stream.data()
+    }
 }
 
 impl Clone for SBValue {
@@ -269,9 +447,36 @@ impl Clone for SBValue {
 
 impl fmt::Debug for SBValue {
     fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
-        let stream = SBStream::new();
-        unsafe { sys::SBValueGetDescription(self.raw, stream.raw) };
-        write!(fmt, "SBValue {{ {} }}", stream.data())
+        write!(fmt, "SBValue {{ {} }}", self.description())
+    }
+}
+
+impl fmt::Display for SBValue {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "{}", self.description())
+    }
+}
+
+/// Values are compared by their ID.
+impl PartialEq for SBValue {
+    fn eq(&self, other: &SBValue) -> bool {
+        self.id() == other.id()
+    }
+}
+
+impl Eq for SBValue {}
+
+impl Hash for SBValue {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.id().hash(state);
+    }
+}
+
+impl TryFrom<sys::SBValueRef> for SBValue {
+    type Error = InvalidRefError;
+
+    fn try_from(raw: sys::SBValueRef) -> Result<SBValue, InvalidRefError> {
+        SBValue::maybe_wrap(raw).ok_or(InvalidRefError)
     }
 }
 
@@ -285,34 +490,72 @@ unsafe impl Send for SBValue {}
 unsafe impl Sync for SBValue {}
 
 #[cfg(feature = "graphql")]
-graphql_object!(SBValue: super::debugger::SBDebugger | &self | {
-    field is_valid() -> bool {
+#[graphql_object(context = super::debugger::SBDebugger)]
+impl SBValue {
+    #[graphql(name = "isValid")]
+    fn graphql_is_valid(&self) -> bool {
         self.is_valid()
     }
 
-    // TODO(bm): This should be u64
-    field id() -> i32 {
-        self.id() as i32
+    #[graphql(name = "id")]
+    fn graphql_id(&self) -> U64 {
+        self.id().into()
     }
 
-    field name() -> &str {
+    #[graphql(name = "name")]
+    fn graphql_name(&self) -> Option<String> {
         self.name()
     }
 
-    field type_name() -> &str {
+    #[graphql(name = "typeName")]
+    fn graphql_type_name(&self) -> Option<String> {
         self.type_name()
     }
 
-    field display_type_name() -> &str {
+    #[graphql(name = "displayTypeName")]
+    fn graphql_display_type_name(&self) -> Option<String> {
         self.display_type_name()
     }
 
-    // TODO(bm): This should be usize.
-    field byte_size() -> i32 {
-        self.byte_size() as i32
+    #[graphql(name = "expressionPath")]
+    fn graphql_expression_path(&self) -> Option<String> {
+        self.expression_path()
+    }
+
+    #[graphql(name = "byteSize")]
+    fn graphql_byte_size(&self) -> U64 {
+        self.byte_size().into()
     }
 
-    field is_in_scope() -> bool {
+    #[graphql(name = "isInScope")]
+    fn graphql_is_in_scope(&self) -> bool {
         self.is_in_scope()
     }
-});
+
+    #[graphql(name = "value")]
+    fn graphql_value(&self) -> Option<String> {
+        self.value()
+    }
+
+    #[graphql(name = "summary")]
+    fn graphql_summary(&self) -> Option<String> {
+        self.summary()
+    }
+
+    #[graphql(name = "numChildren")]
+    fn graphql_num_children(&self) -> U64 {
+        self.num_children().into()
+    }
+
+    #[graphql(name = "children")]
+    fn graphql_children(&self) -> Vec<SBValue> {
+        (0..self.num_children())
+            .filter_map(|idx| self.child_at_index(idx))
+            .collect()
+    }
+
+    #[graphql(name = "dereference")]
+    fn graphql_dereference(&self) -> Option<SBValue> {
+        self.dereference()
+    }
+}
@@ -4,15 +4,17 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
+use super::error::InvalidRefError;
 use super::lldb_user_id_t;
 use super::value::SBValue;
+use std::convert::TryFrom;
 use std::ffi::CString;
+use std::fmt;
 use sys;
 
 /// A list of [values].
 ///
 /// [values]: struct.SBValue.html
-#[derive(Debug)]
 pub struct SBValueList {
     /// The underlying raw `SBValueListRef`.
     pub raw: sys::SBValueListRef,
@@ -20,14 +22,24 @@ pub struct SBValueList {
 
 impl SBValueList {
     /// Construct a new `SBValueList`.
-    pub fn wrap(raw: sys::SBValueListRef) -> SBValueList {
+    ///
+    /// # Safety
+    ///
+    /// `raw` must be a valid reference, which is disposed of when the
+    /// `SBValueList` is dropped. Use [`maybe_wrap`] when it may be invalid.
+    ///
+    /// [`maybe_wrap`]: #method.maybe_wrap
+    pub unsafe fn wrap(raw: sys::SBValueListRef) -> SBValueList {
         SBValueList { raw }
     }
 
     /// Construct a new `Some(SBValueList)` or `None`.
+    ///
+    /// `raw` is disposed of if it is invalid.
     pub fn maybe_wrap(raw: sys::SBValueListRef) -> Option<SBValueList> {
-        if unsafe { sys::SBValueListIsValid(raw) != 0 } {
-            Some(SBValueList { raw })
+        let wrapped = SBValueList { raw };
+        if wrapped.is_valid() {
+            Some(wrapped)
         } else {
             None
         }
@@ -86,6 +98,20 @@ impl Clone for SBValueList {
     }
 }
 
+impl fmt::Debug for SBValueList {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl TryFrom<sys::SBValueListRef> for SBValueList {
+    type Error = InvalidRefError;
+
+    fn try_from(raw: sys::SBValueListRef) -> Result<SBValueList, InvalidRefError> {
+        SBValueList::maybe_wrap(raw).ok_or(InvalidRefError)
+    }
+}
+
 impl Drop for SBValueList {
     fn drop(&mut self) {
         unsafe { sys::DisposeSBValueList(self.raw) };
@@ -93,7 +119,6 @@ impl Drop for SBValueList {
 }
 
 unsafe impl Send for SBValueList {}
-unsafe impl Sync for SBValueList {}
 
 /// An iterator over the [values] in an [`SBValueList`].
 ///
@@ -109,9 +134,12 @@ impl<'d> Iterator for SBValueListIter<'d> {
 
     fn next(&mut self) -> Option<SBValue> {
         if self.idx < unsafe { sys::SBValueListGetSize(self.value_list.raw) as usize } {
-            let r = SBValue::wrap(unsafe {
-                sys::SBValueListGetValueAtIndex(self.value_list.raw, self.idx as u32)
-            });
+            let r = unsafe {
+                SBValue::wrap(sys::SBValueListGetValueAtIndex(
+                    self.value_list.raw,
+                    self.idx as u32,
+                ))
+            };
             self.idx += 1;
             Some(r)
         } else {
@@ -0,0 +1,157 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use super::lldb_tid_t;
+use super::process::SBProcess;
+use super::value::SBValue;
+use std::collections::HashMap;
+
+/// A cache of what is shown about variables in a variables pane, kept
+/// for as long as the process stays at the same stop.
+///
+/// Fetching the type, value, summary and children of a value each
+/// calls into LLDB, and summaries can run code in the process, so a
+/// pane which fetches the whole tree again whenever a node is
+/// expanded is slow. This keeps what was fetched, keyed by the thread
+/// and frame of the value and its expression path, such as
+/// `request.headers[2]`. The cache empties itself when the process has
+/// run or been replaced since it was filled:
+///
+/// ```no_run
+/// # use lldb::{SBFrame, VariableCache};
+/// # fn show(cache: &mut VariableCache, frame: &SBFrame) {
+/// for variable in frame.locals().iter() {
+///     let cached = cache.value(&variable);
+///     println!("{} = {:?}", cached.name.as_deref().unwrap_or(""), cached.summary);
+///     for child in cache.children(&variable) {
+///         println!("    {:?}", cache.value(&child).value);
+///     }
+/// }
+/// # }
+/// ```
+///
+/// Values without an expression path, such as the results of
+/// expressions, aren't cached.
+#[derive(Debug, Default)]
+pub struct VariableCache {
+    stop: Option<(u32, u32)>,
+    values: HashMap<CacheKey, CachedValue>,
+    children: HashMap<CacheKey, Vec<SBValue>>,
+}
+
+/// What a [`VariableCache`] keeps about a value.
+///
+/// [`VariableCache`]: struct.VariableCache.html
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CachedValue {
+    /// The name of the value, if it has one.
+    pub name: Option<String>,
+    /// The name of the type of the value, as it should be shown.
+    pub type_name: Option<String>,
+    /// The value formatted as a string, if it has a value.
+    pub value: Option<String>,
+    /// A summary of the value, if there is one.
+    pub summary: Option<String>,
+    /// The number of children, such as the fields of a struct.
+    pub num_children: u32,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+struct CacheKey {
+    thread_id: lldb_tid_t,
+    frame_id: u32,
+    path: String,
+}
+
+impl VariableCache {
+    /// Construct a new, empty `VariableCache`.
+    pub fn new() -> VariableCache {
+        VariableCache::default()
+    }
+
+    /// The name, type, value and summary of `value`, fetched from LLDB
+    /// the first time it is asked for at this stop.
+    pub fn value(&mut self, value: &SBValue) -> CachedValue {
+        let key = match self.key(value) {
+            Some(key) => key,
+            None => return CachedValue::fetch(value),
+        };
+        self.values
+            .entry(key)
+            .or_insert_with(|| CachedValue::fetch(value))
+            .clone()
+    }
+
+    /// The children of `value`, fetched from LLDB the first time they
+    /// are asked for at this stop.
+    pub fn children(&mut self, value: &SBValue) -> Vec<SBValue> {
+        let fetch = || {
+            (0..value.num_children())
+                .filter_map(|idx| value.child_at_index(idx))
+                .collect()
+        };
+        let key = match self.key(value) {
+            Some(key) => key,
+            None => return fetch(),
+        };
+        self.children.entry(key).or_insert_with(fetch).clone()
+    }
+
+    /// Forget everything that has been cached.
+    pub fn invalidate(&mut self) {
+        self.stop = None;
+        self.values.clear();
+        self.children.clear();
+    }
+
+    /// The number of values cached.
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Is nothing cached?
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty() && self.children.is_empty()
+    }
+
+    /// Empty the cache if `process` isn't at the stop it was filled at.
+    fn sync(&mut self, process: &SBProcess) {
+        let stop = (process.unique_id(), process.stop_id());
+        if self.stop != Some(stop) {
+            self.invalidate();
+            self.stop = Some(stop);
+        }
+    }
+
+    /// The key for `value`, after emptying the cache if the process
+    /// has moved on, or `None` if it can't be cached.
+    fn key(&mut self, value: &SBValue) -> Option<CacheKey> {
+        let process = value.process().filter(SBProcess::is_stopped)?;
+        self.sync(&process);
+        let path = value.expression_path()?;
+        let (thread_id, frame_id) = match value.frame() {
+            Some(frame) => (frame.thread().thread_id(), frame.frame_id()),
+            None => (0, 0),
+        };
+        Some(CacheKey {
+            thread_id,
+            frame_id,
+            path,
+        })
+    }
+}
+
+impl CachedValue {
+    fn fetch(value: &SBValue) -> CachedValue {
+        CachedValue {
+            name: value.name(),
+            type_name: value.display_type_name(),
+            value: value.value(),
+            summary: value.summary(),
+            num_children: value.num_children(),
+        }
+    }
+}
@@ -4,7 +4,9 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
+use super::error::InvalidRefError;
 use super::DynamicValueType;
+use std::convert::TryFrom;
 use sys;
 
 #[allow(missing_docs)]
@@ -17,18 +19,28 @@ pub struct SBVariablesOptions {
 impl SBVariablesOptions {
     /// Construct a new `SBVariablesOptions`.
     pub fn new() -> SBVariablesOptions {
-        SBVariablesOptions::wrap(unsafe { sys::CreateSBVariablesOptions() })
+        unsafe { SBVariablesOptions::wrap(sys::CreateSBVariablesOptions()) }
     }
 
     /// Construct a new `SBVariablesOptions`.
-    pub fn wrap(raw: sys::SBVariablesOptionsRef) -> SBVariablesOptions {
+    ///
+    /// # Safety
+    ///
+    /// `raw` must be a valid reference, which is disposed of when the
+    /// `SBVariablesOptions` is dropped. Use [`maybe_wrap`] when it may be invalid.
+    ///
+    /// [`maybe_wrap`]: #method.maybe_wrap
+    pub unsafe fn wrap(raw: sys::SBVariablesOptionsRef) -> SBVariablesOptions {
         SBVariablesOptions { raw }
     }
 
     /// Construct a new `Some(SBVariablesOptions)` or `None`.
+    ///
+    /// `raw` is disposed of if it is invalid.
     pub fn maybe_wrap(raw: sys::SBVariablesOptionsRef) -> Option<SBVariablesOptions> {
-        if unsafe { sys::SBVariablesOptionsIsValid(raw) != 0 } {
-            Some(SBVariablesOptions { raw })
+        let wrapped = SBVariablesOptions { raw };
+        if wrapped.is_valid() {
+            Some(wrapped)
         } else {
             None
         }
@@ -114,6 +126,14 @@ impl Default for SBVariablesOptions {
     }
 }
 
+impl TryFrom<sys::SBVariablesOptionsRef> for SBVariablesOptions {
+    type Error = InvalidRefError;
+
+    fn try_from(raw: sys::SBVariablesOptionsRef) -> Result<SBVariablesOptions, InvalidRefError> {
+        SBVariablesOptions::maybe_wrap(raw).ok_or(InvalidRefError)
+    }
+}
+
 impl Drop for SBVariablesOptions {
     fn drop(&mut self) {
         unsafe { sys::DisposeSBVariablesOptions(self.raw) };
@@ -121,4 +141,3 @@ impl Drop for SBVariablesOptions {
 }
 
 unsafe impl Send for SBVariablesOptions {}
-unsafe impl Sync for SBVariablesOptions {}
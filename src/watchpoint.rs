@@ -4,10 +4,15 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
-use super::error::SBError;
+use super::error::{InvalidRefError, SBError};
+use super::event::SBEvent;
+#[cfg(feature = "graphql")]
+use super::graphql::U64;
 use super::stream::SBStream;
-use super::{lldb_addr_t, DescriptionLevel};
+use super::{lldb_addr_t, DescriptionLevel, WatchpointEventType};
+use std::convert::TryFrom;
 use std::fmt;
+use std::hash::{Hash, Hasher};
 use sys;
 
 /// An instance of a watch point for a specific target program.
@@ -35,6 +40,18 @@ use sys;
 /// [`ignore_count`]: #method.ignore_count
 /// [`set_ignore_count`]: #method.set_ignore_count
 /// [`hit_count`]: #method.hit_count
+///
+/// # Events
+///
+/// The target broadcasts an event with
+/// [`SBTarget::BROADCAST_BIT_WATCHPOINT_CHANGED`] whenever one of its
+/// watchpoints changes, including changes made by breakpoint
+/// commands or other clients of the debugger. Decode them with
+/// [`WatchpointEvent::from_event`] to keep a view of the watchpoints
+/// up to date.
+///
+/// [`SBTarget::BROADCAST_BIT_WATCHPOINT_CHANGED`]: struct.SBTarget.html#associatedconstant.BROADCAST_BIT_WATCHPOINT_CHANGED
+/// [`WatchpointEvent::from_event`]: enum.WatchpointEvent.html#method.from_event
 pub struct SBWatchpoint {
     /// The underlying raw `SBWatchpointRef`.
     pub raw: sys::SBWatchpointRef,
@@ -42,14 +59,24 @@ pub struct SBWatchpoint {
 
 impl SBWatchpoint {
     /// Construct a new `SBWatchpoint`.
-    pub fn wrap(raw: sys::SBWatchpointRef) -> SBWatchpoint {
+    ///
+    /// # Safety
+    ///
+    /// `raw` must be a valid reference, which is disposed of when the
+    /// `SBWatchpoint` is dropped. Use [`maybe_wrap`] when it may be invalid.
+    ///
+    /// [`maybe_wrap`]: #method.maybe_wrap
+    pub unsafe fn wrap(raw: sys::SBWatchpointRef) -> SBWatchpoint {
         SBWatchpoint { raw }
     }
 
     /// Construct a new `Some(SBWatchpoint)` or `None`.
+    ///
+    /// `raw` is disposed of if it is invalid.
     pub fn maybe_wrap(raw: sys::SBWatchpointRef) -> Option<SBWatchpoint> {
-        if unsafe { sys::SBWatchpointIsValid(raw) != 0 } {
-            Some(SBWatchpoint { raw })
+        let wrapped = SBWatchpoint { raw };
+        if wrapped.is_valid() {
+            Some(wrapped)
         } else {
             None
         }
@@ -114,6 +141,22 @@ impl SBWatchpoint {
     pub fn set_ignore_count(&self, count: u32) {
         unsafe { sys::SBWatchpointSetIgnoreCount(self.raw, count) }
     }
+
+    /// If the given event is a watchpoint event, return it as an
+    /// `SBWatchpointEvent`. Otherwise, return `None`.
+    pub fn event_as_watchpoint_event(event: &SBEvent) -> Option<SBWatchpointEvent<'_>> {
+        if unsafe { sys::SBWatchpointEventIsWatchpointEvent(event.raw) != 0 } {
+            Some(SBWatchpointEvent::new(event))
+        } else {
+            None
+        }
+    }
+
+    fn description(&self) -> String {
+        let stream = SBStream::new();
+        unsafe { sys::SBWatchpointGetDescription(self.raw, stream.raw, DescriptionLevel::Brief) };
+        stream.data()
+    }
 }
 
 impl Clone for SBWatchpoint {
@@ -126,9 +169,36 @@ impl Clone for SBWatchpoint {
 
 impl fmt::Debug for SBWatchpoint {
     fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
-        let stream = SBStream::new();
-        unsafe { sys::SBWatchpointGetDescription(self.raw, stream.raw, DescriptionLevel::Brief) };
-        write!(fmt, "SBWatchpoint {{ {} }}", stream.data())
+        write!(fmt, "SBWatchpoint {{ {} }}", self.description())
+    }
+}
+
+impl fmt::Display for SBWatchpoint {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "{}", self.description())
+    }
+}
+
+/// Watchpoints are compared by their ID, which is unique within a target.
+impl PartialEq for SBWatchpoint {
+    fn eq(&self, other: &SBWatchpoint) -> bool {
+        self.id() == other.id()
+    }
+}
+
+impl Eq for SBWatchpoint {}
+
+impl Hash for SBWatchpoint {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.id().hash(state);
+    }
+}
+
+impl TryFrom<sys::SBWatchpointRef> for SBWatchpoint {
+    type Error = InvalidRefError;
+
+    fn try_from(raw: sys::SBWatchpointRef) -> Result<SBWatchpoint, InvalidRefError> {
+        SBWatchpoint::maybe_wrap(raw).ok_or(InvalidRefError)
     }
 }
 
@@ -141,41 +211,132 @@ impl Drop for SBWatchpoint {
 unsafe impl Send for SBWatchpoint {}
 unsafe impl Sync for SBWatchpoint {}
 
+/// A watchpoint event.
+pub struct SBWatchpointEvent<'e> {
+    event: &'e SBEvent,
+}
+
+impl<'e> SBWatchpointEvent<'e> {
+    /// Construct a new `SBWatchpointEvent`.
+    pub fn new(event: &'e SBEvent) -> Self {
+        SBWatchpointEvent { event }
+    }
+
+    /// What happened to the watchpoint.
+    pub fn event_type(&self) -> WatchpointEventType {
+        unsafe { sys::SBWatchpointGetWatchpointEventTypeFromEvent(self.event.raw) }
+    }
+
+    /// The watchpoint which changed.
+    pub fn watchpoint(&self) -> Option<SBWatchpoint> {
+        SBWatchpoint::maybe_wrap(unsafe { sys::SBWatchpointGetWatchpointFromEvent(self.event.raw) })
+    }
+
+    /// Decode what happened to the watchpoint.
+    pub fn decode(&self) -> Option<WatchpointEvent> {
+        let watchpoint = self.watchpoint()?;
+        Some(match self.event_type() {
+            WatchpointEventType::Added => WatchpointEvent::Added(watchpoint),
+            WatchpointEventType::Removed => WatchpointEvent::Removed(watchpoint),
+            WatchpointEventType::Enabled => WatchpointEvent::Enabled(watchpoint),
+            WatchpointEventType::Disabled => WatchpointEvent::Disabled(watchpoint),
+            WatchpointEventType::CommandChanged => WatchpointEvent::CommandChanged(watchpoint),
+            WatchpointEventType::ConditionChanged => WatchpointEvent::ConditionChanged(watchpoint),
+            WatchpointEventType::IgnoreChanged => WatchpointEvent::IgnoreCountChanged(watchpoint),
+            WatchpointEventType::ThreadChanged => WatchpointEvent::ThreadChanged(watchpoint),
+            WatchpointEventType::TypeChanged => WatchpointEvent::TypeChanged(watchpoint),
+            WatchpointEventType::InvalidType => return None,
+        })
+    }
+}
+
+/// What a [watchpoint event] says happened.
+///
+/// [watchpoint event]: struct.SBWatchpointEvent.html
+#[derive(Clone, Debug)]
+pub enum WatchpointEvent {
+    /// The watchpoint was added.
+    Added(SBWatchpoint),
+    /// The watchpoint was removed.
+    Removed(SBWatchpoint),
+    /// The watchpoint was enabled.
+    Enabled(SBWatchpoint),
+    /// The watchpoint was disabled.
+    Disabled(SBWatchpoint),
+    /// The commands run when the watchpoint is hit changed.
+    CommandChanged(SBWatchpoint),
+    /// The condition of the watchpoint changed.
+    ConditionChanged(SBWatchpoint),
+    /// The ignore count of the watchpoint changed.
+    IgnoreCountChanged(SBWatchpoint),
+    /// The thread that the watchpoint applies to changed.
+    ThreadChanged(SBWatchpoint),
+    /// Whether the watchpoint is for reads, writes or both changed.
+    TypeChanged(SBWatchpoint),
+}
+
+impl WatchpointEvent {
+    /// Decode `event`, if it is a watchpoint event.
+    pub fn from_event(event: &SBEvent) -> Option<WatchpointEvent> {
+        SBWatchpoint::event_as_watchpoint_event(event)?.decode()
+    }
+
+    /// The watchpoint which the event is about.
+    pub fn watchpoint(&self) -> &SBWatchpoint {
+        match self {
+            WatchpointEvent::Added(watchpoint)
+            | WatchpointEvent::Removed(watchpoint)
+            | WatchpointEvent::Enabled(watchpoint)
+            | WatchpointEvent::Disabled(watchpoint)
+            | WatchpointEvent::CommandChanged(watchpoint)
+            | WatchpointEvent::ConditionChanged(watchpoint)
+            | WatchpointEvent::IgnoreCountChanged(watchpoint)
+            | WatchpointEvent::ThreadChanged(watchpoint)
+            | WatchpointEvent::TypeChanged(watchpoint) => watchpoint,
+        }
+    }
+}
+
 #[cfg(feature = "graphql")]
-graphql_object!(SBWatchpoint: super::debugger::SBDebugger | &self | {
-    field is_valid() -> bool {
+#[graphql_object(context = super::debugger::SBDebugger)]
+impl SBWatchpoint {
+    #[graphql(name = "isValid")]
+    fn graphql_is_valid(&self) -> bool {
         self.is_valid()
     }
 
-    field id() -> i32 {
+    #[graphql(name = "id")]
+    fn graphql_id(&self) -> i32 {
         self.id()
     }
 
-    field hardware_index() -> Option<i32> {
+    #[graphql(name = "hardwareIndex")]
+    fn graphql_hardware_index(&self) -> Option<i32> {
         self.hardware_index()
     }
 
-    // TODO(bm) This should be u64
-    field watch_address() -> i32 {
-        self.watch_address() as i32
+    #[graphql(name = "watchAddress")]
+    fn graphql_watch_address(&self) -> U64 {
+        self.watch_address().into()
     }
 
-    // TODO(bm) This should be u32
-    field watch_size() -> i32 {
-        self.watch_size() as i32
+    #[graphql(name = "watchSize")]
+    fn graphql_watch_size(&self) -> U64 {
+        self.watch_size().into()
     }
 
-    field is_enabled() -> bool {
+    #[graphql(name = "isEnabled")]
+    fn graphql_is_enabled(&self) -> bool {
         self.is_enabled()
     }
 
-    // TODO(bm) This should be u32
-    field hit_count() -> i32 {
-        self.hit_count() as i32
+    #[graphql(name = "hitCount")]
+    fn graphql_hit_count(&self) -> U64 {
+        self.hit_count().into()
     }
 
-    // TODO(bm) This should be u32
-    field ignore_count() -> i32 {
-        self.ignore_count() as i32
+    #[graphql(name = "ignoreCount")]
+    fn graphql_ignore_count(&self) -> U64 {
+        self.ignore_count().into()
     }
-});
+}
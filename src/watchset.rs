@@ -0,0 +1,161 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use super::frame::SBFrame;
+use super::session::DebugSession;
+use std::mem;
+use std::sync::{Arc, Mutex};
+
+/// A set of expressions which are evaluated each time the process
+/// stops, reporting those whose values have changed.
+///
+/// This is the "watch" panel of a debugger user interface. Register
+/// the set with a [`DebugSession`] using [`attach`], and the callback
+/// is given a [`WatchEvent`] for each expression whose value differs
+/// from the last stop:
+///
+/// ```no_run
+/// # use lldb::{DebugSession, WatchEvent, WatchSet};
+/// # fn watch(session: &DebugSession) {
+/// let watches = WatchSet::new();
+/// watches.add("count");
+/// watches.add("buffer->len");
+/// watches.attach(session, |event| match event {
+///     WatchEvent::Changed { expr, old, new } => println!("{}: {:?} -> {:?}", expr, old, new),
+/// });
+/// # }
+/// ```
+///
+/// A `WatchSet` is a handle to shared state, so a clone can add and
+/// remove expressions after the set has been attached.
+///
+/// [`DebugSession`]: struct.DebugSession.html
+/// [`attach`]: #method.attach
+/// [`WatchEvent`]: enum.WatchEvent.html
+#[derive(Clone, Debug, Default)]
+pub struct WatchSet {
+    watches: Arc<Mutex<Vec<Watch>>>,
+}
+
+#[derive(Debug)]
+struct Watch {
+    expr: String,
+    value: Option<String>,
+}
+
+/// A change reported by a [`WatchSet`].
+///
+/// [`WatchSet`]: struct.WatchSet.html
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum WatchEvent {
+    /// The value of an expression changed.
+    ///
+    /// A value is `None` when the expression couldn't be evaluated,
+    /// such as when a variable is out of scope, or when the expression
+    /// hadn't been evaluated before.
+    Changed {
+        /// The expression.
+        expr: String,
+        /// The value at the previous stop.
+        old: Option<String>,
+        /// The value at this stop.
+        new: Option<String>,
+    },
+}
+
+impl WatchSet {
+    /// Create an empty set.
+    pub fn new() -> WatchSet {
+        WatchSet::default()
+    }
+
+    /// Watch `expr`. Adding an expression which is already watched
+    /// does nothing.
+    pub fn add(&self, expr: &str) {
+        let mut watches = self.watches.lock().unwrap();
+        if !watches.iter().any(|w| w.expr == expr) {
+            watches.push(Watch {
+                expr: expr.to_string(),
+                value: None,
+            });
+        }
+    }
+
+    /// Stop watching `expr`, returning whether it was watched.
+    pub fn remove(&self, expr: &str) -> bool {
+        let mut watches = self.watches.lock().unwrap();
+        let len = watches.len();
+        watches.retain(|w| w.expr != expr);
+        watches.len() != len
+    }
+
+    /// The watched expressions, in the order they were added.
+    pub fn expressions(&self) -> Vec<String> {
+        let watches = self.watches.lock().unwrap();
+        watches.iter().map(|w| w.expr.clone()).collect()
+    }
+
+    /// The value of each watched expression when it was last
+    /// evaluated.
+    pub fn values(&self) -> Vec<(String, Option<String>)> {
+        let watches = self.watches.lock().unwrap();
+        watches
+            .iter()
+            .map(|w| (w.expr.clone(), w.value.clone()))
+            .collect()
+    }
+
+    /// Evaluate each expression in `frame`, returning an event for
+    /// each whose value has changed.
+    pub fn evaluate(&self, frame: &SBFrame) -> Vec<WatchEvent> {
+        let options = frame.expression_options();
+        let mut watches = self.watches.lock().unwrap();
+        watches
+            .iter_mut()
+            .filter_map(|watch| {
+                let value = frame.evaluate_expression(&watch.expr, &options);
+                let new = match value.error() {
+                    Some(ref error) if error.is_failure() => None,
+                    _ => value.value().or_else(|| value.summary()),
+                };
+                if new == watch.value {
+                    None
+                } else {
+                    let old = mem::replace(&mut watch.value, new.clone());
+                    Some(WatchEvent::Changed {
+                        expr: watch.expr.clone(),
+                        old,
+                        new,
+                    })
+                }
+            })
+            .collect()
+    }
+
+    /// Evaluate the expressions in the selected frame each time the
+    /// process of `session` stops, calling `callback` with each
+    /// change.
+    ///
+    /// The callback is called from the session's event thread.
+    pub fn attach<F>(&self, session: &DebugSession, mut callback: F)
+    where
+        F: FnMut(&WatchEvent) + Send + 'static,
+    {
+        let watches = self.clone();
+        let target = session.target().clone();
+        session.on_stop(move |_| {
+            let frame = target
+                .process()
+                .and_then(|process| process.selected_thread())
+                .and_then(|thread| thread.selected_frame());
+            if let Some(frame) = frame {
+                for event in watches.evaluate(&frame) {
+                    callback(&event);
+                }
+            }
+        });
+    }
+}